@@ -0,0 +1,411 @@
+//! Read-only JSON values allocated in a `JsonArena`. Requires the `arena`
+//! feature.
+//!
+//! All strings, arrays and objects of values parsed with `JsonArena::parse`
+//! are bump-allocated in the arena and freed together when it is dropped or
+//! reset, so parsing a document costs a handful of allocations no matter how
+//! many nodes it has.
+//!
+//! ```
+//! # use json::{ JsonArena, ArenaValue };
+//! let mut arena = JsonArena::new();
+//!
+//! for message in &[r#"{"id":1,"tags":["a","b"]}"#, r#"{"id":2,"tags":[]}"#] {
+//!     let data = arena.parse(message).unwrap();
+//!
+//!     assert!(data["id"].as_u64().is_some());
+//!     assert_eq!(data["tags"][5], ArenaValue::Null);
+//!
+//!     // Memory of the last message is reused for the next one
+//!     arena.reset();
+//! }
+//! ```
+
+use std::collections::{ HashMap, HashSet };
+use std::convert::TryInto;
+use std::ops::Index;
+use std::{ fmt, slice };
+
+use bumpalo::Bump;
+
+use crate::{ JsonValue, Result };
+use crate::base64;
+use crate::number::Number;
+use crate::object::Object;
+use crate::parser;
+
+/// Bump allocator owning the strings, arrays and objects of `ArenaValue`s.
+/// Nothing is freed until the arena is dropped or reset.
+pub struct JsonArena {
+    bump: Bump,
+}
+
+impl JsonArena {
+    /// Create an empty arena, the first chunk is allocated on first use.
+    pub fn new() -> Self {
+        JsonArena {
+            bump: Bump::new(),
+        }
+    }
+
+    /// Create an arena with a first chunk of at least `bytes` bytes.
+    pub fn with_capacity(bytes: usize) -> Self {
+        JsonArena {
+            bump: Bump::with_capacity(bytes),
+        }
+    }
+
+    /// Parse `source` into a value allocated in this arena.
+    pub fn parse(&self, source: &str) -> Result<ArenaValue<'_>> {
+        parser::parse_in(source, self)
+    }
+
+    /// Copy a string into the arena.
+    pub fn alloc_str(&self, value: &str) -> &str {
+        self.bump.alloc_str(value)
+    }
+
+
+    /// Copy a slice into the arena, for building arrays and objects by hand.
+    ///
+    /// ```
+    /// # use json::{ JsonArena, ArenaValue };
+    /// let arena = JsonArena::new();
+    ///
+    /// let tags = arena.alloc_slice(&[ArenaValue::String("a"), ArenaValue::Boolean(true)]);
+    /// let data = arena.alloc_slice(&[("tags", ArenaValue::Array(tags))]);
+    /// let data = ArenaValue::Object(data);
+    ///
+    /// assert_eq!(data, arena.parse(r#"{"tags":["a",true]}"#).unwrap());
+    /// ```
+    pub fn alloc_slice<T: Copy>(&self, values: &[T]) -> &[T] {
+        self.bump.alloc_slice_copy(values)
+    }
+
+    /// Copy `value` into the arena, allocating every distinct string, array
+    /// and object only once. Identical subtrees of the result share their
+    /// memory, which for documents repeating the same parts a lot, like
+    /// schemas in API specs, takes a fraction of the space of the original.
+    /// Binary values become strings of base64, the way they are written.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::JsonArena;
+    /// # fn main() {
+    /// let schema = object!{ "type" => "object", "required" => array!["id"] };
+    /// let spec = object!{ "user" => schema.clone(), "order" => schema };
+    ///
+    /// let arena = JsonArena::new();
+    /// let shared = arena.alloc_dedup(&spec);
+    ///
+    /// assert_eq!(shared, arena.parse(&spec.dump()).unwrap());
+    /// assert!(std::ptr::eq(shared["user"].entries().as_slice(), shared["order"].entries().as_slice()));
+    /// # }
+    /// ```
+    pub fn alloc_dedup(&self, value: &JsonValue) -> ArenaValue<'_> {
+        Dedup {
+            arena: self,
+            strings: HashSet::new(),
+            shared: HashMap::new(),
+        }.value(value)
+    }
+
+    /// Total size of the chunks allocated by this arena, in bytes.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Free all values at once, keeping the largest chunk around to be
+    /// reused by the values allocated next.
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+}
+
+impl Default for JsonArena {
+    fn default() -> Self {
+        JsonArena::new()
+    }
+}
+
+impl fmt::Debug for JsonArena {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JsonArena")
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish()
+    }
+}
+
+/// JSON value borrowing all of its contents from a `JsonArena`. Values are
+/// `Copy`, so they can be passed around freely while the arena lives.
+///
+/// Objects are slices of key value pairs in source order. Duplicate keys are
+/// kept as they are, with lookups by key finding the last one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArenaValue<'arena> {
+    Null,
+    String(&'arena str),
+    Number(Number),
+    Boolean(bool),
+    Object(&'arena [(&'arena str, ArenaValue<'arena>)]),
+    Array(&'arena [ArenaValue<'arena>]),
+}
+
+static NULL: ArenaValue<'static> = ArenaValue::Null;
+
+impl<'arena> ArenaValue<'arena> {
+    /// Name of the type of this value, same as `JsonValue::type_name`.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            ArenaValue::Null       => "null",
+            ArenaValue::String(_)  => "string",
+            ArenaValue::Number(_)  => "number",
+            ArenaValue::Boolean(_) => "boolean",
+            ArenaValue::Object(_)  => "object",
+            ArenaValue::Array(_)   => "array",
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(*self, ArenaValue::Null)
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(*self, ArenaValue::String(_))
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(*self, ArenaValue::Number(_))
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        matches!(*self, ArenaValue::Boolean(_))
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(*self, ArenaValue::Object(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(*self, ArenaValue::Array(_))
+    }
+
+    /// The string, borrowed for as long as the arena lives.
+    pub fn as_str(&self) -> Option<&'arena str> {
+        match *self {
+            ArenaValue::String(value) => Some(value),
+            _                         => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<Number> {
+        match *self {
+            ArenaValue::Number(value) => Some(value),
+            _                         => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(|value| value.into())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            ArenaValue::Boolean(value) => Some(value),
+            _                          => None,
+        }
+    }
+
+    /// Get the member of an object under `key`, the last one if the key is
+    /// duplicated.
+    pub fn get(&self, key: &str) -> Option<&'arena ArenaValue<'arena>> {
+        match *self {
+            ArenaValue::Object(entries) => {
+                entries.iter().rev().find(|entry| entry.0 == key).map(|entry| &entry.1)
+            },
+            _ => None,
+        }
+    }
+
+    /// Length of an array or object, `0` for other types.
+    pub fn len(&self) -> usize {
+        match *self {
+            ArenaValue::Array(members)  => members.len(),
+            ArenaValue::Object(entries) => entries.len(),
+            _                           => 0,
+        }
+    }
+
+    /// Same as `JsonValue::is_empty`, true for `null`, `""`, `0`, `false`
+    /// and empty arrays and objects.
+    pub fn is_empty(&self) -> bool {
+        match *self {
+            ArenaValue::Null            => true,
+            ArenaValue::String(value)   => value.is_empty(),
+            ArenaValue::Number(value)   => value.is_empty(),
+            ArenaValue::Boolean(value)  => !value,
+            ArenaValue::Array(members)  => members.is_empty(),
+            ArenaValue::Object(entries) => entries.is_empty(),
+        }
+    }
+
+    /// Members of an array, empty for other types.
+    pub fn members(&self) -> slice::Iter<'arena, ArenaValue<'arena>> {
+        match *self {
+            ArenaValue::Array(members) => members.iter(),
+            _                          => [].iter(),
+        }
+    }
+
+    /// Key value pairs of an object, empty for other types.
+    pub fn entries(&self) -> slice::Iter<'arena, (&'arena str, ArenaValue<'arena>)> {
+        match *self {
+            ArenaValue::Object(entries) => entries.iter(),
+            _                           => [].iter(),
+        }
+    }
+
+    /// Copy this value out of the arena into a regular `JsonValue`.
+    pub fn to_json(&self) -> JsonValue {
+        match *self {
+            ArenaValue::Null           => JsonValue::Null,
+            ArenaValue::String(value)  => value.into(),
+            ArenaValue::Number(value)  => JsonValue::Number(value),
+            ArenaValue::Boolean(value) => JsonValue::Boolean(value),
+            ArenaValue::Array(members) => {
+                JsonValue::Array(members.iter().map(ArenaValue::to_json).collect())
+            },
+            ArenaValue::Object(entries) => {
+                let mut object = Object::with_capacity(entries.len());
+                for &(key, ref value) in entries {
+                    object.insert(key, value.to_json());
+                }
+                JsonValue::Object(object)
+            },
+        }
+    }
+}
+
+impl<'arena> From<ArenaValue<'arena>> for JsonValue {
+    fn from(value: ArenaValue<'arena>) -> JsonValue {
+        value.to_json()
+    }
+}
+
+/// Implements indexing by `usize` into arrays, returning `Null` for
+/// other types and out of bounds indexes.
+impl<'arena> Index<usize> for ArenaValue<'arena> {
+    type Output = ArenaValue<'arena>;
+
+    fn index(&self, index: usize) -> &ArenaValue<'arena> {
+        match *self {
+            ArenaValue::Array(members) => members.get(index).unwrap_or(&NULL),
+            _                          => &NULL,
+        }
+    }
+}
+
+/// Implements indexing by `&str` into objects, returning `Null` for
+/// other types and missing keys.
+impl<'arena> Index<&str> for ArenaValue<'arena> {
+    type Output = ArenaValue<'arena>;
+
+    fn index(&self, key: &str) -> &ArenaValue<'arena> {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+// Copies values into an arena for `JsonArena::alloc_dedup`. Subtrees are
+// copied bottom up, so by the time an array or object is looked up all of
+// its members are deduplicated already, and can be compared by address.
+struct Dedup<'arena> {
+    arena: &'arena JsonArena,
+    strings: HashSet<&'arena str>,
+    shared: HashMap<Shape<'arena>, ArenaValue<'arena>>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Shape<'arena> {
+    Array(Vec<Member<'arena>>),
+    Object(Vec<(&'arena str, Member<'arena>)>),
+}
+
+// Value compared by identity rather than by contents, with slices as their
+// address and length.
+#[derive(PartialEq, Eq, Hash)]
+enum Member<'arena> {
+    Null,
+    String(&'arena str),
+    Number(bool, bool, u64, i16, Option<u128>),
+    Boolean(bool),
+    Object(usize, usize),
+    Array(usize, usize),
+}
+
+impl<'arena> Member<'arena> {
+    fn new(value: ArenaValue<'arena>) -> Self {
+        match value {
+            ArenaValue::Null            => Member::Null,
+            ArenaValue::String(value)   => Member::String(value),
+            ArenaValue::Number(value)   => {
+                let (positive, mantissa, exponent) = value.as_parts();
+                Member::Number(value.is_nan(), positive, mantissa, exponent, value.wide())
+            },
+            ArenaValue::Boolean(value)  => Member::Boolean(value),
+            ArenaValue::Object(entries) => Member::Object(entries.as_ptr() as usize, entries.len()),
+            ArenaValue::Array(members)  => Member::Array(members.as_ptr() as usize, members.len()),
+        }
+    }
+}
+
+impl<'arena> Dedup<'arena> {
+    fn value(&mut self, value: &JsonValue) -> ArenaValue<'arena> {
+        match *value {
+            JsonValue::Null               => ArenaValue::Null,
+            JsonValue::Short(ref value)   => ArenaValue::String(self.string(value)),
+            JsonValue::String(ref value)  => ArenaValue::String(self.string(value)),
+            JsonValue::Number(value)      => ArenaValue::Number(value),
+            JsonValue::Boolean(value)     => ArenaValue::Boolean(value),
+            JsonValue::Binary(ref bytes)  => ArenaValue::String(self.string(&base64::encode(bytes))),
+            JsonValue::Array(ref vec)     => {
+                let members: Vec<_> = vec.iter().map(|member| self.value(member)).collect();
+                let shape = Shape::Array(members.iter().map(|&member| Member::new(member)).collect());
+
+                self.share(shape, |arena| ArenaValue::Array(arena.alloc_slice(&members)))
+            },
+            JsonValue::Object(ref object) => {
+                let entries: Vec<_> = object.iter().map(|(key, value)| (self.string(key), self.value(value))).collect();
+                let shape = Shape::Object(entries.iter().map(|&(key, value)| (key, Member::new(value))).collect());
+
+                self.share(shape, |arena| ArenaValue::Object(arena.alloc_slice(&entries)))
+            },
+        }
+    }
+
+    fn string(&mut self, value: &str) -> &'arena str {
+        if let Some(&shared) = self.strings.get(value) {
+            return shared;
+        }
+
+        let shared = self.arena.alloc_str(value);
+        self.strings.insert(shared);
+        shared
+    }
+
+    fn share<F>(&mut self, shape: Shape<'arena>, alloc: F) -> ArenaValue<'arena>
+    where
+        F: FnOnce(&'arena JsonArena) -> ArenaValue<'arena>,
+    {
+        let arena = self.arena;
+
+        *self.shared.entry(shape).or_insert_with(|| alloc(arena))
+    }
+}
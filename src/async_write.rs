@@ -0,0 +1,87 @@
+// Serialization to non-blocking writers implementing `AsyncWrite` of the
+// `futures-io` crate. No async runtime is depended upon, Tokio writers can be
+// adapted with the `compat` module of `tokio-util`.
+
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+
+use futures_io::AsyncWrite;
+
+use crate::JsonValue;
+use crate::codegen::{ DumpGenerator, Generator };
+
+// Buffered output is written out once it grows past this many bytes
+const CHUNK: usize = 8 * 1024;
+
+/// Writes `value` as compact JSON to `writer`, same as `JsonValue::write`
+/// but without blocking. Members of a top-level array or object are
+/// serialized one at a time and written out in chunks of a few kilobytes, so
+/// a large response doesn't have to be held in memory twice. Requires the
+/// `async` feature, and `json::AsyncWrite` is the trait of `futures-io`.
+///
+/// ```
+/// use json::{ AsyncWrite, JsonValue };
+///
+/// async fn respond<W: AsyncWrite + Unpin>(body: &JsonValue, socket: W) -> std::io::Result<()> {
+///     json::write_async(body, socket).await
+/// }
+/// ```
+///
+/// The writer is flushed once the whole value has been written.
+pub async fn write_async<W: AsyncWrite + Unpin>(value: &JsonValue, mut writer: W) -> io::Result<()> {
+    let mut gen = DumpGenerator::new();
+
+    match *value {
+        JsonValue::Array(ref members) => {
+            gen.write_char(b'[')?;
+            for (index, member) in members.iter().enumerate() {
+                if index > 0 {
+                    gen.write_char(b',')?;
+                }
+                gen.write_json(member)?;
+                drain(&mut gen, &mut writer, CHUNK).await?;
+            }
+            gen.write_char(b']')?;
+        },
+        JsonValue::Object(ref object) => {
+            gen.write_char(b'{')?;
+            for (index, (key, member)) in object.iter().enumerate() {
+                if index > 0 {
+                    gen.write_char(b',')?;
+                }
+                gen.write_key(key)?;
+                gen.write_key_separator()?;
+                gen.write_json(member)?;
+                drain(&mut gen, &mut writer, CHUNK).await?;
+            }
+            gen.write_char(b'}')?;
+        },
+        _ => gen.write_json(value)?,
+    }
+
+    drain(&mut gen, &mut writer, 0).await?;
+    poll_fn(|cx| Pin::new(&mut writer).poll_flush(cx)).await
+}
+
+// Writes out the buffered output once there is more than `above` bytes of it.
+async fn drain<W: AsyncWrite + Unpin>(gen: &mut DumpGenerator, writer: &mut W, above: usize) -> io::Result<()> {
+    let code = gen.get_writer();
+
+    if code.len() <= above {
+        return Ok(());
+    }
+
+    let mut written = 0;
+    while written < code.len() {
+        let count = poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, &code[written ..])).await?;
+
+        if count == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        written += count;
+    }
+
+    code.clear();
+    Ok(())
+}
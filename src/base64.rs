@@ -0,0 +1,76 @@
+// Base64 with the standard alphabet of RFC 4648, for binary data in JSON
+// strings.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Encode `bytes` with `=` padding.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let block = (chunk[0] as u32) << 16 |
+                    (*chunk.get(1).unwrap_or(&0) as u32) << 8 |
+                    *chunk.get(2).unwrap_or(&0) as u32;
+
+        for index in 0 .. 4 {
+            match index <= chunk.len() {
+                true  => result.push(ALPHABET[(block >> (18 - 6 * index)) as usize & 0x3F] as char),
+                false => result.push('='),
+            }
+        }
+    }
+
+    result
+}
+
+// Decode `source`, with or without padding. `None` if it has characters
+// outside of the alphabet, a length no encoding produces, or bits set past
+// the end of the data.
+#[cfg(feature = "base64")]
+pub fn decode(source: &str) -> Option<Vec<u8>> {
+    let source = source.as_bytes();
+    let source = match source.len() % 4 {
+        0 if source.ends_with(b"==") => &source[.. source.len() - 2],
+        0 if source.ends_with(b"=")  => &source[.. source.len() - 1],
+        1                            => return None,
+        _                            => source,
+    };
+
+    let mut result = Vec::with_capacity(source.len() * 3 / 4);
+
+    for chunk in source.chunks(4) {
+        let mut block = 0;
+
+        for &ch in chunk {
+            block = block << 6 | value(ch)?;
+        }
+
+        // Align a partial chunk as if it was padded
+        block <<= 6 * (4 - chunk.len() as u32);
+
+        let bytes = [(block >> 16) as u8, (block >> 8) as u8, block as u8];
+        let len = chunk.len() - 1;
+
+        if len == 0 || bytes[len ..].iter().any(|&byte| byte != 0) {
+            return None;
+        }
+
+        result.extend_from_slice(&bytes[.. len]);
+    }
+
+    Some(result)
+}
+
+#[cfg(feature = "base64")]
+fn value(ch: u8) -> Option<u32> {
+    let value = match ch {
+        b'A' ..= b'Z' => ch - b'A',
+        b'a' ..= b'z' => ch - b'a' + 26,
+        b'0' ..= b'9' => ch - b'0' + 52,
+        b'+'          => 62,
+        b'/'          => 63,
+        _             => return None,
+    };
+
+    Some(value as u32)
+}
@@ -0,0 +1,98 @@
+/// Naming conventions that object keys can be converted between.
+///
+/// Words are detected on `_`, `-` and space separators, as well as on
+/// changes of letter case, so `userId`, `user_id`, `user-id` and `UserID`
+/// are all made of the words `user` and `id`.
+///
+/// ```
+/// # use json::Case;
+/// assert_eq!(Case::SnakeCase.convert("HTTPServerName"), "http_server_name");
+/// assert_eq!(Case::CamelCase.convert("user_id"), "userId");
+/// assert_eq!(Case::KebabCase.convert("userId"), "user-id");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// `snake_case`
+    SnakeCase,
+
+    /// `camelCase`
+    CamelCase,
+
+    /// `kebab-case`
+    KebabCase,
+}
+
+impl Case {
+    /// Convert `key` to this case.
+    pub fn convert(&self, key: &str) -> String {
+        let mut result = String::with_capacity(key.len() + 4);
+
+        for (index, word) in words(key).into_iter().enumerate() {
+            match *self {
+                Case::SnakeCase | Case::KebabCase => {
+                    if index > 0 {
+                        result.push(if *self == Case::SnakeCase { '_' } else { '-' });
+                    }
+                    result.extend(word.chars().flat_map(char::to_lowercase));
+                },
+                Case::CamelCase => {
+                    let mut chars = word.chars();
+                    if let Some(first) = chars.next() {
+                        if index == 0 {
+                            result.extend(first.to_lowercase());
+                        } else {
+                            result.extend(first.to_uppercase());
+                        }
+                    }
+                    result.extend(chars.flat_map(char::to_lowercase));
+                },
+            }
+        }
+
+        result
+    }
+}
+
+fn is_separator(ch: char) -> bool {
+    ch == '_' || ch == '-' || ch == ' '
+}
+
+// Split a key into words, see the docs on `Case` for the rules.
+fn words(key: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = key.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (i, &(index, ch)) in chars.iter().enumerate() {
+        if is_separator(ch) {
+            if let Some(start) = start.take() {
+                words.push(&key[start .. index]);
+            }
+            continue;
+        }
+
+        if let Some(word_start) = start {
+            let prev = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|&(_, ch)| ch);
+
+            // `fooBar` -> `foo`, `Bar` and `HTTPServer` -> `HTTP`, `Server`
+            let boundary = ch.is_uppercase() && (
+                !prev.is_uppercase() ||
+                next.is_some_and(char::is_lowercase)
+            );
+
+            if boundary {
+                words.push(&key[word_start .. index]);
+                start = Some(index);
+            }
+        } else {
+            start = Some(index);
+        }
+    }
+
+    if let Some(start) = start {
+        words.push(&key[start ..]);
+    }
+
+    words
+}
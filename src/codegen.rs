@@ -1,23 +1,36 @@
+use std::str;
+#[cfg(not(feature = "forbid-unsafe"))]
 use std::ptr;
 use std::io::Write;
 use std::io;
 
-use crate::JsonValue;
+use crate::{ JsonValue, Case };
+use crate::base64;
 use crate::number::Number;
 use crate::object::Object;
-use crate::util::print_dec;
+use crate::util::{ pointer, print_dec };
 
+#[cfg(not(feature = "optimize-size"))]
 const QU: u8 = b'"';
+#[cfg(not(feature = "optimize-size"))]
 const BS: u8 = b'\\';
+#[cfg(not(feature = "optimize-size"))]
 const BB: u8 = b'b';
+#[cfg(not(feature = "optimize-size"))]
 const TT: u8 = b't';
+#[cfg(not(feature = "optimize-size"))]
 const NN: u8 = b'n';
+#[cfg(not(feature = "optimize-size"))]
 const FF: u8 = b'f';
+#[cfg(not(feature = "optimize-size"))]
 const RR: u8 = b'r';
+#[cfg(not(feature = "optimize-size"))]
 const UU: u8 = b'u';
+#[cfg(not(feature = "optimize-size"))]
 const __: u8 = 0;
 
 // Look up table for characters that need escaping in a product string
+#[cfg(not(feature = "optimize-size"))]
 static ESCAPED: [u8; 256] = [
 // 0   1   2   3   4   5   6   7   8   9   A   B   C   D   E   F
   UU, UU, UU, UU, UU, UU, UU, UU, BB, TT, NN, UU, FF, RR, UU, UU, // 0
@@ -38,12 +51,483 @@ static ESCAPED: [u8; 256] = [
   __, __, __, __, __, __, __, __, __, __, __, __, __, __, __, __, // F
 ];
 
+#[cfg(not(feature = "optimize-size"))]
+#[inline(always)]
+fn escaped(ch: u8) -> u8 {
+    ESCAPED[ch as usize]
+}
+
+// Same as the look up table, without the 256 bytes it takes.
+#[cfg(feature = "optimize-size")]
+#[inline(always)]
+fn escaped(ch: u8) -> u8 {
+    match ch {
+        0x08        => b'b',
+        b'\t'       => b't',
+        b'\n'       => b'n',
+        0x0C        => b'f',
+        b'\r'       => b'r',
+        0x00..=0x1F => b'u',
+        b'"'        => b'"',
+        b'\\'       => b'\\',
+        _           => 0,
+    }
+}
+
+/// Settings shared by all generators. Start from `GeneratorOptions::default()`
+/// and override the fields you need:
+///
+/// ```
+/// # use json::codegen::GeneratorOptions;
+/// let options = GeneratorOptions {
+///     escape_slashes: true,
+///     ..GeneratorOptions::default()
+/// };
+///
+/// let data = json::JsonValue::from("</script>");
+///
+/// assert_eq!(data.dump_with(&options), r#""<\/script>""#);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct GeneratorOptions {
+    /// Write forward slashes as `\/`. Some legacy parsers expect this when
+    /// JSON is embedded in HTML. Defaults to `false`.
+    pub escape_slashes: bool,
+
+    /// Write all non-ASCII characters as `\u` escapes, using surrogate pairs
+    /// outside of the Basic Multilingual Plane, so that the output is pure
+    /// ASCII. When `false` strings are written as raw UTF-8, escaping only
+    /// what RFC 8259 requires: `"`, `\` and control characters. Defaults to
+    /// `false`.
+    ///
+    /// ```
+    /// # use json::codegen::GeneratorOptions;
+    /// let options = GeneratorOptions {
+    ///     escape_unicode: true,
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = json::JsonValue::from("zażółć 😀");
+    ///
+    /// assert_eq!(data.dump(), r#""zażółć 😀""#);
+    /// assert_eq!(data.dump_with(&options), r#""za\u017c\u00f3\u0142\u0107 \ud83d\ude00""#);
+    /// ```
+    pub escape_unicode: bool,
+
+    /// Write integral numbers, such as a parsed `3.0` or `3e2`, as plain
+    /// integers (`3`, `300`), never using a fraction or the `e` notation.
+    /// Defaults to `false`.
+    pub integral_as_integer: bool,
+
+    /// Magnitudes at which numbers switch to the scientific notation. When
+    /// `None` the notation is picked based on the number of digits needed to
+    /// print the number in full. Digits are never rounded off, so integers
+    /// too wide for a double keep all of their digits. Defaults to `None`.
+    pub exponent_thresholds: Option<ExponentThresholds>,
+
+    /// What to do with numbers that can't be represented in JSON.
+    /// Defaults to `NonFinite::Null`.
+    pub non_finite: NonFinite,
+
+    /// When pretty printing, write arrays containing only numbers in rows of
+    /// up to this many members, instead of one member per line. Defaults to
+    /// `None`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::codegen::GeneratorOptions;
+    /// # fn main() {
+    /// let options = GeneratorOptions {
+    ///     numbers_per_line: Some(3),
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = array![1, 2, 3, 4, 5];
+    ///
+    /// assert_eq!(data.pretty_with(2, &options), "[\n  1, 2, 3,\n  4, 5\n]");
+    /// # }
+    /// ```
+    pub numbers_per_line: Option<usize>,
+
+    /// Convert object keys to the given case on output, leaving the values
+    /// themselves untouched. Defaults to `None`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::Case;
+    /// # use json::codegen::GeneratorOptions;
+    /// # fn main() {
+    /// let options = GeneratorOptions {
+    ///     key_case: Some(Case::CamelCase),
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = object!{ "user_id" => 1 };
+    ///
+    /// assert_eq!(data.dump_with(&options), r#"{"userId":1}"#);
+    /// # }
+    /// ```
+    pub key_case: Option<Case>,
+
+    /// Callback deciding what to do with each object member, given the JSON
+    /// Pointer of the object (empty string for the root) and the key of the
+    /// member. Lets secrets be kept out of the output without modifying the
+    /// value. Defaults to `None`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::codegen::{ GeneratorOptions, Redact };
+    /// # fn main() {
+    /// fn redact(_path: &str, key: &str) -> Redact {
+    ///     match key {
+    ///         "password" => Redact::Mask,
+    ///         "token"    => Redact::Drop,
+    ///         _          => Redact::Keep,
+    ///     }
+    /// }
+    ///
+    /// let options = GeneratorOptions {
+    ///     redact: Some(redact),
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = object!{
+    ///     "user" => object!{ "name" => "Jon", "password" => "hunter2" },
+    ///     "token" => "abc"
+    /// };
+    ///
+    /// assert_eq!(data.dump_with(&options), r#"{"user":{"name":"Jon","password":"***"}}"#);
+    /// # }
+    /// ```
+    pub redact: Option<fn(&str, &str) -> Redact>,
+
+    /// End the document with a `\n`, as expected of text files by POSIX
+    /// tools. Defaults to `false`.
+    pub trailing_newline: bool,
+
+    /// Unit of indentation used when pretty printing, such as `"\t"`. When
+    /// set, it replaces the number of spaces passed to the pretty generator.
+    /// Defaults to `None`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::codegen::GeneratorOptions;
+    /// # fn main() {
+    /// let options = GeneratorOptions {
+    ///     indent: Some("\t"),
+    ///     key_separator: ":",
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = object!{ "a" => array![1] };
+    ///
+    /// assert_eq!(data.pretty_with(0, &options), "{\n\t\"a\":[\n\t\t1\n\t]\n}");
+    /// # }
+    /// ```
+    pub indent: Option<&'static str>,
+
+    /// Separator between object keys and values when pretty printing.
+    /// Compact output always uses `:`. Defaults to `": "`.
+    pub key_separator: &'static str,
+
+    /// Write object keys that are valid identifiers, such as `name` or
+    /// `_id`, without quotes. The output is JSON5 rather than JSON. Defaults
+    /// to `false`.
+    pub unquoted_keys: bool,
+
+    /// Write a comma after the last member of every non-empty array and
+    /// object. Intended for pretty printed JSON5, where it keeps diffs of
+    /// hand edited files small. Defaults to `false`.
+    pub trailing_commas: bool,
+
+    /// How to write `JsonValue::Binary`, which has no JSON type of its own.
+    /// Defaults to `BinaryFormat::Base64`.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// # use json::codegen::{ GeneratorOptions, BinaryFormat };
+    /// let data = JsonValue::Binary(vec![0xCA, 0xFE]);
+    ///
+    /// let options = GeneratorOptions {
+    ///     binary: BinaryFormat::Hex,
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// assert_eq!(data.dump(), r#""yv4=""#);
+    /// assert_eq!(data.dump_with(&options), r#""cafe""#);
+    /// ```
+    pub binary: BinaryFormat,
+
+    /// Which numbers to write as quoted strings, to keep consumers that
+    /// parse all numbers as doubles, such as JavaScript, from silently
+    /// rounding them. Defaults to `IntegerStrings::Never`.
+    ///
+    /// ```
+    /// # use json::codegen::{ GeneratorOptions, IntegerStrings };
+    /// let options = GeneratorOptions {
+    ///     integer_strings: IntegerStrings::Unsafe,
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = json::parse("[9007199254740992, 9007199254740993, -1e20, 1.5]").unwrap();
+    ///
+    /// assert_eq!(data.dump_with(&options), r#"[9007199254740992,"9007199254740993","-1e20",1.5]"#);
+    /// ```
+    pub integer_strings: IntegerStrings,
+
+    /// Callback given the JSON Pointer and the value of everything being
+    /// written, root included, that can replace how it is written. Lets the
+    /// formatting of specific paths or types be changed without modifying
+    /// the value or post-processing the output. Defaults to `None`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::JsonValue;
+    /// # use json::codegen::{ GeneratorOptions, Emit };
+    /// # fn main() {
+    /// fn prices(path: &str, value: &JsonValue) -> Emit {
+    ///     match value.as_f64() {
+    ///         Some(price) if path.starts_with("/totals/") => Emit::Raw(format!("{:.2}", price)),
+    ///         _                                           => Emit::Default,
+    ///     }
+    /// }
+    ///
+    /// let options = GeneratorOptions {
+    ///     on_value: Some(prices),
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = object!{ "count" => 2, "totals" => object!{ "net" => 12.5, "tax" => 3 } };
+    ///
+    /// assert_eq!(data.dump_with(&options), r#"{"count":2,"totals":{"net":12.50,"tax":3.00}}"#);
+    /// # }
+    /// ```
+    pub on_value: Option<fn(&str, &JsonValue) -> Emit>,
+
+    /// When pretty printing, write arrays and objects more than this many
+    /// levels deep compactly on a single line, counting the root as the
+    /// first level. Keeps huge nested documents skimmable. Defaults to
+    /// `None`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::codegen::GeneratorOptions;
+    /// # fn main() {
+    /// let options = GeneratorOptions {
+    ///     max_pretty_depth: Some(1),
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = object!{ "a" => object!{ "b" => array![1, 2] }, "c" => 3 };
+    ///
+    /// assert_eq!(data.pretty_with(2, &options), "{\n  \"a\": {\"b\":[1,2]},\n  \"c\": 3\n}");
+    /// # }
+    /// ```
+    pub max_pretty_depth: Option<u16>,
+
+    /// Predicate picking characters to write as `\u` escapes, in addition to
+    /// those covered by `escape_unicode`, for targets with unusual
+    /// requirements. Characters with a short escape, such as `\n`, keep it.
+    /// Defaults to `None`.
+    ///
+    /// ```
+    /// # use json::codegen::GeneratorOptions;
+    /// fn c1_and_emoji(ch: char) -> bool {
+    ///     matches!(ch, '\u{80}' ..= '\u{9f}' | '\u{1f300}' ..= '\u{1faff}')
+    /// }
+    ///
+    /// let options = GeneratorOptions {
+    ///     escape: Some(c1_and_emoji),
+    ///     ..GeneratorOptions::default()
+    /// };
+    ///
+    /// let data = json::JsonValue::from("ok \u{85}👍 é");
+    ///
+    /// assert_eq!(data.dump_with(&options), r#""ok \u0085\ud83d\udc4d é""#);
+    /// ```
+    pub escape: Option<fn(char) -> bool>,
+}
+
+impl GeneratorOptions {
+    /// Options for writing human editable JSON5 config files, with unquoted
+    /// keys and trailing commas. Best combined with pretty printing.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::codegen::GeneratorOptions;
+    /// # fn main() {
+    /// let data = object!{ "name" => "app", "content-type" => array!["json"] };
+    ///
+    /// assert_eq!(
+    ///     data.pretty_with(2, &GeneratorOptions::JSON5),
+    ///     "{\n  name: \"app\",\n  \"content-type\": [\n    \"json\",\n  ],\n}"
+    /// );
+    /// # }
+    /// ```
+    pub const JSON5: GeneratorOptions = GeneratorOptions {
+        unquoted_keys: true,
+        trailing_commas: true,
+        ..DEFAULT_OPTIONS
+    };
+}
+
+const DEFAULT_OPTIONS: GeneratorOptions = GeneratorOptions {
+    escape_slashes: false,
+    escape_unicode: false,
+    integral_as_integer: false,
+    exponent_thresholds: None,
+    non_finite: NonFinite::Null,
+    numbers_per_line: None,
+    key_case: None,
+    redact: None,
+    trailing_newline: false,
+    indent: None,
+    key_separator: ": ",
+    unquoted_keys: false,
+    trailing_commas: false,
+    binary: BinaryFormat::Base64,
+    integer_strings: IntegerStrings::Never,
+    on_value: None,
+    max_pretty_depth: None,
+    escape: None,
+};
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        DEFAULT_OPTIONS
+    }
+}
+
+/// Policy for serializing numbers that have no JSON representation. Note that
+/// `Number` stores infinite floats as NaN, so this covers both cases.
+///
+/// ```
+/// # use json::codegen::{ GeneratorOptions, NonFinite };
+/// let data = json::JsonValue::from(std::f64::NAN);
+///
+/// let options = GeneratorOptions {
+///     non_finite: NonFinite::String,
+///     ..GeneratorOptions::default()
+/// };
+/// assert_eq!(data.dump_with(&options), r#""NaN""#);
+///
+/// let options = GeneratorOptions {
+///     non_finite: NonFinite::Error,
+///     ..GeneratorOptions::default()
+/// };
+/// assert!(data.write_with(&mut Vec::new(), &options).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonFinite {
+    /// Write `null`.
+    Null,
+
+    /// Write a `"NaN"` string.
+    String,
+
+    /// Fail with an `io::Error` of `InvalidData` kind.
+    Error,
+}
+
+/// Ways of writing bytes, see `GeneratorOptions::binary`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryFormat {
+    /// A string of base64 in the standard alphabet, with padding.
+    Base64,
+
+    /// A string of lowercase hexadecimal digits.
+    Hex,
+
+    /// An array of numbers, one for each byte.
+    Array,
+}
+
+/// Numbers written as strings, see `GeneratorOptions::integer_strings`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerStrings {
+    /// Write all numbers as numbers.
+    Never,
+
+    /// Write numbers with a magnitude greater than 2^53 as strings. These
+    /// can't all be represented exactly by a double.
+    Unsafe,
+
+    /// Write all integers as strings, along with the numbers covered by
+    /// `IntegerStrings::Unsafe`, so that consumers can treat every `u64` and
+    /// `i64` field the same way regardless of its value.
+    Always,
+}
+
+/// Result of the `GeneratorOptions::on_value` callback.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Emit {
+    /// Write the value as usual.
+    Default,
+
+    /// Write the given text in place of the value. It is written as is, so
+    /// it has to be valid JSON for the output to be.
+    Raw(String),
+}
+
+/// Result of the `GeneratorOptions::redact` callback.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Redact {
+    /// Write the member as is.
+    Keep,
+
+    /// Write the key, but replace the value with a `"***"` string.
+    Mask,
+
+    /// Leave the member out of the output.
+    Drop,
+}
+
+/// Range of magnitudes within which numbers are written in plain decimal
+/// notation. Numbers outside of it are written in scientific notation, with
+/// an explicit sign on the exponent (`1e+21`, `1.5e-7`).
+///
+/// ```
+/// # use json::codegen::{ GeneratorOptions, ExponentThresholds };
+/// let options = GeneratorOptions {
+///     exponent_thresholds: Some(ExponentThresholds::JAVASCRIPT),
+///     ..GeneratorOptions::default()
+/// };
+///
+/// let data = json::parse("[1e20, 1e21, 0.000001, 1.5e-7]").unwrap();
+///
+/// assert_eq!(data.dump_with(&options), "[100000000000000000000,1e+21,0.000001,1.5e-7]");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExponentThresholds {
+    /// Numbers smaller than `10^min` in magnitude use scientific notation.
+    pub min: i16,
+
+    /// Numbers greater than or equal to `10^max` in magnitude use
+    /// scientific notation.
+    pub max: i16,
+}
+
+impl ExponentThresholds {
+    /// Thresholds used by JavaScript's `Number.prototype.toString`: numbers
+    /// are written in full when `1e-6 <= |x| < 1e21`.
+    pub const JAVASCRIPT: ExponentThresholds = ExponentThresholds {
+        min: -6,
+        max: 21,
+    };
+}
+
 /// Default trait for serializing JSONValue into string.
 pub trait Generator {
     type T: Write;
 
     fn get_writer(&mut self) -> &mut Self::T;
 
+    /// Options used by this generator, defaults to `GeneratorOptions::default()`.
+    #[inline(always)]
+    fn options(&self) -> &GeneratorOptions {
+        &DEFAULT_OPTIONS
+    }
+
     #[inline(always)]
     fn write(&mut self, slice: &[u8]) -> io::Result<()> {
         self.get_writer().write_all(slice)
@@ -56,6 +540,13 @@ pub trait Generator {
 
     fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()>;
 
+    /// Write the separator between an object key and its value.
+    #[inline(always)]
+    fn write_key_separator(&mut self) -> io::Result<()> {
+        let separator = self.options().key_separator;
+        self.write_min(separator.as_bytes(), b':')
+    }
+
     #[inline(always)]
     fn new_line(&mut self) -> io::Result<()> { Ok(()) }
 
@@ -69,8 +560,34 @@ pub trait Generator {
     fn write_string_complex(&mut self, string: &str, mut start: usize) -> io::Result<()> {
         self.write(&string.as_bytes()[ .. start])?;
 
+        let escape_slashes = self.options().escape_slashes;
+        let escape_unicode = self.options().escape_unicode;
+        let escape = self.options().escape;
+
         for (index, ch) in string.bytes().enumerate().skip(start) {
-            let escape = ESCAPED[ch as usize];
+            if index < start {
+                // Rest of an already escaped multi-byte character
+                continue;
+            }
+
+            let unicode = escape_unicode && ch >= 0x80;
+            // Custom escapes are checked at the first byte of each character
+            if unicode || (escape.is_some() && escaped(ch) == 0 && ch & 0xC0 != 0x80) {
+                let character = string[index ..].chars().next().unwrap();
+
+                if unicode || escape.is_some_and(|escape| escape(character)) {
+                    self.write(&string.as_bytes()[start .. index])?;
+                    for unit in character.encode_utf16(&mut [0; 2]) {
+                        write!(self.get_writer(), "\\u{:04x}", unit)?;
+                    }
+                    start = index + character.len_utf8();
+                    continue;
+                }
+            }
+            let escape = match ch {
+                b'/' if escape_slashes => b'/',
+                _                      => escaped(ch),
+            };
             if escape > 0 {
                 self.write(&string.as_bytes()[start .. index])?;
                 self.write(&[b'\\', escape])?;
@@ -85,12 +602,21 @@ pub trait Generator {
         self.write_char(b'"')
     }
 
-    #[inline(always)]
+    #[cfg_attr(not(feature = "optimize-size"), inline(always))]
     fn write_string(&mut self, string: &str) -> io::Result<()> {
         self.write_char(b'"')?;
 
+        let escape_slashes = self.options().escape_slashes;
+        let escape_unicode = self.options().escape_unicode;
+
+        if self.options().escape.is_some() {
+            return self.write_string_complex(string, 0);
+        }
+
         for (index, ch) in string.bytes().enumerate() {
-            if ESCAPED[ch as usize] > 0 {
+            if escaped(ch) > 0 ||
+               (escape_slashes && ch == b'/') ||
+               (escape_unicode && ch >= 0x80) {
                 return self.write_string_complex(string, index)
             }
         }
@@ -99,23 +625,122 @@ pub trait Generator {
         self.write_char(b'"')
     }
 
-    #[inline(always)]
+    /// Write an object key, applying `GeneratorOptions::key_case` and
+    /// `GeneratorOptions::unquoted_keys`.
+    #[cfg_attr(not(feature = "optimize-size"), inline(always))]
+    fn write_key(&mut self, key: &str) -> io::Result<()> {
+        let converted;
+        let key = match self.options().key_case {
+            Some(case) => {
+                converted = case.convert(key);
+                &converted
+            },
+            None => key,
+        };
+
+        if self.options().unquoted_keys && is_identifier(key) {
+            self.write(key.as_bytes())
+        } else {
+            self.write_string(key)
+        }
+    }
+
+    #[cfg_attr(not(feature = "optimize-size"), inline(always))]
+    fn write_binary(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self.options().binary {
+            BinaryFormat::Base64 => self.write_string(&base64::encode(bytes)),
+            BinaryFormat::Hex    => {
+                let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+                self.write_string(&hex)
+            },
+            BinaryFormat::Array  => {
+                let array = bytes.iter().map(|&byte| byte.into()).collect();
+
+                self.write_json(&JsonValue::Array(array))
+            },
+        }
+    }
+
     fn write_number(&mut self, num: &Number) -> io::Result<()> {
         if num.is_nan() {
-            return self.write(b"null");
+            return match self.options().non_finite {
+                NonFinite::Null   => self.write(b"null"),
+                NonFinite::String => self.write(b"\"NaN\""),
+                NonFinite::Error  => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "NaN can't be represented in JSON"
+                )),
+            };
         }
+
+        let quote = match self.options().integer_strings {
+            IntegerStrings::Never  => false,
+            IntegerStrings::Unsafe => is_unsafe(num),
+            IntegerStrings::Always => num.as_integer().is_some() || is_unsafe(num),
+        };
+        if quote {
+            self.write_char(b'"')?;
+            self.write_digits(num)?;
+            return self.write_char(b'"');
+        }
+
+        self.write_digits(num)
+    }
+
+    // Writes a number that isn't NaN.
+    fn write_digits(&mut self, num: &Number) -> io::Result<()> {
+        if let Some(wide) = num.wide() {
+            // Wide numbers are always integers, so they are written in full
+            // unless the thresholds say otherwise
+            if let (false, Some(thresholds)) = (self.options().integral_as_integer, self.options().exponent_thresholds) {
+                return print_dec::write_wide_with_thresholds(
+                    self.get_writer(),
+                    num.is_sign_positive(),
+                    wide,
+                    thresholds.min,
+                    thresholds.max
+                );
+            }
+            if !num.is_sign_positive() {
+                self.write_char(b'-')?;
+            }
+            return self.write(wide.to_string().as_bytes());
+        }
+
         let (positive, mantissa, exponent) = num.as_parts();
-        unsafe {
-            print_dec::write(
+
+        if self.options().integral_as_integer {
+            let (mut int, mut e) = (mantissa, exponent);
+            while e < 0 && int % 10 == 0 {
+                int /= 10;
+                e += 1;
+            }
+            if e >= 0 {
+                return print_dec::write_integral(self.get_writer(), positive, int, e as u16);
+            }
+        }
+
+        if let Some(thresholds) = self.options().exponent_thresholds {
+            return print_dec::write_with_thresholds(
                 self.get_writer(),
                 positive,
                 mantissa,
-                exponent
-            )
+                exponent,
+                thresholds.min,
+                thresholds.max
+            );
         }
+
+        print_dec::write(
+            self.get_writer(),
+            positive,
+            mantissa,
+            exponent
+        )
     }
 
-    #[inline(always)]
+    #[cfg_attr(not(feature = "optimize-size"), inline(always))]
     fn write_object(&mut self, object: &Object) -> io::Result<()> {
         self.write_char(b'{')?;
         let mut iter = object.iter();
@@ -123,8 +748,8 @@ pub trait Generator {
         if let Some((key, value)) = iter.next() {
             self.indent();
             self.new_line()?;
-            self.write_string(key)?;
-            self.write_min(b": ", b':')?;
+            self.write_key(key)?;
+            self.write_key_separator()?;
             self.write_json(value)?;
         } else {
             self.write_char(b'}')?;
@@ -134,17 +759,164 @@ pub trait Generator {
         for (key, value) in iter {
             self.write_char(b',')?;
             self.new_line()?;
-            self.write_string(key)?;
-            self.write_min(b": ", b':')?;
+            self.write_key(key)?;
+            self.write_key_separator()?;
             self.write_json(value)?;
         }
 
+        if self.options().trailing_commas {
+            self.write_char(b',')?;
+        }
         self.dedent();
         self.new_line()?;
         self.write_char(b'}')
     }
 
+    // Writes an array of numbers in rows, see `GeneratorOptions::numbers_per_line`.
+    fn write_number_rows(&mut self, array: &[JsonValue], per_line: usize, path: &mut String) -> io::Result<()> {
+        self.write_char(b'[')?;
+        self.indent();
+
+        for (index, item) in array.iter().enumerate() {
+            if index == 0 {
+                self.new_line()?;
+            } else if index % per_line == 0 {
+                self.write_char(b',')?;
+                self.new_line()?;
+            } else {
+                self.write_min(b", ", b',')?;
+            }
+
+            let len = path.len();
+            pointer::push_index(path, index);
+            self.write_json_at(item, path)?;
+            path.truncate(len);
+        }
+
+        if self.options().trailing_commas {
+            self.write_char(b',')?;
+        }
+        self.dedent();
+        self.new_line()?;
+        self.write_char(b']')
+    }
+
+    // Slower variant of `write_json` that keeps track of the JSON Pointer to
+    // the value being written, used when options depend on the path.
+    fn write_json_at(&mut self, json: &JsonValue, path: &mut String) -> io::Result<()> {
+        if let Some(on_value) = self.options().on_value {
+            if let Emit::Raw(code) = on_value(path, json) {
+                return self.write(code.as_bytes());
+            }
+        }
+
+        match *json {
+            JsonValue::Array(ref array) if array.is_empty() => self.write(b"[]"),
+            JsonValue::Object(ref object) if object.is_empty() => self.write(b"{}"),
+            JsonValue::Array(ref array) => {
+                if let Some(per_line) = self.options().numbers_per_line {
+                    if per_line > 0 && array.iter().all(JsonValue::is_number) {
+                        return self.write_number_rows(array, per_line, path);
+                    }
+                }
+
+                self.write_char(b'[')?;
+                self.indent();
+
+                for (index, item) in array.iter().enumerate() {
+                    if index > 0 {
+                        self.write_char(b',')?;
+                    }
+                    self.new_line()?;
+
+                    let len = path.len();
+                    pointer::push_index(path, index);
+                    self.write_json_at(item, path)?;
+                    path.truncate(len);
+                }
+
+                if self.options().trailing_commas {
+                    self.write_char(b',')?;
+                }
+                self.dedent();
+                self.new_line()?;
+                self.write_char(b']')
+            },
+            JsonValue::Object(ref object) => {
+                let redact = self.options().redact;
+                let mut first = true;
+
+                self.write_char(b'{')?;
+                self.indent();
+
+                for (key, value) in object.iter() {
+                    let action = match redact {
+                        Some(redact) => redact(path, key),
+                        None         => Redact::Keep,
+                    };
+
+                    if action == Redact::Drop {
+                        continue;
+                    }
+                    if !first {
+                        self.write_char(b',')?;
+                    }
+                    first = false;
+
+                    self.new_line()?;
+                    self.write_key(key)?;
+                    self.write_key_separator()?;
+
+                    if action == Redact::Mask {
+                        self.write(b"\"***\"")?;
+                        continue;
+                    }
+
+                    let len = path.len();
+                    pointer::push_key(path, key);
+                    self.write_json_at(value, path)?;
+                    path.truncate(len);
+                }
+
+                if !first && self.options().trailing_commas {
+                    self.write_char(b',')?;
+                }
+                self.dedent();
+                if !first {
+                    self.new_line()?;
+                }
+                self.write_char(b'}')
+            },
+            JsonValue::Null               => self.write(b"null"),
+            JsonValue::Short(ref short)   => self.write_string(short.as_str()),
+            JsonValue::String(ref string) => self.write_string(string),
+            JsonValue::Number(ref number) => self.write_number(number),
+            JsonValue::Boolean(true)      => self.write(b"true"),
+            JsonValue::Boolean(false)     => self.write(b"false"),
+            JsonValue::Binary(ref bytes)  => self.write_binary(bytes),
+        }
+    }
+
+    /// Write `json` as a complete document. Unlike `write_json`, this also
+    /// writes the trailing newline if `GeneratorOptions::trailing_newline`
+    /// is set.
+    fn write_document(&mut self, json: &JsonValue) -> io::Result<()> {
+        self.write_json(json)?;
+
+        if self.options().trailing_newline {
+            self.write_char(b'\n')?;
+        }
+
+        Ok(())
+    }
+
     fn write_json(&mut self, json: &JsonValue) -> io::Result<()> {
+        let redact = self.options().redact.is_some() && (json.is_object() || json.is_array());
+
+        if redact || self.options().on_value.is_some() {
+            return self.write_json_at(json, &mut String::new());
+        }
+
         match *json {
             JsonValue::Null               => self.write(b"null"),
             JsonValue::Short(ref short)   => self.write_string(short.as_str()),
@@ -152,7 +924,14 @@ pub trait Generator {
             JsonValue::Number(ref number) => self.write_number(number),
             JsonValue::Boolean(true)      => self.write(b"true"),
             JsonValue::Boolean(false)     => self.write(b"false"),
+            JsonValue::Binary(ref bytes)  => self.write_binary(bytes),
             JsonValue::Array(ref array)   => {
+                if let Some(per_line) = self.options().numbers_per_line {
+                    if per_line > 0 && !array.is_empty() && array.iter().all(JsonValue::is_number) {
+                        return self.write_number_rows(array, per_line, &mut String::new());
+                    }
+                }
+
                 self.write_char(b'[')?;
                 let mut iter = array.iter();
 
@@ -171,6 +950,9 @@ pub trait Generator {
                     self.write_json(item)?;
                 }
 
+                if self.options().trailing_commas {
+                    self.write_char(b',')?;
+                }
                 self.dedent();
                 self.new_line()?;
                 self.write_char(b']')
@@ -182,28 +964,52 @@ pub trait Generator {
     }
 }
 
+// Checks if `key` can be written unquoted as a JSON5 identifier. Only ASCII
+// identifiers are considered, everything else gets quoted.
+fn is_identifier(key: &str) -> bool {
+    let mut bytes = key.bytes();
+
+    match bytes.next() {
+        Some(ch) if ch.is_ascii_alphabetic() || ch == b'_' || ch == b'$' => {},
+        _ => return false,
+    }
+
+    bytes.all(|ch| ch.is_ascii_alphanumeric() || ch == b'_' || ch == b'$')
+}
+
 /// In-Memory Generator, this uses a Vec to store the JSON result.
 pub struct DumpGenerator {
     code: Vec<u8>,
+    options: GeneratorOptions,
 }
 
 impl DumpGenerator {
     pub fn new() -> Self {
+        DumpGenerator::with_options(GeneratorOptions::default())
+    }
+
+    pub fn with_options(options: GeneratorOptions) -> Self {
         DumpGenerator {
             code: Vec::with_capacity(1024),
+            options,
         }
     }
 
     pub fn consume(self) -> String {
         // Original strings were unicode, numbers are all ASCII,
         // therefore this is safe.
-        unsafe { String::from_utf8_unchecked(self.code) }
+        from_utf8(self.code)
     }
 }
 
 impl Generator for DumpGenerator {
     type T = Vec<u8>;
 
+    #[inline(always)]
+    fn options(&self) -> &GeneratorOptions {
+        &self.options
+    }
+
     fn write(&mut self, slice: &[u8]) -> io::Result<()> {
         extend_from_slice(&mut self.code, slice);
         Ok(())
@@ -227,30 +1033,126 @@ impl Generator for DumpGenerator {
     }
 }
 
+/// Reusable in-memory serializer. The output buffer is kept between calls to
+/// `clear`, so serializing many values in a loop doesn't allocate once the
+/// buffer has grown to fit the largest of them.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # use json::codegen::Serializer;
+/// # fn main() {
+/// let mut ser = Serializer::new();
+///
+/// for id in 0..3 {
+///     ser.clear();
+///     ser.write(&object!{ "id" => id }).unwrap();
+///
+///     assert_eq!(ser.as_str(), format!(r#"{{"id":{}}}"#, id));
+/// }
+/// # }
+/// ```
+pub struct Serializer {
+    gen: DumpGenerator,
+}
+
+impl Serializer {
+    pub fn new() -> Self {
+        Serializer::with_options(GeneratorOptions::default())
+    }
+
+    pub fn with_options(options: GeneratorOptions) -> Self {
+        Serializer {
+            gen: DumpGenerator::with_options(options),
+        }
+    }
+
+    /// Append the serialized `value` to the buffer. On failure the buffer is
+    /// left as it was before the call.
+    pub fn write(&mut self, value: &JsonValue) -> io::Result<()> {
+        let len = self.gen.code.len();
+
+        let result = self.gen.write_document(value);
+        if result.is_err() {
+            self.gen.code.truncate(len);
+        }
+        result
+    }
+
+    /// Empty the buffer, keeping its capacity.
+    pub fn clear(&mut self) {
+        self.gen.code.clear();
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.gen.code
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only valid UTF-8 is ever written to the buffer.
+        #[cfg(not(feature = "forbid-unsafe"))]
+        let code = unsafe { str::from_utf8_unchecked(&self.gen.code) };
+        #[cfg(feature = "forbid-unsafe")]
+        let code = str::from_utf8(&self.gen.code).expect("Can't fail");
+
+        code
+    }
+
+    pub fn len(&self) -> usize {
+        self.gen.code.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gen.code.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.gen.code.capacity()
+    }
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Serializer::new()
+    }
+}
+
 /// Pretty In-Memory Generator, this uses a Vec to store the JSON result and add indent.
 pub struct PrettyGenerator {
     code: Vec<u8>,
     dent: u16,
+    closing: bool,
     spaces_per_indent: u16,
+    options: GeneratorOptions,
 }
 
 impl PrettyGenerator {
     pub fn new(spaces: u16) -> Self {
+        PrettyGenerator::with_options(spaces, GeneratorOptions::default())
+    }
+
+    pub fn with_options(spaces: u16, options: GeneratorOptions) -> Self {
         PrettyGenerator {
             code: Vec::with_capacity(1024),
             dent: 0,
-            spaces_per_indent: spaces
+            closing: false,
+            spaces_per_indent: spaces,
+            options,
         }
     }
 
     pub fn consume(self) -> String {
-        unsafe { String::from_utf8_unchecked(self.code) }
+        from_utf8(self.code)
     }
 }
 
 impl Generator for PrettyGenerator {
     type T = Vec<u8>;
 
+    #[inline(always)]
+    fn options(&self) -> &GeneratorOptions {
+        &self.options
+    }
+
     #[inline(always)]
     fn write(&mut self, slice: &[u8]) -> io::Result<()> {
         extend_from_slice(&mut self.code, slice);
@@ -259,6 +1161,7 @@ impl Generator for PrettyGenerator {
 
     #[inline(always)]
     fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.closing = false;
         self.code.push(ch);
         Ok(())
     }
@@ -269,15 +1172,27 @@ impl Generator for PrettyGenerator {
     }
 
     #[inline(always)]
-    fn write_min(&mut self, slice: &[u8], _: u8) -> io::Result<()> {
-        extend_from_slice(&mut self.code, slice);
+    fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()> {
+        if collapsed(&self.options, self.dent, false) {
+            self.code.push(min);
+        } else {
+            extend_from_slice(&mut self.code, slice);
+        }
         Ok(())
     }
 
     fn new_line(&mut self) -> io::Result<()> {
+        if collapsed(&self.options, self.dent, self.closing) {
+            return Ok(());
+        }
         self.code.push(b'\n');
-        for _ in 0..(self.dent * self.spaces_per_indent) {
-            self.code.push(b' ');
+        match self.options.indent {
+            Some(indent) => for _ in 0..self.dent {
+                extend_from_slice(&mut self.code, indent.as_bytes());
+            },
+            None => for _ in 0..(self.dent * self.spaces_per_indent) {
+                self.code.push(b' ');
+            },
         }
         Ok(())
     }
@@ -288,18 +1203,160 @@ impl Generator for PrettyGenerator {
 
     fn dedent(&mut self) {
         self.dent -= 1;
+        self.closing = true;
+    }
+}
+
+/// Pretty In-Memory Generator highlighting the syntax with ANSI escape codes,
+/// for printing JSON to terminals.
+#[cfg(feature = "color")]
+pub struct ColoredGenerator {
+    inner: PrettyGenerator,
+}
+
+#[cfg(feature = "color")]
+const KEY: &[u8] = b"\x1b[1;34m";
+#[cfg(feature = "color")]
+const STRING: &[u8] = b"\x1b[32m";
+#[cfg(feature = "color")]
+const NUMBER: &[u8] = b"\x1b[36m";
+#[cfg(feature = "color")]
+const LITERAL: &[u8] = b"\x1b[35m";
+#[cfg(feature = "color")]
+const PUNCTUATION: &[u8] = b"\x1b[37m";
+#[cfg(feature = "color")]
+const RESET: &[u8] = b"\x1b[0m";
+
+#[cfg(feature = "color")]
+impl ColoredGenerator {
+    pub fn new(spaces: u16) -> Self {
+        ColoredGenerator {
+            inner: PrettyGenerator::new(spaces),
+        }
+    }
+
+    pub fn consume(self) -> String {
+        self.inner.consume()
+    }
+
+    fn paint<F>(&mut self, color: &[u8], write: F) -> io::Result<()>
+        where F: FnOnce(&mut Self) -> io::Result<()>
+    {
+        self.write(color)?;
+        write(self)?;
+        self.write(RESET)
+    }
+
+    fn punctuation(&mut self, ch: u8) -> io::Result<()> {
+        self.paint(PUNCTUATION, |gen| gen.write_char(ch))
+    }
+}
+
+#[cfg(feature = "color")]
+impl Generator for ColoredGenerator {
+    type T = Vec<u8>;
+
+    #[inline(always)]
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        self.inner.write(slice)
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.inner.write_char(ch)
+    }
+
+    #[inline(always)]
+    fn get_writer(&mut self) -> &mut Vec<u8> {
+        self.inner.get_writer()
+    }
+
+    #[inline(always)]
+    fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()> {
+        self.inner.write_min(slice, min)
+    }
+
+    fn new_line(&mut self) -> io::Result<()> {
+        self.inner.new_line()
+    }
+
+    fn indent(&mut self) {
+        self.inner.indent()
+    }
+
+    fn dedent(&mut self) {
+        self.inner.dedent()
+    }
+
+    fn write_json(&mut self, json: &JsonValue) -> io::Result<()> {
+        match *json {
+            JsonValue::Null               => self.paint(LITERAL, |gen| gen.write(b"null")),
+            JsonValue::Short(ref short)   => self.paint(STRING, |gen| gen.write_string(short)),
+            JsonValue::String(ref string) => self.paint(STRING, |gen| gen.write_string(string)),
+            JsonValue::Number(ref number) => self.paint(NUMBER, |gen| gen.write_number(number)),
+            JsonValue::Boolean(true)      => self.paint(LITERAL, |gen| gen.write(b"true")),
+            JsonValue::Boolean(false)     => self.paint(LITERAL, |gen| gen.write(b"false")),
+            JsonValue::Binary(ref bytes)  => self.paint(STRING, |gen| gen.write_binary(bytes)),
+            JsonValue::Array(ref array)   => {
+                self.punctuation(b'[')?;
+
+                if array.is_empty() {
+                    return self.punctuation(b']');
+                }
+
+                self.indent();
+                for (index, item) in array.iter().enumerate() {
+                    if index > 0 {
+                        self.punctuation(b',')?;
+                    }
+                    self.new_line()?;
+                    self.write_json(item)?;
+                }
+                self.dedent();
+                self.new_line()?;
+                self.punctuation(b']')
+            },
+            JsonValue::Object(ref object) => {
+                self.punctuation(b'{')?;
+
+                if object.is_empty() {
+                    return self.punctuation(b'}');
+                }
+
+                self.indent();
+                for (index, (key, value)) in object.iter().enumerate() {
+                    if index > 0 {
+                        self.punctuation(b',')?;
+                    }
+                    self.new_line()?;
+                    self.paint(KEY, |gen| gen.write_key(key))?;
+                    self.punctuation(b':')?;
+                    self.write_char(b' ')?;
+                    self.write_json(value)?;
+                }
+                self.dedent();
+                self.new_line()?;
+                self.punctuation(b'}')
+            },
+        }
     }
 }
 
 /// Writer Generator, this uses a custom writer to store the JSON result.
 pub struct WriterGenerator<'a, W: 'a + Write> {
-    writer: &'a mut W
+    writer: &'a mut W,
+    options: GeneratorOptions,
 }
 
 impl<'a, W> WriterGenerator<'a, W> where W: 'a + Write {
     pub fn new(writer: &'a mut W) -> Self {
+        WriterGenerator::with_options(writer, GeneratorOptions::default())
+    }
+
+    pub fn with_options(writer: &'a mut W, options: GeneratorOptions) -> Self {
         WriterGenerator {
-            writer: writer
+            writer: writer,
+            options,
         }
     }
 }
@@ -307,6 +1364,11 @@ impl<'a, W> WriterGenerator<'a, W> where W: 'a + Write {
 impl<'a, W> Generator for WriterGenerator<'a, W> where W: Write {
     type T = W;
 
+    #[inline(always)]
+    fn options(&self) -> &GeneratorOptions {
+        &self.options
+    }
+
     #[inline(always)]
     fn get_writer(&mut self) -> &mut W {
         &mut self.writer
@@ -322,15 +1384,23 @@ impl<'a, W> Generator for WriterGenerator<'a, W> where W: Write {
 pub struct PrettyWriterGenerator<'a, W: 'a + Write> {
     writer: &'a mut W,
     dent: u16,
+    closing: bool,
     spaces_per_indent: u16,
+    options: GeneratorOptions,
 }
 
 impl<'a, W> PrettyWriterGenerator<'a, W> where W: 'a + Write {
     pub fn new(writer: &'a mut W, spaces: u16) -> Self {
+        PrettyWriterGenerator::with_options(writer, spaces, GeneratorOptions::default())
+    }
+
+    pub fn with_options(writer: &'a mut W, spaces: u16, options: GeneratorOptions) -> Self {
         PrettyWriterGenerator {
             writer: writer,
             dent: 0,
+            closing: false,
             spaces_per_indent: spaces,
+            options,
         }
     }
 }
@@ -338,20 +1408,42 @@ impl<'a, W> PrettyWriterGenerator<'a, W> where W: 'a + Write {
 impl<'a, W> Generator for PrettyWriterGenerator<'a, W> where W: Write {
     type T = W;
 
+    #[inline(always)]
+    fn options(&self) -> &GeneratorOptions {
+        &self.options
+    }
+
     #[inline(always)]
     fn get_writer(&mut self) -> &mut W {
         &mut self.writer
     }
 
     #[inline(always)]
-    fn write_min(&mut self, slice: &[u8], _: u8) -> io::Result<()> {
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.closing = false;
+        self.writer.write_all(&[ch])
+    }
+
+    #[inline(always)]
+    fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()> {
+        if collapsed(&self.options, self.dent, false) {
+            return self.write_char(min);
+        }
         self.writer.write_all(slice)
     }
 
     fn new_line(&mut self) -> io::Result<()> {
+        if collapsed(&self.options, self.dent, self.closing) {
+            return Ok(());
+        }
         self.write_char(b'\n')?;
-        for _ in 0..(self.dent * self.spaces_per_indent) {
-            self.write_char(b' ')?;
+        match self.options.indent {
+            Some(indent) => for _ in 0..self.dent {
+                self.writer.write_all(indent.as_bytes())?;
+            },
+            None => for _ in 0..(self.dent * self.spaces_per_indent) {
+                self.write_char(b' ')?;
+            },
         }
         Ok(())
     }
@@ -362,6 +1454,283 @@ impl<'a, W> Generator for PrettyWriterGenerator<'a, W> where W: Write {
 
     fn dedent(&mut self) {
         self.dent -= 1;
+        self.closing = true;
+    }
+}
+
+/// Generator that doesn't produce any output, only counting the number of
+/// bytes the serialized JSON would take.
+pub struct LengthGenerator {
+    counter: Counter,
+    options: GeneratorOptions,
+}
+
+/// Implementor of `std::io::Write` that discards the data, only counting
+/// the bytes written to it.
+pub struct Counter(pub usize);
+
+impl Write for Counter {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    #[inline(always)]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.0 += buf.len();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl LengthGenerator {
+    pub fn new() -> Self {
+        LengthGenerator::with_options(GeneratorOptions::default())
+    }
+
+    pub fn with_options(options: GeneratorOptions) -> Self {
+        LengthGenerator {
+            counter: Counter(0),
+            options,
+        }
+    }
+
+    /// Number of bytes counted so far.
+    pub fn count(&self) -> usize {
+        self.counter.0
+    }
+}
+
+impl Default for LengthGenerator {
+    fn default() -> Self {
+        LengthGenerator::new()
+    }
+}
+
+impl Generator for LengthGenerator {
+    type T = Counter;
+
+    #[inline(always)]
+    fn options(&self) -> &GeneratorOptions {
+        &self.options
+    }
+
+    #[inline(always)]
+    fn write(&mut self, slice: &[u8]) -> io::Result<()> {
+        self.counter.0 += slice.len();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, _: u8) -> io::Result<()> {
+        self.counter.0 += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_writer(&mut self) -> &mut Counter {
+        &mut self.counter
+    }
+
+    #[inline(always)]
+    fn write_min(&mut self, _: &[u8], _: u8) -> io::Result<()> {
+        self.counter.0 += 1;
+        Ok(())
+    }
+}
+
+// Fewest members of an array to give to each thread, below that splitting
+// the work costs more than it saves.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK_LEN: usize = 256;
+
+/// Dump `members` of an array in chunks on the `rayon` thread pool. Chunks
+/// hold the members separated by commas, without the surrounding brackets.
+/// Short arrays come back in a single chunk, dumped on the calling thread.
+#[cfg(feature = "parallel")]
+pub(crate) fn dump_chunks(members: &[JsonValue]) -> Vec<Vec<u8>> {
+    use rayon::prelude::*;
+
+    fn dump_chunk(chunk: &[JsonValue]) -> Vec<u8> {
+        let mut gen = DumpGenerator::new();
+
+        for (index, member) in chunk.iter().enumerate() {
+            if index > 0 {
+                gen.write_char(b',').expect("Can't fail");
+            }
+            gen.write_json(member).expect("Can't fail");
+        }
+
+        gen.code
+    }
+
+    let chunk_len = members.len().div_ceil(rayon::current_num_threads()).max(PARALLEL_CHUNK_LEN);
+
+    if members.len() <= chunk_len {
+        return vec![dump_chunk(members)];
+    }
+
+    members.par_chunks(chunk_len).map(dump_chunk).collect()
+}
+
+#[cfg(feature = "parallel")]
+pub(crate) fn dump_parallel(members: &[JsonValue]) -> String {
+    let chunks = dump_chunks(members);
+    let mut code = Vec::with_capacity(chunks.iter().map(|chunk| chunk.len() + 1).sum::<usize>() + 1);
+
+    code.push(b'[');
+    for (index, chunk) in chunks.iter().enumerate() {
+        if index > 0 {
+            code.push(b',');
+        }
+        extend_from_slice(&mut code, chunk);
+    }
+    code.push(b']');
+
+    from_utf8(code)
+}
+
+#[cfg(feature = "parallel")]
+pub(crate) fn write_parallel<W: Write>(members: &[JsonValue], writer: &mut W) -> io::Result<()> {
+    writer.write_all(b"[")?;
+    for (index, chunk) in dump_chunks(members).iter().enumerate() {
+        if index > 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(chunk)?;
+    }
+    writer.write_all(b"]")
+}
+
+// Writes a preview of `value` to `out`, keeping `out` within `limit` bytes
+// where that is possible, see `JsonValue::preview`. Returns `false` if any
+// part of the value had to be left out.
+pub(crate) fn preview(out: &mut String, value: &JsonValue, limit: usize) -> bool {
+    if out.len() + value.serialized_len() <= limit {
+        out.push_str(&value.dump());
+        return true;
+    }
+
+    match *value {
+        JsonValue::Short(ref short)   => preview_string(out, short, limit),
+        JsonValue::String(ref string) => preview_string(out, string, limit),
+        JsonValue::Array(ref array)   => {
+            out.push('[');
+            let complete = preview_members(out, array.iter().map(|value| (None, value)), array.len(), limit);
+            out.push(']');
+            complete
+        },
+        JsonValue::Object(ref object) => {
+            out.push('{');
+            let complete = preview_members(out, object.iter().map(|(key, value)| (Some(key), value)), object.len(), limit);
+            out.push('}');
+            complete
+        },
+        _ => {
+            out.push('…');
+            false
+        },
+    }
+}
+
+fn preview_members<'a, I>(out: &mut String, members: I, count: usize, limit: usize) -> bool
+where I: Iterator<Item = (Option<&'a str>, &'a JsonValue)> {
+    let more = |count: usize| format!("… {} more", count);
+
+    for (index, (key, value)) in members.enumerate() {
+        // Room for the closing bracket and the note on the members after this one
+        let rest = count - index - 1;
+        let reserve = match rest {
+            0    => 1,
+            rest => 2 + more(rest).len(),
+        };
+
+        let start = out.len();
+        if index > 0 {
+            out.push(',');
+        }
+        if let Some(key) = key {
+            let mut gen = DumpGenerator::new();
+            gen.write_string(key).expect("Can't fail");
+            out.push_str(&gen.consume());
+            out.push(':');
+        }
+
+        // At least a byte of the value has to fit
+        if out.len() + 1 + reserve > limit {
+            out.truncate(start);
+            if index > 0 {
+                out.push(',');
+            }
+            out.push_str(&more(count - index));
+            return false;
+        }
+
+        if !preview(out, value, limit - reserve) {
+            if rest > 0 {
+                out.push(',');
+                out.push_str(&more(rest));
+            }
+            return false;
+        }
+    }
+
+    true
+}
+
+fn preview_string(out: &mut String, string: &str, limit: usize) -> bool {
+    let mut gen = DumpGenerator::new();
+    gen.write_string(string).expect("Can't fail");
+    let code = gen.consume();
+
+    // Cut between escapes and characters, leaving room for `…"`
+    let content = &code[1 .. code.len() - 1];
+    let budget = limit.saturating_sub(out.len() + "\"…\"".len());
+    let bytes = content.as_bytes();
+    let mut end = 0;
+
+    while end < bytes.len() {
+        let step = match bytes[end] {
+            b'\\' if bytes[end + 1] == b'u' => 6,
+            b'\\'                           => 2,
+            _                                => content[end ..].chars().next().map_or(1, char::len_utf8),
+        };
+        if end + step > budget {
+            break;
+        }
+        end += step;
+    }
+
+    out.push('"');
+    out.push_str(&content[.. end]);
+    out.push_str("…\"");
+    false
+}
+
+// Whether a pretty generator at `dent` levels of indentation is within a
+// level written on a single line, see `GeneratorOptions::max_pretty_depth`.
+// `closing` is set right after a dedent, as the line break before a closing
+// bracket belongs to the level of the bracket.
+#[inline(always)]
+pub(crate) fn collapsed(options: &GeneratorOptions, dent: u16, closing: bool) -> bool {
+    match options.max_pretty_depth {
+        Some(max) => dent + closing as u16 > max,
+        None      => false,
+    }
+}
+
+// Whether the magnitude of `num` is greater than 2^53.
+fn is_unsafe(num: &Number) -> bool {
+    const SAFE: u128 = 1 << 53;
+
+    match num.as_integer() {
+        Some(magnitude) => magnitude > SAFE,
+        // Fractions and integers too large for `u128`
+        None            => f64::from(*num).abs() > SAFE as f64,
     }
 }
 
@@ -369,6 +1738,7 @@ impl<'a, W> Generator for PrettyWriterGenerator<'a, W> where W: Write {
 //
 // LLVM is not able to lower `Vec::extend_from_slice` into a memcpy, so this
 // helps eke out that last bit of performance.
+#[cfg(not(feature = "forbid-unsafe"))]
 #[inline]
 fn extend_from_slice(dst: &mut Vec<u8>, src: &[u8]) {
     let dst_len = dst.len();
@@ -387,7 +1757,27 @@ fn extend_from_slice(dst: &mut Vec<u8>, src: &[u8]) {
     }
 }
 
-#[cfg(test)]
+#[cfg(feature = "forbid-unsafe")]
+#[inline]
+fn extend_from_slice(dst: &mut Vec<u8>, src: &[u8]) {
+    dst.extend_from_slice(src);
+}
+
+// Generated code is always valid UTF-8.
+#[cfg(not(feature = "forbid-unsafe"))]
+#[inline]
+fn from_utf8(code: Vec<u8>) -> String {
+    unsafe { String::from_utf8_unchecked(code) }
+}
+
+#[cfg(feature = "forbid-unsafe")]
+#[inline]
+fn from_utf8(code: Vec<u8>) -> String {
+    String::from_utf8(code).expect("Can't fail")
+}
+
+// These feed invalid UTF-8 into the generator, which can't be done safely.
+#[cfg(all(test, not(feature = "forbid-unsafe")))]
 mod tests {
     use super::*;
 
@@ -0,0 +1,177 @@
+//! Layered configuration, merged from defaults, files and environment
+//! variables, keeping track of which layer each setting came from.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # use json::config::Layers;
+//! # fn main() {
+//! let config = Layers::new()
+//!     .layer("defaults", object!{ "port" => 80, "db" => object!{ "host" => "localhost", "pool" => 4 } })
+//!     .layer("file", object!{ "db" => object!{ "host" => "db.internal" } })
+//!     .load();
+//!
+//! assert_eq!(config.value, object!{ "port" => 80, "db" => object!{ "host" => "db.internal", "pool" => 4 } });
+//! assert_eq!(config.source("/db/host"), Some("file"));
+//! assert_eq!(config.source("/db/pool"), Some("defaults"));
+//! # }
+//! ```
+//!
+//! Each layer overrides the layers added before it. Objects present in
+//! several layers are merged key by key, at any depth, while all other
+//! values, arrays and `null` included, replace the earlier value as a whole.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use crate::{ parse, parse_from_reader, JsonValue };
+use crate::util::pointer;
+
+/// Builder collecting the layers of a configuration, from the lowest
+/// precedence to the highest.
+#[derive(Clone, Debug, Default)]
+pub struct Layers {
+    layers: Vec<(String, JsonValue)>,
+}
+
+/// Result of merging `Layers`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// The merged configuration.
+    pub value: JsonValue,
+
+    /// Name of the layer each setting came from, by the JSON Pointer of the
+    /// setting. Arrays and empty objects are settings of their own, their
+    /// members aren't listed.
+    pub sources: BTreeMap<String, String>,
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        Layers::default()
+    }
+
+    /// Add a layer overriding all layers added so far.
+    pub fn layer<N: Into<String>>(mut self, name: N, value: JsonValue) -> Self {
+        self.layers.push((name.into(), value));
+        self
+    }
+
+    /// Add the JSON file at `path` as a layer, named by the path. See
+    /// `parse_from_reader` for the errors.
+    pub fn file<P: AsRef<Path>>(self, path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let value = parse_from_reader(File::open(path)?)?;
+
+        Ok(self.layer(path.display().to_string(), value))
+    }
+
+    /// Same as `file`, but a file that doesn't exist is skipped.
+    pub fn optional_file<P: AsRef<Path>>(self, path: P) -> io::Result<Self> {
+        if path.as_ref().exists() {
+            self.file(path)
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Add the environment variables starting with `prefix` as a layer,
+    /// named `env`. The rest of the name is the path of the setting, in
+    /// lowercase, with `__` separating the keys of nested objects, so that
+    /// `APP_DB__HOST` sets `/db/host` for the `APP_` prefix. Values that
+    /// are valid JSON, such as `8080`, `true` or `[1, 2]`, are parsed as
+    /// such, all others are kept as strings.
+    pub fn env(self, prefix: &str) -> Self {
+        let vars = env::vars().filter_map(|(name, value)| {
+            name.strip_prefix(prefix).map(|name| (name.to_string(), value))
+        });
+
+        let mut layer = JsonValue::new_object();
+
+        for (name, value) in vars {
+            let value = parse(&value).unwrap_or_else(|_| value.into());
+            let mut keys = name.split("__").map(str::to_lowercase).peekable();
+            let mut target = &mut layer;
+
+            while let Some(key) = keys.next() {
+                if !target.is_object() {
+                    *target = JsonValue::new_object();
+                }
+                if keys.peek().is_none() {
+                    target[key.as_str()] = value;
+                    break;
+                }
+                target = &mut target[key.as_str()];
+            }
+        }
+
+        self.layer("env", layer)
+    }
+
+    /// Merge all layers.
+    pub fn load(self) -> Config {
+        let mut value = JsonValue::Null;
+
+        for (_, layer) in &self.layers {
+            merge(&mut value, layer);
+        }
+
+        let mut sources = BTreeMap::new();
+        settings(&value, &mut String::new(), &mut |path| {
+            // The setting came from the last layer that has it
+            let source = self.layers.iter().rev().find(|(_, layer)| layer.pointer(path).is_some());
+
+            if let Some((name, _)) = source {
+                sources.insert(path.to_string(), name.clone());
+            }
+        });
+
+        Config { value, sources }
+    }
+}
+
+impl Config {
+    /// Name of the layer the setting at the JSON Pointer `pointer` came
+    /// from, also for members of arrays. `None` if there is no such setting.
+    pub fn source(&self, pointer: &str) -> Option<&str> {
+        let mut pointer = pointer;
+
+        loop {
+            if let Some(name) = self.sources.get(pointer) {
+                return Some(name);
+            }
+            pointer = &pointer[.. pointer.rfind('/')?];
+        }
+    }
+}
+
+fn merge(target: &mut JsonValue, layer: &JsonValue) {
+    match (target, layer) {
+        (JsonValue::Object(target), JsonValue::Object(layer)) => {
+            for (key, value) in layer.iter() {
+                match target.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None           => target.insert(key, value.clone()),
+                }
+            }
+        },
+        (target, layer) => *target = layer.clone(),
+    }
+}
+
+// Calls `found` with the path of every setting in `value`.
+fn settings<F: FnMut(&str)>(value: &JsonValue, path: &mut String, found: &mut F) {
+    match *value {
+        JsonValue::Object(ref object) if !object.is_empty() => {
+            for (key, value) in object.iter() {
+                let len = path.len();
+                pointer::push_key(path, key);
+                settings(value, path, found);
+                path.truncate(len);
+            }
+        },
+        _ => found(path),
+    }
+}
@@ -0,0 +1,206 @@
+use std::io::{ self, Read, Write };
+
+use crate::JsonValue;
+use crate::base64;
+use crate::object::Object;
+
+/// Write an array of flat objects as CSV, with a header row made of the keys
+/// in order of first appearance. Objects missing some of the keys get empty
+/// cells. Requires the `csv` feature.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let data = array![
+///     object!{ "name" => "Jon", "age" => 30 },
+///     object!{ "name" => "Snow, Jon", "admin" => true }
+/// ];
+///
+/// let mut out = Vec::new();
+/// json::to_csv(&data, &mut out).unwrap();
+///
+/// assert_eq!(out, b"name,age,admin\r\nJon,30,\r\n\"Snow, Jon\",,true\r\n");
+/// # }
+/// ```
+///
+/// Strings are written as they are, numbers and booleans as JSON and `null`
+/// as an empty cell. Anything other than an array of objects, or objects with
+/// nested arrays or objects, results in an `io::Error` of `InvalidInput`
+/// kind.
+pub fn to_csv<W: Write>(value: &JsonValue, mut writer: W) -> io::Result<()> {
+    let rows = match *value {
+        JsonValue::Array(ref rows) => rows,
+        _ => return Err(invalid_input("Expected an array of objects")),
+    };
+
+    let mut header: Vec<&str> = Vec::new();
+
+    for row in rows {
+        let object = match *row {
+            JsonValue::Object(ref object) => object,
+            _ => return Err(invalid_input("Expected an array of objects")),
+        };
+
+        for (key, value) in object.iter() {
+            if value.is_object() || value.is_array() {
+                return Err(invalid_input("Nested values can't be written to CSV"));
+            }
+            if !header.contains(&key) {
+                header.push(key);
+            }
+        }
+    }
+
+    if header.is_empty() {
+        return writer.flush();
+    }
+
+    write_row(&mut writer, header.iter().cloned())?;
+
+    for row in rows {
+        let cells = header.iter().map(|&key| match row[key] {
+            JsonValue::Null               => String::new(),
+            JsonValue::Short(ref short)   => short.to_string(),
+            JsonValue::String(ref string) => string.clone(),
+            JsonValue::Binary(ref bytes)  => base64::encode(bytes),
+            ref value                     => value.dump(),
+        });
+
+        write_row(&mut writer, cells)?;
+    }
+
+    writer.flush()
+}
+
+/// Read CSV with a header row into an array of objects, one per row, keyed
+/// by the header. CSV has no types, so all values are strings. Requires the
+/// `csv` feature.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let csv = "name,age\nJon,30\n\"Snow, \"\"Jon\"\"\",\n";
+///
+/// assert_eq!(json::from_csv(csv.as_bytes()).unwrap(), array![
+///     object!{ "name" => "Jon", "age" => "30" },
+///     object!{ "name" => "Snow, \"Jon\"", "age" => "" }
+/// ]);
+/// # }
+/// ```
+///
+/// Malformed input, such as rows with a different number of cells than the
+/// header, results in an `io::Error` of `InvalidData` kind.
+pub fn from_csv<R: Read>(mut reader: R) -> io::Result<JsonValue> {
+    let mut source = String::new();
+    reader.read_to_string(&mut source)?;
+
+    let mut rows = parse_rows(&source)?.into_iter();
+
+    let header = match rows.next() {
+        Some(header) => header,
+        None         => return Ok(JsonValue::new_array()),
+    };
+
+    let mut array = Vec::new();
+
+    for row in rows {
+        if row.len() != header.len() {
+            return Err(invalid_data("Row has a different number of cells than the header"));
+        }
+
+        let mut object = Object::with_capacity(header.len());
+        for (key, cell) in header.iter().zip(row) {
+            object.insert(key, cell.into());
+        }
+        array.push(JsonValue::Object(object));
+    }
+
+    Ok(JsonValue::Array(array))
+}
+
+fn write_row<W, I, S>(writer: &mut W, cells: I) -> io::Result<()>
+    where W: Write, I: Iterator<Item = S>, S: AsRef<str>
+{
+    for (index, cell) in cells.enumerate() {
+        if index > 0 {
+            writer.write_all(b",")?;
+        }
+
+        let cell = cell.as_ref();
+
+        if cell.contains([',', '"', '\r', '\n']) {
+            writer.write_all(b"\"")?;
+            writer.write_all(cell.replace('"', "\"\"").as_bytes())?;
+            writer.write_all(b"\"")?;
+        } else {
+            writer.write_all(cell.as_bytes())?;
+        }
+    }
+
+    writer.write_all(b"\r\n")
+}
+
+// Splits RFC 4180 CSV into rows of cells, accepting both `\r\n` and `\n`
+// line endings.
+fn parse_rows(source: &str) -> io::Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut chars = source.chars().peekable();
+
+    // Whether anything has been read since the end of the last row
+    let mut pending = false;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if cell.is_empty() => {
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            cell.push('"');
+                        },
+                        Some('"') => break,
+                        Some(ch)  => cell.push(ch),
+                        None      => return Err(invalid_data("Unterminated quoted cell")),
+                    }
+                }
+
+                match chars.peek() {
+                    None | Some(',') | Some('\r') | Some('\n') => {},
+                    Some(_) => return Err(invalid_data("Unexpected character after a quoted cell")),
+                }
+                pending = true;
+            },
+            ',' => {
+                row.push(std::mem::take(&mut cell));
+                pending = true;
+            },
+            '\r' if chars.peek() == Some(&'\n') => {},
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+                pending = false;
+            },
+            ch => {
+                cell.push(ch);
+                pending = true;
+            },
+        }
+    }
+
+    if pending {
+        row.push(cell);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn invalid_input(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,105 @@
+// Conversions between `Number` and the `Decimal` of the `rust_decimal`
+// crate, for amounts of money and other values that binary floats can't
+// hold.
+
+use std::convert::TryFrom;
+
+use rust_decimal::Decimal;
+
+use crate::number::{ Number, NumberOutOfScope };
+
+// Largest number of digits after the decimal point of a `Decimal`
+const MAX_SCALE: i64 = 28;
+
+// Largest magnitude of the mantissa of a `Decimal`, 96 bits
+const MAX_MANTISSA: u128 = (1 << 96) - 1;
+
+// `mantissa` times 10 to the power of `exponent`, with trailing zeros
+// dropped only when needed to fit.
+fn from_parts(negative: bool, mut mantissa: u128, mut exponent: i64) -> Option<Decimal> {
+    while exponent < 0 && (exponent < -MAX_SCALE || mantissa > MAX_MANTISSA) && mantissa.is_multiple_of(10) && mantissa != 0 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+
+    if mantissa == 0 {
+        exponent = exponent.clamp(-MAX_SCALE, 0);
+    }
+
+    if exponent > 0 {
+        mantissa = mantissa.checked_mul(10u128.checked_pow(u32::try_from(exponent).ok()?)?)?;
+        exponent = 0;
+    }
+
+    if exponent < -MAX_SCALE || mantissa > MAX_MANTISSA {
+        return None;
+    }
+
+    let mantissa = match negative {
+        true  => -(mantissa as i128),
+        false => mantissa as i128,
+    };
+
+    Decimal::try_from_i128_with_scale(mantissa, -exponent as u32).ok()
+}
+
+/// Fails for NaN and for numbers out of range of `Decimal`, including
+/// those with more than 28 digits after the decimal point. Everything else
+/// converts exactly, keeping trailing zeros after the decimal point.
+impl TryFrom<Number> for Decimal {
+    type Error = NumberOutOfScope;
+
+    fn try_from(num: Number) -> Result<Decimal, NumberOutOfScope> {
+        if num.is_nan() {
+            return Err(NumberOutOfScope);
+        }
+
+        let negative = !num.is_sign_positive();
+
+        let decimal = match num.wide() {
+            Some(wide) => from_parts(negative, wide, 0),
+            None       => {
+                let (_, mantissa, exponent) = num.as_parts();
+
+                from_parts(negative, mantissa as u128, exponent as i64)
+            },
+        };
+
+        decimal.ok_or(NumberOutOfScope)
+    }
+}
+
+/// Exact for all integers and for mantissas that fit into `u64`, which are
+/// all decimals of up to 19 digits. Longer mantissas with digits after the
+/// decimal point are rounded to fit.
+impl From<Decimal> for Number {
+    fn from(decimal: Decimal) -> Number {
+        let positive = !decimal.is_sign_negative();
+        let mut mantissa = decimal.mantissa().unsigned_abs();
+        let mut exponent = -(decimal.scale() as i16);
+
+        if exponent == 0 {
+            return decimal.mantissa().into();
+        }
+
+        let mut rest = 0;
+
+        while mantissa > u64::MAX as u128 {
+            rest = mantissa % 10;
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        if rest >= 5 && mantissa < u64::MAX as u128 {
+            mantissa += 1;
+        }
+
+        Number::from_raw_parts(positive, mantissa as u64, exponent)
+    }
+}
+
+impl PartialEq<Decimal> for Number {
+    fn eq(&self, other: &Decimal) -> bool {
+        Decimal::try_from(*self).is_ok_and(|decimal| decimal == *other)
+    }
+}
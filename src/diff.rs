@@ -0,0 +1,357 @@
+//! Structural differences between values, for finding what changed, for
+//! readable failures of `assert_json_eq!` and for three-way merges.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let before = object!{ "id" => 1, "tags" => array!["a", "b"], "draft" => true };
+//! let after = object!{ "id" => 1, "tags" => array!["a", "c"], "title" => "Hi" };
+//!
+//! let differences: Vec<String> = json::diff(&before, &after)
+//!                                    .iter()
+//!                                    .map(ToString::to_string)
+//!                                    .collect();
+//!
+//! assert_eq!(differences, [
+//!     r#"/tags/1: "b" != "c""#,
+//!     "/draft: true != (missing)",
+//!     r#"/title: (missing) != "Hi""#,
+//! ]);
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::JsonValue;
+use crate::util::pointer;
+
+/// A place where two values differ.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difference<'a> {
+    /// JSON Pointer to the place, empty for the values themselves.
+    pub pointer: String,
+
+    /// The value on the left side, `None` if it is missing there.
+    pub left: Option<&'a JsonValue>,
+
+    /// The value on the right side, `None` if it is missing there.
+    pub right: Option<&'a JsonValue>,
+}
+
+/// A place where both sides of `merge3` changed the base differently.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict<'a> {
+    /// JSON Pointer to the place, empty for the values themselves.
+    pub pointer: String,
+
+    /// The value in the base, `None` if it is missing there.
+    pub base: Option<&'a JsonValue>,
+
+    /// The value on our side, `None` if we removed or never had it.
+    pub ours: Option<&'a JsonValue>,
+
+    /// The value on their side, `None` if they removed or never had it.
+    pub theirs: Option<&'a JsonValue>,
+}
+
+/// Result of `merge3`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Merge<'a> {
+    /// The merged value, with our side of all conflicts.
+    pub value: JsonValue,
+
+    /// The places both sides changed differently, empty if the merge is
+    /// clean.
+    pub conflicts: Vec<Conflict<'a>>,
+}
+
+/// Options for `compare`. The default options compare like `diff`.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # use json::diff::CompareOpts;
+/// # fn main() {
+/// let snapshot = object!{ "ids" => array![1, 2, 3], "score" => 0.3, "requestId" => "a1" };
+/// let current = object!{ "ids" => array![3, 1, 2], "score" => 0.1 + 0.2, "requestId" => "f7" };
+///
+/// let opts = CompareOpts {
+///     ignore_array_order: true,
+///     ignore_keys: &["requestId"],
+///     numeric_epsilon: 1e-9,
+/// };
+///
+/// assert!(json::compare(&snapshot, &current, opts).is_empty());
+/// assert_eq!(json::diff(&snapshot, &current).len(), 5);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CompareOpts<'a> {
+    /// Compare arrays as multisets, with members in any order.
+    pub ignore_array_order: bool,
+
+    /// Keys of objects to skip, at any depth.
+    pub ignore_keys: &'a [&'a str],
+
+    /// Largest difference at which numbers still count as equal.
+    pub numeric_epsilon: f64,
+}
+
+/// Find all places where `left` and `right` differ, in the same sense as
+/// `==` on `JsonValue`, with numbers compared by value and object keys in
+/// any order.
+///
+/// Objects are compared key by key, first the keys of `left` in order and
+/// then the keys only `right` has. Arrays are compared index by index, with
+/// the members past the end of the shorter one missing from it. Values of
+/// different types are a single difference.
+pub fn diff<'a>(left: &'a JsonValue, right: &'a JsonValue) -> Vec<Difference<'a>> {
+    compare(left, right, CompareOpts::default())
+}
+
+/// Find all places where `left` and `right` differ like `diff` does, but
+/// tolerating the differences `opts` allows. The values are equal under
+/// them if there are none.
+///
+/// With `ignore_array_order` each member of an array in `left` is paired
+/// with the first equal member of the other array that isn't paired yet.
+/// The members left over on either side are differences, each with its
+/// pointer into the side that has it.
+pub fn compare<'a>(left: &'a JsonValue, right: &'a JsonValue, opts: CompareOpts) -> Vec<Difference<'a>> {
+    let mut differences = Vec::new();
+    let mut pointer = String::new();
+
+    compare_into(left, right, &opts, &mut pointer, &mut differences);
+
+    differences
+}
+
+fn equal(left: &JsonValue, right: &JsonValue, opts: &CompareOpts) -> bool {
+    let mut differences = Vec::new();
+
+    compare_into(left, right, opts, &mut String::new(), &mut differences);
+
+    differences.is_empty()
+}
+
+fn compare_into<'a>(
+    left: &'a JsonValue,
+    right: &'a JsonValue,
+    opts: &CompareOpts,
+    pointer: &mut String,
+    differences: &mut Vec<Difference<'a>>
+) {
+    let len = pointer.len();
+    let kept = |key: &str| !opts.ignore_keys.contains(&key);
+
+    let push = |differences: &mut Vec<Difference<'a>>, pointer: &String, left, right| {
+        differences.push(Difference { pointer: pointer.clone(), left, right });
+    };
+
+    match (left, right) {
+        (JsonValue::Object(left), JsonValue::Object(right)) => {
+            for (key, value) in left.iter().filter(|&(key, _)| kept(key)) {
+                pointer::push_key(pointer, key);
+
+                match right.get(key) {
+                    Some(other) => compare_into(value, other, opts, pointer, differences),
+                    None        => push(differences, pointer, Some(value), None),
+                }
+
+                pointer.truncate(len);
+            }
+
+            for (key, value) in right.iter().filter(|&(key, _)| kept(key) && left.get(key).is_none()) {
+                pointer::push_key(pointer, key);
+                push(differences, pointer, None, Some(value));
+                pointer.truncate(len);
+            }
+        },
+        (JsonValue::Array(left), JsonValue::Array(right)) if opts.ignore_array_order => {
+            let mut paired = vec![false; right.len()];
+
+            for (index, value) in left.iter().enumerate() {
+                let pair = (0 .. right.len()).find(|&other| !paired[other] && equal(value, &right[other], opts));
+
+                match pair {
+                    Some(other) => paired[other] = true,
+                    None        => {
+                        pointer::push_index(pointer, index);
+                        push(differences, pointer, Some(value), None);
+                        pointer.truncate(len);
+                    },
+                }
+            }
+
+            for (index, value) in right.iter().enumerate().filter(|&(index, _)| !paired[index]) {
+                pointer::push_index(pointer, index);
+                push(differences, pointer, None, Some(value));
+                pointer.truncate(len);
+            }
+        },
+        (JsonValue::Array(left), JsonValue::Array(right)) => {
+            for index in 0 .. left.len().max(right.len()) {
+                pointer::push_index(pointer, index);
+
+                match (left.get(index), right.get(index)) {
+                    (Some(value), Some(other)) => compare_into(value, other, opts, pointer, differences),
+                    (value, other)             => push(differences, pointer, value, other),
+                }
+
+                pointer.truncate(len);
+            }
+        },
+        (JsonValue::Number(a), JsonValue::Number(b)) if opts.numeric_epsilon > 0.0 => {
+            if (f64::from(*a) - f64::from(*b)).abs() > opts.numeric_epsilon {
+                push(differences, pointer, Some(left), Some(right));
+            }
+        },
+        _ => if left != right {
+            push(differences, pointer, Some(left), Some(right));
+        },
+    }
+}
+
+/// Merge the changes `ours` and `theirs` made to `base`, the version both
+/// started from, such as two edits of a config file.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let base = object!{ "port" => 80, "hosts" => array!["a"], "debug" => false };
+/// let ours = object!{ "port" => 8080, "hosts" => array!["a", "b"], "debug" => false };
+/// let theirs = object!{ "port" => 80, "hosts" => array!["c"], "debug" => true, "name" => "x" };
+///
+/// let merge = json::merge3(&base, &ours, &theirs);
+///
+/// assert_eq!(merge.value, object!{ "port" => 8080, "hosts" => array!["a", "b"], "debug" => true, "name" => "x" });
+/// assert_eq!(merge.conflicts.len(), 1);
+/// assert_eq!(merge.conflicts[0].to_string(), r#"/hosts: ["a","b"] != ["c"] (base: ["a"])"#);
+/// # }
+/// ```
+///
+/// Where only one side changed a value, added or removed it, the merge has
+/// that change, and where both made the same change it has it once. Objects
+/// both sides changed are merged key by key, with our keys first and then
+/// the ones only they added. Anything else both sides changed differently,
+/// including arrays, which are merged as a whole, is a conflict, and keeps
+/// our side in the merged value.
+pub fn merge3<'a>(base: &'a JsonValue, ours: &'a JsonValue, theirs: &'a JsonValue) -> Merge<'a> {
+    let mut conflicts = Vec::new();
+    let mut pointer = String::new();
+
+    let value = merge_into(Some(base), Some(ours), Some(theirs), &mut pointer, &mut conflicts);
+
+    Merge { value: value.unwrap_or(JsonValue::Null), conflicts }
+}
+
+// The merged value at `pointer`, `None` if the merge removes it.
+fn merge_into<'a>(
+    base: Option<&'a JsonValue>,
+    ours: Option<&'a JsonValue>,
+    theirs: Option<&'a JsonValue>,
+    pointer: &mut String,
+    conflicts: &mut Vec<Conflict<'a>>
+) -> Option<JsonValue> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+
+    if let (Some(JsonValue::Object(left)), Some(JsonValue::Object(right))) = (ours, theirs) {
+        let len = pointer.len();
+        let original = match base {
+            Some(JsonValue::Object(original)) => Some(original),
+            _                                 => None,
+        };
+        let mut merged = JsonValue::new_object();
+
+        let keys = left.iter().map(|(key, _)| key)
+            .chain(right.iter().map(|(key, _)| key).filter(|key| left.get(key).is_none()));
+
+        for key in keys {
+            pointer::push_key(pointer, key);
+
+            let base = original.and_then(|original| original.get(key));
+
+            if let Some(value) = merge_into(base, left.get(key), right.get(key), pointer, conflicts) {
+                merged[key] = value;
+            }
+
+            pointer.truncate(len);
+        }
+
+        return Some(merged);
+    }
+
+    conflicts.push(Conflict { pointer: pointer.clone(), base, ours, theirs });
+    ours.cloned()
+}
+
+/// Writes the pointer and both values, shortened with
+/// `JsonValue::truncated` so that large values stay readable.
+impl fmt::Display for Difference<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        place(f, &self.pointer)?;
+        side(f, self.left)?;
+        f.write_str(" != ")?;
+        side(f, self.right)
+    }
+}
+
+/// Writes the pointer, our and their value, and the base value in
+/// parentheses, shortened the same way as for `Difference`.
+impl fmt::Display for Conflict<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        place(f, &self.pointer)?;
+        side(f, self.ours)?;
+        f.write_str(" != ")?;
+        side(f, self.theirs)?;
+        f.write_str(" (base: ")?;
+        side(f, self.base)?;
+        f.write_str(")")
+    }
+}
+
+fn place(f: &mut fmt::Formatter, pointer: &str) -> fmt::Result {
+    match pointer.is_empty() {
+        true  => f.write_str("(root): "),
+        false => write!(f, "{}: ", pointer),
+    }
+}
+
+fn side(f: &mut fmt::Formatter, value: Option<&JsonValue>) -> fmt::Result {
+    match value {
+        Some(value) => f.write_str(&value.truncated(2, 8, 60).dump()),
+        None        => f.write_str("(missing)"),
+    }
+}
+
+// Conversion of both sides of `assert_json_eq!`, so that they can be values,
+// references to them, or anything that converts into one.
+#[doc(hidden)]
+pub trait ToJson {
+    fn to_json(&self) -> JsonValue;
+}
+
+impl<T: Clone + Into<JsonValue>> ToJson for T {
+    fn to_json(&self) -> JsonValue {
+        self.clone().into()
+    }
+}
+
+#[doc(hidden)]
+pub fn assert_failed(left: &JsonValue, right: &JsonValue, message: Option<fmt::Arguments>) -> ! {
+    let mut report = String::from("assertion `left == right` failed");
+
+    if let Some(message) = message {
+        report.push_str(&format!(": {}", message));
+    }
+
+    for difference in diff(left, right) {
+        report.push_str(&format!("\n    {}", difference));
+    }
+
+    panic!("{}", report);
+}
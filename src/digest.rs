@@ -0,0 +1,144 @@
+// SHA-256 digests of values in the JSON Canonicalization Scheme of RFC 8785,
+// which writes equal values as the same bytes regardless of key order or
+// whitespace.
+
+use sha2::{ Digest, Sha256 };
+
+use crate::JsonValue;
+use crate::number::Number;
+use crate::base64;
+
+pub fn sha256(value: &JsonValue) -> Option<[u8; 32]> {
+    let mut hasher = Sha256::new();
+
+    canonical(value, &mut hasher)?;
+    Some(hasher.finalize().into())
+}
+
+fn canonical(value: &JsonValue, out: &mut Sha256) -> Option<()> {
+    match *value {
+        JsonValue::Null             => out.update(b"null"),
+        JsonValue::Short(ref short) => string(short.as_str(), out),
+        JsonValue::String(ref text) => string(text, out),
+        JsonValue::Number(number)   => self::number(double(number), out)?,
+        JsonValue::Boolean(true)    => out.update(b"true"),
+        JsonValue::Boolean(false)   => out.update(b"false"),
+        JsonValue::Binary(ref data) => string(&base64::encode(data), out),
+        JsonValue::Array(ref members) => {
+            out.update(b"[");
+            for (index, member) in members.iter().enumerate() {
+                if index > 0 {
+                    out.update(b",");
+                }
+                canonical(member, out)?;
+            }
+            out.update(b"]");
+        },
+        JsonValue::Object(ref object) => {
+            // Keys are sorted by their UTF-16 code units
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.update(b"{");
+            for (index, (key, member)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.update(b",");
+                }
+                string(key, out);
+                out.update(b":");
+                canonical(member, out)?;
+            }
+            out.update(b"}");
+        },
+    }
+
+    Some(())
+}
+
+fn string(text: &str, out: &mut Sha256) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    out.update(b"\"");
+
+    let bytes = text.as_bytes();
+    let mut start = 0;
+
+    for (index, &byte) in bytes.iter().enumerate() {
+        let escape: &[u8] = match byte {
+            b'"'  => b"\\\"",
+            b'\\' => b"\\\\",
+            0x08  => b"\\b",
+            0x0C  => b"\\f",
+            b'\n' => b"\\n",
+            b'\r' => b"\\r",
+            b'\t' => b"\\t",
+            0x00 ..= 0x1F => &[b'\\', b'u', b'0', b'0', HEX[byte as usize >> 4], HEX[byte as usize & 0xF]],
+            _ => continue,
+        };
+
+        out.update(&bytes[start .. index]);
+        out.update(escape);
+        start = index + 1;
+    }
+
+    out.update(&bytes[start ..]);
+    out.update(b"\"");
+}
+
+// Nearest double to `number`, rounded correctly unlike `f64::from`.
+fn double(number: Number) -> f64 {
+    if number.is_nan() {
+        return f64::NAN;
+    }
+
+    let (positive, mantissa, exponent) = number.as_parts();
+    let value: f64 = format!("{}e{}", mantissa, exponent).parse().expect("Valid float");
+
+    if positive { value } else { -value }
+}
+
+// Writes `value` the way ECMAScript's `Number.prototype.toString` does, with
+// the shortest digits that read back as the same double. NaN and the
+// infinities have no canonical form.
+fn number(value: f64, out: &mut Sha256) -> Option<()> {
+    if !value.is_finite() {
+        return None;
+    }
+    if value == 0.0 {
+        out.update(b"0");
+        return Some(());
+    }
+    if value < 0.0 {
+        out.update(b"-");
+    }
+
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').expect("Always has an exponent");
+    let digits = mantissa.replace('.', "");
+    let digits = digits.as_bytes();
+
+    // Position of the decimal point relative to the digits
+    let point = exponent.parse::<i32>().expect("Valid exponent") + 1;
+    let count = digits.len() as i32;
+
+    if count <= point && point <= 21 {
+        out.update(digits);
+        out.update(b"0".repeat((point - count) as usize));
+    } else if 0 < point && point <= 21 {
+        out.update(&digits[.. point as usize]);
+        out.update(b".");
+        out.update(&digits[point as usize ..]);
+    } else if -6 < point && point <= 0 {
+        out.update(b"0.");
+        out.update(b"0".repeat(-point as usize));
+        out.update(digits);
+    } else {
+        out.update(&digits[.. 1]);
+        if count > 1 {
+            out.update(b".");
+            out.update(&digits[1 ..]);
+        }
+        out.update(format!("e{}{}", if point > 0 { '+' } else { '-' }, (point - 1).abs()).as_bytes());
+    }
+    Some(())
+}
@@ -1,5 +1,8 @@
 use std::{ char, error, fmt };
 
+use crate::JsonValue;
+use crate::parser;
+
 /// Error type of this crate.
 ///
 ///
@@ -7,23 +10,225 @@ use std::{ char, error, fmt };
 /// `json::Error` instead!
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
+    /// `expected` lists what could have been in place of `ch`, such as
+    /// `[",", "}"]` inside of an object. Entries starting with "a" or "an"
+    /// describe a kind of token, such as "a value", the rest are written
+    /// as they appear in JSON. Can be empty, when nothing could follow.
     UnexpectedCharacter {
         ch: char,
         line: usize,
         column: usize,
+        expected: &'static [&'static str],
     },
     UnexpectedEndOfJson,
     ExceededDepthLimit,
     FailedUtf8Parsing,
-    WrongType(String),
+    /// A value had a different type than `expected`, or didn't fit into it.
+    /// `found` describes the value and `path` is its JSON Pointer, relative
+    /// to the value the lookup or conversion started from. Returned by the
+    /// `TryFrom<&JsonValue>` implementations and `JsonValue::try_get_path_as`,
+    /// the `as_*` accessors return `None` instead.
+    WrongType {
+        expected: String,
+        found: String,
+        path: String,
+    },
+
+    /// An object lacked the `key` being looked up. `path` is the JSON Pointer
+    /// of the object, relative to the value the lookup started from.
+    MemberNotFound {
+        key: String,
+        path: String,
+    },
+
+    /// A member was looked up on something other than an object, found at
+    /// the JSON Pointer `path`.
+    NotAnObject {
+        path: String,
+    },
+
+    /// An array `index` past the end of the array at the JSON Pointer
+    /// `path`, which has `len` members.
+    IndexOutOfBounds {
+        index: usize,
+        len: usize,
+        path: String,
+    },
+
+    /// A JSON Pointer that is neither empty nor starting with `/`.
+    InvalidPointer {
+        pointer: String,
+    },
+
+    /// A filter or query expression that can't be parsed, with the byte
+    /// offset of the problem in it.
+    InvalidFilter {
+        message: String,
+        position: usize,
+    },
+
+    /// An integer that can't be represented exactly by `f64`, rejected by
+    /// `PrecisionLoss::Error`.
+    PrecisionLoss {
+        number: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// A number too large for `f64`, rejected by `ExponentOverflow::Error`.
+    NumberOutOfRange {
+        number: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// A complete value followed by more than whitespace, from the byte
+    /// offset `index` on. The source up to `index` parses by itself, and
+    /// the rest can be parsed separately.
+    ///
+    /// ```
+    /// let source = r#"{"id": 1} {"id": 2}"#;
+    ///
+    /// let index = match json::parse(source) {
+    ///     Err(json::Error::TrailingCharacters { index }) => index,
+    ///     other => panic!("{:?}", other),
+    /// };
+    ///
+    /// assert_eq!(json::parse(&source[.. index]).unwrap()["id"], 1);
+    /// assert_eq!(json::parse(&source[index ..]).unwrap()["id"], 2);
+    /// ```
+    TrailingCharacters {
+        index: usize,
+    },
+
+    /// Error that happened inside of nested structure, with the JSON Pointer
+    /// of the value being processed at the time. The wrapped error is
+    /// written as part of this one and isn't its `source`, so that reports
+    /// following the chain of sources don't write it twice, use
+    /// `Error::without_path` to get to it.
+    Nested {
+        path: String,
+        error: Box<Error>,
+    },
 }
 
 impl Error {
-    pub fn wrong_type(expected: &str) -> Self {
-        Error::WrongType(expected.into())
+    /// Wrong type error for the value `found`, at the root.
+    pub fn wrong_type(expected: &str, found: &JsonValue) -> Self {
+        Error::WrongType {
+            expected: expected.into(),
+            found: found.type_name().into(),
+            path: String::new(),
+        }
+    }
+
+    /// Wrap `error` with the JSON Pointer of the value it happened in.
+    pub fn nested(path: String, error: Error) -> Self {
+        Error::Nested {
+            path,
+            error: Box::new(error),
+        }
+    }
+
+    /// JSON Pointer of the value the error happened in, if known.
+    ///
+    /// ```
+    /// use json::{ ParseOptions, PrecisionLoss };
+    ///
+    /// let options = ParseOptions { precision_loss: PrecisionLoss::Error, ..ParseOptions::default() };
+    /// let err = json::parse_with(r#"{"ids": [1, 12345678901234567]}"#, &options).unwrap_err();
+    ///
+    /// assert_eq!(err.path(), Some("/ids/1"));
+    /// assert!(matches!(err.without_path(), json::Error::PrecisionLoss { .. }));
+    /// ```
+    pub fn path(&self) -> Option<&str> {
+        match *self {
+            Error::Nested { ref path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The error itself, without the path context added by `Error::Nested`.
+    pub fn without_path(&self) -> &Error {
+        match *self {
+            Error::Nested { ref error, .. } => error.without_path(),
+            _ => self,
+        }
+    }
+
+    /// Format the error followed by the offending line of `source` and a
+    /// caret marking the column, in the style of compiler diagnostics.
+    /// `source` should be the same text that failed to parse. Errors without
+    /// a position in the source are formatted as they are.
+    ///
+    /// ```
+    /// let source = "{\n  \"a\": 1,\n  \"b\": ]\n}";
+    /// let err = json::parse(source).unwrap_err();
+    ///
+    /// assert_eq!(err.format_with_source(source), "\
+    /// Unexpected character: ] at (3:8), expected a value
+    ///   |
+    /// 3 |   \"b\": ]
+    ///   |        ^");
+    /// ```
+    pub fn format_with_source(&self, source: &str) -> String {
+        let (line, column) = match *self.without_path() {
+            Error::UnexpectedCharacter { line, column, .. } |
+            Error::PrecisionLoss { line, column, .. } |
+            Error::NumberOutOfRange { line, column, .. } => (line, column),
+            Error::TrailingCharacters { index } if source.is_char_boundary(index) => {
+                parser::line_and_column(source, index)
+            },
+            Error::UnexpectedEndOfJson => {
+                // Point just past the last character
+                let (index, text) = source.lines()
+                                          .enumerate()
+                                          .last()
+                                          .unwrap_or((0, ""));
+
+                (index + 1, text.chars().count() + 1)
+            },
+            _ => return self.to_string(),
+        };
+
+        let text = source.lines().nth(line - 1).unwrap_or("");
+
+        // Tabs are kept so the caret lines up however they are displayed
+        let marker: String = text.chars()
+                                 .take(column - 1)
+                                 .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                                 .collect();
+
+        let number = line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}^",
+            self, gutter, number, text, gutter, marker
+        )
     }
 }
 
+// Appends the tokens as ", expected `,` or `}`", descriptions such as
+// "a value" are written without the backticks.
+fn write_expected(f: &mut fmt::Formatter, expected: &[&str]) -> fmt::Result {
+    for (index, token) in expected.iter().enumerate() {
+        f.write_str(match index {
+            0                                => ", expected ",
+            _ if index + 1 == expected.len() => " or ",
+            _                                => ", ",
+        })?;
+
+        if token.starts_with("a ") || token.starts_with("an ") {
+            f.write_str(token)?;
+        } else {
+            write!(f, "`{}`", token)?;
+        }
+    }
+
+    Ok(())
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use Error::*;
@@ -33,16 +238,81 @@ impl fmt::Display for Error {
                 ref ch,
                 ref line,
                 ref column,
-            } => write!(f, "Unexpected character: {} at ({}:{})", ch, line, column),
+                expected,
+            } => {
+                if ch.is_control() || ch.is_whitespace() {
+                    // Invisible characters are written as escapes, such as `\t`
+                    write!(f, "Unexpected character: {} at ({}:{})", ch.escape_debug(), line, column)?;
+                } else {
+                    write!(f, "Unexpected character: {} at ({}:{})", ch, line, column)?;
+                }
+
+                write_expected(f, expected)
+            },
 
             UnexpectedEndOfJson   => write!(f, "Unexpected end of JSON"),
             ExceededDepthLimit    => write!(f, "Exceeded depth limit"),
             FailedUtf8Parsing     => write!(f, "Failed to parse UTF-8 bytes"),
-            WrongType(ref s)      => write!(f, "Wrong type, expected: {}", s),
+            WrongType {
+                ref expected,
+                ref found,
+                ref path,
+            } => {
+                write!(f, "Wrong type, expected {}, found {}", expected, found)?;
+                write_path(f, path)
+            },
+            MemberNotFound {
+                ref key,
+                ref path,
+            } => {
+                write!(f, "No member {:?}", key)?;
+                write_path(f, path)
+            },
+            NotAnObject { ref path } => {
+                write!(f, "Not an object")?;
+                write_path(f, path)
+            },
+            IndexOutOfBounds {
+                ref index,
+                ref len,
+                ref path,
+            } => {
+                write!(f, "Index {} is out of bounds for an array of length {}", index, len)?;
+                write_path(f, path)
+            },
+            InvalidPointer { ref pointer } => write!(f, "Invalid JSON Pointer {:?}", pointer),
+            InvalidFilter {
+                ref message,
+                ref position,
+            } => write!(f, "Invalid filter: {} at {}", message, position),
+            PrecisionLoss {
+                ref number,
+                ref line,
+                ref column,
+            } => write!(f, "Number {} can't be represented exactly by f64 at ({}:{})", number, line, column),
+            NumberOutOfRange {
+                ref number,
+                ref line,
+                ref column,
+            } => write!(f, "Number {} is out of range of f64 at ({}:{})", number, line, column),
+            TrailingCharacters { ref index } => write!(f, "Unexpected characters after the value at byte {}", index),
+            Nested {
+                ref path,
+                ref error,
+            } => write!(f, "{} at {}", error, path),
         }
     }
 }
 
+// Paths are relative, an empty one means the value the lookup started from.
+fn write_path(f: &mut fmt::Formatter, path: &str) -> fmt::Result {
+    if path.is_empty() {
+        Ok(())
+    } else {
+        write!(f, " at {}", path)
+    }
+}
+
 impl error::Error for Error {
     fn description(&self) -> &str {
         use Error::*;
@@ -52,7 +322,16 @@ impl error::Error for Error {
             UnexpectedEndOfJson        => "Unexpected end of JSON",
             ExceededDepthLimit         => "Exceeded depth limit",
             FailedUtf8Parsing          => "Failed to read bytes as UTF-8 from JSON",
-            WrongType(_)               => "Wrong type",
+            WrongType { .. }           => "Wrong type",
+            MemberNotFound { .. }      => "Member not found",
+            NotAnObject { .. }         => "Not an object",
+            IndexOutOfBounds { .. }    => "Index out of bounds",
+            InvalidPointer { .. }      => "Invalid JSON Pointer",
+            InvalidFilter { .. }       => "Invalid filter",
+            PrecisionLoss { .. }       => "Number can't be represented exactly",
+            NumberOutOfRange { .. }    => "Number out of range",
+            TrailingCharacters { .. }  => "Unexpected characters after the value",
+            Nested { .. }              => "Error in nested value",
         }
     }
 }
@@ -0,0 +1,1044 @@
+//! Filters in a subset of the jq language, for extracting and reshaping
+//! parts of documents with user supplied expressions.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let data = object!{
+//!     "items" => array![
+//!         object!{ "name" => "pen", "price" => 5 },
+//!         object!{ "name" => "book", "price" => 12 },
+//!         object!{ "name" => "lamp", "price" => 30 }
+//!     ]
+//! };
+//!
+//! let names = json::filter(&data, ".items[] | select(.price > 10) | .name").unwrap();
+//!
+//! assert_eq!(names, ["book", "lamp"]);
+//! # }
+//! ```
+//!
+//! Filters take a value and produce any number of values. The supported
+//! syntax is:
+//!
+//! - `.` for the input itself and `..` for it and everything nested in it.
+//! - `.foo`, `."foo"` and `.["foo"]` for members of objects, `.[0]` and
+//!   `.[-1]` for members of arrays, `.[]` for all members of either.
+//!   Members of `null` are `null`, anything else is an error, unless the
+//!   filter is followed by `?`.
+//! - `a | b` to feed the outputs of `a` into `b`, `a, b` for the outputs of
+//!   both.
+//! - Literals, `[...]` to collect outputs into an array and `{...}` to build
+//!   objects, with `{name}` being short for `{name: .name}`.
+//! - `+`, `-`, `*`, `/` and `%`, with `+` also joining strings, arrays and
+//!   objects and `-` removing members from arrays.
+//! - `==`, `!=`, `<`, `<=`, `>`, `>=`, `and`, `or`. Everything but `false`
+//!   and `null` counts as true, and values of all types can be compared, in
+//!   the order `null`, `false`, `true`, numbers, strings, arrays, objects.
+//! - The functions `select(f)`, `map(f)`, `has(key)`, `length`, `keys`,
+//!   `type`, `not`, `empty`, `add`, `sort`, `first`, `last`, `tostring` and
+//!   `tonumber`.
+//!
+//! Variables, user defined functions, string interpolation, slices and
+//! assignment are not supported.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::{ JsonValue, Error, Result };
+use crate::number::Number;
+use crate::object::Object;
+
+// Depth of the parsed expression, counting both nesting and chained
+// operators other than `,`. Parsing and evaluation recurse for every level, so this keeps
+// large filters from overflowing the stack.
+const DEPTH_LIMIT: usize = 64;
+
+/// Parsed filter, for applying the same expression to many values.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # use json::Filter;
+/// # fn main() {
+/// let filter = Filter::parse("{id, total: (.prices | add)}").unwrap();
+///
+/// let order = object!{ "id" => 7, "prices" => array![1, 2.5], "internal" => true };
+///
+/// assert_eq!(filter.apply(&order).unwrap(), [object!{ "id" => 7, "total" => 3.5 }]);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Filter {
+    source: String,
+    ast: Ast,
+}
+
+/// Apply the filter `source` to `value`, returning all of its outputs. Same
+/// as `Filter::parse` followed by `Filter::apply`.
+pub fn filter(value: &JsonValue, source: &str) -> Result<Vec<JsonValue>> {
+    Filter::parse(source)?.apply(value)
+}
+
+impl Filter {
+    /// Parse a filter. Errors are `Error::InvalidFilter`, with the byte
+    /// offset of the problem in `source`.
+    pub fn parse(source: &str) -> Result<Filter> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            index: 0,
+            end: source.len(),
+            depth: 0,
+        };
+
+        let ast = parser.pipe(true)?;
+
+        match parser.peek() {
+            None        => Ok(Filter { source: source.into(), ast }),
+            Some(token) => Err(parser.unexpected(token)),
+        }
+    }
+
+    /// Apply the filter to `value`, returning all of its outputs. Errors are
+    /// `Error::WrongType` for values of types the filter can't handle.
+    pub fn apply(&self, value: &JsonValue) -> Result<Vec<JsonValue>> {
+        self.ast.eval(value)
+    }
+}
+
+impl fmt::Debug for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Filter").field(&self.source).finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Builtin {
+    Select,
+    Map,
+    Has,
+    Length,
+    Keys,
+    Type,
+    Not,
+    Empty,
+    Add,
+    Sort,
+    First,
+    Last,
+    ToString,
+    ToNumber,
+}
+
+impl Builtin {
+    fn find(name: &str, arity: usize) -> Option<Builtin> {
+        let builtin = match (name, arity) {
+            ("select", 1)   => Builtin::Select,
+            ("map", 1)      => Builtin::Map,
+            ("has", 1)      => Builtin::Has,
+            ("length", 0)   => Builtin::Length,
+            ("keys", 0)     => Builtin::Keys,
+            ("type", 0)     => Builtin::Type,
+            ("not", 0)      => Builtin::Not,
+            ("empty", 0)    => Builtin::Empty,
+            ("add", 0)      => Builtin::Add,
+            ("sort", 0)     => Builtin::Sort,
+            ("first", 0)    => Builtin::First,
+            ("last", 0)     => Builtin::Last,
+            ("tostring", 0) => Builtin::ToString,
+            ("tonumber", 0) => Builtin::ToNumber,
+            _               => return None,
+        };
+
+        Some(builtin)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Ast {
+    Identity,
+    Recurse,
+    Literal(JsonValue),
+    // Member of the input, by a key or index computed from the input
+    Index(Box<Ast>, Box<Ast>),
+    Iterate(Box<Ast>),
+    Try(Box<Ast>),
+    Pipe(Box<Ast>, Box<Ast>),
+    Comma(Vec<Ast>),
+    Array(Option<Box<Ast>>),
+    Object(Vec<(Ast, Ast)>),
+    Binary(Op, Box<Ast>, Box<Ast>),
+    And(Box<Ast>, Box<Ast>),
+    Or(Box<Ast>, Box<Ast>),
+    Call(Builtin, Vec<Ast>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Dot,
+    Recurse,
+    Field(String),
+    Ident(String),
+    Number(Number),
+    String(String),
+    Punct(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Token::Dot               => f.write_str("."),
+            Token::Recurse           => f.write_str(".."),
+            Token::Field(ref name)   => write!(f, ".{}", name),
+            Token::Ident(ref name)   => f.write_str(name),
+            Token::Number(number)    => write!(f, "{}", number),
+            Token::String(ref value) => write!(f, "{:?}", value),
+            Token::Punct(punct)      => f.write_str(punct),
+        }
+    }
+}
+
+// Longest first, so `<=` isn't read as `<` followed by `=`
+const PUNCTUATION: [&str; 22] = [
+    "==", "!=", "<=", ">=",
+    "|", ",", "(", ")", "[", "]", "{", "}", ":", ";", "?",
+    "+", "-", "*", "/", "%", "<", ">",
+];
+
+fn invalid(message: String, position: usize) -> Error {
+    Error::InvalidFilter { message, position }
+}
+
+fn is_ident_start(ch: u8) -> bool {
+    ch.is_ascii_alphabetic() || ch == b'_'
+}
+
+fn is_ident(ch: u8) -> bool {
+    ch.is_ascii_alphanumeric() || ch == b'_'
+}
+
+// Split `source` into tokens along with their byte offsets.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let start = index;
+        let ch = bytes[index];
+
+        let token = match ch {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                index += 1;
+                continue;
+            },
+            b'.' if bytes.get(index + 1) == Some(&b'.') => {
+                index += 2;
+                Token::Recurse
+            },
+            b'.' if bytes.get(index + 1).is_some_and(|&ch| is_ident_start(ch)) => {
+                index += 1;
+                while index < bytes.len() && is_ident(bytes[index]) {
+                    index += 1;
+                }
+                Token::Field(source[start + 1 .. index].into())
+            },
+            b'.' => {
+                index += 1;
+                Token::Dot
+            },
+            b'"' => {
+                index += 1;
+                loop {
+                    match bytes.get(index) {
+                        Some(b'"')  => break,
+                        Some(b'\\') => index += 2,
+                        Some(_)     => index += 1,
+                        None        => return Err(invalid("unterminated string".into(), start)),
+                    }
+                }
+                index += 1;
+
+                match crate::parse(&source[start .. index]) {
+                    Ok(JsonValue::Short(value))  => Token::String(value.as_str().into()),
+                    Ok(JsonValue::String(value)) => Token::String(value),
+                    _                            => return Err(invalid("invalid string".into(), start)),
+                }
+            },
+            b'0' ..= b'9' => {
+                while index < bytes.len() && (bytes[index].is_ascii_digit() || bytes[index] == b'.') {
+                    index += 1;
+                }
+                if index < bytes.len() && (bytes[index] == b'e' || bytes[index] == b'E') {
+                    index += 1;
+                    if index < bytes.len() && (bytes[index] == b'+' || bytes[index] == b'-') {
+                        index += 1;
+                    }
+                    while index < bytes.len() && bytes[index].is_ascii_digit() {
+                        index += 1;
+                    }
+                }
+
+                match crate::parse(&source[start .. index]) {
+                    Ok(JsonValue::Number(number)) => Token::Number(number),
+                    _                             => return Err(invalid("invalid number".into(), start)),
+                }
+            },
+            _ if is_ident_start(ch) => {
+                while index < bytes.len() && is_ident(bytes[index]) {
+                    index += 1;
+                }
+                Token::Ident(source[start .. index].into())
+            },
+            _ => match PUNCTUATION.iter().find(|punct| source[index ..].starts_with(*punct)) {
+                Some(punct) => {
+                    index += punct.len();
+                    Token::Punct(punct)
+                },
+                None => {
+                    let ch = source[index ..].chars().next().unwrap_or('\0');
+                    return Err(invalid(format!("unexpected `{}`", ch), start));
+                },
+            },
+        };
+
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+
+    // Length of the source, the position of errors at the end
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|entry| &entry.0)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).map(|entry| entry.0.clone());
+        self.index += 1;
+        token
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.index).map_or(self.end, |entry| entry.1)
+    }
+
+    fn unexpected(&self, token: &Token) -> Error {
+        invalid(format!("unexpected `{}`", token), self.position())
+    }
+
+    fn error(&self) -> Error {
+        match self.peek() {
+            Some(token) => self.unexpected(token),
+            None        => invalid("unexpected end of filter".into(), self.end),
+        }
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        match self.peek() {
+            Some(Token::Punct(found)) if *found == punct => {
+                self.index += 1;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<()> {
+        match self.eat(punct) {
+            true  => Ok(()),
+            false => Err(self.error()),
+        }
+    }
+
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(found)) if found == name)
+    }
+
+    // Go one level deeper into the expression. Callers reset `depth` once
+    // they are done with the level, errors end parsing anyway.
+    fn enter(&mut self) -> Result<()> {
+        if self.depth == DEPTH_LIMIT {
+            return Err(Error::ExceededDepthLimit);
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn nested<F>(&mut self, parse: F) -> Result<Ast>
+    where
+        F: FnOnce(&mut Parser) -> Result<Ast>,
+    {
+        let depth = self.depth;
+
+        self.enter()?;
+        let ast = parse(self);
+        self.depth = depth;
+
+        ast
+    }
+
+    // `a | b`, and with `comma` set, `a, b`. Both are right associative,
+    // with `|` binding the loosest.
+    fn pipe(&mut self, comma: bool) -> Result<Ast> {
+        let depth = self.depth;
+        let mut left = self.or()?;
+
+        if comma && self.eat(",") {
+            let mut all = vec![left, self.or()?];
+
+            while self.eat(",") {
+                all.push(self.or()?);
+            }
+
+            left = Ast::Comma(all);
+        }
+
+        if self.eat("|") {
+            self.enter()?;
+            left = Ast::Pipe(Box::new(left), Box::new(self.pipe(comma)?));
+        }
+
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Ast> {
+        let depth = self.depth;
+        let mut left = self.and()?;
+
+        while self.is_ident("or") {
+            self.index += 1;
+            self.enter()?;
+            left = Ast::Or(Box::new(left), Box::new(self.and()?));
+        }
+
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Ast> {
+        let depth = self.depth;
+        let mut left = self.comparison()?;
+
+        while self.is_ident("and") {
+            self.index += 1;
+            self.enter()?;
+            left = Ast::And(Box::new(left), Box::new(self.comparison()?));
+        }
+
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Ast> {
+        let left = self.additive()?;
+
+        let op = match self.peek() {
+            Some(Token::Punct("==")) => Op::Eq,
+            Some(Token::Punct("!=")) => Op::Ne,
+            Some(Token::Punct("<"))  => Op::Lt,
+            Some(Token::Punct("<=")) => Op::Le,
+            Some(Token::Punct(">"))  => Op::Gt,
+            Some(Token::Punct(">=")) => Op::Ge,
+            _                        => return Ok(left),
+        };
+
+        self.index += 1;
+
+        Ok(Ast::Binary(op, Box::new(left), Box::new(self.additive()?)))
+    }
+
+    fn additive(&mut self) -> Result<Ast> {
+        let depth = self.depth;
+        let mut left = self.multiplicative()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("+")) => Op::Add,
+                Some(Token::Punct("-")) => Op::Sub,
+                _                       => break,
+            };
+
+            self.index += 1;
+            self.enter()?;
+            left = Ast::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> Result<Ast> {
+        let depth = self.depth;
+        let mut left = self.postfix()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("*")) => Op::Mul,
+                Some(Token::Punct("/")) => Op::Div,
+                Some(Token::Punct("%")) => Op::Rem,
+                _                       => break,
+            };
+
+            self.index += 1;
+            self.enter()?;
+            left = Ast::Binary(op, Box::new(left), Box::new(self.postfix()?));
+        }
+
+        self.depth = depth;
+        Ok(left)
+    }
+
+    // A term followed by any number of `.foo`, `[...]` and `?`.
+    fn postfix(&mut self) -> Result<Ast> {
+        let depth = self.depth;
+        let mut ast = self.term()?;
+
+        loop {
+            ast = match self.peek() {
+                Some(Token::Field(_)) => match self.next() {
+                    Some(Token::Field(name)) => field(ast, name),
+                    _                        => unreachable!(),
+                },
+                Some(Token::Dot) => match self.tokens.get(self.index + 1).map(|entry| &entry.0) {
+                    Some(Token::String(_)) => {
+                        self.index += 1;
+                        self.quoted_field(ast)?
+                    },
+                    Some(Token::Punct("[")) => {
+                        self.index += 1;
+                        self.bracket(ast)?
+                    },
+                    _ => return Err(self.error()),
+                },
+                Some(Token::Punct("[")) => self.bracket(ast)?,
+                Some(Token::Punct("?")) => {
+                    self.index += 1;
+                    Ast::Try(Box::new(ast))
+                },
+                _ => break,
+            };
+
+            self.enter()?;
+        }
+
+        self.depth = depth;
+        Ok(ast)
+    }
+
+    fn quoted_field(&mut self, target: Ast) -> Result<Ast> {
+        match self.next() {
+            Some(Token::String(name)) => Ok(field(target, name)),
+            _                         => unreachable!(),
+        }
+    }
+
+    // `[]` or `[index]` following `target`.
+    fn bracket(&mut self, target: Ast) -> Result<Ast> {
+        self.expect("[")?;
+
+        if self.eat("]") {
+            return Ok(Ast::Iterate(Box::new(target)));
+        }
+
+        let index = self.nested(|parser| parser.pipe(true))?;
+        self.expect("]")?;
+
+        Ok(Ast::Index(Box::new(target), Box::new(index)))
+    }
+
+    fn term(&mut self) -> Result<Ast> {
+        let token = match self.next() {
+            Some(token) => token,
+            None        => return Err(invalid("unexpected end of filter".into(), self.end)),
+        };
+
+        Ok(match token {
+            Token::Dot => match self.peek() {
+                Some(Token::String(_)) => self.quoted_field(Ast::Identity)?,
+                _                      => Ast::Identity,
+            },
+            Token::Recurse       => Ast::Recurse,
+            Token::Field(name)   => field(Ast::Identity, name),
+            Token::Number(value) => Ast::Literal(value.into()),
+            Token::String(value) => Ast::Literal(value.into()),
+            Token::Punct("(") => {
+                let ast = self.nested(|parser| parser.pipe(true))?;
+                self.expect(")")?;
+                ast
+            },
+            Token::Punct("[") => match self.eat("]") {
+                true  => Ast::Array(None),
+                false => {
+                    let ast = self.nested(|parser| parser.pipe(true))?;
+                    self.expect("]")?;
+                    Ast::Array(Some(Box::new(ast)))
+                },
+            },
+            Token::Punct("{") => self.nested(Parser::object)?,
+            Token::Punct("-") => match self.peek() {
+                Some(&Token::Number(value)) => {
+                    self.index += 1;
+                    Ast::Literal((-value).into())
+                },
+                _ => {
+                    let ast = self.nested(Parser::postfix)?;
+                    Ast::Binary(Op::Sub, Box::new(Ast::Literal(0.into())), Box::new(ast))
+                },
+            },
+            Token::Ident(name) => match &*name {
+                "null"  => Ast::Literal(JsonValue::Null),
+                "true"  => Ast::Literal(true.into()),
+                "false" => Ast::Literal(false.into()),
+                "and" | "or" => {
+                    self.index -= 1;
+                    return Err(self.error());
+                },
+                _ => self.call(name)?,
+            },
+            Token::Punct(_) => {
+                self.index -= 1;
+                return Err(self.error());
+            },
+        })
+    }
+
+    fn call(&mut self, name: String) -> Result<Ast> {
+        let position = self.tokens[self.index - 1].1;
+        let mut args = Vec::new();
+
+        if self.eat("(") {
+            loop {
+                args.push(self.nested(|parser| parser.pipe(true))?);
+
+                if !self.eat(";") {
+                    break;
+                }
+            }
+            self.expect(")")?;
+        }
+
+        match Builtin::find(&name, args.len()) {
+            Some(builtin) => Ok(Ast::Call(builtin, args)),
+            None          => Err(invalid(format!("unknown function `{}/{}`", name, args.len()), position)),
+        }
+    }
+
+    // Entries of `{...}`, after the opening brace.
+    fn object(&mut self) -> Result<Ast> {
+        let mut entries = Vec::new();
+
+        if self.eat("}") {
+            return Ok(Ast::Object(entries));
+        }
+
+        loop {
+            let key = match self.next() {
+                Some(Token::Ident(name)) | Some(Token::String(name)) => Ast::Literal(name.into()),
+                Some(Token::Punct("(")) => {
+                    let key = self.pipe(true)?;
+                    self.expect(")")?;
+                    key
+                },
+                _ => {
+                    self.index -= 1;
+                    return Err(self.error());
+                },
+            };
+
+            let value = match self.eat(":") {
+                true  => self.pipe(false)?,
+                false => match key {
+                    Ast::Literal(ref name) => field(Ast::Identity, name.as_str().unwrap_or("").into()),
+                    _                      => return Err(self.error()),
+                },
+            };
+
+            entries.push((key, value));
+
+            if !self.eat(",") {
+                break;
+            }
+        }
+
+        self.expect("}")?;
+
+        Ok(Ast::Object(entries))
+    }
+}
+
+fn field(target: Ast, name: String) -> Ast {
+    Ast::Index(Box::new(target), Box::new(Ast::Literal(name.into())))
+}
+
+fn truthy(value: &JsonValue) -> bool {
+    !matches!(*value, JsonValue::Null | JsonValue::Boolean(false))
+}
+
+// Position of the type in the order values are compared in
+fn rank(value: &JsonValue) -> u8 {
+    match *value {
+        JsonValue::Null                          => 0,
+        JsonValue::Boolean(false)                => 1,
+        JsonValue::Boolean(true)                 => 2,
+        JsonValue::Number(_)                     => 3,
+        JsonValue::Short(_) | JsonValue::String(_) => 4,
+        JsonValue::Array(_)                      => 5,
+        JsonValue::Object(_)                     => 6,
+        JsonValue::Binary(_)                     => 7,
+    }
+}
+
+fn sorted_keys(object: &Object) -> Vec<&str> {
+    let mut keys: Vec<&str> = object.keys().collect();
+    keys.sort_unstable();
+    keys
+}
+
+// Total order of values, see the module docs. Objects are compared by their
+// sorted keys first, then by the values under them.
+fn compare(a: &JsonValue, b: &JsonValue) -> Ordering {
+    match (a, b) {
+        (JsonValue::Number(a), JsonValue::Number(b)) => {
+            f64::from(*a).partial_cmp(&f64::from(*b)).unwrap_or(Ordering::Equal)
+        },
+        (JsonValue::Array(a), JsonValue::Array(b)) => {
+            a.iter()
+             .zip(b.iter())
+             .map(|(a, b)| compare(a, b))
+             .find(|&ordering| ordering != Ordering::Equal)
+             .unwrap_or_else(|| a.len().cmp(&b.len()))
+        },
+        (JsonValue::Object(a), JsonValue::Object(b)) => {
+            let keys = sorted_keys(a);
+
+            keys.cmp(&sorted_keys(b)).then_with(|| {
+                keys.iter()
+                    .map(|&key| compare(&a[key], &b[key]))
+                    .find(|&ordering| ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+        },
+        _ => match (a.as_str(), b.as_str()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            _                  => rank(a).cmp(&rank(b)),
+        },
+    }
+}
+
+fn number(value: &JsonValue) -> Result<f64> {
+    match *value {
+        JsonValue::Number(number) => Ok(number.into()),
+        _                         => Err(Error::wrong_type("number", value)),
+    }
+}
+
+fn binary(op: Op, left: &JsonValue, right: &JsonValue) -> Result<JsonValue> {
+    let ordering = || compare(left, right);
+
+    Ok(match op {
+        Op::Eq => (ordering() == Ordering::Equal).into(),
+        Op::Ne => (ordering() != Ordering::Equal).into(),
+        Op::Lt => (ordering() == Ordering::Less).into(),
+        Op::Le => (ordering() != Ordering::Greater).into(),
+        Op::Gt => (ordering() == Ordering::Greater).into(),
+        Op::Ge => (ordering() != Ordering::Less).into(),
+        Op::Add => match (left, right) {
+            (JsonValue::Null, _) => right.clone(),
+            (_, JsonValue::Null) => left.clone(),
+            (JsonValue::Array(a), JsonValue::Array(b)) => {
+                JsonValue::Array(a.iter().chain(b.iter()).cloned().collect())
+            },
+            (JsonValue::Object(a), JsonValue::Object(b)) => {
+                let mut object = a.clone();
+                object.merge_in(b.clone());
+                JsonValue::Object(object)
+            },
+            _ => match (left.as_str(), right.as_str()) {
+                (Some(a), Some(b))       => format!("{}{}", a, b).into(),
+                (Some(_), None)          => return Err(Error::wrong_type("string", right)),
+                _                        => (number(left)? + number(right)?).into(),
+            },
+        },
+        Op::Sub => match (left, right) {
+            (JsonValue::Array(a), JsonValue::Array(b)) => JsonValue::Array(
+                a.iter()
+                 .filter(|member| !b.iter().any(|other| compare(member, other) == Ordering::Equal))
+                 .cloned()
+                 .collect()
+            ),
+            _ => (number(left)? - number(right)?).into(),
+        },
+        Op::Mul => (number(left)? * number(right)?).into(),
+        Op::Div => {
+            let (a, b) = (number(left)?, number(right)?);
+
+            if b == 0.0 {
+                return Err(Error::wrong_type("non-zero number", right));
+            }
+
+            (a / b).into()
+        },
+        // Same as in jq, on the integer parts of both sides
+        Op::Rem => {
+            let (a, b) = (number(left)? as i64, number(right)? as i64);
+
+            match a.checked_rem(b) {
+                Some(rem) => rem.into(),
+                None      => return Err(Error::wrong_type("non-zero number", right)),
+            }
+        },
+    })
+}
+
+// Member of `target` under `index`. Members of `null` are `null`.
+fn index(target: &JsonValue, index: &JsonValue) -> Result<JsonValue> {
+    match (target, index) {
+        (JsonValue::Null, JsonValue::Number(_)) => Ok(JsonValue::Null),
+        (JsonValue::Array(vec), JsonValue::Number(number)) => {
+            let index = f64::from(*number).floor();
+            let index = if index < 0.0 { index + vec.len() as f64 } else { index };
+
+            match index >= 0.0 {
+                true  => Ok(vec.get(index as usize).cloned().unwrap_or(JsonValue::Null)),
+                false => Ok(JsonValue::Null),
+            }
+        },
+        (_, JsonValue::Number(_)) => Err(Error::wrong_type("array", target)),
+        _ => match index.as_str() {
+            Some(key) => match *target {
+                JsonValue::Null               => Ok(JsonValue::Null),
+                JsonValue::Object(ref object) => Ok(object.get(key).cloned().unwrap_or(JsonValue::Null)),
+                _                             => Err(Error::wrong_type("object", target)),
+            },
+            None => Err(Error::wrong_type("string or number", index)),
+        },
+    }
+}
+
+fn members(value: &JsonValue) -> Result<Vec<JsonValue>> {
+    match *value {
+        JsonValue::Array(ref vec)     => Ok(vec.clone()),
+        JsonValue::Object(ref object) => Ok(object.values().cloned().collect()),
+        _                             => Err(Error::wrong_type("array or object", value)),
+    }
+}
+
+fn recurse(value: &JsonValue, out: &mut Vec<JsonValue>) {
+    out.push(value.clone());
+
+    match *value {
+        JsonValue::Array(ref vec)     => vec.iter().for_each(|member| recurse(member, out)),
+        JsonValue::Object(ref object) => object.values().for_each(|member| recurse(member, out)),
+        _                             => {},
+    }
+}
+
+impl Ast {
+    fn eval(&self, input: &JsonValue) -> Result<Vec<JsonValue>> {
+        Ok(match *self {
+            Ast::Identity => vec![input.clone()],
+            Ast::Recurse => {
+                let mut out = Vec::new();
+                recurse(input, &mut out);
+                out
+            },
+            Ast::Literal(ref value) => vec![value.clone()],
+            Ast::Index(ref target, ref key) => {
+                let mut out = Vec::new();
+                for key in key.eval(input)? {
+                    for target in target.eval(input)? {
+                        out.push(index(&target, &key)?);
+                    }
+                }
+                out
+            },
+            Ast::Iterate(ref target) => {
+                let mut out = Vec::new();
+                for target in target.eval(input)? {
+                    out.extend(members(&target)?);
+                }
+                out
+            },
+            Ast::Try(ref ast) => ast.eval(input).unwrap_or_default(),
+            Ast::Pipe(ref left, ref right) => {
+                let mut out = Vec::new();
+                for value in left.eval(input)? {
+                    out.extend(right.eval(&value)?);
+                }
+                out
+            },
+            Ast::Comma(ref all) => {
+                let mut out = Vec::new();
+                for ast in all {
+                    out.extend(ast.eval(input)?);
+                }
+                out
+            },
+            Ast::Array(None) => vec![JsonValue::new_array()],
+            Ast::Array(Some(ref ast)) => vec![JsonValue::Array(ast.eval(input)?)],
+            Ast::Object(ref entries) => {
+                let mut objects = vec![Object::new()];
+
+                for (key, value) in entries {
+                    let keys = key.eval(input)?;
+                    let values = value.eval(input)?;
+                    let mut next = Vec::with_capacity(objects.len() * keys.len() * values.len());
+
+                    for object in &objects {
+                        for key in &keys {
+                            let key = key.as_str().ok_or_else(|| Error::wrong_type("string", key))?;
+
+                            for value in &values {
+                                let mut object = object.clone();
+                                object.insert(key, value.clone());
+                                next.push(object);
+                            }
+                        }
+                    }
+
+                    objects = next;
+                }
+
+                objects.into_iter().map(JsonValue::Object).collect()
+            },
+            Ast::Binary(op, ref left, ref right) => {
+                let mut out = Vec::new();
+                for right in right.eval(input)? {
+                    for left in left.eval(input)? {
+                        out.push(binary(op, &left, &right)?);
+                    }
+                }
+                out
+            },
+            Ast::And(ref left, ref right) | Ast::Or(ref left, ref right) => {
+                let and = matches!(*self, Ast::And(..));
+                let mut out = Vec::new();
+
+                for left in left.eval(input)? {
+                    // `false and x` and `true or x` don't look at `x`
+                    if truthy(&left) != and {
+                        out.push((!and).into());
+                        continue;
+                    }
+                    for right in right.eval(input)? {
+                        out.push(truthy(&right).into());
+                    }
+                }
+                out
+            },
+            Ast::Call(builtin, ref args) => call(builtin, args, input)?,
+        })
+    }
+}
+
+fn call(builtin: Builtin, args: &[Ast], input: &JsonValue) -> Result<Vec<JsonValue>> {
+    Ok(match builtin {
+        Builtin::Select => {
+            let mut out = Vec::new();
+            for condition in args[0].eval(input)? {
+                if truthy(&condition) {
+                    out.push(input.clone());
+                }
+            }
+            out
+        },
+        Builtin::Map => {
+            let mut array = Vec::new();
+            for member in members(input)? {
+                array.extend(args[0].eval(&member)?);
+            }
+            vec![JsonValue::Array(array)]
+        },
+        Builtin::Has => {
+            let mut out = Vec::new();
+            for key in args[0].eval(input)? {
+                let has = match (input, &key) {
+                    (JsonValue::Object(object), _) => match key.as_str() {
+                        Some(key) => object.get(key).is_some(),
+                        None      => return Err(Error::wrong_type("string", &key)),
+                    },
+                    (JsonValue::Array(vec), JsonValue::Number(number)) => {
+                        let index = f64::from(*number);
+                        index >= 0.0 && index < vec.len() as f64
+                    },
+                    (JsonValue::Array(_), _) => return Err(Error::wrong_type("number", &key)),
+                    _                        => return Err(Error::wrong_type("object or array", input)),
+                };
+                out.push(has.into());
+            }
+            out
+        },
+        Builtin::Length => vec![match *input {
+            JsonValue::Null               => 0.into(),
+            JsonValue::Number(number)     => f64::from(number).abs().into(),
+            JsonValue::Array(ref vec)     => vec.len().into(),
+            JsonValue::Object(ref object) => object.len().into(),
+            _ => match input.as_str() {
+                Some(string) => string.chars().count().into(),
+                None         => return Err(Error::wrong_type("string, array or object", input)),
+            },
+        }],
+        Builtin::Keys => vec![match *input {
+            JsonValue::Object(ref object) => sorted_keys(object).into(),
+            JsonValue::Array(ref vec)     => (0 .. vec.len()).collect::<Vec<_>>().into(),
+            _                             => return Err(Error::wrong_type("object or array", input)),
+        }],
+        Builtin::Type   => vec![input.type_name().into()],
+        Builtin::Not    => vec![(!truthy(input)).into()],
+        Builtin::Empty  => Vec::new(),
+        Builtin::Add    => {
+            let mut sum = JsonValue::Null;
+            for member in members(input)? {
+                sum = binary(Op::Add, &sum, &member)?;
+            }
+            vec![sum]
+        },
+        Builtin::Sort => match *input {
+            JsonValue::Array(ref vec) => {
+                let mut vec = vec.clone();
+                vec.sort_by(compare);
+                vec![JsonValue::Array(vec)]
+            },
+            _ => return Err(Error::wrong_type("array", input)),
+        },
+        Builtin::First => vec![index(input, &0.into())?],
+        Builtin::Last  => vec![index(input, &(-1).into())?],
+        Builtin::ToString => vec![match input.as_str() {
+            Some(_) => input.clone(),
+            None    => input.dump().into(),
+        }],
+        Builtin::ToNumber => vec![match *input {
+            JsonValue::Number(_) => input.clone(),
+            _ => match input.as_str().map(crate::parse) {
+                Some(Ok(number @ JsonValue::Number(_))) => number,
+                _ => return Err(Error::wrong_type("number or numeric string", input)),
+            },
+        }],
+    })
+}
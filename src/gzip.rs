@@ -0,0 +1,25 @@
+// Decompression of gzip and Zstandard input for `parse_from_reader`, by the
+// `flate2` and `zstd` crates. Everything is decompressed into memory at once,
+// as the value is built in memory anyway.
+
+use std::io::{ self, Read };
+
+use flate2::read::MultiGzDecoder;
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+// Decompress all members or frames of the gzip or Zstandard `data`.
+pub fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() * 4);
+
+    let result = match data.starts_with(&ZSTD_MAGIC) {
+        true  => zstd::stream::copy_decode(data, &mut out),
+        false => MultiGzDecoder::new(data).read_to_end(&mut out).map(drop),
+    };
+
+    // Truncated input ends early rather than being invalid, which is the
+    // same thing for a reader that has been read to the end
+    result.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok(out)
+}
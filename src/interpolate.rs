@@ -0,0 +1,136 @@
+use crate::JsonValue;
+
+/// Replaces `${NAME}` placeholders in all strings nested in `value` with the
+/// values returned by `lookup`, such as environment variables referenced by
+/// a config file. A string consisting of just a placeholder is replaced by
+/// the value itself, keeping its type, while placeholders inside of longer
+/// strings are replaced by the contents of string values or the JSON of
+/// other values. Object keys are left alone, and `$${` can be used for a
+/// literal `${`.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let mut config = object!{
+///     "url" => "http://${HOST}:${PORT}/",
+///     "port" => "${PORT}",
+///     "home" => "${HOME}/data",
+///     "raw" => "$${PORT}"
+/// };
+///
+/// let missing = json::interpolate(&mut config, |name| match name {
+///     "HOST" => Some("localhost".into()),
+///     "PORT" => Some(8080.into()),
+///     _      => None,
+/// });
+///
+/// assert_eq!(config, object!{
+///     "url" => "http://localhost:8080/",
+///     "port" => 8080,
+///     "home" => "${HOME}/data",
+///     "raw" => "${PORT}"
+/// });
+/// assert_eq!(missing, ["HOME"]);
+/// # }
+/// ```
+///
+/// Placeholders `lookup` returns `None` for are left as they are, and their
+/// names are returned in the order they were found in.
+pub fn interpolate<F>(value: &mut JsonValue, mut lookup: F) -> Vec<String>
+where F: FnMut(&str) -> Option<JsonValue> {
+    let mut missing = Vec::new();
+
+    walk(value, &mut lookup, &mut missing);
+    missing
+}
+
+fn walk<F>(value: &mut JsonValue, lookup: &mut F, missing: &mut Vec<String>)
+where F: FnMut(&str) -> Option<JsonValue> {
+    match *value {
+        JsonValue::Array(ref mut members) => {
+            for member in members.iter_mut() {
+                walk(member, lookup, missing);
+            }
+        },
+        JsonValue::Object(ref mut object) => {
+            for (_, member) in object.iter_mut() {
+                walk(member, lookup, missing);
+            }
+        },
+        _ => {
+            if let Some(replaced) = value.as_str().and_then(|source| substitute(source, lookup, missing)) {
+                *value = replaced;
+            }
+        },
+    }
+}
+
+// New value of the string `source`, `None` if it has nothing to replace.
+fn substitute<F>(source: &str, lookup: &mut F, missing: &mut Vec<String>) -> Option<JsonValue>
+where F: FnMut(&str) -> Option<JsonValue> {
+    if !source.contains('$') {
+        return None;
+    }
+
+    if let Some(name) = source.strip_prefix("${").and_then(|rest| rest.strip_suffix('}')) {
+        if !name.is_empty() && !name.contains(['{', '}']) {
+            let value = lookup(name);
+
+            if value.is_none() {
+                missing.push(name.into());
+            }
+            return value;
+        }
+    }
+
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    let mut changed = false;
+
+    while let Some(index) = rest.find('$') {
+        out.push_str(&rest[.. index]);
+
+        let after = &rest[index + 1 ..];
+
+        if let Some(tail) = after.strip_prefix("${") {
+            out.push_str("${");
+            rest = tail;
+            changed = true;
+            continue;
+        }
+
+        let placeholder = after.strip_prefix('{').and_then(|inner| {
+            inner.find('}').map(|end| (&inner[.. end], &inner[end + 1 ..]))
+        });
+
+        match placeholder {
+            Some((name, tail)) if !name.is_empty() && !name.contains('{') => {
+                match lookup(name) {
+                    Some(value) => {
+                        match value.as_str() {
+                            Some(string) => out.push_str(string),
+                            None         => out.push_str(&value.dump()),
+                        }
+                        changed = true;
+                    },
+                    None => {
+                        missing.push(name.into());
+                        out.push_str(&rest[index .. rest.len() - tail.len()]);
+                    },
+                }
+                rest = tail;
+            },
+            _ => {
+                out.push('$');
+                rest = after;
+            },
+        }
+    }
+
+    if !changed {
+        return None;
+    }
+
+    out.push_str(rest);
+    Some(out.into())
+}
@@ -0,0 +1,1120 @@
+//! JMESPath queries over `JsonValue`s, as specified at
+//! <https://jmespath.org/specification.html>. Requires the `jmespath`
+//! feature.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let data = object!{
+//!     "Reservations" => array![
+//!         object!{ "Instances" => array![
+//!             object!{ "InstanceId" => "i-1", "State" => object!{ "Name" => "running" } },
+//!             object!{ "InstanceId" => "i-2", "State" => object!{ "Name" => "stopped" } }
+//!         ] },
+//!         object!{ "Instances" => array![
+//!             object!{ "InstanceId" => "i-3", "State" => object!{ "Name" => "running" } }
+//!         ] }
+//!     ]
+//! };
+//!
+//! let running = json::jmespath::search(&data, "Reservations[].Instances[?State.Name == 'running'].InstanceId[]").unwrap();
+//!
+//! assert_eq!(running, array!["i-1", "i-3"]);
+//! # }
+//! ```
+//!
+//! All of the expression syntax is supported, along with the built-in
+//! functions of the specification. Slices work on arrays only.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::{ JsonValue, Error, Result };
+use crate::object::Object;
+
+// Depth of the parsed expression, see the same limit for `Filter`.
+const DEPTH_LIMIT: usize = 64;
+
+// Binding powers of the tokens, from the reference implementation. Tokens
+// binding less than `PROJECTION_STOP` end the right side of a projection.
+const PROJECTION_STOP: u8 = 10;
+const BP_PIPE: u8 = 1;
+const BP_OR: u8 = 2;
+const BP_AND: u8 = 3;
+const BP_COMPARATOR: u8 = 5;
+const BP_FLATTEN: u8 = 9;
+const BP_STAR: u8 = 20;
+const BP_FILTER: u8 = 21;
+const BP_DOT: u8 = 40;
+const BP_NOT: u8 = 45;
+const BP_LBRACE: u8 = 50;
+const BP_LBRACKET: u8 = 55;
+const BP_LPAREN: u8 = 60;
+
+/// Compiled JMESPath expression, for searching many values with it.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # use json::jmespath::Expression;
+/// # fn main() {
+/// let expression = Expression::compile("people[?age > `20`].name | sort(@)").unwrap();
+///
+/// let data = object!{
+///     "people" => array![
+///         object!{ "name" => "Sam", "age" => 30 },
+///         object!{ "name" => "Arya", "age" => 18 },
+///         object!{ "name" => "Jon", "age" => 25 }
+///     ]
+/// };
+///
+/// assert_eq!(expression.search(&data).unwrap(), array!["Jon", "Sam"]);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Expression {
+    source: String,
+    ast: Ast,
+}
+
+/// Search `value` with the expression `source`. Same as
+/// `Expression::compile` followed by `Expression::search`.
+pub fn search(value: &JsonValue, source: &str) -> Result<JsonValue> {
+    Expression::compile(source)?.search(value)
+}
+
+impl Expression {
+    /// Compile an expression. Syntax errors, as well as calls to unknown
+    /// functions and calls with the wrong number of arguments, are
+    /// `Error::InvalidFilter` with the byte offset of the problem.
+    pub fn compile(source: &str) -> Result<Expression> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            index: 0,
+            end: source.len(),
+            depth: 0,
+        };
+
+        let ast = parser.expression(0)?;
+
+        match parser.peek() {
+            Token::Eof => Ok(Expression { source: source.into(), ast }),
+            _          => Err(parser.error()),
+        }
+    }
+
+    /// Search `value`, returning what the expression evaluates to. Errors are
+    /// `Error::WrongType` for function arguments of the wrong type.
+    pub fn search(&self, value: &JsonValue) -> Result<JsonValue> {
+        self.ast.eval(value)
+    }
+
+    /// The source the expression was compiled from.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl fmt::Debug for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Expression").field(&self.source).finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Abs,
+    Avg,
+    Ceil,
+    Contains,
+    EndsWith,
+    Floor,
+    Join,
+    Keys,
+    Length,
+    Map,
+    Max,
+    MaxBy,
+    Merge,
+    Min,
+    MinBy,
+    NotNull,
+    Reverse,
+    Sort,
+    SortBy,
+    StartsWith,
+    Sum,
+    ToArray,
+    ToString,
+    ToNumber,
+    Type,
+    Values,
+}
+
+impl Function {
+    // The function, with the number of its arguments and whether it takes
+    // any number of arguments past that.
+    fn find(name: &str) -> Option<(Function, usize, bool)> {
+        let function = match name {
+            "abs"         => (Function::Abs, 1, false),
+            "avg"         => (Function::Avg, 1, false),
+            "ceil"        => (Function::Ceil, 1, false),
+            "contains"    => (Function::Contains, 2, false),
+            "ends_with"   => (Function::EndsWith, 2, false),
+            "floor"       => (Function::Floor, 1, false),
+            "join"        => (Function::Join, 2, false),
+            "keys"        => (Function::Keys, 1, false),
+            "length"      => (Function::Length, 1, false),
+            "map"         => (Function::Map, 2, false),
+            "max"         => (Function::Max, 1, false),
+            "max_by"      => (Function::MaxBy, 2, false),
+            "merge"       => (Function::Merge, 1, true),
+            "min"         => (Function::Min, 1, false),
+            "min_by"      => (Function::MinBy, 2, false),
+            "not_null"    => (Function::NotNull, 1, true),
+            "reverse"     => (Function::Reverse, 1, false),
+            "sort"        => (Function::Sort, 1, false),
+            "sort_by"     => (Function::SortBy, 2, false),
+            "starts_with" => (Function::StartsWith, 2, false),
+            "sum"         => (Function::Sum, 1, false),
+            "to_array"    => (Function::ToArray, 1, false),
+            "to_string"   => (Function::ToString, 1, false),
+            "to_number"   => (Function::ToNumber, 1, false),
+            "type"        => (Function::Type, 1, false),
+            "values"      => (Function::Values, 1, false),
+            _             => return None,
+        };
+
+        Some(function)
+    }
+
+    // Index of the argument that has to be an expression reference
+    fn expref_argument(self) -> Option<usize> {
+        match self {
+            Function::Map                                         => Some(0),
+            Function::MaxBy | Function::MinBy | Function::SortBy => Some(1),
+            _                                                     => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Ast {
+    Identity,
+    Field(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    // Right side evaluated on the result of the left, for both `a.b` and `a | b`
+    Sub(Box<Ast>, Box<Ast>),
+    Projection(Box<Ast>, Box<Ast>),
+    ValueProjection(Box<Ast>, Box<Ast>),
+    FilterProjection(Box<Ast>, Box<Ast>, Box<Ast>),
+    Flatten(Box<Ast>),
+    Or(Box<Ast>, Box<Ast>),
+    And(Box<Ast>, Box<Ast>),
+    Not(Box<Ast>),
+    Compare(Comparator, Box<Ast>, Box<Ast>),
+    MultiList(Vec<Ast>),
+    MultiHash(Vec<(String, Ast)>),
+    Literal(JsonValue),
+    Call(Function, Vec<Ast>),
+    Expref(Box<Ast>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Quoted(String),
+    Literal(JsonValue),
+    Number(i64),
+    Dot,
+    Star,
+    At,
+    Ampersand,
+    Pipe,
+    Or,
+    And,
+    Not,
+    Comparator(Comparator),
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Flatten,
+    Filter,
+    Comma,
+    Colon,
+    Eof,
+}
+
+impl Token {
+    fn binding_power(&self) -> u8 {
+        match *self {
+            Token::Pipe          => BP_PIPE,
+            Token::Or            => BP_OR,
+            Token::And           => BP_AND,
+            Token::Comparator(_) => BP_COMPARATOR,
+            Token::Flatten       => BP_FLATTEN,
+            Token::Star          => BP_STAR,
+            Token::Filter        => BP_FILTER,
+            Token::Dot           => BP_DOT,
+            Token::Not           => BP_NOT,
+            Token::LBrace        => BP_LBRACE,
+            Token::LBracket      => BP_LBRACKET,
+            Token::LParen        => BP_LPAREN,
+            _                    => 0,
+        }
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            Token::Ident(ref name)   => return f.write_str(name),
+            Token::Quoted(ref name)  => return write!(f, "{:?}", name),
+            Token::Literal(ref value) => return write!(f, "`{}`", value.dump()),
+            Token::Number(number)    => return write!(f, "{}", number),
+            Token::Dot               => ".",
+            Token::Star              => "*",
+            Token::At                => "@",
+            Token::Ampersand         => "&",
+            Token::Pipe              => "|",
+            Token::Or                => "||",
+            Token::And               => "&&",
+            Token::Not               => "!",
+            Token::Comparator(Comparator::Eq) => "==",
+            Token::Comparator(Comparator::Ne) => "!=",
+            Token::Comparator(Comparator::Lt) => "<",
+            Token::Comparator(Comparator::Le) => "<=",
+            Token::Comparator(Comparator::Gt) => ">",
+            Token::Comparator(Comparator::Ge) => ">=",
+            Token::LParen            => "(",
+            Token::RParen            => ")",
+            Token::LBrace            => "{",
+            Token::RBrace            => "}",
+            Token::LBracket          => "[",
+            Token::RBracket          => "]",
+            Token::Flatten           => "[]",
+            Token::Filter            => "[?",
+            Token::Comma             => ",",
+            Token::Colon             => ":",
+            Token::Eof               => "end of expression",
+        };
+
+        f.write_str(text)
+    }
+}
+
+fn invalid(message: String, position: usize) -> Error {
+    Error::InvalidFilter { message, position }
+}
+
+fn is_ident_start(ch: u8) -> bool {
+    ch.is_ascii_alphabetic() || ch == b'_'
+}
+
+fn is_ident(ch: u8) -> bool {
+    ch.is_ascii_alphanumeric() || ch == b'_'
+}
+
+// Index past the closing `quote` of a string starting at `start`, skipping
+// over backslash escapes.
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8) -> Result<usize> {
+    let mut index = start + 1;
+
+    loop {
+        match bytes.get(index) {
+            Some(&ch) if ch == quote => return Ok(index + 1),
+            Some(b'\\')              => index += 2,
+            Some(_)                  => index += 1,
+            None                     => return Err(invalid("unterminated string".into(), start)),
+        }
+    }
+}
+
+// Split `source` into tokens along with their byte offsets, ending with
+// `Token::Eof`.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let start = index;
+        let next = bytes.get(index + 1).copied();
+
+        let (token, len) = match bytes[index] {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                index += 1;
+                continue;
+            },
+            b'.' => (Token::Dot, 1),
+            b'*' => (Token::Star, 1),
+            b'@' => (Token::At, 1),
+            b'(' => (Token::LParen, 1),
+            b')' => (Token::RParen, 1),
+            b'{' => (Token::LBrace, 1),
+            b'}' => (Token::RBrace, 1),
+            b']' => (Token::RBracket, 1),
+            b',' => (Token::Comma, 1),
+            b':' => (Token::Colon, 1),
+            b'[' => match next {
+                Some(b']') => (Token::Flatten, 2),
+                Some(b'?') => (Token::Filter, 2),
+                _          => (Token::LBracket, 1),
+            },
+            b'&' => match next {
+                Some(b'&') => (Token::And, 2),
+                _          => (Token::Ampersand, 1),
+            },
+            b'|' => match next {
+                Some(b'|') => (Token::Or, 2),
+                _          => (Token::Pipe, 1),
+            },
+            b'!' => match next {
+                Some(b'=') => (Token::Comparator(Comparator::Ne), 2),
+                _          => (Token::Not, 1),
+            },
+            b'=' if next == Some(b'=') => (Token::Comparator(Comparator::Eq), 2),
+            b'<' => match next {
+                Some(b'=') => (Token::Comparator(Comparator::Le), 2),
+                _          => (Token::Comparator(Comparator::Lt), 1),
+            },
+            b'>' => match next {
+                Some(b'=') => (Token::Comparator(Comparator::Ge), 2),
+                _          => (Token::Comparator(Comparator::Gt), 1),
+            },
+            b'"' => {
+                let end = skip_quoted(bytes, index, b'"')?;
+
+                match crate::parse(&source[start .. end]) {
+                    Ok(name) => (Token::Quoted(name.as_str().unwrap_or("").into()), end - start),
+                    Err(_)   => return Err(invalid("invalid quoted identifier".into(), start)),
+                }
+            },
+            b'\'' => {
+                let end = skip_quoted(bytes, index, b'\'')?;
+
+                let raw = source[start + 1 .. end - 1].replace("\\\\", "\\").replace("\\'", "'");
+
+                (Token::Literal(raw.into()), end - start)
+            },
+            b'`' => {
+                let end = skip_quoted(bytes, index, b'`')?;
+
+                match crate::parse(&source[start + 1 .. end - 1].replace("\\`", "`")) {
+                    Ok(value) => (Token::Literal(value), end - start),
+                    Err(_)    => return Err(invalid("invalid literal".into(), start)),
+                }
+            },
+            b'-' | b'0' ..= b'9' => {
+                let digits = bytes[index + 1 ..].iter().take_while(|ch| ch.is_ascii_digit()).count();
+                let end = index + 1 + digits;
+
+                match source[start .. end].parse() {
+                    Ok(number) => (Token::Number(number), end - start),
+                    Err(_)     => return Err(invalid("invalid number".into(), start)),
+                }
+            },
+            ch if is_ident_start(ch) => {
+                let len = bytes[index ..].iter().take_while(|&&ch| is_ident(ch)).count();
+
+                (Token::Ident(source[start .. start + len].into()), len)
+            },
+            _ => {
+                let ch = source[index ..].chars().next().unwrap_or('\0');
+                return Err(invalid(format!("unexpected `{}`", ch), start));
+            },
+        };
+
+        tokens.push((token, start));
+        index += len;
+    }
+
+    tokens.push((Token::Eof, source.len()));
+
+    Ok(tokens)
+}
+
+// Top down operator precedence parser, following the reference
+// implementation.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        self.tokens.get(self.index + offset).map_or(&Token::Eof, |entry| &entry.0)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        self.index = (self.index + 1).min(self.tokens.len() - 1);
+        token
+    }
+
+    fn error(&self) -> Error {
+        let position = self.tokens.get(self.index).map_or(self.end, |entry| entry.1);
+
+        invalid(format!("unexpected {}", match *self.peek() {
+            Token::Eof     => "end of expression".to_string(),
+            ref token      => format!("`{}`", token),
+        }), position)
+    }
+
+    // Error for the token just taken with `next`
+    fn unexpected(&mut self) -> Error {
+        self.index -= 1;
+        self.error()
+    }
+
+    fn expect(&mut self, token: Token) -> Result<()> {
+        match *self.peek() == token {
+            true  => {
+                self.next();
+                Ok(())
+            },
+            false => Err(self.error()),
+        }
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        if self.depth == DEPTH_LIMIT {
+            return Err(Error::ExceededDepthLimit);
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn expression(&mut self, binding_power: u8) -> Result<Ast> {
+        let depth = self.depth;
+
+        self.enter()?;
+
+        let token = self.next();
+        let mut left = self.prefix(token)?;
+
+        while binding_power < self.peek().binding_power() {
+            self.enter()?;
+
+            let token = self.next();
+            left = self.infix(token, left)?;
+        }
+
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn prefix(&mut self, token: Token) -> Result<Ast> {
+        Ok(match token {
+            Token::Literal(value) => Ast::Literal(value),
+            Token::Ident(name)    => Ast::Field(name),
+            Token::Quoted(name)   => match *self.peek() {
+                Token::LParen => return Err(self.error()),
+                _             => Ast::Field(name),
+            },
+            Token::Star => {
+                let right = match *self.peek() {
+                    Token::RBracket => Ast::Identity,
+                    _               => self.projection_rhs(BP_STAR)?,
+                };
+                Ast::ValueProjection(Box::new(Ast::Identity), Box::new(right))
+            },
+            Token::Filter => self.filter(Ast::Identity)?,
+            Token::LBrace => self.multi_hash()?,
+            Token::Flatten => {
+                let right = self.projection_rhs(BP_FLATTEN)?;
+                Ast::Projection(Box::new(Ast::Flatten(Box::new(Ast::Identity))), Box::new(right))
+            },
+            Token::LParen => {
+                let ast = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                ast
+            },
+            Token::Not => Ast::Not(Box::new(self.expression(BP_NOT)?)),
+            Token::LBracket => match (self.peek(), self.peek_at(1)) {
+                (Token::Number(_), _) | (Token::Colon, _) => {
+                    let right = self.index_expression()?;
+                    self.project_if_slice(Ast::Identity, right)?
+                },
+                (Token::Star, Token::RBracket) => {
+                    self.next();
+                    self.next();
+                    let right = self.projection_rhs(BP_STAR)?;
+                    Ast::Projection(Box::new(Ast::Identity), Box::new(right))
+                },
+                _ => self.multi_list()?,
+            },
+            Token::At        => Ast::Identity,
+            Token::Ampersand => Ast::Expref(Box::new(self.expression(0)?)),
+            _                => return Err(self.unexpected()),
+        })
+    }
+
+    fn infix(&mut self, token: Token, left: Ast) -> Result<Ast> {
+        Ok(match token {
+            Token::Dot => match *self.peek() {
+                Token::Star => {
+                    self.next();
+                    let right = self.projection_rhs(BP_DOT)?;
+                    Ast::ValueProjection(Box::new(left), Box::new(right))
+                },
+                _ => Ast::Sub(Box::new(left), Box::new(self.dot_rhs(BP_DOT)?)),
+            },
+            Token::Pipe => Ast::Sub(Box::new(left), Box::new(self.expression(BP_PIPE)?)),
+            Token::Or   => Ast::Or(Box::new(left), Box::new(self.expression(BP_OR)?)),
+            Token::And  => Ast::And(Box::new(left), Box::new(self.expression(BP_AND)?)),
+            Token::Comparator(comparator) => {
+                Ast::Compare(comparator, Box::new(left), Box::new(self.expression(BP_COMPARATOR)?))
+            },
+            Token::LParen => match left {
+                Ast::Field(name) => self.call(name)?,
+                _                => return Err(self.unexpected()),
+            },
+            Token::Filter => self.filter(left)?,
+            Token::Flatten => {
+                let right = self.projection_rhs(BP_FLATTEN)?;
+                Ast::Projection(Box::new(Ast::Flatten(Box::new(left))), Box::new(right))
+            },
+            Token::LBracket => match *self.peek() {
+                Token::Number(_) | Token::Colon => {
+                    let right = self.index_expression()?;
+                    self.project_if_slice(left, right)?
+                },
+                _ => {
+                    self.expect(Token::Star)?;
+                    self.expect(Token::RBracket)?;
+                    let right = self.projection_rhs(BP_STAR)?;
+                    Ast::Projection(Box::new(left), Box::new(right))
+                },
+            },
+            _ => return Err(self.unexpected()),
+        })
+    }
+
+    // Function call, after the opening parenthesis.
+    fn call(&mut self, name: String) -> Result<Ast> {
+        let position = self.tokens[self.index - 2].1;
+        let mut args = Vec::new();
+
+        if *self.peek() != Token::RParen {
+            loop {
+                args.push(self.expression(0)?);
+
+                match self.next() {
+                    Token::Comma  => continue,
+                    Token::RParen => break,
+                    _             => return Err(self.unexpected()),
+                }
+            }
+        } else {
+            self.next();
+        }
+
+        let (function, arity, variadic) = match Function::find(&name) {
+            Some(function) => function,
+            None           => return Err(invalid(format!("unknown function `{}`", name), position)),
+        };
+
+        if args.len() < arity || (!variadic && args.len() > arity) {
+            let message = format!(
+                "`{}` takes {}{} arguments, found {}",
+                name, if variadic { "at least " } else { "" }, arity, args.len()
+            );
+            return Err(invalid(message, position));
+        }
+
+        if let Some(index) = function.expref_argument() {
+            if !matches!(args[index], Ast::Expref(_)) {
+                let message = format!("argument {} of `{}` has to be an expression reference", index + 1, name);
+                return Err(invalid(message, position));
+            }
+        }
+
+        Ok(Ast::Call(function, args))
+    }
+
+    // Filter projection, after the `[?`.
+    fn filter(&mut self, left: Ast) -> Result<Ast> {
+        let condition = self.expression(0)?;
+        self.expect(Token::RBracket)?;
+
+        let right = match *self.peek() {
+            Token::Flatten => Ast::Identity,
+            _              => self.projection_rhs(BP_FILTER)?,
+        };
+
+        Ok(Ast::FilterProjection(Box::new(left), Box::new(right), Box::new(condition)))
+    }
+
+    // Index or slice, after the opening bracket.
+    fn index_expression(&mut self) -> Result<Ast> {
+        if *self.peek() != Token::Colon && *self.peek_at(1) != Token::Colon {
+            let index = match self.next() {
+                Token::Number(index) => index,
+                _                    => return Err(self.unexpected()),
+            };
+            self.expect(Token::RBracket)?;
+
+            return Ok(Ast::Index(index));
+        }
+
+        let mut parts = [None; 3];
+        let mut part = 0;
+
+        loop {
+            let position = self.tokens[self.index].1;
+
+            match self.next() {
+                Token::RBracket => break,
+                Token::Colon if part < 2 => part += 1,
+                Token::Number(number) if parts[part].is_none() => {
+                    if part == 2 && number == 0 {
+                        return Err(invalid("slice step can't be 0".into(), position));
+                    }
+                    parts[part] = Some(number);
+                },
+                _ => return Err(self.unexpected()),
+            }
+        }
+
+        Ok(Ast::Slice(parts[0], parts[1], parts[2]))
+    }
+
+    fn project_if_slice(&mut self, left: Ast, right: Ast) -> Result<Ast> {
+        let slice = matches!(right, Ast::Slice(..));
+        let index = Ast::Sub(Box::new(left), Box::new(right));
+
+        match slice {
+            true  => Ok(Ast::Projection(Box::new(index), Box::new(self.projection_rhs(BP_STAR)?))),
+            false => Ok(index),
+        }
+    }
+
+    // What a projection applies to each of its elements.
+    fn projection_rhs(&mut self, binding_power: u8) -> Result<Ast> {
+        match *self.peek() {
+            ref token if token.binding_power() < PROJECTION_STOP => Ok(Ast::Identity),
+            Token::LBracket | Token::Filter => self.expression(binding_power),
+            Token::Dot => {
+                self.next();
+                self.dot_rhs(binding_power)
+            },
+            _ => Err(self.error()),
+        }
+    }
+
+    fn dot_rhs(&mut self, binding_power: u8) -> Result<Ast> {
+        match *self.peek() {
+            Token::Ident(_) | Token::Quoted(_) | Token::Star => self.expression(binding_power),
+            Token::LBracket => {
+                self.next();
+                self.multi_list()
+            },
+            Token::LBrace => {
+                self.next();
+                self.multi_hash()
+            },
+            _ => Err(self.error()),
+        }
+    }
+
+    // `[a, b]`, after the opening bracket.
+    fn multi_list(&mut self) -> Result<Ast> {
+        let mut list = Vec::new();
+
+        loop {
+            list.push(self.expression(0)?);
+
+            match self.next() {
+                Token::Comma    => continue,
+                Token::RBracket => return Ok(Ast::MultiList(list)),
+                _               => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    // `{a: b}`, after the opening brace.
+    fn multi_hash(&mut self) -> Result<Ast> {
+        let mut entries = Vec::new();
+
+        loop {
+            let key = match self.next() {
+                Token::Ident(key) | Token::Quoted(key) => key,
+                _                                      => return Err(self.unexpected()),
+            };
+
+            self.expect(Token::Colon)?;
+            entries.push((key, self.expression(0)?));
+
+            match self.next() {
+                Token::Comma  => continue,
+                Token::RBrace => return Ok(Ast::MultiHash(entries)),
+                _             => return Err(self.unexpected()),
+            }
+        }
+    }
+}
+
+// Everything but empty strings, arrays and objects, `false` and `null`.
+fn truthy(value: &JsonValue) -> bool {
+    match *value {
+        JsonValue::Null               => false,
+        JsonValue::Boolean(value)     => value,
+        JsonValue::Array(ref vec)     => !vec.is_empty(),
+        JsonValue::Object(ref object) => !object.is_empty(),
+        JsonValue::Number(_)          => true,
+        _                             => !value.as_str().unwrap_or("").is_empty(),
+    }
+}
+
+fn number(value: &JsonValue) -> Result<f64> {
+    match *value {
+        JsonValue::Number(number) => Ok(number.into()),
+        _                         => Err(Error::wrong_type("number", value)),
+    }
+}
+
+fn string(value: &JsonValue) -> Result<&str> {
+    value.as_str().ok_or_else(|| Error::wrong_type("string", value))
+}
+
+fn array(value: &JsonValue) -> Result<&[JsonValue]> {
+    match *value {
+        JsonValue::Array(ref vec) => Ok(vec),
+        _                         => Err(Error::wrong_type("array", value)),
+    }
+}
+
+fn object(value: &JsonValue) -> Result<&Object> {
+    match *value {
+        JsonValue::Object(ref object) => Ok(object),
+        _                             => Err(Error::wrong_type("object", value)),
+    }
+}
+
+// Elements of `vec` in the slice, with the same rules as in Python.
+fn slice(vec: &[JsonValue], start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> JsonValue {
+    let len = vec.len() as i64;
+    let step = step.unwrap_or(1);
+
+    let bound = |value: Option<i64>, default: i64| match value {
+        None                    => default,
+        Some(value) if value < 0 => (value + len).max(if step < 0 { -1 } else { 0 }),
+        Some(value)             => value.min(if step < 0 { len - 1 } else { len }),
+    };
+
+    let (mut index, stop) = match step > 0 {
+        true  => (bound(start, 0), bound(stop, len)),
+        false => (bound(start, len - 1), bound(stop, -1)),
+    };
+
+    let mut result = Vec::new();
+
+    while (step > 0 && index < stop) || (step < 0 && index > stop) {
+        result.push(vec[index as usize].clone());
+        index += step;
+    }
+
+    JsonValue::Array(result)
+}
+
+// Order of two numbers or two strings, the only values that can be sorted.
+fn order(a: &JsonValue, b: &JsonValue) -> Option<Ordering> {
+    match (a, b) {
+        (JsonValue::Number(a), JsonValue::Number(b)) => f64::from(*a).partial_cmp(&f64::from(*b)),
+        _ => match (a.as_str(), b.as_str()) {
+            (Some(a), Some(b)) => Some(a.cmp(b)),
+            _                  => None,
+        },
+    }
+}
+
+// `keys` if they can be sorted, being all numbers or all strings.
+fn sort_keys(keys: &[JsonValue]) -> Result<&[JsonValue]> {
+    let first = match keys.first() {
+        Some(first) => first,
+        None        => return Ok(keys),
+    };
+
+    match keys.iter().find(|key| order(first, key).is_none()) {
+        Some(key) if first.is_number() => Err(Error::wrong_type("number", key)),
+        Some(key) if first.is_string() => Err(Error::wrong_type("string", key)),
+        Some(_)                       => Err(Error::wrong_type("number or string", first)),
+        None                          => Ok(keys),
+    }
+}
+
+impl Ast {
+    fn eval(&self, value: &JsonValue) -> Result<JsonValue> {
+        Ok(match *self {
+            Ast::Identity => value.clone(),
+            Ast::Field(ref name) => match *value {
+                JsonValue::Object(ref object) => object.get(name).cloned().unwrap_or(JsonValue::Null),
+                _                             => JsonValue::Null,
+            },
+            Ast::Index(index) => match *value {
+                JsonValue::Array(ref vec) => {
+                    let index = if index < 0 { index + vec.len() as i64 } else { index };
+
+                    match index >= 0 {
+                        true  => vec.get(index as usize).cloned().unwrap_or(JsonValue::Null),
+                        false => JsonValue::Null,
+                    }
+                },
+                _ => JsonValue::Null,
+            },
+            Ast::Slice(start, stop, step) => match *value {
+                JsonValue::Array(ref vec) => slice(vec, start, stop, step),
+                _                         => JsonValue::Null,
+            },
+            Ast::Sub(ref left, ref right) => right.eval(&left.eval(value)?)?,
+            Ast::Projection(ref left, ref right) => match left.eval(value)? {
+                JsonValue::Array(vec) => project(vec.iter(), right)?,
+                _                     => JsonValue::Null,
+            },
+            Ast::ValueProjection(ref left, ref right) => match left.eval(value)? {
+                JsonValue::Object(object) => project(object.values(), right)?,
+                _                         => JsonValue::Null,
+            },
+            Ast::FilterProjection(ref left, ref right, ref condition) => match left.eval(value)? {
+                JsonValue::Array(vec) => {
+                    let mut matching = Vec::new();
+                    for member in vec {
+                        if truthy(&condition.eval(&member)?) {
+                            matching.push(member);
+                        }
+                    }
+                    project(matching.iter(), right)?
+                },
+                _ => JsonValue::Null,
+            },
+            Ast::Flatten(ref ast) => match ast.eval(value)? {
+                JsonValue::Array(vec) => {
+                    let mut flat = Vec::with_capacity(vec.len());
+                    for member in vec {
+                        match member {
+                            JsonValue::Array(inner) => flat.extend(inner),
+                            member                  => flat.push(member),
+                        }
+                    }
+                    JsonValue::Array(flat)
+                },
+                _ => JsonValue::Null,
+            },
+            Ast::Or(ref left, ref right) => {
+                let left = left.eval(value)?;
+                match truthy(&left) {
+                    true  => left,
+                    false => right.eval(value)?,
+                }
+            },
+            Ast::And(ref left, ref right) => {
+                let left = left.eval(value)?;
+                match truthy(&left) {
+                    true  => right.eval(value)?,
+                    false => left,
+                }
+            },
+            Ast::Not(ref ast) => (!truthy(&ast.eval(value)?)).into(),
+            Ast::Compare(comparator, ref left, ref right) => {
+                let (left, right) = (left.eval(value)?, right.eval(value)?);
+
+                match comparator {
+                    Comparator::Eq => (left == right).into(),
+                    Comparator::Ne => (left != right).into(),
+                    _ => match (left.is_number() && right.is_number(), order(&left, &right)) {
+                        (true, Some(ordering)) => match comparator {
+                            Comparator::Lt => ordering == Ordering::Less,
+                            Comparator::Le => ordering != Ordering::Greater,
+                            Comparator::Gt => ordering == Ordering::Greater,
+                            _              => ordering != Ordering::Less,
+                        }.into(),
+                        _ => JsonValue::Null,
+                    },
+                }
+            },
+            Ast::MultiList(ref list) => match *value {
+                JsonValue::Null => JsonValue::Null,
+                _ => JsonValue::Array(list.iter().map(|ast| ast.eval(value)).collect::<Result<_>>()?),
+            },
+            Ast::MultiHash(ref entries) => match *value {
+                JsonValue::Null => JsonValue::Null,
+                _ => {
+                    let mut object = Object::with_capacity(entries.len());
+                    for (key, ast) in entries {
+                        object.insert(key, ast.eval(value)?);
+                    }
+                    JsonValue::Object(object)
+                },
+            },
+            Ast::Literal(ref literal) => literal.clone(),
+            Ast::Call(function, ref args) => call(function, args, value)?,
+            Ast::Expref(_) => JsonValue::Null,
+        })
+    }
+
+    // Expression of an `&expression` argument
+    fn expref(&self) -> &Ast {
+        match *self {
+            Ast::Expref(ref ast) => ast,
+            _                    => self,
+        }
+    }
+}
+
+// Apply `right` to all of `members`, dropping the results that are `null`.
+fn project<'a, I>(members: I, right: &Ast) -> Result<JsonValue>
+where
+    I: Iterator<Item = &'a JsonValue>,
+{
+    let mut result = Vec::new();
+
+    for member in members {
+        match right.eval(member)? {
+            JsonValue::Null => {},
+            value           => result.push(value),
+        }
+    }
+
+    Ok(JsonValue::Array(result))
+}
+
+fn call(function: Function, args: &[Ast], value: &JsonValue) -> Result<JsonValue> {
+    // Arguments that are expression references are evaluated by the
+    // function itself
+    let expref = function.expref_argument();
+    let mut values = Vec::with_capacity(args.len());
+
+    for (index, arg) in args.iter().enumerate() {
+        values.push(match Some(index) == expref {
+            true  => JsonValue::Null,
+            false => arg.eval(value)?,
+        });
+    }
+
+    let first = &values[0];
+
+    Ok(match function {
+        Function::Abs  => number(first)?.abs().into(),
+        Function::Ceil => number(first)?.ceil().into(),
+        Function::Floor => number(first)?.floor().into(),
+        Function::Avg | Function::Sum => {
+            let vec = array(first)?;
+            let mut sum = 0.0;
+            for member in vec {
+                sum += number(member)?;
+            }
+            match function {
+                Function::Sum          => sum.into(),
+                _ if vec.is_empty()    => JsonValue::Null,
+                _                      => (sum / vec.len() as f64).into(),
+            }
+        },
+        Function::Contains => match *first {
+            JsonValue::Array(ref vec) => vec.contains(&values[1]).into(),
+            _ => string(first)?.contains(string(&values[1])?).into(),
+        },
+        Function::EndsWith   => string(first)?.ends_with(string(&values[1])?).into(),
+        Function::StartsWith => string(first)?.starts_with(string(&values[1])?).into(),
+        Function::Join => {
+            let separator = string(first)?;
+            let parts = array(&values[1])?.iter().map(string).collect::<Result<Vec<_>>>()?;
+            parts.join(separator).into()
+        },
+        Function::Keys   => object(first)?.keys().collect::<Vec<_>>().into(),
+        Function::Values => JsonValue::Array(object(first)?.values().cloned().collect()),
+        Function::Length => match *first {
+            JsonValue::Array(ref vec)     => vec.len().into(),
+            JsonValue::Object(ref object) => object.len().into(),
+            _ => match first.as_str() {
+                Some(string) => string.chars().count().into(),
+                None         => return Err(Error::wrong_type("string, array or object", first)),
+            },
+        },
+        Function::Map => {
+            let ast = args[0].expref();
+            let mapped = array(&values[1])?.iter().map(|member| ast.eval(member)).collect::<Result<_>>()?;
+            JsonValue::Array(mapped)
+        },
+        Function::Max | Function::Min => {
+            let vec = sort_keys(array(first)?)?;
+            let wanted = if function == Function::Max { Ordering::Greater } else { Ordering::Less };
+
+            vec.iter()
+               .fold(None, |best: Option<&JsonValue>, member| match best {
+                   Some(best) if order(member, best) != Some(wanted) => Some(best),
+                   _                                                 => Some(member),
+               })
+               .cloned()
+               .unwrap_or(JsonValue::Null)
+        },
+        Function::MaxBy | Function::MinBy | Function::SortBy => {
+            let vec = array(first)?;
+            let ast = args[1].expref();
+            let keys = vec.iter().map(|member| ast.eval(member)).collect::<Result<Vec<_>>>()?;
+            let keys = sort_keys(&keys)?;
+
+            let mut indexes: Vec<usize> = (0 .. vec.len()).collect();
+            indexes.sort_by(|&a, &b| order(&keys[a], &keys[b]).unwrap_or(Ordering::Equal));
+
+            match function {
+                Function::SortBy => JsonValue::Array(indexes.iter().map(|&index| vec[index].clone()).collect()),
+                Function::MinBy  => indexes.first().map_or(JsonValue::Null, |&index| vec[index].clone()),
+                _                => indexes.last().map_or(JsonValue::Null, |&index| vec[index].clone()),
+            }
+        },
+        Function::Merge => {
+            let mut merged = Object::new();
+            for value in &values {
+                merged.merge_in(object(value)?.clone());
+            }
+            JsonValue::Object(merged)
+        },
+        Function::NotNull => values.iter().find(|value| !value.is_null()).cloned().unwrap_or(JsonValue::Null),
+        Function::Reverse => match *first {
+            JsonValue::Array(ref vec) => JsonValue::Array(vec.iter().rev().cloned().collect()),
+            _                         => string(first)?.chars().rev().collect::<String>().into(),
+        },
+        Function::Sort => {
+            let mut vec = sort_keys(array(first)?)?.to_vec();
+            vec.sort_by(|a, b| order(a, b).unwrap_or(Ordering::Equal));
+            JsonValue::Array(vec)
+        },
+        Function::ToArray => match *first {
+            JsonValue::Array(_) => first.clone(),
+            _                   => JsonValue::Array(vec![first.clone()]),
+        },
+        Function::ToString => match first.as_str() {
+            Some(_) => first.clone(),
+            None    => first.dump().into(),
+        },
+        Function::ToNumber => match *first {
+            JsonValue::Number(_) => first.clone(),
+            _ => match first.as_str().map(crate::parse) {
+                Some(Ok(number @ JsonValue::Number(_))) => number,
+                _                                       => JsonValue::Null,
+            },
+        },
+        Function::Type => first.type_name().into(),
+    })
+}
@@ -0,0 +1,413 @@
+//! On-demand parsing of large documents.
+//!
+//! A `LazyValue` wraps the source of a value, and only looks at it once it
+//! is navigated. Indexing an object or an array splits it into its members,
+//! skipping over their contents, and caches the result, so every part of the
+//! document gets split at most once. Strings and numbers are parsed when
+//! they are read.
+//!
+//! ```
+//! # use json::lazy::LazyValue;
+//! let lazy = LazyValue::new(r#"{"huge":[1,2,3],"config":{"name":"lazy","depth":3}}"#);
+//!
+//! assert_eq!(lazy["config"]["name"].as_str(), Some("lazy"));
+//! assert_eq!(lazy["config"]["depth"].as_u64(), Some(3));
+//!
+//! // `huge` was skipped over, but never split
+//! assert_eq!(lazy["huge"].raw(), "[1,2,3]");
+//! ```
+//!
+//! Skipped values are only checked for matching brackets and quotes. Errors
+//! in values that are read show up through `LazyValue::error`, and `to_json`
+//! parses a value along with all of its contents.
+
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::fmt;
+use std::ops::Index;
+use std::sync::OnceLock;
+
+use crate::{ JsonValue, Error, Result };
+use crate::number::Number;
+use crate::parser::{ expected, parse_range, unexpected_character_at };
+
+/// Value in a document that is parsed as it is navigated, see the module
+/// docs.
+pub struct LazyValue<'a> {
+    // The whole document, and the part of it that is this value
+    source: &'a str,
+    start: usize,
+    end: usize,
+
+    parsed: OnceLock<Parsed<'a>>,
+}
+
+struct Parsed<'a> {
+    kind: Kind<'a>,
+
+    // Returned when indexing for members that aren't there
+    null: Box<LazyValue<'a>>,
+}
+
+enum Kind<'a> {
+    Scalar(JsonValue),
+    Array(Vec<LazyValue<'a>>),
+    Object(Vec<(Cow<'a, str>, LazyValue<'a>)>),
+    Invalid(Error),
+}
+
+impl<'a> LazyValue<'a> {
+    /// Wrap `source`, without reading it yet.
+    pub fn new(source: &'a str) -> Self {
+        let end = source.trim_end().len();
+        let start = skip_whitespace(source.as_bytes(), 0, end);
+
+        LazyValue::in_range(source, start, end)
+    }
+
+    fn in_range(source: &'a str, start: usize, end: usize) -> Self {
+        LazyValue {
+            source,
+            start,
+            end,
+            parsed: OnceLock::new(),
+        }
+    }
+
+    fn kind(&self) -> &Kind<'a> {
+        &self.parsed().kind
+    }
+
+    fn parsed(&self) -> &Parsed<'a> {
+        self.parsed.get_or_init(|| {
+            let kind = match self.split() {
+                Ok(kind)   => kind,
+                Err(error) => Kind::Invalid(error),
+            };
+
+            Parsed {
+                kind,
+                null: Box::new(LazyValue::new("null")),
+            }
+        })
+    }
+
+    // Byte at `index`, if it is part of this value.
+    fn byte(&self, index: usize) -> Option<u8> {
+        match index < self.end {
+            true  => Some(self.source.as_bytes()[index]),
+            false => None,
+        }
+    }
+
+    fn split(&self) -> Result<Kind<'a>> {
+        let bytes = self.source.as_bytes();
+
+        let (close, object) = match self.byte(self.start) {
+            Some(b'[') => (b']', false),
+            Some(b'{') => (b'}', true),
+            _          => return parse_range(self.source, self.start, self.end).map(Kind::Scalar),
+        };
+
+        let mut members = Vec::new();
+        let mut entries = Vec::new();
+        let mut index = skip_whitespace(bytes, self.start + 1, self.end);
+
+        if self.byte(index) != Some(close) {
+            loop {
+                index = skip_whitespace(bytes, index, self.end);
+
+                let key = if object {
+                    let start = index;
+                    index = self.expect(index, b'"', expected::KEY)?;
+                    index = skip_string(bytes, index, self.end)?;
+
+                    let key = self.key(start, index)?;
+                    index = self.expect(skip_whitespace(bytes, index, self.end), b':', expected::COLON)?;
+
+                    Some(key)
+                } else {
+                    None
+                };
+
+                let start = skip_whitespace(bytes, index, self.end);
+                index = self.skip_value(start)?;
+
+                let value = LazyValue::in_range(self.source, start, index);
+                match key {
+                    Some(key) => entries.push((key, value)),
+                    None      => members.push(value),
+                }
+
+                index = skip_whitespace(bytes, index, self.end);
+                match self.byte(index) {
+                    Some(b',') => index += 1,
+                    Some(ch) if ch == close => break,
+                    _ if object => return self.unexpected(index, expected::OBJECT_NEXT),
+                    _          => return self.unexpected(index, expected::ARRAY_NEXT),
+                }
+            }
+        }
+
+        if index + 1 != self.end {
+            return self.unexpected(index + 1, &[]);
+        }
+
+        Ok(match object {
+            true  => Kind::Object(entries),
+            false => Kind::Array(members),
+        })
+    }
+
+    // Keys without escapes are borrowed from the source.
+    fn key(&self, start: usize, end: usize) -> Result<Cow<'a, str>> {
+        let raw = &self.source[start + 1 .. end - 1];
+
+        if !raw.contains('\\') {
+            return Ok(Cow::Borrowed(raw));
+        }
+
+        match parse_range(self.source, start, end)? {
+            JsonValue::Short(key)  => Ok(Cow::Owned(key.to_string())),
+            JsonValue::String(key) => Ok(Cow::Owned(key)),
+            _                      => self.unexpected(start, expected::KEY),
+        }
+    }
+
+    // Index past the value starting at `start`. Strings and brackets are
+    // matched up, anything else runs until the next separator.
+    fn skip_value(&self, start: usize) -> Result<usize> {
+        let bytes = self.source.as_bytes();
+
+        match self.byte(start) {
+            None => Err(Error::UnexpectedEndOfJson),
+            Some(b'"') => skip_string(bytes, start + 1, self.end),
+            Some(b'[') | Some(b'{') => {
+                let mut depth = 0;
+                let mut index = start;
+
+                while let Some(ch) = self.byte(index) {
+                    match ch {
+                        b'"' => {
+                            index = skip_string(bytes, index + 1, self.end)?;
+                            continue;
+                        },
+                        b'[' | b'{' => depth += 1,
+                        b']' | b'}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                return Ok(index + 1);
+                            }
+                        },
+                        _ => {},
+                    }
+                    index += 1;
+                }
+
+                Err(Error::UnexpectedEndOfJson)
+            },
+            Some(b',') | Some(b':') | Some(b']') | Some(b'}') => self.unexpected(start, expected::VALUE),
+            Some(_) => {
+                let mut index = start;
+
+                while let Some(ch) = self.byte(index) {
+                    if matches!(ch, b',' | b']' | b'}' | 9 ..= 13 | b' ') {
+                        break;
+                    }
+                    index += 1;
+                }
+
+                Ok(index)
+            },
+        }
+    }
+
+    fn expect(&self, index: usize, ch: u8, expected: &'static [&'static str]) -> Result<usize> {
+        match self.byte(index) {
+            Some(found) if found == ch => Ok(index + 1),
+            _                          => self.unexpected(index, expected),
+        }
+    }
+
+    fn unexpected<T>(&self, index: usize, expected: &'static [&'static str]) -> Result<T> {
+        match index < self.end {
+            true  => Err(unexpected_character_at(self.source, index, expected)),
+            false => Err(Error::UnexpectedEndOfJson),
+        }
+    }
+
+    fn scalar(&self) -> Option<&JsonValue> {
+        match *self.kind() {
+            Kind::Scalar(ref value) => Some(value),
+            _                       => None,
+        }
+    }
+
+    /// Source of this value, as it is in the document.
+    pub fn raw(&self) -> &'a str {
+        &self.source[self.start .. self.end]
+    }
+
+    /// Error found while reading this value, if any. Only the parts of the
+    /// document that were read are checked.
+    pub fn error(&self) -> Option<&Error> {
+        match *self.kind() {
+            Kind::Invalid(ref error) => Some(error),
+            _                        => None,
+        }
+    }
+
+    /// Parse this value with all of its contents.
+    pub fn to_json(&self) -> Result<JsonValue> {
+        parse_range(self.source, self.start, self.end)
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.scalar().is_some_and(JsonValue::is_null)
+    }
+
+    pub fn is_string(&self) -> bool {
+        self.scalar().is_some_and(JsonValue::is_string)
+    }
+
+    pub fn is_number(&self) -> bool {
+        self.scalar().is_some_and(JsonValue::is_number)
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        self.scalar().is_some_and(JsonValue::is_boolean)
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(*self.kind(), Kind::Object(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(*self.kind(), Kind::Array(_))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        self.scalar().and_then(JsonValue::as_str)
+    }
+
+    pub fn as_number(&self) -> Option<Number> {
+        self.scalar().and_then(JsonValue::as_number)
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(|value| value.into())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        self.scalar().and_then(JsonValue::as_bool)
+    }
+
+    /// Length of an array or object, `0` for other types.
+    pub fn len(&self) -> usize {
+        match *self.kind() {
+            Kind::Array(ref members)  => members.len(),
+            Kind::Object(ref entries) => entries.len(),
+            _                         => 0,
+        }
+    }
+
+    /// True for empty arrays and objects, as well as for values that are
+    /// neither.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the member of an object under `key`, the last one if the key is
+    /// duplicated.
+    pub fn get(&self, key: &str) -> Option<&LazyValue<'a>> {
+        match *self.kind() {
+            Kind::Object(ref entries) => {
+                entries.iter().rev().find(|entry| entry.0 == key).map(|entry| &entry.1)
+            },
+            _ => None,
+        }
+    }
+
+    /// Get the member of an array at `index`.
+    pub fn at(&self, index: usize) -> Option<&LazyValue<'a>> {
+        match *self.kind() {
+            Kind::Array(ref members) => members.get(index),
+            _                        => None,
+        }
+    }
+
+    /// Members of an array, empty for other types.
+    pub fn members(&self) -> impl Iterator<Item = &LazyValue<'a>> {
+        let members = match *self.kind() {
+            Kind::Array(ref members) => &members[..],
+            _                        => &[],
+        };
+
+        members.iter()
+    }
+
+    /// Key value pairs of an object, empty for other types.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &LazyValue<'a>)> {
+        let entries = match *self.kind() {
+            Kind::Object(ref entries) => &entries[..],
+            _                         => &[],
+        };
+
+        entries.iter().map(|(key, value)| (&**key, value))
+    }
+}
+
+impl<'a> fmt::Debug for LazyValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("LazyValue").field(&self.raw()).finish()
+    }
+}
+
+/// Implements indexing by `usize` into arrays, returning `null` for other
+/// types and out of bounds indexes.
+impl<'a> Index<usize> for LazyValue<'a> {
+    type Output = LazyValue<'a>;
+
+    fn index(&self, index: usize) -> &LazyValue<'a> {
+        self.at(index).unwrap_or(&self.parsed().null)
+    }
+}
+
+/// Implements indexing by `&str` into objects, returning `null` for other
+/// types and missing keys.
+impl<'a> Index<&str> for LazyValue<'a> {
+    type Output = LazyValue<'a>;
+
+    fn index(&self, key: &str) -> &LazyValue<'a> {
+        self.get(key).unwrap_or(&self.parsed().null)
+    }
+}
+
+fn skip_whitespace(bytes: &[u8], mut index: usize, end: usize) -> usize {
+    while index < end && matches!(bytes[index], 9 ..= 13 | b' ') {
+        index += 1;
+    }
+    index
+}
+
+// Index past the closing quote of a string, `index` being past the opening
+// quote.
+fn skip_string(bytes: &[u8], mut index: usize, end: usize) -> Result<usize> {
+    while index < end {
+        match bytes[index] {
+            b'"'  => return Ok(index + 1),
+            b'\\' => index += 2,
+            _     => index += 1,
+        }
+    }
+
+    Err(Error::UnexpectedEndOfJson)
+}
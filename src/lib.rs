@@ -196,21 +196,87 @@
 //! # }
 //! ```
 
+// With the `forbid-unsafe` feature the fast paths using raw pointers and
+// unchecked UTF-8 conversions are swapped for safe code.
+#![cfg_attr(feature = "forbid-unsafe", forbid(unsafe_code))]
+
 use std::result;
+use std::borrow::Borrow;
+use std::io::{ self, Write };
 
 pub mod codegen;
 mod parser;
 mod value;
 mod error;
 mod util;
+mod writer;
+mod case;
+mod reformat;
+mod interpolate;
+#[cfg(feature = "csv")]
+mod csv;
+mod base64;
+#[cfg(feature = "gzip")]
+mod gzip;
+#[cfg(feature = "async")]
+mod async_write;
+#[cfg(feature = "serde")]
+mod serde_write;
+#[cfg(feature = "digest")]
+mod digest;
+#[cfg(feature = "decimal")]
+mod decimal;
 
 pub mod short;
 pub mod object;
 pub mod number;
+pub mod query;
+pub mod schema;
+pub mod tape;
+pub mod lazy;
+pub mod filter;
+pub mod diff;
+pub mod repair;
+pub mod pipeline;
+pub mod config;
+pub mod shared;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+#[cfg(feature = "ubjson")]
+pub mod ubjson;
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "jmespath")]
+pub mod jmespath;
 
 pub use error::Error;
 pub use value::JsonValue;
 pub use value::JsonValue::Null;
+pub use writer::JsonWriter;
+pub use case::Case;
+pub use reformat::{ reformat, minify, Style };
+pub use filter::{ filter, Filter };
+pub use diff::{ diff, compare, merge3, Conflict, Difference };
+pub use repair::{ repair, Fix };
+pub use interpolate::interpolate;
+pub use pipeline::{ project, project_value, split_array };
+pub use shared::{ dedup_subtrees, SharedValue };
+#[cfg(feature = "arena")]
+pub use arena::{ JsonArena, ArenaValue };
+#[cfg(feature = "csv")]
+pub use csv::{ to_csv, from_csv };
+#[cfg(feature = "async")]
+pub use async_write::write_async;
+#[cfg(feature = "async")]
+pub use futures_io::AsyncWrite;
+#[cfg(feature = "datetime")]
+pub use chrono;
+#[cfg(feature = "decimal")]
+pub use rust_decimal;
+#[cfg(feature = "uuid")]
+pub use uuid;
+#[cfg(feature = "serde")]
+pub use serde_write::{ to_writer_serde, to_writer_serde_with, to_writer_serde_pretty_with };
 
 /// Result type used by this crate.
 ///
@@ -231,6 +297,19 @@ pub mod iterators {
 
     /// Mutable iterator over key value pairs of `JsonValue::Object`.
     pub type EntriesMut<'a> = super::object::IterMut<'a>;
+
+    /// Iterator over keys of `JsonValue::Object`.
+    pub type Keys<'a> = super::object::Keys<'a>;
+
+    /// Iterator over values of `JsonValue::Object`.
+    pub type Values<'a> = super::object::Values<'a>;
+
+    /// Mutable iterator over values of `JsonValue::Object`.
+    pub type ValuesMut<'a> = super::object::ValuesMut<'a>;
+
+    /// Iterator over scalar values of a `JsonValue` tree with their JSON
+    /// Pointers.
+    pub type Leaves<'a> = super::value::Leaves<'a>;
 }
 
 #[deprecated(since="0.9.0", note="use `json::Error` instead")]
@@ -239,7 +318,8 @@ pub use Error as JsonError;
 #[deprecated(since="0.9.0", note="use `json::Result` instead")]
 pub use crate::Result as JsonResult;
 
-pub use parser::parse;
+pub use parser::{ parse, parse_with, parse_bytes_lossy, parse_from_reader, parse_with_reviver, Parser };
+pub use parser::{ ParseOptions, PrecisionLoss, ExponentOverflow };
 
 pub type Array = Vec<JsonValue>;
 
@@ -261,6 +341,88 @@ pub fn stringify_pretty<T>(root: T, spaces: u16) -> String where T: Into<JsonVal
     root.pretty(spaces)
 }
 
+/// Writes values as [JSON Lines](http://jsonlines.org/): each value is
+/// serialized compactly and followed by a newline.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let rows = vec![object!{ "id" => 1 }, object!{ "id" => 2 }];
+/// let mut out = Vec::new();
+///
+/// json::write_lines(&rows, &mut out).unwrap();
+///
+/// assert_eq!(out, b"{\"id\":1}\n{\"id\":2}\n");
+/// # }
+/// ```
+pub fn write_lines<I, W>(values: I, writer: &mut W) -> io::Result<()>
+where I: IntoIterator, I::Item: Borrow<JsonValue>, W: Write {
+    for value in values {
+        value.borrow().write(writer)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Writes values as a JSON text sequence ([RFC 7464](https://tools.ietf.org/html/rfc7464)):
+/// each value is preceded by an ASCII record separator (`0x1E`) and followed
+/// by a newline. Since every record is self-delimiting, a reader can recover
+/// from a truncated record, which makes this a good fit for append-only logs.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let records = vec![object!{ "id" => 1 }, array![true]];
+/// let mut out = Vec::new();
+///
+/// json::write_seq(&records, &mut out).unwrap();
+///
+/// assert_eq!(out, b"\x1E{\"id\":1}\n\x1E[true]\n");
+/// # }
+/// ```
+pub fn write_seq<I, W>(values: I, writer: &mut W) -> io::Result<()>
+where I: IntoIterator, I::Item: Borrow<JsonValue>, W: Write {
+    for value in values {
+        writer.write_all(b"\x1E")?;
+        value.borrow().write(writer)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Reads all of the standard input and parses it, for command line filters.
+/// Same as `parse_from_reader` on a locked `io::stdin()`, so with the `gzip`
+/// feature compressed input is handled too.
+///
+/// ```no_run
+/// use json::Style;
+///
+/// let mut data = json::read_stdin().unwrap();
+/// data["seen"] = true.into();
+///
+/// json::write_stdout(&data, Style::Pretty(2)).unwrap();
+/// ```
+pub fn read_stdin() -> io::Result<JsonValue> {
+    let stdin = io::stdin();
+    let lock = stdin.lock();
+
+    parse_from_reader(lock)
+}
+
+/// Writes `value` to the standard output in the given `Style`, followed by
+/// a newline. The output is buffered and flushed once done.
+pub fn write_stdout(value: &JsonValue, style: Style) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut writer = io::BufWriter::new(stdout.lock());
+
+    match style {
+        Style::Compact        => value.write(&mut writer)?,
+        Style::Pretty(spaces) => value.write_pretty(&mut writer, spaces)?,
+    }
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
 /// Helper macro for creating instances of `JsonValue::Array`.
 ///
 /// ```
@@ -338,3 +500,54 @@ macro_rules! object {
     })
 }
 
+/// Asserts that two values are equal, like `assert_eq!`, but on failure
+/// lists the JSON Pointers of the places where they differ instead of
+/// printing both values whole. See `json::diff` for how values are compared.
+///
+/// Either side can be a `JsonValue`, a reference to one, or anything that
+/// converts into one, such as the output of `object!` and `array!`.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let response = json::parse(r#"{"id": 7, "tags": ["new"]}"#).unwrap();
+///
+/// assert_json_eq!(response, object!{ "tags" => array!["new"], "id" => 7.0 });
+/// assert_json_eq!(response["id"], 7, "for user {}", "sam");
+/// # }
+/// ```
+///
+/// ```should_panic
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// // Panics with:
+/// //
+/// // assertion `left == right` failed
+/// //     /tags/0: "new" != "old"
+/// //     /id: (missing) != 7
+/// assert_json_eq!(object!{ "tags" => array!["new"] }, object!{ "tags" => array!["old"], "id" => 7 });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_json_eq {
+    ($left:expr, $right:expr $(,)?) => ({
+        use $crate::diff::ToJson;
+
+        let (left, right) = ((&$left).to_json(), (&$right).to_json());
+
+        if left != right {
+            $crate::diff::assert_failed(&left, &right, None);
+        }
+    });
+
+    ($left:expr, $right:expr, $( $message:tt )+) => ({
+        use $crate::diff::ToJson;
+
+        let (left, right) = ((&$left).to_json(), (&$right).to_json());
+
+        if left != right {
+            $crate::diff::assert_failed(&left, &right, Some(format_args!($( $message )+)));
+        }
+    });
+}
+
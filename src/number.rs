@@ -1,4 +1,4 @@
-use std::{ ops, fmt, f32, f64 };
+use std::{ ops, fmt, str, f32, f64 };
 use std::convert::{TryFrom, Infallible};
 use std::num::{FpCategory, TryFromIntError};
 use crate::util::grisu2;
@@ -8,6 +8,7 @@ use crate::util::print_dec;
 pub const NAN: Number = Number {
     category: NAN_MASK,
     mantissa: 0,
+    high: 0,
     exponent: 0
 };
 
@@ -30,6 +31,19 @@ const NAN_MASK: u8 = !1;
 ///
 /// More often than not you will deal with `JsonValue::Number` variant that
 /// wraps around this type, instead of using the methods here directly.
+///
+/// Integers are exact up to 128 bits, both when parsed and when converted
+/// from `i128` and `u128`:
+///
+/// ```
+/// # use json::number::Number;
+/// # use std::convert::TryFrom;
+/// let max = json::parse("170141183460469231731687303715884105727").unwrap();
+///
+/// assert_eq!(max.as_i128(), Some(i128::MAX));
+/// assert_eq!(max.dump(), "170141183460469231731687303715884105727");
+/// assert_eq!(i128::try_from(Number::from(i128::MIN)).ok(), Some(i128::MIN));
+/// ```
 #[derive(Copy, Clone, Debug)]
 pub struct Number {
     // A byte describing the sign and NaN-ness of the number.
@@ -44,6 +58,10 @@ pub struct Number {
 
     // Integer base before sing and exponent applied.
     mantissa: u64,
+
+    // High 64 bits of the mantissa. Only integers that don't fit into `u64`
+    // have them set, always with a zero exponent.
+    high: u64,
 }
 
 impl Number {
@@ -59,12 +77,71 @@ impl Number {
     /// While this method is marked unsafe, it doesn't actually perform any unsafe operations.
     /// THe goal of the 'unsafe' is to deter from using this method in favor of its safe equivalent
     /// `from_parts`, at least in context when the associated performance cost is negligible.
+    ///
+    /// With the `forbid-unsafe` feature this is a safe function.
+    #[cfg(not(feature = "forbid-unsafe"))]
     #[inline]
-    pub unsafe fn from_parts_unchecked(positive: bool, mantissa: u64, exponent: i16) -> Self {
+    pub const unsafe fn from_parts_unchecked(positive: bool, mantissa: u64, exponent: i16) -> Self {
+        Number::from_raw_parts(positive, mantissa, exponent)
+    }
+
+    /// Construct a new `Number` from parts, without stripping trailing
+    /// zeroes like `from_parts` does. This can't create a NaN value.
+    #[cfg(feature = "forbid-unsafe")]
+    #[inline]
+    pub const fn from_parts_unchecked(positive: bool, mantissa: u64, exponent: i16) -> Self {
+        Number::from_raw_parts(positive, mantissa, exponent)
+    }
+
+    // Same as `from_parts_unchecked`, for use inside of the crate.
+    #[inline]
+    pub(crate) const fn from_raw_parts(positive: bool, mantissa: u64, exponent: i16) -> Self {
         Number {
             category: positive as u8,
             exponent: exponent,
             mantissa: mantissa,
+            high: 0,
+        }
+    }
+
+    // Integer with a magnitude that may not fit into `u64`.
+    pub(crate) const fn from_wide(positive: bool, magnitude: u128) -> Self {
+        Number {
+            category: positive as u8,
+            exponent: 0,
+            mantissa: magnitude as u64,
+            high: (magnitude >> 64) as u64,
+        }
+    }
+
+    // Magnitude of integers that don't fit into `u64`, `None` for all other
+    // numbers.
+    #[inline]
+    pub(crate) fn wide(&self) -> Option<u128> {
+        match self.high {
+            0    => None,
+            high => Some((high as u128) << 64 | self.mantissa as u128),
+        }
+    }
+
+    // Magnitude of the number as an integer, if it is one that fits.
+    pub(crate) fn as_integer(&self) -> Option<u128> {
+        if let Some(wide) = self.wide() {
+            return Some(wide);
+        }
+
+        let mut mantissa = self.mantissa as u128;
+        let mut exponent = self.exponent;
+
+        while exponent < 0 && mantissa.is_multiple_of(10) && mantissa != 0 {
+            mantissa /= 10;
+            exponent += 1;
+        }
+
+        match exponent {
+            _ if mantissa == 0 => Some(0),
+            exponent if exponent < 0 => None,
+            exponent => (0 .. exponent).try_fold(mantissa, |mantissa, _| mantissa.checked_mul(10)),
         }
     }
 
@@ -81,15 +158,17 @@ impl Number {
     /// assert_eq!(0, exponent);
     /// ```
     #[inline]
-    pub fn from_parts(positive: bool, mut mantissa: u64, mut exponent: i16) -> Self {
+    pub const fn from_parts(positive: bool, mut mantissa: u64, mut exponent: i16) -> Self {
         while exponent < 0 && mantissa % 10 == 0 {
             exponent += 1;
             mantissa /= 10;
         }
-        unsafe { Number::from_parts_unchecked(positive, mantissa, exponent) }
+        Number::from_raw_parts(positive, mantissa, exponent)
     }
 
     /// Reverse to `from_parts` - obtain parts from an existing `Number`.
+    /// Integers that don't fit into `u64` are cut down to their 19 or 20
+    /// leading digits, with the exponent making up for the rest.
     ///
     /// ```
     /// # use json::number::Number;
@@ -102,7 +181,21 @@ impl Number {
     /// ```
     #[inline]
     pub fn as_parts(&self) -> (bool, u64, i16) {
-        (self.category == POSITIVE, self.mantissa, self.exponent)
+        let positive = self.category == POSITIVE;
+
+        match self.wide() {
+            Some(mut wide) => {
+                let mut exponent = 0;
+
+                while wide > u64::MAX as u128 {
+                    wide /= 10;
+                    exponent += 1;
+                }
+
+                (positive, wide as u64, exponent)
+            },
+            None => (positive, self.mantissa, self.exponent),
+        }
     }
 
     #[inline]
@@ -112,7 +205,7 @@ impl Number {
 
     #[inline]
     pub fn is_zero(&self) -> bool {
-        self.mantissa == 0 && !self.is_nan()
+        self.mantissa == 0 && self.high == 0 && !self.is_nan()
     }
 
     #[inline]
@@ -123,7 +216,7 @@ impl Number {
     /// Test if the number is NaN or has a zero value.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.mantissa == 0 || self.is_nan()
+        self.is_zero() || self.is_nan()
     }
 
     /// Obtain an integer at a fixed decimal point. This is useful for
@@ -147,14 +240,15 @@ impl Number {
             return None;
         }
 
-        let e_diff = point as i16 + self.exponent;
+        let (_, mantissa, exponent) = self.as_parts();
+        let e_diff = point as i16 + exponent;
 
         Some(if e_diff == 0 {
-            self.mantissa
+            mantissa
         } else if e_diff < 0 {
-            self.mantissa.wrapping_div(decimal_power(-e_diff as u16))
+            mantissa.wrapping_div(decimal_power(-e_diff as u16))
         } else {
-            self.mantissa.wrapping_mul(decimal_power(e_diff as u16))
+            mantissa.wrapping_mul(decimal_power(e_diff as u16))
         })
     }
 
@@ -174,13 +268,15 @@ impl Number {
             return None;
         }
 
-        let num = if self.is_sign_positive() {
-            self.mantissa as i64
+        let (positive, mantissa, exponent) = self.as_parts();
+
+        let num = if positive {
+            mantissa as i64
         } else {
-            -(self.mantissa as i64)
+            -(mantissa as i64)
         };
 
-        let e_diff = point as i16 + self.exponent;
+        let e_diff = point as i16 + exponent;
 
         Some(if e_diff == 0 {
             num
@@ -204,6 +300,10 @@ impl PartialEq for Number {
             return false;
         }
 
+        if self.high != 0 || other.high != 0 {
+            return self.as_integer() == other.as_integer();
+        }
+
         let e_diff = self.exponent - other.exponent;
 
         if e_diff == 0 {
@@ -223,15 +323,19 @@ impl PartialEq for Number {
 
 impl fmt::Display for Number {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        unsafe {
-            if self.is_nan() {
-                return f.write_str("nan")
-            }
-            let (positive, mantissa, exponent) = self.as_parts();
-            let mut buf = Vec::new();
-            print_dec::write(&mut buf, positive, mantissa, exponent).unwrap();
-            f.write_str(&String::from_utf8_unchecked(buf))
+        if self.is_nan() {
+            return f.write_str("nan")
         }
+        if let Some(wide) = self.wide() {
+            let sign = if self.is_sign_positive() { "" } else { "-" };
+            return write!(f, "{}{}", sign, wide);
+        }
+        let (positive, mantissa, exponent) = self.as_parts();
+        let mut buf = Vec::new();
+        print_dec::write(&mut buf, positive, mantissa, exponent).unwrap();
+
+        // Digits are all ASCII
+        f.write_str(str::from_utf8(&buf).expect("Can't fail"))
     }
 }
 
@@ -292,6 +396,10 @@ impl From<Number> for f64 {
     fn from(num: Number) -> f64 {
         if num.is_nan() { return f64::NAN; }
 
+        if let Some(wide) = num.wide() {
+            return if num.is_sign_positive() { wide as f64 } else { -(wide as f64) };
+        }
+
         let mut n = num.mantissa as f64;
         let mut e = num.exponent;
 
@@ -309,6 +417,10 @@ impl From<Number> for f32 {
     fn from(num: Number) -> f32 {
         if num.is_nan() { return f32::NAN; }
 
+        if let Some(wide) = num.wide() {
+            return if num.is_sign_positive() { wide as f32 } else { -(wide as f32) };
+        }
+
         let mut n = num.mantissa as f32;
         let mut e = num.exponent;
 
@@ -410,6 +522,7 @@ macro_rules! impl_unsigned {
                     category: POSITIVE,
                     exponent: 0,
                     mantissa: num as u64,
+                    high: 0,
                 }
             }
         }
@@ -440,13 +553,15 @@ macro_rules! impl_signed {
                     Number {
                         category: NEGATIVE,
                         exponent: 0,
-                        mantissa: -num as u64,
+                        mantissa: (num as i64).unsigned_abs(),
+                        high: 0,
                     }
                 } else {
                     Number {
                         category: POSITIVE,
                         exponent: 0,
                         mantissa: num as u64,
+                        high: 0,
                     }
                 }
             }
@@ -495,6 +610,45 @@ macro_rules! impl_integer {
 impl_signed!(isize, i8, i16, i32, i64);
 impl_unsigned!(usize, u8, u16, u32, u64);
 
+impl From<u128> for Number {
+    fn from(num: u128) -> Number {
+        Number::from_wide(true, num)
+    }
+}
+
+impl From<i128> for Number {
+    fn from(num: i128) -> Number {
+        Number::from_wide(num >= 0, num.unsigned_abs())
+    }
+}
+
+impl TryFrom<Number> for u128 {
+    type Error = NumberOutOfScope;
+
+    fn try_from(num: Number) -> Result<Self, Self::Error> {
+        match num.as_integer() {
+            Some(magnitude) if num.is_sign_positive() || magnitude == 0 => Ok(magnitude),
+            _ => Err(NumberOutOfScope),
+        }
+    }
+}
+
+impl TryFrom<Number> for i128 {
+    type Error = NumberOutOfScope;
+
+    fn try_from(num: Number) -> Result<Self, Self::Error> {
+        let magnitude = num.as_integer().ok_or(NumberOutOfScope)?;
+
+        match num.is_sign_positive() {
+            true  => i128::try_from(magnitude).map_err(Into::into),
+            false => 0i128.checked_sub_unsigned(magnitude).ok_or(NumberOutOfScope),
+        }
+    }
+}
+
+impl_integer!(i128);
+impl_integer!(u128);
+
 impl ops::Neg for Number {
     type Output = Number;
 
@@ -502,8 +656,7 @@ impl ops::Neg for Number {
     fn neg(self) -> Number {
         Number {
             category: self.category ^ POSITIVE,
-            exponent: self.exponent,
-            mantissa: self.mantissa,
+            ..self
         }
     }
 }
@@ -1,10 +1,17 @@
-use std::{ ptr, mem, str, slice, fmt };
+use std::{ mem, slice, fmt };
+#[cfg(not(feature = "forbid-unsafe"))]
+use std::{ ptr, str };
 use std::ops::{ Index, IndexMut, Deref };
 use std::iter::FromIterator;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 use crate::codegen::{ DumpGenerator, Generator, PrettyGenerator };
 use crate::value::JsonValue;
+use crate::Result;
+use crate::parser::{ self, Guide };
 
+#[cfg(not(feature = "forbid-unsafe"))]
 const KEY_BUF_LEN: usize = 32;
 static NULL: JsonValue = JsonValue::Null;
 
@@ -49,6 +56,7 @@ fn hash_key(key: &[u8]) -> u64 {
     hash
 }
 
+#[cfg(not(feature = "forbid-unsafe"))]
 struct Key {
     // Internal buffer to store keys that fit within `KEY_BUF_LEN`,
     // otherwise this field will contain garbage.
@@ -59,13 +67,15 @@ struct Key {
 
     // Cached raw pointer to the key, so that we can cheaply construct
     // a `&str` slice from the `Node` without checking if the key is
-    // allocated separately on the heap, or in the `key_buf`.
+    // allocated separately on the heap, or in the `key_buf`. Keys on the
+    // heap are an `Arc<str>`, possibly shared through a `KeyInterner`.
     pub ptr: *mut u8,
 
     // A hash of the key, explanation below.
     pub hash: u64,
 }
 
+#[cfg(not(feature = "forbid-unsafe"))]
 impl Key {
     #[inline]
     fn new(hash: u64, len: usize) -> Self {
@@ -96,7 +106,7 @@ impl Key {
     // For that reason it's not set in `Key::new` but only after
     // the `Node` is created and allocated.
     #[inline]
-    fn attach(&mut self, key: &[u8]) {
+    fn attach(&mut self, key: &str, interner: Option<&mut KeyInterner>) {
         if self.len <= KEY_BUF_LEN {
             unsafe {
                 ptr::copy_nonoverlapping(
@@ -107,12 +117,20 @@ impl Key {
             }
             self.ptr = self.buf.as_mut_ptr();
         } else {
-            let mut heap = key.to_vec();
-            self.ptr = heap.as_mut_ptr();
-            mem::forget(heap);
+            let heap = match interner {
+                Some(interner) => interner.intern(key),
+                None           => Arc::from(key),
+            };
+            self.ptr = Arc::into_raw(heap) as *mut u8;
         }
     }
 
+    // Pointer to the `Arc<str>` of a key on the heap.
+    #[inline]
+    fn as_arc_ptr(&self) -> *const str {
+        ptr::slice_from_raw_parts(self.ptr as *const u8, self.len) as *const str
+    }
+
     // Since we store `Node`s on a vector, it will suffer from reallocation.
     // Whenever that happens, `key.ptr` for short keys will turn into dangling
     // pointers and will need to be re-cached.
@@ -126,43 +144,37 @@ impl Key {
 
 // Implement `Sync` and `Send` for `Key` despite the use of raw pointers. The struct
 // itself should be memory safe.
+#[cfg(not(feature = "forbid-unsafe"))]
 unsafe impl Sync for Key {}
+#[cfg(not(feature = "forbid-unsafe"))]
 unsafe impl Send for Key {}
 
 // Because long keys _can_ be stored separately from the `Key` on heap,
 // it's essential to clean up the heap allocation when the `Key` is dropped.
+#[cfg(not(feature = "forbid-unsafe"))]
 impl Drop for Key {
     fn drop(&mut self) {
-        unsafe {
-            if self.len > KEY_BUF_LEN {
-                // Construct a `Vec` out of the `key_ptr`. Since the key is
-                // always allocated from a slice, the capacity is equal to length.
-                let heap = Vec::from_raw_parts(
-                    self.ptr,
-                    self.len,
-                    self.len
-                );
-
-                // Now that we have an owned `Vec<u8>`, drop it.
-                drop(heap);
-            }
+        if self.len > KEY_BUF_LEN {
+            // Take back the `Arc<str>` turned into `ptr` by `attach`, and
+            // drop it.
+            unsafe { drop(Arc::from_raw(self.as_arc_ptr())) };
         }
     }
 }
 
 // Just like with `Drop`, `Clone` needs a custom implementation that accounts
 // for the fact that key _can_ be separately heap allocated.
+#[cfg(not(feature = "forbid-unsafe"))]
 impl Clone for Key {
     fn clone(&self) -> Self {
         if self.len > KEY_BUF_LEN {
-            let mut heap = self.as_bytes().to_vec();
-            let ptr = heap.as_mut_ptr();
-            mem::forget(heap);
+            // Both keys share the same `Arc<str>`
+            unsafe { Arc::increment_strong_count(self.as_arc_ptr()) };
 
             Key {
                 buf: [0; KEY_BUF_LEN],
                 len: self.len,
-                ptr: ptr,
+                ptr: self.ptr,
                 hash: self.hash,
             }
         } else {
@@ -176,6 +188,46 @@ impl Clone for Key {
     }
 }
 
+// With `forbid-unsafe` all keys are simply allocated on the heap.
+#[cfg(feature = "forbid-unsafe")]
+#[derive(Clone)]
+struct Key {
+    pub name: Arc<str>,
+    pub hash: u64,
+}
+
+#[cfg(feature = "forbid-unsafe")]
+impl Key {
+    #[inline]
+    fn new(hash: u64, _len: usize) -> Self {
+        Key {
+            name: Arc::from(""),
+            hash
+        }
+    }
+
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.name.as_bytes()
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    fn attach(&mut self, key: &str, interner: Option<&mut KeyInterner>) {
+        self.name = match interner {
+            Some(interner) => interner.intern(key),
+            None           => Arc::from(key),
+        };
+    }
+
+    #[inline]
+    fn fix_ptr(&mut self) {}
+}
+
 #[derive(Clone)]
 struct Node {
     // String-esque key abstraction
@@ -233,7 +285,7 @@ impl Object {
     /// Create a new, empty instance of `Object`. Empty `Object` performs no
     /// allocation until a value is inserted into it.
     #[inline(always)]
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Object {
             store: Vec::new()
         }
@@ -248,13 +300,46 @@ impl Object {
         }
     }
 
-    #[inline]
-    fn node_at_index_mut(&mut self, index: usize) -> *mut Node {
-        unsafe { self.store.as_mut_ptr().offset(index as isize) }
+    // Indexes of nodes always come from the tree itself, so they are never
+    // out of bounds.
+    #[cfg(not(feature = "forbid-unsafe"))]
+    #[inline(always)]
+    fn node(&self, index: usize) -> &Node {
+        unsafe { self.store.get_unchecked(index) }
     }
 
+    #[cfg(not(feature = "forbid-unsafe"))]
     #[inline(always)]
-    fn add_node(&mut self, key: &[u8], value: JsonValue, hash: u64) -> usize {
+    fn node_mut(&mut self, index: usize) -> &mut Node {
+        unsafe { self.store.get_unchecked_mut(index) }
+    }
+
+    #[cfg(feature = "forbid-unsafe")]
+    #[inline(always)]
+    fn node(&self, index: usize) -> &Node {
+        &self.store[index]
+    }
+
+    #[cfg(feature = "forbid-unsafe")]
+    #[inline(always)]
+    fn node_mut(&mut self, index: usize) -> &mut Node {
+        &mut self.store[index]
+    }
+
+    #[cfg(feature = "forbid-unsafe")]
+    #[inline(always)]
+    fn add_node(&mut self, key: &str, value: JsonValue, hash: u64, interner: Option<&mut KeyInterner>) -> usize {
+        let index = self.store.len();
+
+        self.store.push(Node::new(value, hash, key.len()));
+        self.store[index].key.attach(key, interner);
+
+        index
+    }
+
+    #[cfg(not(feature = "forbid-unsafe"))]
+    #[inline(always)]
+    fn add_node(&mut self, key: &str, value: JsonValue, hash: u64, interner: Option<&mut KeyInterner>) -> usize {
         let index = self.store.len();
 
         if index < self.store.capacity() {
@@ -279,11 +364,11 @@ impl Object {
                 mem::forget(node);
             }
 
-            unsafe { self.store.get_unchecked_mut(index).key.attach(key) };
+            self.node_mut(index).key.attach(key, interner);
         } else {
             self.store.push(Node::new(value, hash, key.len()));
 
-            unsafe { self.store.get_unchecked_mut(index).key.attach(key) };
+            self.node_mut(index).key.attach(key, interner);
 
             // Index up to the index (old length), we don't need to fix
             // anything on the Node that just got pushed.
@@ -304,40 +389,45 @@ impl Object {
         self.insert_index(key, value);
     }
 
+    #[inline]
     pub(crate) fn insert_index(&mut self, key: &str, value: JsonValue) -> usize {
-        let key = key.as_bytes();
-        let hash = hash_key(key);
+        self.insert_index_interned(key, value, None)
+    }
+
+    // Same as `insert_index`, sharing new keys through `interner`.
+    pub(crate) fn insert_index_interned(&mut self, key: &str, value: JsonValue, interner: Option<&mut KeyInterner>) -> usize {
+        let bytes = key.as_bytes();
+        let hash = hash_key(bytes);
 
         if self.store.len() == 0 {
             self.store.push(Node::new(value, hash, key.len()));
-            self.store[0].key.attach(key);
+            self.store[0].key.attach(key, interner);
             return 0;
         }
 
-        let mut node = unsafe { &mut *self.node_at_index_mut(0) };
         let mut parent = 0;
 
         loop {
-            if hash == node.key.hash && key == node.key.as_bytes() {
+            let node = self.node_mut(parent);
+
+            if hash == node.key.hash && bytes == node.key.as_bytes() {
                 node.value = value;
                 return parent;
             } else if hash < node.key.hash {
                 if node.left != 0 {
                     parent = node.left;
-                    node = unsafe { &mut *self.node_at_index_mut(node.left) };
                     continue;
                 }
-                let index = self.add_node(key, value, hash);
+                let index = self.add_node(key, value, hash, interner);
                 self.store[parent].left = index;
 
                 return index;
             } else {
                 if node.right != 0 {
                     parent = node.right;
-                    node = unsafe { &mut *self.node_at_index_mut(node.right) };
                     continue;
                 }
-                let index = self.add_node(key, value, hash);
+                let index = self.add_node(key, value, hash, interner);
                 self.store[parent].right = index;
 
                 return index;
@@ -366,7 +456,7 @@ impl Object {
         let key = key.as_bytes();
         let hash = hash_key(key);
 
-        let mut node = unsafe { self.store.get_unchecked(0) };
+        let mut node = self.node(0);
 
         loop {
             if hash == node.key.hash && key == node.key.as_bytes() {
@@ -375,12 +465,12 @@ impl Object {
                 if node.left == 0 {
                     return None;
                 }
-                node = unsafe { self.store.get_unchecked(node.left) };
+                node = self.node(node.left);
             } else {
                 if node.right == 0 {
                     return None;
                 }
-                node = unsafe { self.store.get_unchecked(node.right) };
+                node = self.node(node.right);
             }
         }
     }
@@ -395,7 +485,7 @@ impl Object {
 
         let mut index = 0;
         {
-            let mut node = unsafe { self.store.get_unchecked(0) };
+            let mut node = self.node(0);
 
             loop {
                 if hash == node.key.hash && key == node.key.as_bytes() {
@@ -405,18 +495,18 @@ impl Object {
                         return None;
                     }
                     index = node.left;
-                    node = unsafe { self.store.get_unchecked(node.left) };
+                    node = self.node(node.left);
                 } else {
                     if node.right == 0 {
                         return None;
                     }
                     index = node.right;
-                    node = unsafe { self.store.get_unchecked(node.right) };
+                    node = self.node(node.right);
                 }
             }
         }
 
-        let node = unsafe { self.store.get_unchecked_mut(index) };
+        let node = self.node_mut(index);
 
         Some(&mut node.value)
     }
@@ -433,7 +523,7 @@ impl Object {
         let mut index = 0;
 
         {
-            let mut node = unsafe { self.store.get_unchecked(0) };
+            let mut node = self.node(0);
 
             // Try to find the node
             loop {
@@ -444,13 +534,13 @@ impl Object {
                         return None;
                     }
                     index = node.left;
-                    node = unsafe { self.store.get_unchecked(node.left) };
+                    node = self.node(node.left);
                 } else {
                     if node.right == 0 {
                         return None;
                     }
                     index = node.right;
-                    node = unsafe { self.store.get_unchecked(node.right) };
+                    node = self.node(node.right);
                 }
             }
         }
@@ -479,6 +569,36 @@ impl Object {
         removed
     }
 
+    /// Rename the entry under `old` to `new`, keeping its position. An
+    /// entry already under `new` is dropped. Returns `false` if there is no
+    /// entry under `old`.
+    pub fn rename(&mut self, old: &str, new: &str) -> bool {
+        if self.get(old).is_none() {
+            return false;
+        }
+
+        if old == new {
+            return true;
+        }
+
+        // Same as with `remove`, the tree is rebuilt
+        let mut new_object = Object::with_capacity(self.store.len());
+
+        for node in self.store.iter_mut() {
+            let value = mem::replace(&mut node.value, JsonValue::Null);
+
+            match node.key.as_str() {
+                key if key == old => new_object.insert(new, value),
+                key if key == new => {},
+                key               => new_object.insert(key, value),
+            }
+        }
+
+        mem::swap(self, &mut new_object);
+
+        true
+    }
+
     #[inline(always)]
     pub fn len(&self) -> usize {
         self.store.len()
@@ -494,6 +614,16 @@ impl Object {
         self.store.clear();
     }
 
+    /// Move all entries of `other` into this object, overriding the values
+    /// of keys present in both. Nested objects are replaced as a whole, not
+    /// merged. Keys new to this object are added at the end, in the order
+    /// they have in `other`.
+    pub fn merge_in(&mut self, mut other: Object) {
+        for (key, value) in other.iter_mut() {
+            self.insert(key, value.take());
+        }
+    }
+
     #[inline(always)]
     pub fn iter(&self) -> Iter {
         Iter {
@@ -508,6 +638,27 @@ impl Object {
         }
     }
 
+    #[inline(always)]
+    pub fn keys(&self) -> Keys<'_> {
+        Keys {
+            inner: self.iter()
+        }
+    }
+
+    #[inline(always)]
+    pub fn values(&self) -> Values<'_> {
+        Values {
+            inner: self.iter()
+        }
+    }
+
+    #[inline(always)]
+    pub fn values_mut(&mut self) -> ValuesMut<'_> {
+        ValuesMut {
+            inner: self.iter_mut()
+        }
+    }
+
     /// Prints out the value as JSON string.
     pub fn dump(&self) -> String {
         let mut gen = DumpGenerator::new();
@@ -553,6 +704,22 @@ impl<K: AsRef<str>, V: Into<JsonValue>> FromIterator<(K, V)> for Object {
     }
 }
 
+impl<K: AsRef<str>, V: Into<JsonValue>> Extend<(K, V)> for Object {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.store.reserve(iter.size_hint().0);
+
+        // Reserving can move the nodes, same as in `add_node`
+        for node in self.store.iter_mut() {
+            node.key.fix_ptr();
+        }
+
+        for (key, value) in iter {
+            self.insert(key.as_ref(), value.into());
+        }
+    }
+}
+
 // Because keys can inserted in different order, the safe way to
 // compare `Object`s is to iterate over one and check if the other
 // has all the same keys.
@@ -643,6 +810,114 @@ impl<'a> ExactSizeIterator for IterMut<'a> {
     }
 }
 
+/// Iterator over keys of an `Object`.
+pub struct Keys<'a> {
+    inner: Iter<'a>
+}
+
+impl<'a> Keys<'a> {
+    /// Create an empty iterator that always returns `None`
+    pub fn empty() -> Self {
+        Keys {
+            inner: Iter::empty()
+        }
+    }
+}
+
+impl<'a> Iterator for Keys<'a> {
+    type Item = &'a str;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Keys<'a> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a> ExactSizeIterator for Keys<'a> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+/// Iterator over values of an `Object`.
+pub struct Values<'a> {
+    inner: Iter<'a>
+}
+
+impl<'a> Values<'a> {
+    /// Create an empty iterator that always returns `None`
+    pub fn empty() -> Self {
+        Values {
+            inner: Iter::empty()
+        }
+    }
+}
+
+impl<'a> Iterator for Values<'a> {
+    type Item = &'a JsonValue;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Values<'a> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a> ExactSizeIterator for Values<'a> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+/// Mutable iterator over values of an `Object`.
+pub struct ValuesMut<'a> {
+    inner: IterMut<'a>
+}
+
+impl<'a> ValuesMut<'a> {
+    /// Create an empty iterator that always returns `None`
+    pub fn empty() -> Self {
+        ValuesMut {
+            inner: IterMut::empty()
+        }
+    }
+}
+
+impl<'a> Iterator for ValuesMut<'a> {
+    type Item = &'a mut JsonValue;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+}
+
+impl<'a> DoubleEndedIterator for ValuesMut<'a> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a> ExactSizeIterator for ValuesMut<'a> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
 /// Implements indexing by `&str` to easily access object members:
 ///
 /// ## Example
@@ -729,3 +1004,92 @@ impl<'a> IndexMut<&'a String> for Object {
         self.index_mut(index.deref())
     }
 }
+
+/// Shares the heap allocations of object keys between documents. Parsing
+/// many small documents with the same keys through one `KeyInterner`, such
+/// as messages read off a queue, keeps a single copy of every key for as
+/// long as any parsed value is using it.
+///
+/// ```
+/// # use json::object::KeyInterner;
+/// let mut interner = KeyInterner::new();
+///
+/// for id in 0 .. 3 {
+///     let message = format!(r#"{{"a_rather_long_key_that_is_allocated":{}}}"#, id);
+///     let data = interner.parse(&message).unwrap();
+///
+///     assert_eq!(data, json::parse(&message).unwrap());
+/// }
+///
+/// assert_eq!(interner.len(), 1);
+/// ```
+///
+/// Keys of up to 32 bytes are stored inline in their object without any
+/// allocation, so only longer keys are interned. With the `forbid-unsafe`
+/// feature all keys are allocated, and all are interned. Keys stay in the
+/// interner until it is cleared or dropped, even after all the values using
+/// them are gone.
+#[derive(Debug, Default)]
+pub struct KeyInterner {
+    keys: HashSet<Arc<str>>,
+}
+
+impl KeyInterner {
+    /// Create an empty interner.
+    pub fn new() -> Self {
+        KeyInterner::default()
+    }
+
+    /// Parse `source`, sharing the keys of its objects with all documents
+    /// parsed by this interner before.
+    pub fn parse(&mut self, source: &str) -> Result<JsonValue> {
+        parser::parse_guided(source, self)
+    }
+
+    /// Number of distinct keys held.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Forget all keys. Values parsed before keep theirs, but won't share
+    /// them with values parsed after.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    fn intern(&mut self, key: &str) -> Arc<str> {
+        if let Some(shared) = self.keys.get(key) {
+            return shared.clone();
+        }
+
+        let shared = Arc::from(key);
+        self.keys.insert(Arc::clone(&shared));
+        shared
+    }
+}
+
+impl Guide for KeyInterner {
+    #[inline(always)]
+    fn value(&mut self, _: u8) -> Result<()> { Ok(()) }
+
+    #[inline(always)]
+    fn open(&mut self) {}
+
+    #[inline(always)]
+    fn item(&mut self) {}
+
+    #[inline(always)]
+    fn key(&mut self, _: &str) -> Result<()> { Ok(()) }
+
+    #[inline(always)]
+    fn close(&mut self, _: &JsonValue, _: bool) -> Result<()> { Ok(()) }
+
+    #[inline(always)]
+    fn insert(&mut self, object: &mut Object, key: &str) -> usize {
+        object.insert_index_interned(key, JsonValue::Null, Some(self))
+    }
+}
@@ -17,12 +17,19 @@
 // This makes for some ugly code, but it is faster. Hopefully in the future
 // with MIR support the compiler will get smarter about this.
 
-use std::{str, slice};
+use std::{ io, mem, str };
+use std::io::Read;
+#[cfg(not(feature = "forbid-unsafe"))]
+use std::slice;
 use std::char::decode_utf16;
 use std::convert::TryFrom;
 use crate::object::Object;
 use crate::number::Number;
 use crate::{JsonValue, Error, Result};
+use crate::util::pointer;
+use crate::tape::{ Tape, Entry };
+#[cfg(feature = "arena")]
+use crate::arena::{ JsonArena, ArenaValue };
 
 // This is not actual max precision, but a threshold at which number parsing
 // kicks into checked math.
@@ -30,13 +37,21 @@ const MAX_PRECISION: u64 = 576460752303423500;
 
 
 // How many nested Objects/Arrays are allowed to be parsed
-const DEPTH_LIMIT: usize = 512;
+pub(crate) const DEPTH_LIMIT: usize = 512;
 
+// Bounds of the capacity new arrays start with. The capacity follows the
+// lengths of the arrays parsed so far at the same depth, arrays longer than
+// the upper bound grow as usual. Arrays nested deeper than the last depth
+// share its capacity.
+const MIN_ARRAY_CAPACITY: u8 = 2;
+const MAX_ARRAY_CAPACITY: u8 = 64;
+const ARRAY_CAPACITY_DEPTHS: usize = 8;
 
-// The `Parser` struct keeps track of indexing over our buffer. All niceness
+
+// The `Scanner` struct keeps track of indexing over our buffer. All niceness
 // has been abandoned in favor of raw pointer magic. Does that make you feel
 // dirty? _Good._
-struct Parser<'a> {
+struct Scanner<'a> {
     // Helper buffer for parsing strings that can't be just memcopied from
     // the original source (escaped characters)
     buffer: Vec<u8>,
@@ -45,6 +60,7 @@ struct Parser<'a> {
     source: &'a str,
 
     // Byte pointer to the slice above
+    #[cfg(not(feature = "forbid-unsafe"))]
     byte_ptr: *const u8,
 
     // Current index
@@ -52,6 +68,13 @@ struct Parser<'a> {
 
     // Length of the source
     length: usize,
+
+    // Capacity to allocate new arrays with at each depth, see
+    // `MAX_ARRAY_CAPACITY`
+    array_capacity: [u8; ARRAY_CAPACITY_DEPTHS],
+
+    // Set by `parse_with`, the other ways of parsing always use the defaults
+    options: ParseOptions,
 }
 
 
@@ -79,11 +102,11 @@ macro_rules! expect_byte {
 //
 // Anything else is an error.
 macro_rules! expect_sequence {
-    ($parser:ident, $( $ch:pat ),*) => {
+    ($parser:ident, $expected:expr; $( $ch:pat ),*) => {
         $(
             match expect_byte!($parser) {
                 $ch => {},
-                _   => return $parser.unexpected_character(),
+                _   => return $parser.unexpected_character($expected),
             }
         )*
     }
@@ -123,10 +146,7 @@ macro_rules! expect_eof {
         while !$parser.is_eof() {
             match $parser.read_byte() {
                 9 ..= 13 | 32 => $parser.bump(),
-                _             => {
-                    $parser.bump();
-                    return $parser.unexpected_character();
-                }
+                _             => return Err(Error::TrailingCharacters { index: $parser.index }),
             }
         }
     })
@@ -135,22 +155,22 @@ macro_rules! expect_eof {
 // Expect a particular byte to be next. Also available with a variant
 // creates a `match` expression just to ease some pain.
 macro_rules! expect {
-    ($parser:ident, $byte:expr) => ({
+    ($parser:ident, $byte:expr, $expected:expr) => ({
         let ch = expect_byte_ignore_whitespace!($parser);
 
         if ch != $byte {
-            return $parser.unexpected_character()
+            return $parser.unexpected_character($expected)
         }
     });
 
-    {$parser:ident $(, $byte:pat => $then:expr )*} => ({
+    {$parser:ident, $expected:expr $(, $byte:pat => $then:expr )*} => ({
         let ch = expect_byte_ignore_whitespace!($parser);
 
         match ch {
             $(
                 $byte => $then,
             )*
-            _ => return $parser.unexpected_character()
+            _ => return $parser.unexpected_character($expected)
         }
 
     })
@@ -159,11 +179,16 @@ macro_rules! expect {
 
 // Look up table that marks which characters are allowed in their raw
 // form in a string.
+#[cfg(not(feature = "optimize-size"))]
 const QU: bool = false;  // double quote       0x22
+#[cfg(not(feature = "optimize-size"))]
 const BS: bool = false;  // backslash          0x5C
+#[cfg(not(feature = "optimize-size"))]
 const CT: bool = false;  // control character  0x00 ..= 0x1F
+#[cfg(not(feature = "optimize-size"))]
 const __: bool = true;
 
+#[cfg(not(feature = "optimize-size"))]
 static ALLOWED: [bool; 256] = [
 // 0   1   2   3   4   5   6   7   8   9   A   B   C   D   E   F
   CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, CT, // 0
@@ -184,6 +209,19 @@ static ALLOWED: [bool; 256] = [
   __, __, __, __, __, __, __, __, __, __, __, __, __, __, __, __, // F
 ];
 
+#[cfg(not(feature = "optimize-size"))]
+#[inline(always)]
+fn allowed(ch: u8) -> bool {
+    ALLOWED[ch as usize]
+}
+
+// Same as the look up table, without the 256 bytes it takes.
+#[cfg(feature = "optimize-size")]
+#[inline(always)]
+fn allowed(ch: u8) -> bool {
+    ch >= 0x20 && ch != b'"' && ch != b'\\'
+}
+
 
 // Expect a string. This is called after encountering, and consuming, a
 // double quote character. This macro has a happy path variant where it
@@ -198,23 +236,32 @@ macro_rules! expect_string {
 
         loop {
             let ch = expect_byte!($parser);
-            if ALLOWED[ch as usize] {
+            if allowed(ch) {
                 continue;
             }
             if ch == b'"' {
+                #[cfg(not(feature = "forbid-unsafe"))]
                 unsafe {
                     let ptr = $parser.byte_ptr.offset(start as isize);
                     let len = $parser.index - 1 - start;
                     result = str::from_utf8_unchecked(slice::from_raw_parts(ptr, len));
                 }
+                #[cfg(feature = "forbid-unsafe")]
+                {
+                    let source = $parser.source;
+                    result = &source[start .. $parser.index - 1];
+                }
                 break;
             }
             if ch == b'\\' {
                 result = $parser.read_complex_string(start)?;
                 break;
             }
+            if $parser.options.raw_control_characters {
+                continue;
+            }
 
-            return $parser.unexpected_character();
+            return $parser.unexpected_character(expected::ESCAPED_CONTROL);
         }
 
         result
@@ -320,12 +367,12 @@ macro_rules! expect_fraction {
                     }
                 }
             },
-            _ => return $parser.unexpected_character()
+            _ => return $parser.unexpected_character(expected::DIGIT)
         }
 
         loop {
             if $parser.is_eof() {
-                result = unsafe { Number::from_parts_unchecked(true, $num, $e) };
+                result = Number::from_raw_parts(true, $num, $e);
                 break;
             }
             let ch = $parser.read_byte();
@@ -354,7 +401,7 @@ macro_rules! expect_fraction {
                     break;
                 }
                 _ => {
-                    result = unsafe { Number::from_parts_unchecked(true, $num, $e) };
+                    result = Number::from_raw_parts(true, $num, $e);
                     break;
                 }
             }
@@ -364,14 +411,21 @@ macro_rules! expect_fraction {
     })
 }
 
-impl<'a> Parser<'a> {
+impl<'a> Scanner<'a> {
     pub fn new(source: &'a str) -> Self {
-        Parser {
-            buffer: Vec::with_capacity(30),
+        Scanner::with_buffer(source, Vec::with_capacity(30))
+    }
+
+    fn with_buffer(source: &'a str, buffer: Vec<u8>) -> Self {
+        Scanner {
+            buffer,
             source: source,
+            #[cfg(not(feature = "forbid-unsafe"))]
             byte_ptr: source.as_ptr(),
             index: 0,
             length: source.len(),
+            array_capacity: [MIN_ARRAY_CAPACITY; ARRAY_CAPACITY_DEPTHS],
+            options: DEFAULT_OPTIONS,
         }
     }
 
@@ -387,6 +441,7 @@ impl<'a> Parser<'a> {
     // very very rarely, lead to a situation where the same byte is read
     // twice, but since this operation is using a raw pointer, the cost
     // is virtually irrelevant.
+    #[cfg(not(feature = "forbid-unsafe"))]
     #[inline(always)]
     fn read_byte(&mut self) -> u8 {
         debug_assert!(self.index < self.length, "Reading out of bounds");
@@ -394,6 +449,12 @@ impl<'a> Parser<'a> {
         unsafe { *self.byte_ptr.offset(self.index as isize) }
     }
 
+    #[cfg(feature = "forbid-unsafe")]
+    #[inline(always)]
+    fn read_byte(&mut self) -> u8 {
+        self.source.as_bytes()[self.index]
+    }
+
     // Manually increment the index. Calling `read_byte` and then `bump`
     // is equivalent to consuming a byte on an iterator.
     #[inline(always)]
@@ -403,27 +464,8 @@ impl<'a> Parser<'a> {
 
     // So we got an unexpected character, now what? Well, figure out where
     // it is, and throw an error!
-    fn unexpected_character<T: Sized>(&mut self) -> Result<T> {
-        let at = self.index - 1;
-
-        let ch = self.source[at..]
-                     .chars()
-                     .next()
-                     .expect("Must have a character");
-
-        let (lineno, col) = self.source[..at]
-                                .lines()
-                                .enumerate()
-                                .last()
-                                .unwrap_or((0, ""));
-
-        let colno = col.chars().count();
-
-        Err(Error::UnexpectedCharacter {
-            ch: ch,
-            line: lineno + 1,
-            column: colno + 1,
-        })
+    fn unexpected_character<T: Sized>(&mut self, expected: &'static [&'static str]) -> Result<T> {
+        Err(unexpected_character_at(self.source, self.index - 1, expected))
     }
 
     // Boring
@@ -433,7 +475,7 @@ impl<'a> Parser<'a> {
             b'0' ..= b'9' => (ch - b'0'),
             b'a' ..= b'f' => (ch + 10 - b'a'),
             b'A' ..= b'F' => (ch + 10 - b'A'),
-            _             => return self.unexpected_character(),
+            _             => return self.unexpected_character(expected::HEX_DIGIT),
         } as u16)
     }
 
@@ -458,7 +500,7 @@ impl<'a> Parser<'a> {
             Ok(code) => code,
             // Handle surrogate pairs
             Err(_) => {
-                expect_sequence!(self, b'\\', b'u');
+                expect_sequence!(self, expected::UNICODE_ESCAPE; b'\\', b'u');
 
                 match decode_utf16(
                     [codepoint, self.read_hexdec_codepoint()?].iter().copied()
@@ -482,7 +524,38 @@ impl<'a> Parser<'a> {
     // is whole lot slower than parsing "foobar", as the former suffers from
     // having to be read from source to a buffer and then from a buffer to
     // our target string. Nothing to be done about this, really.
+    #[cfg(not(feature = "forbid-unsafe"))]
     fn read_complex_string<'b>(&mut self, start: usize) -> Result<&'b str> {
+        let len = self.read_escaped_string(start)?;
+
+        // Since the original source is already valid UTF-8, and `\`
+        // cannot occur in front of a codepoint > 127, this is safe.
+        Ok(unsafe {
+            str::from_utf8_unchecked(
+                // Because the buffer is stored on the parser, returning it
+                // as a slice here freaks out the borrow checker. The compiler
+                // can't know that the buffer isn't used till the result
+                // of this function is long used and irrelevant. To avoid
+                // issues here, we construct a new slice from raw parts, which
+                // then has lifetime bound to the outer function scope instead
+                // of the parser itself.
+                slice::from_raw_parts(self.buffer[len .. ].as_ptr(), self.buffer.len() - len)
+            )
+        })
+    }
+
+    // Without the raw parts trick, the slice borrows the parser, which is
+    // fine as long as it's used before the parser moves on.
+    #[cfg(feature = "forbid-unsafe")]
+    fn read_complex_string(&mut self, start: usize) -> Result<&str> {
+        let len = self.read_escaped_string(start)?;
+
+        str::from_utf8(&self.buffer[len ..]).map_err(|_| Error::FailedUtf8Parsing)
+    }
+
+    // Reads the string into the buffer, returning the offset it starts at.
+    #[inline(always)]
+    fn read_escaped_string(&mut self, start: usize) -> Result<usize> {
         // Since string slices are returned by this function that are created via pointers into `self.buffer`
         // we shouldn't be clearing or modifying the buffer in consecutive calls to this function. Instead
         // we continuously append bytes to `self.buffer` and keep track of the starting offset of the buffer on each
@@ -499,7 +572,7 @@ impl<'a> Parser<'a> {
         self.buffer.extend_from_slice(&self.source.as_bytes()[start .. self.index - 1]);
 
         loop {
-            if ALLOWED[ch as usize] {
+            if allowed(ch) {
                 self.buffer.push(ch);
                 ch = expect_byte!(self);
                 continue;
@@ -514,6 +587,14 @@ impl<'a> Parser<'a> {
                             ch = expect_byte!(self);
                             continue;
                         },
+                        b'x' if self.options.hex_escapes => {
+                            let mut buf = [0; 4];
+                            let code = self.read_hexdec_digit()? << 4 | self.read_hexdec_digit()?;
+
+                            self.buffer.extend_from_slice((code as u8 as char).encode_utf8(&mut buf).as_bytes());
+                            ch = expect_byte!(self);
+                            continue;
+                        },
                         b'"'  |
                         b'\\' |
                         b'/'  => escaped,
@@ -522,47 +603,39 @@ impl<'a> Parser<'a> {
                         b't'  => b'\t',
                         b'r'  => b'\r',
                         b'n'  => b'\n',
-                        _     => return self.unexpected_character()
+                        _     => return self.unexpected_character(expected::ESCAPE)
                     };
                     self.buffer.push(escaped);
                 },
-                _ => return self.unexpected_character()
+                _ if self.options.raw_control_characters => self.buffer.push(ch),
+                _ => return self.unexpected_character(expected::ESCAPED_CONTROL)
             }
             ch = expect_byte!(self);
         }
 
-        // Since the original source is already valid UTF-8, and `\`
-        // cannot occur in front of a codepoint > 127, this is safe.
-        Ok(unsafe {
-            str::from_utf8_unchecked(
-                // Because the buffer is stored on the parser, returning it
-                // as a slice here freaks out the borrow checker. The compiler
-                // can't know that the buffer isn't used till the result
-                // of this function is long used and irrelevant. To avoid
-                // issues here, we construct a new slice from raw parts, which
-                // then has lifetime bound to the outer function scope instead
-                // of the parser itself.
-                slice::from_raw_parts(self.buffer[len .. ].as_ptr(), self.buffer.len() - len)
-            )
-        })
+        Ok(len)
     }
 
     // Big numbers! If the `expect_number!` reaches a point where the decimal
     // mantissa could have overflown the size of u64, it will switch to this
     // control path instead. This method will pick up where the macro started,
     // but instead of continuing to read into the mantissa, it will increment
-    // the exponent. Note that no digits are actually read here, as we already
-    // exceeded the precision range of f64 anyway.
+    // the exponent. Integers up to 128 bits are also read into `wide`, and
+    // kept whole if the number ends up being one.
     fn read_big_number(&mut self, mut num: u64) -> Result<Number> {
         let mut e = 0i16;
+        let mut wide = Some(num as u128);
+
         loop {
             if self.is_eof() {
-                return Ok(unsafe { Number::from_parts_unchecked(true, num, e) });
+                break;
             }
             let ch = self.read_byte();
             match ch {
                 b'0' ..= b'9' => {
                     self.bump();
+                    wide = wide.and_then(|wide| wide.checked_mul(10)?.checked_add((ch - b'0') as u128));
+
                     match num.checked_mul(10).and_then(|num| {
                         num.checked_add((ch - b'0') as u64)
                     }) {
@@ -582,7 +655,10 @@ impl<'a> Parser<'a> {
             }
         }
 
-        Ok(unsafe { Number::from_parts_unchecked(true, num, e) })
+        Ok(match wide {
+            Some(wide) if e > 0 => Number::from_wide(true, wide),
+            _                   => Number::from_raw_parts(true, num, e),
+        })
     }
 
     // Called in the rare case that a number with `e` notation has been
@@ -603,7 +679,7 @@ impl<'a> Parser<'a> {
 
         let mut e = match ch {
             b'0' ..= b'9' => (ch - b'0') as i16,
-            _ => return self.unexpected_character(),
+            _ => return self.unexpected_character(expected::DIGIT),
         };
 
         loop {
@@ -620,25 +696,170 @@ impl<'a> Parser<'a> {
             }
         }
 
-        Ok(unsafe { Number::from_parts_unchecked(true, num, big_e.saturating_add(e * sign)) })
+        Ok(Number::from_raw_parts(true, num, big_e.saturating_add(e * sign)))
     }
 
-    // Parse away!
-    fn parse(&mut self) -> Result<JsonValue> {
-        let mut stack = Vec::with_capacity(3);
+    // Apply the `precision_loss` option to the number that started at byte
+    // `start` and was just read.
+    #[cold]
+    fn check_precision(&mut self, start: usize, stack: &[StackBlock]) -> Result<()> {
+        let token = &self.source[start .. self.index];
+        let digits = token.strip_prefix(['-', '+']).unwrap_or(token);
+
+        if !digits.bytes().all(|ch| ch.is_ascii_digit()) || exact_in_f64(digits.as_bytes()) {
+            return Ok(());
+        }
+
+        if let PrecisionLoss::Warn(warn) = self.options.precision_loss {
+            warn(&stack_path(stack), token);
+            return Ok(());
+        }
+
+        Err(self.number_error(start, stack, |number, line, column| Error::PrecisionLoss { number, line, column }))
+    }
+
+    // Apply the `exponent_overflow` option to the `number` that started at
+    // byte `start` and was just read.
+    #[cold]
+    fn check_overflow(&mut self, start: usize, number: Number, stack: &[StackBlock]) -> Result<Number> {
+        if !f64::from(number).is_infinite() {
+            return Ok(number);
+        }
+
+        match self.options.exponent_overflow {
+            ExponentOverflow::Infinity => Ok(number),
+            ExponentOverflow::Clamp    => Ok(match number.is_sign_positive() {
+                true  => f64::MAX.into(),
+                false => f64::MIN.into(),
+            }),
+            ExponentOverflow::Error    => Err(self.number_error(start, stack, |number, line, column| {
+                Error::NumberOutOfRange { number, line, column }
+            })),
+        }
+    }
+
+    // Error about the number that started at byte `start` and was just read,
+    // nested in the path to it.
+    fn number_error<F>(&self, start: usize, stack: &[StackBlock], error: F) -> Error
+    where
+        F: FnOnce(String, usize, usize) -> Error
+    {
+        let (line, column) = line_and_column(self.source, start);
+        let error = error(self.source[start .. self.index].into(), line, column);
+
+        match stack.is_empty() {
+            true  => error,
+            false => Error::nested(stack_path(stack), error),
+        }
+    }
+
+    // Read a number that started at byte `start` in the relaxed syntax of
+    // `ParseOptions::lenient_numbers`, by rewriting it into strict JSON.
+    #[cold]
+    fn read_lenient_number(&mut self, start: usize) -> Result<Number> {
+        let mut strict = String::new();
+
+        self.index = start;
+
+        match self.read_byte() {
+            b'-' => {
+                strict.push('-');
+                self.bump();
+            },
+            b'+' => self.bump(),
+            _    => {},
+        }
+
+        let integer = self.lenient_digits();
+        let fraction = match !self.is_eof() && self.read_byte() == b'.' {
+            true  => {
+                self.bump();
+                self.lenient_digits()
+            },
+            false => "",
+        };
+
+        if integer.is_empty() && fraction.is_empty() {
+            expect_byte!(self);
+            return self.unexpected_character(expected::DIGIT);
+        }
+
+        strict.push_str(match integer.trim_start_matches('0') {
+            ""     => "0",
+            digits => digits,
+        });
+
+        if !fraction.is_empty() {
+            strict.push('.');
+            strict.push_str(fraction);
+        }
+
+        if !self.is_eof() && matches!(self.read_byte(), b'e' | b'E') {
+            strict.push('e');
+            self.bump();
+
+            if !self.is_eof() && matches!(self.read_byte(), b'+' | b'-') {
+                strict.push(self.read_byte() as char);
+                self.bump();
+            }
+
+            let exponent = self.lenient_digits();
+
+            if exponent.is_empty() {
+                expect_byte!(self);
+                return self.unexpected_character(expected::DIGIT);
+            }
+
+            strict.push_str(exponent);
+        }
+
+        match parse(&strict)? {
+            JsonValue::Number(number) => Ok(number),
+            _                         => unreachable!("{} is a number", strict),
+        }
+    }
+
+    fn lenient_digits(&mut self) -> &'a str {
+        let start = self.index;
+
+        while !self.is_eof() && self.read_byte().is_ascii_digit() {
+            self.bump();
+        }
+
+        &self.source[start .. self.index]
+    }
+
+    // Move the capacity for new arrays at `depth` halfway towards `len`,
+    // rounding up so that arrays of the same length settle on an exact fit.
+    #[inline(always)]
+    fn fit_array_capacity(&mut self, depth: usize, len: usize) {
+        let capacity = &mut self.array_capacity[depth.min(ARRAY_CAPACITY_DEPTHS - 1)];
+        let len = len.min(MAX_ARRAY_CAPACITY as usize) as u8;
+
+        *capacity = (*capacity + len).div_ceil(2).max(MIN_ARRAY_CAPACITY);
+    }
+
+    // Parse away! The `stack` is expected to be empty.
+    fn parse<G: Guide>(&mut self, guide: &mut G, stack: &mut Vec<StackBlock>) -> Result<JsonValue> {
         let mut ch = expect_byte_ignore_whitespace!(self);
 
         'parsing: loop {
+            guide.value(ch)?;
+
             let mut value = match ch {
                 b'[' => {
                     ch = expect_byte_ignore_whitespace!(self);
 
                     if ch != b']' {
                         if stack.len() == DEPTH_LIMIT {
-                            return Err(Error::ExceededDepthLimit);
+                            return Err(Error::nested(stack_path(stack), Error::ExceededDepthLimit));
                         }
 
-                        stack.push(StackBlock(JsonValue::Array(Vec::with_capacity(2)), 0));
+                        guide.open();
+                        guide.item();
+                        let capacity = self.array_capacity[stack.len().min(ARRAY_CAPACITY_DEPTHS - 1)];
+
+                        stack.push(StackBlock(JsonValue::Array(Vec::with_capacity(capacity as usize)), 0));
                         continue 'parsing;
                     }
 
@@ -649,17 +870,21 @@ impl<'a> Parser<'a> {
 
                     if ch != b'}' {
                         if stack.len() == DEPTH_LIMIT {
-                            return Err(Error::ExceededDepthLimit);
+                            return Err(Error::nested(stack_path(stack), Error::ExceededDepthLimit));
                         }
 
                         let mut object = Object::with_capacity(3);
 
                         if ch != b'"' {
-                            return self.unexpected_character()
+                            return self.unexpected_character(expected::FIRST_KEY)
                         }
 
-                        let index = object.insert_index(expect_string!(self), JsonValue::Null);
-                        expect!(self, b':');
+                        guide.open();
+                        let key = expect_string!(self);
+                        guide.key(key)?;
+
+                        let index = guide.insert(&mut object, key);
+                        expect!(self, b':', expected::COLON);
 
                         stack.push(StackBlock(JsonValue::Object(object), index));
 
@@ -668,34 +893,51 @@ impl<'a> Parser<'a> {
                         continue 'parsing;
                     }
 
-                    JsonValue::Object(Object::new())
+                    let value = JsonValue::Object(Object::new());
+                    guide.close(&value, false)?;
+                    value
                 },
                 b'"' => expect_string!(self).into(),
-                b'0' => JsonValue::Number(allow_number_extensions!(self)),
-                b'1' ..= b'9' => {
-                    JsonValue::Number(expect_number!(self, ch))
-                },
-                b'-' => {
-                    let ch = expect_byte!(self);
-                    JsonValue::Number(- match ch {
+                b'0' ..= b'9' | b'-' | b'+' | b'.' => {
+                    let start = self.index - 1;
+
+                    let mut number = match ch {
+                        _ if self.options.lenient_numbers => self.read_lenient_number(start)?,
                         b'0' => allow_number_extensions!(self),
                         b'1' ..= b'9' => expect_number!(self, ch),
-                        _    => return self.unexpected_character()
-                    })
-                }
+                        b'-' => {
+                            let ch = expect_byte!(self);
+                            - match ch {
+                                b'0' => allow_number_extensions!(self),
+                                b'1' ..= b'9' => expect_number!(self, ch),
+                                _    => return self.unexpected_character(expected::DIGIT)
+                            }
+                        },
+                        _ => return self.unexpected_character(expected::VALUE),
+                    };
+
+                    if !matches!(self.options.precision_loss, PrecisionLoss::Ignore) {
+                        self.check_precision(start, stack)?;
+                    }
+                    if !matches!(self.options.exponent_overflow, ExponentOverflow::Infinity) {
+                        number = self.check_overflow(start, number, stack)?;
+                    }
+
+                    JsonValue::Number(number)
+                },
                 b't' => {
-                    expect_sequence!(self, b'r', b'u', b'e');
+                    expect_sequence!(self, expected::TRUE; b'r', b'u', b'e');
                     JsonValue::Boolean(true)
                 },
                 b'f' => {
-                    expect_sequence!(self, b'a', b'l', b's', b'e');
+                    expect_sequence!(self, expected::FALSE; b'a', b'l', b's', b'e');
                     JsonValue::Boolean(false)
                 },
                 b'n' => {
-                    expect_sequence!(self, b'u', b'l', b'l');
+                    expect_sequence!(self, expected::NULL; b'u', b'l', b'l');
                     JsonValue::Null
                 },
-                _    => return self.unexpected_character()
+                _    => return self.unexpected_character(expected::VALUE)
             };
 
             'popping: loop {
@@ -714,11 +956,12 @@ impl<'a> Parser<'a> {
                         match ch {
                             b',' => {
                                 ch = expect_byte_ignore_whitespace!(self);
+                                guide.item();
 
                                 continue 'parsing;
                             },
                             b']' => {},
-                            _    => return self.unexpected_character()
+                            _    => return self.unexpected_character(expected::ARRAY_NEXT)
                         }
                     },
 
@@ -729,16 +972,19 @@ impl<'a> Parser<'a> {
 
                         match ch {
                             b',' => {
-                                expect!(self, b'"');
-                                *index = object.insert_index(expect_string!(self), JsonValue::Null);
-                                expect!(self, b':');
+                                expect!(self, b'"', expected::KEY);
+                                let key = expect_string!(self);
+                                guide.key(key)?;
+
+                                *index = guide.insert(object, key);
+                                expect!(self, b':', expected::COLON);
 
                                 ch = expect_byte_ignore_whitespace!(self);
 
                                 continue 'parsing;
                             },
                             b'}' => {},
-                            _    => return self.unexpected_character()
+                            _    => return self.unexpected_character(expected::OBJECT_NEXT)
                         }
                     },
 
@@ -748,18 +994,919 @@ impl<'a> Parser<'a> {
                 value = match stack.pop() {
                     Some(StackBlock(value, _)) => value,
                     None                       => break 'popping
+                };
+
+                if let JsonValue::Array(ref array) = value {
+                    self.fit_array_capacity(stack.len(), array.len());
                 }
+
+                guide.close(&value, true)?;
+            }
+        }
+    }
+
+    // Same as `parse`, building an `ArenaValue` instead. Members of unfinished
+    // arrays and objects wait on the `items` and `entries` stacks, shared by
+    // all levels of nesting, and are copied into the arena in one go once
+    // their array or object is closed.
+    #[cfg(feature = "arena")]
+    fn parse_arena<'arena>(&mut self, arena: &'arena JsonArena) -> Result<ArenaValue<'arena>> {
+        let mut stack = Vec::with_capacity(3);
+        let mut items = Vec::new();
+        let mut entries = Vec::new();
+        let mut ch = expect_byte_ignore_whitespace!(self);
+
+        'parsing: loop {
+            let mut value = match ch {
+                b'[' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    if ch != b']' {
+                        if stack.len() == DEPTH_LIMIT {
+                            let path = arena_stack_path(&stack, items.len());
+                            return Err(Error::nested(path, Error::ExceededDepthLimit));
+                        }
+
+                        stack.push(ArenaBlock::Array(items.len()));
+                        continue 'parsing;
+                    }
+
+                    ArenaValue::Array(&[])
+                },
+                b'{' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    if ch != b'}' {
+                        if stack.len() == DEPTH_LIMIT {
+                            let path = arena_stack_path(&stack, items.len());
+                            return Err(Error::nested(path, Error::ExceededDepthLimit));
+                        }
+
+                        if ch != b'"' {
+                            return self.unexpected_character(expected::FIRST_KEY)
+                        }
+
+                        let key = arena.alloc_str(expect_string!(self));
+                        expect!(self, b':', expected::COLON);
+
+                        stack.push(ArenaBlock::Object(entries.len(), key));
+
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        continue 'parsing;
+                    }
+
+                    ArenaValue::Object(&[])
+                },
+                b'"' => ArenaValue::String(arena.alloc_str(expect_string!(self))),
+                b'0' => ArenaValue::Number(allow_number_extensions!(self)),
+                b'1' ..= b'9' => {
+                    ArenaValue::Number(expect_number!(self, ch))
+                },
+                b'-' => {
+                    let ch = expect_byte!(self);
+                    ArenaValue::Number(- match ch {
+                        b'0' => allow_number_extensions!(self),
+                        b'1' ..= b'9' => expect_number!(self, ch),
+                        _    => return self.unexpected_character(expected::DIGIT)
+                    })
+                }
+                b't' => {
+                    expect_sequence!(self, expected::TRUE; b'r', b'u', b'e');
+                    ArenaValue::Boolean(true)
+                },
+                b'f' => {
+                    expect_sequence!(self, expected::FALSE; b'a', b'l', b's', b'e');
+                    ArenaValue::Boolean(false)
+                },
+                b'n' => {
+                    expect_sequence!(self, expected::NULL; b'u', b'l', b'l');
+                    ArenaValue::Null
+                },
+                _    => return self.unexpected_character(expected::VALUE)
+            };
+
+            loop {
+                match stack.last_mut() {
+                    None => {
+                        expect_eof!(self);
+
+                        return Ok(value);
+                    },
+
+                    Some(&mut ArenaBlock::Array(_)) => {
+                        items.push(value);
+
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        match ch {
+                            b',' => {
+                                ch = expect_byte_ignore_whitespace!(self);
+
+                                continue 'parsing;
+                            },
+                            b']' => {},
+                            _    => return self.unexpected_character(expected::ARRAY_NEXT)
+                        }
+                    },
+
+                    Some(&mut ArenaBlock::Object(_, ref mut key)) => {
+                        entries.push((*key, value));
+
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        match ch {
+                            b',' => {
+                                expect!(self, b'"', expected::KEY);
+                                *key = arena.alloc_str(expect_string!(self));
+                                expect!(self, b':', expected::COLON);
+
+                                ch = expect_byte_ignore_whitespace!(self);
+
+                                continue 'parsing;
+                            },
+                            b'}' => {},
+                            _    => return self.unexpected_character(expected::OBJECT_NEXT)
+                        }
+                    },
+                }
+
+                value = match stack.pop() {
+                    Some(ArenaBlock::Array(start)) => {
+                        let array = ArenaValue::Array(arena.alloc_slice(&items[start ..]));
+                        items.truncate(start);
+                        array
+                    },
+                    Some(ArenaBlock::Object(start, _)) => {
+                        let object = ArenaValue::Object(arena.alloc_slice(&entries[start ..]));
+                        entries.truncate(start);
+                        object
+                    },
+                    None => unreachable!(),
+                };
+            }
+        }
+    }
+
+    // Same as `parse`, writing entries to a `Tape` instead. Arrays and objects
+    // get their entry as they are opened, filled in with the number of members
+    // and their end once they are closed.
+    fn parse_tape(&mut self, tape: &mut Tape) -> Result<()> {
+        let mut stack: Vec<TapeBlock> = Vec::with_capacity(3);
+        let mut ch = expect_byte_ignore_whitespace!(self);
+
+        'parsing: loop {
+            match ch {
+                b'[' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    let index = tape.entries.len();
+                    tape.entries.push(Entry::Array(0, index + 1));
+
+                    if ch != b']' {
+                        if stack.len() == DEPTH_LIMIT {
+                            return Err(Error::nested(tape_stack_path(tape, &stack), Error::ExceededDepthLimit));
+                        }
+
+                        stack.push(TapeBlock(index, 0, 0));
+                        continue 'parsing;
+                    }
+                },
+                b'{' => {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    let index = tape.entries.len();
+                    tape.entries.push(Entry::Object(0, index + 1));
+
+                    if ch != b'}' {
+                        if stack.len() == DEPTH_LIMIT {
+                            return Err(Error::nested(tape_stack_path(tape, &stack), Error::ExceededDepthLimit));
+                        }
+
+                        if ch != b'"' {
+                            return self.unexpected_character(expected::FIRST_KEY)
+                        }
+
+                        tape.push_str(expect_string!(self));
+                        expect!(self, b':', expected::COLON);
+
+                        stack.push(TapeBlock(index, 0, index + 1));
+
+                        ch = expect_byte_ignore_whitespace!(self);
+
+                        continue 'parsing;
+                    }
+                },
+                b'"' => tape.push_str(expect_string!(self)),
+                b'0' => tape.entries.push(Entry::Number(allow_number_extensions!(self))),
+                b'1' ..= b'9' => {
+                    tape.entries.push(Entry::Number(expect_number!(self, ch)));
+                },
+                b'-' => {
+                    let ch = expect_byte!(self);
+                    tape.entries.push(Entry::Number(- match ch {
+                        b'0' => allow_number_extensions!(self),
+                        b'1' ..= b'9' => expect_number!(self, ch),
+                        _    => return self.unexpected_character(expected::DIGIT)
+                    }));
+                }
+                b't' => {
+                    expect_sequence!(self, expected::TRUE; b'r', b'u', b'e');
+                    tape.entries.push(Entry::Boolean(true));
+                },
+                b'f' => {
+                    expect_sequence!(self, expected::FALSE; b'a', b'l', b's', b'e');
+                    tape.entries.push(Entry::Boolean(false));
+                },
+                b'n' => {
+                    expect_sequence!(self, expected::NULL; b'u', b'l', b'l');
+                    tape.entries.push(Entry::Null);
+                },
+                _    => return self.unexpected_character(expected::VALUE)
+            }
+
+            loop {
+                let block = match stack.last_mut() {
+                    Some(block) => block,
+                    None => {
+                        expect_eof!(self);
+
+                        return Ok(());
+                    },
+                };
+
+                block.1 += 1;
+
+                ch = expect_byte_ignore_whitespace!(self);
+
+                let closed = match (ch, tape.entries[block.0]) {
+                    (b',', Entry::Array(..)) => false,
+                    (b',', _) => {
+                        expect!(self, b'"', expected::KEY);
+                        block.2 = tape.entries.len();
+                        tape.push_str(expect_string!(self));
+                        expect!(self, b':', expected::COLON);
+
+                        false
+                    },
+                    (b']', Entry::Array(..)) => true,
+                    (b'}', Entry::Object(..)) => true,
+                    (_, Entry::Array(..)) => return self.unexpected_character(expected::ARRAY_NEXT),
+                    _ => return self.unexpected_character(expected::OBJECT_NEXT),
+                };
+
+                if !closed {
+                    ch = expect_byte_ignore_whitespace!(self);
+
+                    continue 'parsing;
+                }
+
+                let end = tape.entries.len();
+                tape.entries[block.0] = match tape.entries[block.0] {
+                    Entry::Array(..) => Entry::Array(block.1, end),
+                    _                => Entry::Object(block.1, end),
+                };
+
+                stack.pop();
             }
         }
     }
 }
 
+#[derive(Debug)]
 struct StackBlock(JsonValue, usize);
 
+// Unfinished array or object on a tape: index of its entry, number of members
+// so far and for objects the index of the key being parsed.
+struct TapeBlock(usize, usize, usize);
+
+// Unfinished array or object with the index of its first member on the
+// shared stack, objects also keep the key of the member being parsed.
+#[cfg(feature = "arena")]
+enum ArenaBlock<'arena> {
+    Array(usize),
+    Object(usize, &'arena str),
+}
+
+// JSON Pointer of the value about to be parsed, used for errors.
+fn stack_path(stack: &[StackBlock]) -> String {
+    let mut path = String::new();
+
+    for block in stack {
+        match *block {
+            StackBlock(JsonValue::Array(ref array), _) => {
+                pointer::push_index(&mut path, array.len());
+            },
+            StackBlock(JsonValue::Object(ref object), index) => {
+                if let Some((key, _)) = object.iter().nth(index) {
+                    pointer::push_key(&mut path, key);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    path
+}
+
+// Same as `stack_path`, `items` being the length of the shared stack of
+// array members.
+#[cfg(feature = "arena")]
+fn arena_stack_path(stack: &[ArenaBlock], items: usize) -> String {
+    let mut path = String::new();
+
+    for (depth, block) in stack.iter().enumerate() {
+        match *block {
+            ArenaBlock::Array(start) => {
+                // Members of this array end where the next nested array starts
+                let end = stack[depth + 1 ..].iter().find_map(|block| match *block {
+                    ArenaBlock::Array(start) => Some(start),
+                    _                        => None,
+                });
+                pointer::push_index(&mut path, end.unwrap_or(items) - start);
+            },
+            ArenaBlock::Object(_, key) => pointer::push_key(&mut path, key),
+        }
+    }
+
+    path
+}
+
+// What could have been in place of an unexpected character, listed in
+// `Error::UnexpectedCharacter`. Literal tokens are written as they appear in
+// JSON, descriptions of a kind of token start with "a" or "an".
+pub(crate) mod expected {
+    pub const VALUE: &[&str] = &["a value"];
+    pub const CONTAINER: &[&str] = &["{", "["];
+    pub const ARRAY_NEXT: &[&str] = &[",", "]"];
+    pub const OBJECT_NEXT: &[&str] = &[",", "}"];
+    pub const FIRST_KEY: &[&str] = &["a string", "}"];
+    pub const KEY: &[&str] = &["a string"];
+    pub const COLON: &[&str] = &[":"];
+    pub const TRUE: &[&str] = &["true"];
+    pub const FALSE: &[&str] = &["false"];
+    pub const NULL: &[&str] = &["null"];
+    pub const DIGIT: &[&str] = &["a digit"];
+    pub const HEX_DIGIT: &[&str] = &["a hex digit"];
+    pub const ESCAPE: &[&str] = &["\"", "\\", "/", "b", "f", "n", "r", "t", "u"];
+    pub const UNICODE_ESCAPE: &[&str] = &["\\u"];
+    pub const ESCAPED_CONTROL: &[&str] = &["an escaped control character"];
+}
+
+// Error for the character starting at byte `at` of `source`.
+pub(crate) fn unexpected_character_at(source: &str, at: usize, expected: &'static [&'static str]) -> Error {
+    let ch = source[at..]
+                 .chars()
+                 .next()
+                 .expect("Must have a character");
+
+    let (line, column) = line_and_column(source, at);
+
+    Error::UnexpectedCharacter {
+        ch: ch,
+        line,
+        column,
+        expected,
+    }
+}
+
+// Line and column, both counted from 1, of byte `at` of `source`.
+pub(crate) fn line_and_column(source: &str, at: usize) -> (usize, usize) {
+    let (lineno, col) = source[..at]
+                            .lines()
+                            .enumerate()
+                            .last()
+                            .unwrap_or((0, ""));
+
+    (lineno + 1, col.chars().count() + 1)
+}
+
+// Same as `stack_path`, for a tape.
+fn tape_stack_path(tape: &Tape, stack: &[TapeBlock]) -> String {
+    let mut path = String::new();
+
+    for &TapeBlock(start, len, key) in stack {
+        match tape.entries[start] {
+            Entry::Array(..) => pointer::push_index(&mut path, len),
+            _                => pointer::push_key(&mut path, tape.str_at(key)),
+        }
+    }
+
+    path
+}
+
+// Hooks called by the parser as it goes, letting values be checked before
+// they are parsed. All hooks refer to the value being parsed at the time:
+//
+// - `value` is called with the first byte of every value.
+// - `open` is called when a non-empty array or object is entered, followed
+//   by `item` before every array item or `key` with the key of every object
+//   member.
+// - `close` is called with every finished object, `nested` is false for
+//   empty objects, for which there was no `open`.
+//
+// `insert` adds every key to its object after `key`, returning its index.
+pub(crate) trait Guide {
+    fn value(&mut self, ch: u8) -> Result<()>;
+    fn open(&mut self);
+    fn item(&mut self);
+    fn key(&mut self, key: &str) -> Result<()>;
+    fn close(&mut self, value: &JsonValue, nested: bool) -> Result<()>;
+
+    #[inline(always)]
+    fn insert(&mut self, object: &mut Object, key: &str) -> usize {
+        object.insert_index(key, JsonValue::Null)
+    }
+}
+
+// Guide that accepts everything, optimized away entirely.
+struct Unguided;
+
+impl Guide for Unguided {
+    #[inline(always)]
+    fn value(&mut self, _: u8) -> Result<()> { Ok(()) }
+
+    #[inline(always)]
+    fn open(&mut self) {}
+
+    #[inline(always)]
+    fn item(&mut self) {}
+
+    #[inline(always)]
+    fn key(&mut self, _: &str) -> Result<()> { Ok(()) }
+
+    #[inline(always)]
+    fn close(&mut self, _: &JsonValue, _: bool) -> Result<()> { Ok(()) }
+}
+
 // All that hard work, and in the end it's just a single function in the API.
 #[inline]
 pub fn parse(source: &str) -> Result<JsonValue> {
-    Parser::new(source).parse()
+    Scanner::new(source).parse(&mut Unguided, &mut Vec::with_capacity(3))
+}
+
+/// Parse `source` with custom `ParseOptions`.
+///
+/// ```
+/// # use json::{ ParseOptions, PrecisionLoss };
+/// let options = ParseOptions {
+///     precision_loss: PrecisionLoss::Error,
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(json::parse_with(r#"{"id":9007199254740992}"#, &options).is_ok());
+///
+/// let err = json::parse_with(r#"{"id":9007199254740993}"#, &options).unwrap_err();
+///
+/// assert_eq!(err.to_string(), "Number 9007199254740993 can't be represented exactly by f64 at (1:7) at /id");
+/// ```
+pub fn parse_with(source: &str, options: &ParseOptions) -> Result<JsonValue> {
+    if options.require_container {
+        let root = source.bytes().position(|ch| !matches!(ch, 9 ..= 13 | 32));
+
+        if let Some(index) = root.filter(|&index| !matches!(source.as_bytes()[index], b'{' | b'[')) {
+            return Err(unexpected_character_at(source, index, expected::CONTAINER));
+        }
+    }
+
+    let mut scanner = Scanner::new(source);
+    scanner.options = *options;
+    scanner.parse(&mut Unguided, &mut Vec::with_capacity(3))
+}
+
+/// Parse `source` that may not be valid UTF-8, replacing every invalid
+/// sequence with U+FFFD, the replacement character, for salvaging what's
+/// left of damaged data. Only strings can hold the replacement, invalid
+/// bytes anywhere else still fail as unexpected characters.
+///
+/// ```
+/// let data = json::parse_bytes_lossy(b"{\"msg\": \"caf\xe9 au lait\"}").unwrap();
+///
+/// assert_eq!(data["msg"], "caf\u{FFFD} au lait");
+/// assert!(json::parse_bytes_lossy(b"[1, \xff]").is_err());
+/// ```
+pub fn parse_bytes_lossy(source: &[u8]) -> Result<JsonValue> {
+    parse(&String::from_utf8_lossy(source))
+}
+
+/// Read all of `reader` and parse it. With the `gzip` feature, input that
+/// starts with the magic bytes of gzip or Zstandard is decompressed first.
+///
+/// ```
+/// let data = json::parse_from_reader(&b"{\"ok\": true}"[..]).unwrap();
+///
+/// assert_eq!(data["ok"], true);
+/// ```
+///
+/// Invalid JSON results in an `io::Error` of `InvalidData` kind wrapping the
+/// `json::Error`, as does compressed input that can't be decompressed, or
+/// any compressed input without the `gzip` feature.
+pub fn parse_from_reader<R: Read>(mut reader: R) -> io::Result<JsonValue> {
+    let mut source = Vec::new();
+    reader.read_to_end(&mut source)?;
+
+    // Magic bytes of gzip and Zstandard
+    if source.starts_with(&[0x1F, 0x8B]) || source.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+        #[cfg(feature = "gzip")]
+        {
+            source = crate::gzip::decompress(&source)?;
+        }
+        #[cfg(not(feature = "gzip"))]
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Compressed input requires the `gzip` feature"));
+    }
+
+    let source = String::from_utf8(source).map_err(|_| invalid_data(Error::FailedUtf8Parsing))?;
+
+    parse(&source).map_err(invalid_data)
+}
+
+fn invalid_data(err: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Parse `source`, then pass every value of the result through `reviver`,
+/// in the order the JavaScript `JSON.parse(text, reviver)` calls it. The
+/// whole document is parsed before the first call, so dropping values saves
+/// no memory while parsing, it only leaves them out of the result.
+///
+/// Values are visited bottom-up, members before the array or object holding
+/// them and the root last, each with its JSON Pointer, which is empty for
+/// the root. The reviver returns the value to keep in its place, or `None`
+/// to drop it from its parent. A dropped root leaves `null`.
+///
+/// Members dropped from an array shift the ones after them, whose pointers
+/// still have their index in the source.
+///
+/// ```
+/// # use json::JsonValue;
+/// let data = json::parse_with_reviver(r#"{"at": "2024-02-29", "debug": {"x": 1}, "n": 2}"#, |path, value| {
+///     match path {
+///         "/debug" => None,
+///         "/at"    => Some(json::object!{ "$date" => value }),
+///         _        => Some(value),
+///     }
+/// }).unwrap();
+///
+/// assert_eq!(data.dump(), r#"{"at":{"$date":"2024-02-29"},"n":2}"#);
+/// ```
+pub fn parse_with_reviver<F>(source: &str, mut reviver: F) -> Result<JsonValue>
+where
+    F: FnMut(&str, JsonValue) -> Option<JsonValue>
+{
+    let value = parse(source)?;
+
+    Ok(revive(value, &mut String::new(), &mut reviver).unwrap_or(JsonValue::Null))
+}
+
+fn revive<F>(value: JsonValue, path: &mut String, reviver: &mut F) -> Option<JsonValue>
+where
+    F: FnMut(&str, JsonValue) -> Option<JsonValue>
+{
+    let len = path.len();
+
+    let value = match value {
+        JsonValue::Array(members) => {
+            let mut revived = Vec::with_capacity(members.len());
+
+            for (index, member) in members.into_iter().enumerate() {
+                pointer::push_index(path, index);
+                revived.extend(revive(member, path, reviver));
+                path.truncate(len);
+            }
+
+            JsonValue::Array(revived)
+        },
+        JsonValue::Object(mut object) => {
+            let mut revived = Object::with_capacity(object.len());
+
+            for (key, member) in object.iter_mut() {
+                pointer::push_key(path, key);
+
+                if let Some(member) = revive(mem::replace(member, JsonValue::Null), path, reviver) {
+                    revived.insert(key, member);
+                }
+
+                path.truncate(len);
+            }
+
+            JsonValue::Object(revived)
+        },
+        value => value,
+    };
+
+    reviver(path, value)
+}
+
+/// Settings for `parse_with`. Start from `ParseOptions::default()`, which
+/// parses exactly like `json::parse`, and override the fields you need.
+#[derive(Clone, Copy, Debug)]
+pub struct ParseOptions {
+    /// What to do with integers that `f64` can't represent exactly, such as
+    /// 64-bit IDs above 2^53. `Number` itself keeps integers exact up to
+    /// 128 bits, but converting such a number to `f64`, or handing the JSON
+    /// over to JavaScript, silently rounds it. Only numbers without a
+    /// fraction or exponent are checked. Defaults to `PrecisionLoss::Ignore`.
+    ///
+    /// ```
+    /// # use json::{ ParseOptions, PrecisionLoss };
+    /// fn warn(path: &str, number: &str) {
+    ///     eprintln!("{} at {} will be rounded", number, path);
+    /// }
+    ///
+    /// let options = ParseOptions {
+    ///     precision_loss: PrecisionLoss::Warn(warn),
+    ///     ..ParseOptions::default()
+    /// };
+    ///
+    /// let data = json::parse_with("[18446744073709551615]", &options).unwrap();
+    ///
+    /// assert_eq!(data[0].as_u64(), Some(18446744073709551615));
+    /// ```
+    pub precision_loss: PrecisionLoss,
+
+    /// Accept the number syntax of JSON5 besides strict JSON: a leading
+    /// `+`, leading zeros, and a decimal point without digits on one side,
+    /// as in `+1`, `007`, `.5` and `5.`. Defaults to `false`.
+    ///
+    /// ```
+    /// # use json::ParseOptions;
+    /// let options = ParseOptions { lenient_numbers: true, ..ParseOptions::default() };
+    /// let data = json::parse_with("[+1, .5, 5., 007, -.25e1]", &options).unwrap();
+    ///
+    /// assert_eq!(data.dump(), "[1,0.5,5,7,-2.5]");
+    /// assert!(json::parse("[.5]").is_err());
+    /// ```
+    pub lenient_numbers: bool,
+
+    /// Accept raw control characters such as tabs and newlines inside
+    /// strings, which strict JSON only allows escaped, and keep them as they
+    /// are. Defaults to `false`.
+    ///
+    /// ```
+    /// # use json::ParseOptions;
+    /// let options = ParseOptions { raw_control_characters: true, ..ParseOptions::default() };
+    /// let data = json::parse_with("[\"line one\nline two\"]", &options).unwrap();
+    ///
+    /// assert_eq!(data[0], "line one\nline two");
+    /// assert_eq!(data.dump(), r#"["line one\nline two"]"#);
+    /// assert!(json::parse("[\"line one\nline two\"]").is_err());
+    /// ```
+    pub raw_control_characters: bool,
+
+    /// Accept the `\xNN` escapes of JSON5 in strings, as written by Python's
+    /// `ascii()` for instance, each one standing for the code point U+00NN.
+    /// Defaults to `false`.
+    ///
+    /// ```
+    /// # use json::ParseOptions;
+    /// let options = ParseOptions { hex_escapes: true, ..ParseOptions::default() };
+    /// let data = json::parse_with(r#"["caf\xe9", "\x41\u0042"]"#, &options).unwrap();
+    ///
+    /// assert_eq!(data[0], "café");
+    /// assert_eq!(data[1], "AB");
+    /// ```
+    pub hex_escapes: bool,
+
+    /// What to do with numbers too large for `f64`, such as `1e400`.
+    /// `Number` keeps them as written, so they are written out unchanged,
+    /// but they convert to `f64` as infinity. Defaults to
+    /// `ExponentOverflow::Infinity`.
+    ///
+    /// ```
+    /// # use json::{ ParseOptions, ExponentOverflow };
+    /// let options = ParseOptions {
+    ///     exponent_overflow: ExponentOverflow::Clamp,
+    ///     ..ParseOptions::default()
+    /// };
+    ///
+    /// let data = json::parse_with("[1e400, -1e400, 1e300]", &options).unwrap();
+    ///
+    /// assert_eq!(data[0].as_f64(), Some(f64::MAX));
+    /// assert_eq!(data[1].as_f64(), Some(f64::MIN));
+    /// assert_eq!(data[2].as_f64(), Some(1e300));
+    /// assert_eq!(json::parse("1e400").unwrap().as_f64(), Some(f64::INFINITY));
+    /// ```
+    pub exponent_overflow: ExponentOverflow,
+
+    /// Require the root of the document to be an object or an array, as
+    /// RFC 4627 did before RFC 7159 allowed any value. Any other root fails
+    /// as an unexpected character. Defaults to `false`.
+    ///
+    /// ```
+    /// # use json::ParseOptions;
+    /// let options = ParseOptions { require_container: true, ..ParseOptions::default() };
+    ///
+    /// assert!(json::parse_with(r#"{"ok": true}"#, &options).is_ok());
+    /// assert!(json::parse_with(r#""just a string""#, &options).is_err());
+    /// ```
+    pub require_container: bool,
+}
+
+/// Policy for integers that `f64` can't represent exactly, see
+/// `ParseOptions::precision_loss`.
+#[derive(Clone, Copy, Debug)]
+pub enum PrecisionLoss {
+    /// Parse them like any other number.
+    Ignore,
+
+    /// Fail with `Error::PrecisionLoss`.
+    Error,
+
+    /// Parse them, calling the function with the JSON Pointer of each one and
+    /// its text in the source.
+    Warn(fn(&str, &str)),
+}
+
+/// Policy for numbers too large for `f64`, see
+/// `ParseOptions::exponent_overflow`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExponentOverflow {
+    /// Parse them as they are, so that they convert to positive or negative
+    /// infinity.
+    Infinity,
+
+    /// Replace them with `f64::MAX` or `f64::MIN`, whichever has their sign.
+    Clamp,
+
+    /// Fail with `Error::NumberOutOfRange`.
+    Error,
+}
+
+const DEFAULT_OPTIONS: ParseOptions = ParseOptions {
+    precision_loss: PrecisionLoss::Ignore,
+    lenient_numbers: false,
+    raw_control_characters: false,
+    hex_escapes: false,
+    exponent_overflow: ExponentOverflow::Infinity,
+    require_container: false,
+};
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        DEFAULT_OPTIONS
+    }
+}
+
+// Whether the integer with the decimal `digits` is exactly some `f64`, that
+// is its odd factor fits into the 53 bits of the significand.
+fn exact_in_f64(digits: &[u8]) -> bool {
+    let zeros = digits.iter().rev().take_while(|&&ch| ch == b'0').count();
+    let mut odd: u128 = 0;
+
+    for &ch in &digits[.. digits.len() - zeros] {
+        odd = match odd.checked_mul(10).and_then(|odd| odd.checked_add((ch - b'0') as u128)) {
+            Some(odd) => odd,
+            None      => return false,
+        };
+    }
+
+    if odd == 0 {
+        return true;
+    }
+
+    odd >>= odd.trailing_zeros();
+
+    // The trailing zeros are a factor of 10^zeros = 2^zeros * 5^zeros
+    for _ in 0 .. zeros {
+        match odd.checked_mul(5) {
+            Some(next) if next < 1 << 53 => odd = next,
+            _                            => return false,
+        }
+    }
+
+    odd < 1 << 53
+}
+
+/// Parser holding on to its scratch buffers between documents, so parsing
+/// many small documents in a row doesn't allocate them anew for every one.
+///
+/// ```
+/// let mut parser = json::Parser::new();
+///
+/// for message in &[r#"{"id":1}"#, r#"{"id":2,"tags":["\u00e9"]}"#] {
+///     let data = parser.parse(message).unwrap();
+///
+///     assert_eq!(data, json::parse(message).unwrap());
+/// }
+/// ```
+///
+/// The source is passed to `parse` rather than kept by the parser, so every
+/// document can come from a buffer that only lives as long as its `parse`
+/// call. Alternatively `reset` copies the source into a buffer the parser
+/// keeps, to be parsed later with `document`.
+///
+/// Arrays start out with a capacity close to the lengths of the arrays
+/// parsed before them, and the parser carries that over from one document
+/// to the next, so documents of the same shape get arrays allocated at the
+/// right size from the start.
+#[derive(Debug, Default)]
+pub struct Parser {
+    source: String,
+    buffer: Vec<u8>,
+    stack: Vec<StackBlock>,
+    array_capacity: [u8; ARRAY_CAPACITY_DEPTHS],
+}
+
+impl Parser {
+    /// Create a parser, its buffers are allocated on first use.
+    pub fn new() -> Self {
+        Parser::default()
+    }
+
+    /// Create a parser that starts arrays out with room for `capacity`
+    /// members, up to 64. This is only the starting point, the capacity
+    /// still follows the arrays that get parsed.
+    ///
+    /// ```
+    /// let mut parser = json::Parser::with_array_capacity(12);
+    /// let row = parser.parse("[1,2,3,4,5,6,7,8,9,10,11,12]").unwrap();
+    ///
+    /// assert_eq!(row.len(), 12);
+    /// ```
+    pub fn with_array_capacity(capacity: usize) -> Self {
+        let capacity = capacity.min(MAX_ARRAY_CAPACITY as usize) as u8;
+
+        Parser {
+            array_capacity: [capacity; ARRAY_CAPACITY_DEPTHS],
+            ..Parser::default()
+        }
+    }
+
+    /// Parse `source`, same as `json::parse`.
+    pub fn parse(&mut self, source: &str) -> Result<JsonValue> {
+        // After an error both may have leftovers
+        self.buffer.clear();
+        self.stack.clear();
+
+        let buffer = mem::take(&mut self.buffer);
+        let mut scanner = Scanner::with_buffer(source, buffer);
+        for (capacity, &hint) in scanner.array_capacity.iter_mut().zip(self.array_capacity.iter()) {
+            *capacity = hint.clamp(MIN_ARRAY_CAPACITY, MAX_ARRAY_CAPACITY);
+        }
+
+        let result = scanner.parse(&mut Unguided, &mut self.stack);
+
+        self.buffer = scanner.buffer;
+        self.array_capacity = scanner.array_capacity;
+        result
+    }
+
+    /// Replace the source held by the parser with a copy of `source`, for
+    /// `document` to parse. The memory of the previous source is reused, as
+    /// are the scratch buffers.
+    ///
+    /// ```
+    /// let mut parser = json::Parser::new();
+    /// let mut message = String::from(r#"{"id":1}"#);
+    ///
+    /// parser.reset(&message);
+    /// message.clear();
+    ///
+    /// assert_eq!(parser.document().unwrap()["id"], 1);
+    /// ```
+    pub fn reset(&mut self, source: &str) {
+        self.source.clear();
+        self.source.push_str(source);
+    }
+
+    /// Parse the source given to the last `reset`, empty if there was none.
+    pub fn document(&mut self) -> Result<JsonValue> {
+        let source = mem::take(&mut self.source);
+        let result = self.parse(&source);
+
+        self.source = source;
+        result
+    }
+}
+
+// Parse the value between bytes `start` and `end` of `source`, with errors
+// pointing to their place in all of the `source`.
+pub(crate) fn parse_range(source: &str, start: usize, end: usize) -> Result<JsonValue> {
+    let mut scanner = Scanner::new(source);
+    scanner.index = start;
+    scanner.length = end;
+
+    // The range is part of a larger document, so there is nothing trailing
+    match scanner.parse(&mut Unguided, &mut Vec::with_capacity(3)) {
+        Err(Error::TrailingCharacters { index }) => Err(unexpected_character_at(source, index, &[])),
+        result                                   => result,
+    }
+}
+
+#[inline]
+pub(crate) fn parse_tape(source: &str, tape: &mut Tape) -> Result<()> {
+    Scanner::new(source).parse_tape(tape)
+}
+
+#[cfg(feature = "arena")]
+#[inline]
+pub(crate) fn parse_in<'arena>(source: &str, arena: &'arena JsonArena) -> Result<ArenaValue<'arena>> {
+    Scanner::new(source).parse_arena(arena)
+}
+
+#[inline]
+pub(crate) fn parse_guided<G: Guide>(source: &str, guide: &mut G) -> Result<JsonValue> {
+    Scanner::new(source).parse(guide, &mut Vec::with_capacity(3))
 }
 
 
@@ -773,7 +1920,6 @@ mod tests {
     use crate::array;
 
     use std::fs::File;
-    use std::io::prelude::*;
 
     #[test]
     fn it_should_parse_escaped_forward_slashes_with_quotes() {
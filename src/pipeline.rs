@@ -0,0 +1,930 @@
+//! Streaming transformation of JSON from an `io::Read` to an `io::Write`.
+//! The source is read as a sequence of `Event`s, which pass through user
+//! provided stages that can change or drop them, and what is left is written
+//! out by a `JsonWriter`. Memory use depends on the depth of nesting and the
+//! longest string, not on the size of the document.
+//!
+//! ```
+//! # use json::JsonWriter;
+//! # use json::pipeline::{ Event, Pipeline };
+//! let source = br#"{"user": "ada", "password": "hunter2", "meta": {"tags": [1, 2]}, "age": 36}"#;
+//!
+//! let out = Pipeline::new()
+//!     .stage(|_: &str, event: Event| match event {
+//!         Event::Key(ref key) if key == "user"     => Some(Event::Key("name".into())),
+//!         Event::Key(ref key) if key == "password" => None,
+//!         event                                    => Some(event),
+//!     })
+//!     .stage(|path: &str, event: Event| match event {
+//!         _ if path == "/meta"                  => None,
+//!         Event::Number(age) if path == "/age" => Some(Event::String(age.to_string())),
+//!         event                                 => Some(event),
+//!     })
+//!     .run(&source[..], JsonWriter::new(Vec::new()))
+//!     .unwrap();
+//!
+//! assert_eq!(out, br#"{"name":"ada","age":"36"}"#);
+//! ```
+
+use std::io::{ self, Read, Write };
+
+use crate::{ Error, JsonValue, JsonWriter };
+use crate::number::Number;
+use crate::parser::{ expected, DEPTH_LIMIT };
+use crate::reformat::{ is_whitespace, Fail, Input };
+use crate::util::pointer;
+
+/// A token of a JSON document, as read by `Events`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    BeginObject,
+    EndObject,
+    BeginArray,
+    EndArray,
+
+    /// Key of an object member, followed by the events of its value.
+    Key(String),
+    String(String),
+    Number(Number),
+    Boolean(bool),
+    Null,
+}
+
+/// Streaming parser reading the `Event`s of a single JSON document from an
+/// `io::Read`, checking its syntax along the way.
+///
+/// ```
+/// # use json::pipeline::{ Event, Events };
+/// let events: Vec<Event> = Events::new(&b"{\"a\": [true]}"[..]).collect::<Result<_, _>>().unwrap();
+///
+/// assert_eq!(events, [
+///     Event::BeginObject,
+///     Event::Key("a".into()),
+///     Event::BeginArray,
+///     Event::Boolean(true),
+///     Event::EndArray,
+///     Event::EndObject,
+/// ]);
+/// ```
+///
+/// Invalid JSON results in an `io::Error` of `InvalidData` kind wrapping the
+/// `json::Error`, after which there are no more events.
+pub struct Events<R: Read> {
+    input: Input<R>,
+    // True for objects
+    stack: Vec<bool>,
+    state: State,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    // Before a value
+    Value,
+    // After the opening bracket of an array or object
+    First,
+    // After a member of an array or object
+    Next,
+    // After the whole document
+    Done,
+    Failed,
+}
+
+impl<R: Read> Events<R> {
+    pub fn new(reader: R) -> Self {
+        Events {
+            input: Input::new(reader),
+            stack: Vec::new(),
+            state: State::Value,
+        }
+    }
+
+    // Next event inside of the document.
+    fn next_event(&mut self) -> io::Result<Event> {
+        self.next().unwrap_or_else(|| Err(Fail::Json(Error::UnexpectedEndOfJson).into()))
+    }
+
+    fn event(&mut self) -> Result<Option<Event>, Fail> {
+        if self.state == State::Done {
+            while let Some(ch) = self.input.peek()? {
+                self.input.bump();
+                if !is_whitespace(ch) {
+                    return self.input.unexpected(ch, &[]);
+                }
+            }
+
+            return Ok(None);
+        }
+
+        let ch = self.input.next_token()?;
+
+        let event = match (self.state, self.stack.last()) {
+            (State::First, Some(true)) => match ch {
+                b'}' => self.end(Event::EndObject),
+                _    => self.key(ch, expected::FIRST_KEY)?,
+            },
+            (State::First, Some(false)) if ch == b']' => self.end(Event::EndArray),
+            (State::Next, Some(true)) => match ch {
+                b',' => {
+                    let ch = self.input.next_token()?;
+                    self.key(ch, expected::KEY)?
+                },
+                b'}' => self.end(Event::EndObject),
+                _    => return self.input.unexpected(ch, expected::OBJECT_NEXT),
+            },
+            (State::Next, Some(false)) => match ch {
+                b',' => {
+                    let ch = self.input.next_token()?;
+                    self.value(ch)?
+                },
+                b']' => self.end(Event::EndArray),
+                _    => return self.input.unexpected(ch, expected::ARRAY_NEXT),
+            },
+            _ => self.value(ch)?,
+        };
+
+        Ok(Some(event))
+    }
+
+    fn value(&mut self, ch: u8) -> Result<Event, Fail> {
+        let event = match ch {
+            b'{' | b'[' => {
+                if self.stack.len() == DEPTH_LIMIT {
+                    return Err(Fail::Json(Error::ExceededDepthLimit));
+                }
+                self.stack.push(ch == b'{');
+                self.state = State::First;
+
+                return Ok(match ch {
+                    b'{' => Event::BeginObject,
+                    _    => Event::BeginArray,
+                });
+            },
+            b'"'               => Event::String(self.string()?),
+            b'-' | b'0'..=b'9' => Event::Number(self.number(ch)?),
+            b't'               => self.literal(expected::TRUE, Event::Boolean(true))?,
+            b'f'               => self.literal(expected::FALSE, Event::Boolean(false))?,
+            b'n'               => self.literal(expected::NULL, Event::Null)?,
+            _                  => return self.input.unexpected(ch, expected::VALUE),
+        };
+
+        self.after_value();
+        Ok(event)
+    }
+
+    fn after_value(&mut self) {
+        self.state = match self.stack.is_empty() {
+            true  => State::Done,
+            false => State::Next,
+        };
+    }
+
+    fn end(&mut self, event: Event) -> Event {
+        self.stack.pop();
+        self.after_value();
+        event
+    }
+
+    fn key(&mut self, ch: u8, expected: &'static [&'static str]) -> Result<Event, Fail> {
+        if ch != b'"' {
+            return self.input.unexpected(ch, expected);
+        }
+        let key = self.string()?;
+
+        match self.input.next_token()? {
+            b':' => {},
+            ch   => return self.input.unexpected(ch, expected::COLON),
+        }
+        self.state = State::Value;
+
+        Ok(Event::Key(key))
+    }
+
+    // Reads and unescapes a string, the opening quote has already been read.
+    fn string(&mut self) -> Result<String, Fail> {
+        let mut bytes = Vec::new();
+
+        loop {
+            let ch = self.input.next()?;
+
+            match ch {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = self.input.next()?;
+
+                    bytes.push(match escaped {
+                        b'"' | b'\\' | b'/' => escaped,
+                        b'b' => 0x8,
+                        b'f' => 0xC,
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'u' => {
+                            let ch = self.codepoint()?;
+                            let mut buf = [0; 4];
+
+                            bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                            continue;
+                        },
+                        _ => return self.input.unexpected(escaped, expected::ESCAPE),
+                    });
+                },
+                0x00..=0x1F => return self.input.unexpected(ch, expected::ESCAPED_CONTROL),
+                _ => bytes.push(ch),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| Fail::Json(Error::FailedUtf8Parsing))
+    }
+
+    // Character of a `\u` escape, the `\u` has already been read.
+    fn codepoint(&mut self) -> Result<char, Fail> {
+        let mut codepoint = self.hex()?;
+
+        // Surrogate pairs
+        if (0xD800 .. 0xDC00).contains(&codepoint) {
+            for &byte in b"\\u" {
+                let ch = self.input.next()?;
+                if ch != byte {
+                    return self.input.unexpected(ch, expected::UNICODE_ESCAPE);
+                }
+            }
+
+            let low = self.hex()?;
+            if !(0xDC00 .. 0xE000).contains(&low) {
+                return Err(Fail::Json(Error::FailedUtf8Parsing));
+            }
+            codepoint = 0x10000 + ((codepoint - 0xD800) << 10) + (low - 0xDC00);
+        }
+
+        char::from_u32(codepoint).ok_or(Fail::Json(Error::FailedUtf8Parsing))
+    }
+
+    fn hex(&mut self) -> Result<u32, Fail> {
+        let mut value = 0;
+
+        for _ in 0..4 {
+            let ch = self.input.next()?;
+            let digit = match (ch as char).to_digit(16) {
+                Some(digit) => digit,
+                None        => return self.input.unexpected(ch, expected::HEX_DIGIT),
+            };
+            value = value << 4 | digit;
+        }
+
+        Ok(value)
+    }
+
+    fn number(&mut self, first: u8) -> Result<Number, Fail> {
+        let mut text = vec![first];
+
+        let mut ch = first;
+        if first == b'-' {
+            ch = self.input.next()?;
+            if !ch.is_ascii_digit() {
+                return self.input.unexpected(ch, expected::DIGIT);
+            }
+            text.push(ch);
+        }
+        if ch != b'0' {
+            self.digits(&mut text)?;
+        }
+
+        if self.input.peek()? == Some(b'.') {
+            self.input.bump();
+            text.push(b'.');
+            self.required_digits(&mut text)?;
+        }
+
+        if let Some(e @ b'e') | Some(e @ b'E') = self.input.peek()? {
+            self.input.bump();
+            text.push(e);
+
+            if let Some(sign @ b'+') | Some(sign @ b'-') = self.input.peek()? {
+                self.input.bump();
+                text.push(sign);
+            }
+            self.required_digits(&mut text)?;
+        }
+
+        match std::str::from_utf8(&text).map(crate::parse) {
+            Ok(Ok(JsonValue::Number(number))) => Ok(number),
+            _ => unreachable!("Number syntax has been checked"),
+        }
+    }
+
+    fn required_digits(&mut self, text: &mut Vec<u8>) -> Result<(), Fail> {
+        let ch = self.input.next()?;
+        if !ch.is_ascii_digit() {
+            return self.input.unexpected(ch, expected::DIGIT);
+        }
+        text.push(ch);
+        self.digits(text)
+    }
+
+    fn digits(&mut self, text: &mut Vec<u8>) -> Result<(), Fail> {
+        while let Some(ch) = self.input.peek()? {
+            if !ch.is_ascii_digit() {
+                break;
+            }
+            self.input.bump();
+            text.push(ch);
+        }
+        Ok(())
+    }
+
+    // Checks the rest of a literal, the first byte has already been read.
+    fn literal(&mut self, literal: &'static [&'static str], event: Event) -> Result<Event, Fail> {
+        for &byte in &literal[0].as_bytes()[1..] {
+            let ch = self.input.next()?;
+            if ch != byte {
+                return self.input.unexpected(ch, literal);
+            }
+        }
+        Ok(event)
+    }
+}
+
+impl<R: Read> Iterator for Events<R> {
+    type Item = io::Result<Event>;
+
+    fn next(&mut self) -> Option<io::Result<Event>> {
+        if self.state == State::Failed {
+            return None;
+        }
+
+        match self.event() {
+            Ok(event) => event.map(Ok),
+            Err(fail) => {
+                self.state = State::Failed;
+                Some(Err(fail.into()))
+            },
+        }
+    }
+}
+
+/// A step of a `Pipeline`, called for every event that reaches it with the
+/// JSON Pointer of the value the event belongs to in the source. For a
+/// `Key` that is the member the key starts.
+///
+/// Returning `None` drops the event. Dropping a key, or the start of an
+/// array or object, drops the whole member or value with it, and so does
+/// dropping the value of a member. The `EndObject` and `EndArray` events
+/// should be passed on as they are.
+pub trait Stage {
+    fn event(&mut self, path: &str, event: Event) -> Option<Event>;
+}
+
+impl<F: FnMut(&str, Event) -> Option<Event>> Stage for F {
+    fn event(&mut self, path: &str, event: Event) -> Option<Event> {
+        self(path, event)
+    }
+}
+
+/// Stages between `Events` read from the source and a `JsonWriter`, see the
+/// `pipeline` module.
+#[derive(Default)]
+pub struct Pipeline<'a> {
+    stages: Vec<Step<'a>>,
+}
+
+struct Step<'a> {
+    stage: Box<dyn Stage + 'a>,
+    // Key the stage passed on, held back until it's known whether its value
+    // is dropped
+    key: Option<String>,
+    skip: Skip,
+}
+
+#[derive(Clone, Copy)]
+enum Skip {
+    None,
+    // The next value, after a dropped key
+    Value,
+    // The rest of a dropped array or object, at the given depth
+    Depth(usize),
+}
+
+impl Skip {
+    fn after(self, event: &Event) -> Skip {
+        let open = match *event {
+            Event::BeginObject | Event::BeginArray => true,
+            Event::EndObject | Event::EndArray     => false,
+            _                                      => return match self {
+                Skip::Value => Skip::None,
+                skip        => skip,
+            },
+        };
+
+        match (self, open) {
+            (Skip::Depth(1), false)     => Skip::None,
+            (Skip::Depth(depth), false) => Skip::Depth(depth - 1),
+            (Skip::Depth(depth), true)  => Skip::Depth(depth + 1),
+            (_, _)                      => Skip::Depth(1),
+        }
+    }
+}
+
+// Where the reader is in the source, for the paths passed to the stages.
+// `len` is the length of the path of the array or object, `index` the
+// index of the next member of an array.
+struct Frame {
+    len: usize,
+    index: Option<usize>,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new() -> Self {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Add a stage after the ones added so far.
+    pub fn stage<S: Stage + 'a>(mut self, stage: S) -> Self {
+        self.stages.push(Step {
+            stage: Box::new(stage),
+            key: None,
+            skip: Skip::None,
+        });
+        self
+    }
+
+    /// Read JSON from `reader`, pass it through the stages and write the
+    /// result with `writer`. Returns the writer of the finished `JsonWriter`.
+    ///
+    /// Invalid JSON in the source results in an `io::Error` of `InvalidData`
+    /// kind, and events in an order that doesn't make a document in one of
+    /// `InvalidInput` kind, as the `JsonWriter` reports them.
+    pub fn run<R: Read, W: Write>(&mut self, reader: R, mut writer: JsonWriter<W>) -> io::Result<W> {
+        self.drive(reader, &mut writer)?;
+        writer.finish()
+    }
+
+    /// Like `run`, building a `JsonValue` of the result instead.
+    pub fn run_to_value<R: Read>(&mut self, reader: R) -> io::Result<JsonValue> {
+        let mut builder = Builder { stack: Vec::new(), key: None, root: None };
+
+        self.drive(reader, &mut builder)?;
+        builder.finish()
+    }
+
+    fn drive<R: Read, O: Output>(&mut self, reader: R, out: &mut O) -> io::Result<()> {
+        let mut path = String::new();
+        let mut frames: Vec<Frame> = Vec::new();
+
+        for event in Events::new(reader) {
+            let event = event?;
+
+            match event {
+                Event::EndObject | Event::EndArray => {
+                    if let Some(frame) = frames.pop() {
+                        path.truncate(frame.len);
+                    }
+                },
+                Event::Key(ref key) => if let Some(frame) = frames.last() {
+                    path.truncate(frame.len);
+                    pointer::push_key(&mut path, key);
+                },
+                _ => if let Some(&mut Frame { len, index: Some(ref mut index) }) = frames.last_mut() {
+                    path.truncate(len);
+                    pointer::push_index(&mut path, *index);
+                    *index += 1;
+                },
+            }
+
+            let index = match event {
+                Event::BeginObject => Some(None),
+                Event::BeginArray  => Some(Some(0)),
+                _                  => None,
+            };
+
+            feed(&mut self.stages, &path, event, out)?;
+
+            if let Some(index) = index {
+                frames.push(Frame { len: path.len(), index });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Stream a document from `reader` to `writer`, keeping only the values at
+/// the given JSON Pointers along with the arrays and objects they are in.
+/// A `*` token stands for every key or index, a literal `*` key can't be
+/// selected. Everything else is read and checked, but not held in memory.
+///
+/// ```
+/// # use json::JsonWriter;
+/// let source = br#"{"meta": {"v": 2}, "items": [{"id": 1, "blob": "..."}, {"id": 2}], "rest": [3]}"#;
+///
+/// let out = json::project(&source[..], &["/meta", "/items/*/id"], JsonWriter::new(Vec::new())).unwrap();
+///
+/// assert_eq!(out, br#"{"meta":{"v":2},"items":[{"id":1},{"id":2}]}"#);
+/// ```
+///
+/// A pointer that is neither empty nor starting with `/` results in an
+/// `io::Error` of `InvalidInput` kind wrapping `Error::InvalidPointer`.
+pub fn project<R: Read, W: Write>(reader: R, paths: &[&str], writer: JsonWriter<W>) -> io::Result<W> {
+    Pipeline::new().stage(Projection::new(paths)?).run(reader, writer)
+}
+
+/// Like `project`, building a `JsonValue` of the result instead.
+pub fn project_value<R: Read>(reader: R, paths: &[&str]) -> io::Result<JsonValue> {
+    Pipeline::new().stage(Projection::new(paths)?).run_to_value(reader)
+}
+
+// Stage of `project`, with the escaped tokens of each pointer.
+struct Projection {
+    patterns: Vec<Vec<String>>,
+}
+
+impl Projection {
+    fn new(paths: &[&str]) -> io::Result<Self> {
+        let patterns = paths.iter().map(|&path| match pointer::tokens(path) {
+            Some(_) => Ok(path.split('/').skip(1).map(String::from).collect()),
+            None    => Err(io::Error::new(io::ErrorKind::InvalidInput, Error::InvalidPointer { pointer: path.into() })),
+        });
+
+        Ok(Projection { patterns: patterns.collect::<io::Result<_>>()? })
+    }
+
+    // Whether `path` is inside of one of the patterns, or leads to one if
+    // it's not a `scalar`.
+    fn keeps(&self, path: &str, scalar: bool) -> bool {
+        path.is_empty() || self.patterns.iter().any(|pattern| {
+            let matched = path.split('/').skip(1).zip(pattern).all(|(token, pattern)| pattern == "*" || token == pattern);
+
+            matched && (!scalar || path.matches('/').count() >= pattern.len())
+        })
+    }
+}
+
+impl Stage for Projection {
+    fn event(&mut self, path: &str, event: Event) -> Option<Event> {
+        let scalar = match event {
+            Event::EndObject | Event::EndArray => return Some(event),
+            Event::BeginObject | Event::BeginArray | Event::Key(_) => false,
+            _ => true,
+        };
+
+        match self.keeps(path, scalar) {
+            true  => Some(event),
+            false => None,
+        }
+    }
+}
+
+/// Stream a document with an array at the top from `reader`, writing its
+/// elements out in arrays of up to `size` elements each. `open` is called
+/// with the number of each chunk, starting at 0, to get the writer of the
+/// next chunk, such as a new file. Returns the number of chunks written,
+/// none for an empty array.
+///
+/// ```no_run
+/// # use std::fs::File;
+/// # use std::io::BufWriter;
+/// # use json::JsonWriter;
+/// let source = File::open("events.json").unwrap();
+///
+/// json::split_array(source, 10_000, |chunk| {
+///     let file = File::create(format!("events-{}.json", chunk))?;
+///
+///     Ok(JsonWriter::new(BufWriter::new(file)))
+/// }).unwrap();
+/// ```
+///
+/// Only one element at a time is held in memory, see `Chunks` to get the
+/// chunks as values instead. A top-level value other than an array results
+/// in an `io::Error` of `InvalidData` kind wrapping `Error::WrongType`.
+///
+/// # Panics
+///
+/// If `size` is 0.
+pub fn split_array<R, W, F>(reader: R, size: usize, mut open: F) -> io::Result<usize>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(usize) -> io::Result<JsonWriter<W>>,
+{
+    assert!(size != 0, "chunk size must be non-zero");
+
+    let mut events = Events::new(reader);
+    let mut writer: Option<JsonWriter<W>> = None;
+    let mut chunks = 0;
+    let mut elements = 0;
+    let mut depth = 0;
+
+    begin_root(&mut events)?;
+
+    loop {
+        let event = events.next_event()?;
+
+        if depth == 0 {
+            if event == Event::EndArray {
+                break;
+            }
+            if elements % size == 0 {
+                if let Some(writer) = writer.take() {
+                    finish_chunk(writer)?;
+                }
+
+                let mut next = open(chunks)?;
+                next.begin_array()?;
+                writer = Some(next);
+                chunks += 1;
+            }
+            elements += 1;
+        }
+
+        match event {
+            Event::BeginObject | Event::BeginArray => depth += 1,
+            Event::EndObject | Event::EndArray     => depth -= 1,
+            _                                      => {},
+        }
+
+        writer.as_mut().expect("Chunk was opened at its first element").event(event)?;
+    }
+
+    if let Some(writer) = writer {
+        finish_chunk(writer)?;
+    }
+    end_root(&mut events)?;
+
+    Ok(chunks)
+}
+
+fn finish_chunk<W: Write>(mut writer: JsonWriter<W>) -> io::Result<()> {
+    writer.end_array()?;
+    writer.finish().map(drop)
+}
+
+/// Iterator over the elements of an array at the top of a document, in
+/// arrays of up to `size` elements each. The chunks are read as they are
+/// needed, so only one of them is held in memory at a time.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # use json::pipeline::Chunks;
+/// # fn main() {
+/// let chunks: Vec<_> = Chunks::new(&b"[1, {\"a\": [2]}, 3]"[..], 2).collect::<Result<_, _>>().unwrap();
+///
+/// assert_eq!(chunks, [array![1, object!{ "a" => array![2] }], array![3]]);
+/// # }
+/// ```
+///
+/// Errors are reported the same way as by `split_array`, after which there
+/// are no more chunks.
+pub struct Chunks<R: Read> {
+    events: Events<R>,
+    size: usize,
+    started: bool,
+    done: bool,
+}
+
+impl<R: Read> Chunks<R> {
+    /// # Panics
+    ///
+    /// If `size` is 0.
+    pub fn new(reader: R, size: usize) -> Self {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        Chunks {
+            events: Events::new(reader),
+            size,
+            started: false,
+            done: false,
+        }
+    }
+
+    fn chunk(&mut self) -> io::Result<Option<JsonValue>> {
+        if !self.started {
+            self.started = true;
+            begin_root(&mut self.events)?;
+        }
+
+        let mut chunk = Vec::new();
+
+        while chunk.len() < self.size {
+            match self.element()? {
+                Some(element) => chunk.push(element),
+                None          => {
+                    self.done = true;
+                    end_root(&mut self.events)?;
+                    break;
+                },
+            }
+        }
+
+        Ok(match chunk.is_empty() {
+            true  => None,
+            false => Some(JsonValue::Array(chunk)),
+        })
+    }
+
+    // Next element of the array, `None` at its end.
+    fn element(&mut self) -> io::Result<Option<JsonValue>> {
+        let mut builder = Builder { stack: Vec::new(), key: None, root: None };
+        let mut depth = 0;
+
+        loop {
+            let event = self.events.next_event()?;
+
+            match event {
+                Event::EndArray if depth == 0          => return Ok(None),
+                Event::BeginObject | Event::BeginArray => depth += 1,
+                Event::EndObject | Event::EndArray     => depth -= 1,
+                _                                      => {},
+            }
+
+            builder.event(event)?;
+
+            if depth == 0 {
+                return builder.finish().map(Some);
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Chunks<R> {
+    type Item = io::Result<JsonValue>;
+
+    fn next(&mut self) -> Option<io::Result<JsonValue>> {
+        if self.done {
+            return None;
+        }
+
+        let chunk = self.chunk();
+        if chunk.is_err() {
+            self.done = true;
+        }
+
+        chunk.transpose()
+    }
+}
+
+// Reads the start of the array at the top of the document.
+fn begin_root<R: Read>(events: &mut Events<R>) -> io::Result<()> {
+    let found = match events.next_event()? {
+        Event::BeginArray  => return Ok(()),
+        Event::BeginObject => "object",
+        Event::String(_)   => "string",
+        Event::Number(_)   => "number",
+        Event::Boolean(_)  => "boolean",
+        _                  => "null",
+    };
+
+    Err(io::Error::new(io::ErrorKind::InvalidData, Error::WrongType {
+        expected: "array".into(),
+        found: found.into(),
+        path: String::new(),
+    }))
+}
+
+// Checks that nothing but whitespace follows the array at the top.
+fn end_root<R: Read>(events: &mut Events<R>) -> io::Result<()> {
+    match events.next() {
+        Some(Err(err)) => Err(err),
+        _              => Ok(()),
+    }
+}
+
+// Passes `event` through the first of `steps` and on to the rest.
+fn feed<O: Output>(steps: &mut [Step], path: &str, event: Event, out: &mut O) -> io::Result<()> {
+    let (step, rest) = match steps.split_first_mut() {
+        Some(split) => split,
+        None        => return out.event(event),
+    };
+
+    if let Skip::Value | Skip::Depth(_) = step.skip {
+        step.skip = step.skip.after(&event);
+        return Ok(());
+    }
+
+    let key = step.key.take();
+    let drop = match event {
+        Event::Key(_)                          => Skip::Value,
+        Event::BeginObject | Event::BeginArray => Skip::Depth(1),
+        _                                      => Skip::None,
+    };
+
+    match step.stage.event(path, event) {
+        Some(Event::Key(key)) => {
+            step.key = Some(key);
+            Ok(())
+        },
+        Some(event) => {
+            if let Some(key) = key {
+                feed(rest, path, Event::Key(key), out)?;
+            }
+            feed(rest, path, event, out)
+        },
+        None => {
+            step.skip = drop;
+            Ok(())
+        },
+    }
+}
+
+// Where the events that make it through all stages end up.
+trait Output {
+    fn event(&mut self, event: Event) -> io::Result<()>;
+}
+
+impl<W: Write> Output for JsonWriter<W> {
+    fn event(&mut self, event: Event) -> io::Result<()> {
+        match event {
+            Event::BeginObject    => self.begin_object(),
+            Event::EndObject      => self.end_object(),
+            Event::BeginArray     => self.begin_array(),
+            Event::EndArray       => self.end_array(),
+            Event::Key(key)       => self.key(&key),
+            Event::String(value)  => self.string(&value),
+            Event::Number(value)  => self.number(value),
+            Event::Boolean(value) => self.boolean(value),
+            Event::Null           => self.null(),
+        }
+    }
+}
+
+// Builds a `JsonValue`, checking the order of events like `JsonWriter`.
+// Open arrays and objects are on the `stack`, with the keys they will be
+// inserted under.
+struct Builder {
+    stack: Vec<(JsonValue, Option<String>)>,
+    key: Option<String>,
+    root: Option<JsonValue>,
+}
+
+impl Builder {
+    fn insert(&mut self, value: JsonValue, key: Option<String>) -> io::Result<()> {
+        let container = match self.stack.last_mut() {
+            Some(&mut (ref mut container, _)) => container,
+            None if self.root.is_none()       => {
+                self.root = Some(value);
+                return Ok(());
+            },
+            None => return Err(invalid("JSON document is already complete")),
+        };
+
+        match (container, key) {
+            (&mut JsonValue::Array(ref mut array), None)        => array.push(value),
+            (&mut JsonValue::Object(ref mut object), Some(key)) => object.insert(&key, value),
+            (&mut JsonValue::Object(_), None)                   => return Err(invalid("Expected an object key")),
+            (_, _)                                              => return Err(invalid("Key outside of an object")),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<JsonValue> {
+        match self.root {
+            Some(root) if self.stack.is_empty() => Ok(root),
+            _                                   => Err(invalid("Incomplete JSON document")),
+        }
+    }
+}
+
+impl Output for Builder {
+    fn event(&mut self, event: Event) -> io::Result<()> {
+        let value = match event {
+            Event::BeginObject | Event::BeginArray => {
+                let container = match event {
+                    Event::BeginObject => JsonValue::new_object(),
+                    _                  => JsonValue::new_array(),
+                };
+
+                if self.stack.is_empty() && self.root.is_some() {
+                    return Err(invalid("JSON document is already complete"));
+                }
+                self.stack.push((container, self.key.take()));
+                return Ok(());
+            },
+            Event::EndObject | Event::EndArray => {
+                let object = matches!(event, Event::EndObject);
+
+                return match self.stack.pop() {
+                    Some((value, key)) if value.is_object() == object && self.key.is_none() => self.insert(value, key),
+                    _ => Err(invalid("Unexpected end of an array or object")),
+                };
+            },
+            Event::Key(key) => {
+                return match self.stack.last() {
+                    Some(&(JsonValue::Object(_), _)) if self.key.is_none() => {
+                        self.key = Some(key);
+                        Ok(())
+                    },
+                    _ => Err(invalid("Key outside of an object")),
+                };
+            },
+            Event::String(value)  => value.into(),
+            Event::Number(value)  => value.into(),
+            Event::Boolean(value) => value.into(),
+            Event::Null           => JsonValue::Null,
+        };
+
+        let key = self.key.take();
+        self.insert(value, key)
+    }
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
@@ -0,0 +1,242 @@
+//! Conversion between `JsonValue` and `application/x-www-form-urlencoded`
+//! data, such as URL query strings, with nesting expressed in bracket
+//! notation.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let data = json::query::parse("user[name]=Jon+Snow&user[tags][]=a&user[tags][]=b&page=2");
+//!
+//! assert_eq!(data, object!{
+//!     "user" => object!{
+//!         "name" => "Jon Snow",
+//!         "tags" => array!["a", "b"]
+//!     },
+//!     "page" => "2"
+//! });
+//!
+//! assert_eq!(
+//!     json::query::stringify(&data),
+//!     "user[name]=Jon+Snow&user[tags][0]=a&user[tags][1]=b&page=2"
+//! );
+//! # }
+//! ```
+
+use crate::JsonValue;
+use crate::parser::DEPTH_LIMIT;
+
+/// Parse urlencoded `source` into an object. All values are strings, as the
+/// format has no other types.
+///
+/// - `a[b]=1` sets key `b` of object `a`.
+/// - `a[]=1` appends to array `a`, `a[0]=1` sets its first element. Indexes
+///   past the end of the array append to it, so that no holes are created.
+/// - Keys given more than once, like `a=1&a=2`, collect their values into an
+///   array.
+///
+/// Parsing never fails. Malformed percent escapes are kept as they are, and
+/// any text following the bracketed segments of a key becomes a segment of
+/// its own.
+pub fn parse(source: &str) -> JsonValue {
+    let mut root = JsonValue::new_object();
+
+    for pair in source.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = match pair.find('=') {
+            Some(index) => (&pair[.. index], &pair[index + 1 ..]),
+            None        => (pair, ""),
+        };
+
+        let key = decode(key);
+
+        insert(&mut root, &segments(&key), decode(value));
+    }
+
+    root
+}
+
+/// Serialize `value` as urlencoded data, using bracket notation with
+/// indexes for nested objects and arrays. Only objects have a
+/// representation, other values produce an empty string.
+///
+/// `null` is written as an empty value, empty arrays and objects are left
+/// out.
+pub fn stringify(value: &JsonValue) -> String {
+    let mut out = String::new();
+
+    if let JsonValue::Object(ref object) = *value {
+        let mut key = String::new();
+
+        for (name, value) in object.iter() {
+            encode(&mut key, name);
+            write_pairs(&mut out, &mut key, value);
+            key.clear();
+        }
+    }
+
+    out
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+    Push,
+}
+
+// Splits `a[b][0][]` into `a`, `b`, `0` and an empty segment.
+fn segments(key: &str) -> Vec<Segment<'_>> {
+    let (root, mut rest) = match key.find('[') {
+        Some(index) if index > 0 => (&key[.. index], &key[index ..]),
+        _                        => return vec![Segment::Key(key)],
+    };
+
+    let mut segments = vec![Segment::Key(root)];
+
+    while rest.starts_with('[') && segments.len() < DEPTH_LIMIT {
+        let end = match rest.find(']') {
+            Some(end) => end,
+            None      => break,
+        };
+
+        let inner = &rest[1 .. end];
+
+        segments.push(if inner.is_empty() {
+            Segment::Push
+        } else if inner.bytes().all(|ch| ch.is_ascii_digit()) {
+            match inner.parse() {
+                Ok(index) => Segment::Index(index),
+                Err(_)    => Segment::Key(inner),
+            }
+        } else {
+            Segment::Key(inner)
+        });
+
+        rest = &rest[end + 1 ..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Key(rest));
+    }
+
+    segments
+}
+
+fn insert(target: &mut JsonValue, segments: &[Segment], value: String) {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            match *target {
+                JsonValue::Null               => *target = value.into(),
+                JsonValue::Array(ref mut vec) => vec.push(value.into()),
+                _ => {
+                    let previous = target.take();
+                    *target = JsonValue::Array(vec![previous, value.into()]);
+                },
+            }
+            return;
+        },
+    };
+
+    match *segment {
+        Segment::Key(key) => insert(&mut target[key], rest, value),
+        Segment::Index(index) if target.is_object() => {
+            insert(&mut target[index.to_string()], rest, value)
+        },
+        Segment::Index(_) | Segment::Push => {
+            if !target.is_array() {
+                *target = JsonValue::new_array();
+            }
+            let index = match *segment {
+                Segment::Index(index) => index,
+                _                     => target.len(),
+            };
+            // Out of bounds index appends to the array
+            insert(&mut target[index], rest, value)
+        },
+    }
+}
+
+fn write_pairs(out: &mut String, key: &mut String, value: &JsonValue) {
+    let len = key.len();
+
+    match *value {
+        JsonValue::Object(ref object) => {
+            for (name, value) in object.iter() {
+                key.push('[');
+                encode(key, name);
+                key.push(']');
+                write_pairs(out, key, value);
+                key.truncate(len);
+            }
+        },
+        JsonValue::Array(ref array) => {
+            for (index, value) in array.iter().enumerate() {
+                key.push_str(&format!("[{}]", index));
+                write_pairs(out, key, value);
+                key.truncate(len);
+            }
+        },
+        _ => {
+            if !out.is_empty() {
+                out.push('&');
+            }
+            out.push_str(key);
+            out.push('=');
+
+            match *value {
+                JsonValue::Null => {},
+                JsonValue::Short(ref short)   => encode(out, short),
+                JsonValue::String(ref string) => encode(out, string),
+                _                             => encode(out, &value.dump()),
+            }
+        },
+    }
+}
+
+fn encode(out: &mut String, text: &str) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    for &ch in text.as_bytes() {
+        match ch {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' |
+            b'-' | b'.' | b'_' | b'~' => out.push(ch as char),
+            b' '                      => out.push('+'),
+            _ => {
+                out.push('%');
+                out.push(HEX[(ch >> 4) as usize] as char);
+                out.push(HEX[(ch & 0xF) as usize] as char);
+            },
+        }
+    }
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let ch = bytes[index];
+        index += 1;
+
+        if ch == b'+' {
+            out.push(b' ');
+            continue;
+        }
+
+        if ch == b'%' && index + 2 <= bytes.len() {
+            if let (Some(high), Some(low)) = (hex(bytes[index]), hex(bytes[index + 1])) {
+                out.push(high << 4 | low);
+                index += 2;
+                continue;
+            }
+        }
+
+        out.push(ch);
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex(ch: u8) -> Option<u8> {
+    (ch as char).to_digit(16).map(|digit| digit as u8)
+}
@@ -0,0 +1,467 @@
+use std::io::{ self, Read, Write, BufWriter };
+use std::str;
+
+use crate::Error;
+use crate::parser::{ expected, DEPTH_LIMIT };
+
+/// Output style of `reformat`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// No insignificant whitespace at all.
+    Compact,
+
+    /// Pretty printed, indenting new blocks with the given number of spaces.
+    Pretty(u16),
+}
+
+/// Reads JSON from `reader` and writes it back to `writer` in the given
+/// `Style`, validating it along the way. The input is tokenized and copied
+/// directly, without building a `JsonValue`, so memory use doesn't depend on
+/// the size of the document. Strings and numbers are copied as they are, with
+/// their original escapes and digits.
+///
+/// ```
+/// # use json::Style;
+/// let mut out = Vec::new();
+///
+/// json::reformat(&b"[1, {\"a\" : true}]"[..], &mut out, Style::Pretty(2)).unwrap();
+///
+/// assert_eq!(out, b"[\n  1,\n  {\n    \"a\": true\n  }\n]");
+/// ```
+///
+/// Invalid JSON results in an `io::Error` of `InvalidData` kind wrapping the
+/// `json::Error`. Output produced before the problem was detected has already
+/// been written by then.
+pub fn reformat<R: Read, W: Write>(reader: R, writer: W, style: Style) -> io::Result<()> {
+    let mut reformatter = Reformatter::new(reader, BufWriter::new(writer), style);
+
+    reformatter.document()?;
+    reformatter.writer.flush()
+}
+
+/// Strips all insignificant whitespace from `source`, validating it in the
+/// process. Faster than `parse` followed by `dump`, as no `JsonValue` is
+/// created.
+///
+/// ```
+/// let min = json::minify("{ \"a\": [1, 2.50, \"x y\"] }").unwrap();
+///
+/// assert_eq!(min, r#"{"a":[1,2.50,"x y"]}"#);
+/// ```
+pub fn minify(source: &str) -> Result<String, Error> {
+    let mut out = Vec::with_capacity(source.len());
+
+    match Reformatter::new(source.as_bytes(), &mut out, Style::Compact).document() {
+        // Input was valid UTF-8 and is copied byte for byte.
+        #[cfg(not(feature = "forbid-unsafe"))]
+        Ok(())               => Ok(unsafe { String::from_utf8_unchecked(out) }),
+        #[cfg(feature = "forbid-unsafe")]
+        Ok(())               => String::from_utf8(out).map_err(|_| Error::FailedUtf8Parsing),
+        Err(Fail::Json(err)) => Err(err),
+        Err(Fail::Io(_))     => unreachable!("Reading from and writing to memory can't fail"),
+    }
+}
+
+pub(crate) enum Fail {
+    Io(io::Error),
+    Json(Error),
+}
+
+impl From<io::Error> for Fail {
+    fn from(err: io::Error) -> Self {
+        Fail::Io(err)
+    }
+}
+
+impl From<Fail> for io::Error {
+    fn from(fail: Fail) -> Self {
+        match fail {
+            Fail::Io(err)   => err,
+            Fail::Json(err) => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+type Step = Result<(), Fail>;
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+// Buffered input of a tokenizer, keeping track of the position for error
+// messages.
+pub(crate) struct Input<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    index: usize,
+    length: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<R: Read> Input<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Input {
+            reader,
+            buffer: vec![0; BUFFER_SIZE],
+            index: 0,
+            length: 0,
+            line: 1,
+            column: 0,
+        }
+    }
+
+    pub(crate) fn peek(&mut self) -> Result<Option<u8>, Fail> {
+        if self.index == self.length {
+            self.index = 0;
+            self.length = loop {
+                match self.reader.read(&mut self.buffer) {
+                    Ok(length) => break length,
+                    Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {},
+                    Err(err) => return Err(Fail::Io(err)),
+                }
+            };
+
+            if self.length == 0 {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(self.buffer[self.index]))
+    }
+
+    // Advances past the byte returned by `peek`, keeping track of the
+    // position for error messages.
+    pub(crate) fn bump(&mut self) {
+        let ch = self.buffer[self.index];
+        self.index += 1;
+
+        if ch == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        } else if ch & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+    }
+
+    pub(crate) fn next(&mut self) -> Result<u8, Fail> {
+        match self.peek()? {
+            Some(ch) => {
+                self.bump();
+                Ok(ch)
+            },
+            None => Err(Fail::Json(Error::UnexpectedEndOfJson)),
+        }
+    }
+
+    pub(crate) fn next_token(&mut self) -> Result<u8, Fail> {
+        loop {
+            let ch = self.next()?;
+            if !is_whitespace(ch) {
+                return Ok(ch);
+            }
+        }
+    }
+
+    // Reports the byte that was just read as unexpected, reading the rest of
+    // the character if it's not ASCII.
+    pub(crate) fn unexpected<T>(&mut self, ch: u8, expected: &'static [&'static str]) -> Result<T, Fail> {
+        let (line, column) = (self.line, self.column);
+
+        let width = match ch {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _           => return Err(Fail::Json(Error::FailedUtf8Parsing)),
+        };
+
+        let mut bytes = [ch, 0, 0, 0];
+        for byte in bytes.iter_mut().take(width).skip(1) {
+            *byte = self.next()?;
+        }
+
+        let ch = match str::from_utf8(&bytes[..width]) {
+            Ok(s)  => s.chars().next().expect("Must have a character"),
+            Err(_) => return Err(Fail::Json(Error::FailedUtf8Parsing)),
+        };
+
+        Err(Fail::Json(Error::UnexpectedCharacter { ch, line, column, expected }))
+    }
+}
+
+// Tokenizer copying validated input to output. Nesting is handled with
+// recursion, which is bounded by `DEPTH_LIMIT`.
+struct Reformatter<R: Read, W: Write> {
+    input: Input<R>,
+    writer: W,
+    style: Style,
+    dent: u16,
+}
+
+impl<R: Read, W: Write> Reformatter<R, W> {
+    fn new(reader: R, writer: W, style: Style) -> Self {
+        Reformatter {
+            input: Input::new(reader),
+            writer,
+            style,
+            dent: 0,
+        }
+    }
+
+    fn document(&mut self) -> Step {
+        let ch = self.input.next_token()?;
+        self.value(ch, 0)?;
+
+        while let Some(ch) = self.input.peek()? {
+            self.input.bump();
+            if !is_whitespace(ch) {
+                return self.input.unexpected(ch, &[]);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn value(&mut self, ch: u8, depth: usize) -> Step {
+        match ch {
+            b'{'               => self.object(depth),
+            b'['               => self.array(depth),
+            b'"'               => self.string(),
+            b'-' | b'0'..=b'9' => self.number(ch),
+            b't'               => self.literal(expected::TRUE),
+            b'f'               => self.literal(expected::FALSE),
+            b'n'               => self.literal(expected::NULL),
+            _                  => self.input.unexpected(ch, expected::VALUE),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Step {
+        self.write(b"[")?;
+
+        let mut ch = self.input.next_token()?;
+        if ch == b']' {
+            return self.write(b"]");
+        }
+        if depth == DEPTH_LIMIT {
+            return Err(Fail::Json(Error::ExceededDepthLimit));
+        }
+
+        self.dent += 1;
+        loop {
+            self.new_line()?;
+            self.value(ch, depth + 1)?;
+
+            match self.input.next_token()? {
+                b',' => {
+                    self.write(b",")?;
+                    ch = self.input.next_token()?;
+                },
+                b']' => break,
+                ch   => return self.input.unexpected(ch, expected::ARRAY_NEXT),
+            }
+        }
+        self.dent -= 1;
+
+        self.new_line()?;
+        self.write(b"]")
+    }
+
+    fn object(&mut self, depth: usize) -> Step {
+        self.write(b"{")?;
+
+        let mut ch = self.input.next_token()?;
+        if ch == b'}' {
+            return self.write(b"}");
+        }
+        if depth == DEPTH_LIMIT {
+            return Err(Fail::Json(Error::ExceededDepthLimit));
+        }
+
+        self.dent += 1;
+        loop {
+            if ch != b'"' {
+                return self.input.unexpected(ch, expected::KEY);
+            }
+            self.new_line()?;
+            self.string()?;
+
+            match self.input.next_token()? {
+                b':' => {},
+                ch   => return self.input.unexpected(ch, expected::COLON),
+            }
+            match self.style {
+                Style::Compact   => self.write(b":")?,
+                Style::Pretty(_) => self.write(b": ")?,
+            }
+
+            let ch_value = self.input.next_token()?;
+            self.value(ch_value, depth + 1)?;
+
+            match self.input.next_token()? {
+                b',' => {
+                    self.write(b",")?;
+                    ch = self.input.next_token()?;
+                },
+                b'}' => break,
+                ch   => return self.input.unexpected(ch, expected::OBJECT_NEXT),
+            }
+        }
+        self.dent -= 1;
+
+        self.new_line()?;
+        self.write(b"}")
+    }
+
+    // Copies a string, the opening quote has already been read.
+    fn string(&mut self) -> Step {
+        self.write(b"\"")?;
+
+        loop {
+            let ch = self.input.next()?;
+
+            match ch {
+                b'"' => return self.write(b"\""),
+                b'\\' => {
+                    let escaped = self.input.next()?;
+
+                    match escaped {
+                        b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => {
+                            self.write(&[ch, escaped])?;
+                        },
+                        b'u' => {
+                            self.write(b"\\u")?;
+                            for _ in 0..4 {
+                                let digit = self.input.next()?;
+                                if !digit.is_ascii_hexdigit() {
+                                    return self.input.unexpected(digit, expected::HEX_DIGIT);
+                                }
+                                self.write(&[digit])?;
+                            }
+                        },
+                        _ => return self.input.unexpected(escaped, expected::ESCAPE),
+                    }
+                },
+                0x00..=0x1F => return self.input.unexpected(ch, expected::ESCAPED_CONTROL),
+                0x80..=0xFF => self.utf8(ch)?,
+                _ => self.write(&[ch])?,
+            }
+        }
+    }
+
+    // Validates and copies the rest of a multi-byte UTF-8 character.
+    fn utf8(&mut self, lead: u8) -> Step {
+        // Ranges of the second byte exclude overlong encodings, surrogates
+        // and code points above U+10FFFF.
+        let (count, min, max) = match lead {
+            0xC2..=0xDF               => (1, 0x80, 0xBF),
+            0xE0                      => (2, 0xA0, 0xBF),
+            0xE1..=0xEC | 0xEE..=0xEF => (2, 0x80, 0xBF),
+            0xED                      => (2, 0x80, 0x9F),
+            0xF0                      => (3, 0x90, 0xBF),
+            0xF1..=0xF3               => (3, 0x80, 0xBF),
+            0xF4                      => (3, 0x80, 0x8F),
+            _                         => return Err(Fail::Json(Error::FailedUtf8Parsing)),
+        };
+
+        let mut bytes = [lead, 0, 0, 0];
+
+        for (i, byte) in bytes.iter_mut().enumerate().take(count + 1).skip(1) {
+            let ch = self.input.next()?;
+            let (min, max) = if i == 1 { (min, max) } else { (0x80, 0xBF) };
+
+            if ch < min || ch > max {
+                return Err(Fail::Json(Error::FailedUtf8Parsing));
+            }
+            *byte = ch;
+        }
+
+        self.write(&bytes[..=count])
+    }
+
+    fn number(&mut self, first: u8) -> Step {
+        self.write(&[first])?;
+
+        let mut ch = first;
+        if first == b'-' {
+            ch = self.input.next()?;
+            if !ch.is_ascii_digit() {
+                return self.input.unexpected(ch, expected::DIGIT);
+            }
+            self.write(&[ch])?;
+        }
+        if ch != b'0' {
+            self.digits()?;
+        }
+
+        if self.input.peek()? == Some(b'.') {
+            self.input.bump();
+            self.write(b".")?;
+            self.required_digits()?;
+        }
+
+        if let Some(e @ b'e') | Some(e @ b'E') = self.input.peek()? {
+            self.input.bump();
+            self.write(&[e])?;
+
+            if let Some(sign @ b'+') | Some(sign @ b'-') = self.input.peek()? {
+                self.input.bump();
+                self.write(&[sign])?;
+            }
+            self.required_digits()?;
+        }
+
+        Ok(())
+    }
+
+    fn required_digits(&mut self) -> Step {
+        let ch = self.input.next()?;
+        if !ch.is_ascii_digit() {
+            return self.input.unexpected(ch, expected::DIGIT);
+        }
+        self.write(&[ch])?;
+        self.digits()
+    }
+
+    fn digits(&mut self) -> Step {
+        while let Some(ch) = self.input.peek()? {
+            if !ch.is_ascii_digit() {
+                break;
+            }
+            self.input.bump();
+            self.write(&[ch])?;
+        }
+        Ok(())
+    }
+
+    // Checks the rest of a literal, the first byte has already been read.
+    fn literal(&mut self, literal: &'static [&'static str]) -> Step {
+        for &byte in &literal[0].as_bytes()[1..] {
+            let ch = self.input.next()?;
+            if ch != byte {
+                return self.input.unexpected(ch, literal);
+            }
+        }
+        self.write(literal[0].as_bytes())
+    }
+
+    fn new_line(&mut self) -> Step {
+        if let Style::Pretty(spaces) = self.style {
+            self.write(b"\n")?;
+            for _ in 0..(self.dent * spaces) {
+                self.write(b" ")?;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) -> Step {
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+
+}
+
+#[inline(always)]
+pub(crate) fn is_whitespace(ch: u8) -> bool {
+    ch == b' ' || ch == b'\t' || ch == b'\n' || ch == b'\r'
+}
@@ -0,0 +1,182 @@
+//! Best effort repair of almost-JSON, such as the output of language models
+//! or documents cut off in the middle.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # use json::repair::Fix;
+//! # fn main() {
+//! let (value, fixes) = json::repair(r#"{name: "Ada", "tags": ["math",], "note": "cut off"#).unwrap();
+//!
+//! assert_eq!(value, object!{ "name" => "Ada", "tags" => array!["math"], "note" => "cut off" });
+//! assert_eq!(fixes, [
+//!     Fix::QuotedKey { index: 1 },
+//!     Fix::RemovedTrailingComma { index: 29 },
+//!     Fix::ClosedString { index: 49 },
+//!     Fix::ClosedBracket { index: 49, bracket: '}' },
+//! ]);
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::{ parse, JsonValue, Result };
+
+/// A change made by `repair`. `index` is the byte offset in the source it
+/// applies to, the length of the source for things added at its end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fix {
+    /// A string was still open at the end of the source, the closing quote
+    /// was added. A trailing backslash is dropped along the way.
+    ClosedString { index: usize },
+
+    /// An array or object was still open at the end of the source, the
+    /// closing `bracket` was added.
+    ClosedBracket { index: usize, bracket: char },
+
+    /// A comma in front of a `]`, a `}` or the end of the source was
+    /// removed.
+    RemovedTrailingComma { index: usize },
+
+    /// An object key without quotes, such as `name` in `{name: 1}`, was put
+    /// in quotes.
+    QuotedKey { index: usize },
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Fix::ClosedString { index }           => write!(f, "Closed string at byte {}", index),
+            Fix::ClosedBracket { index, bracket } => write!(f, "Added {} at byte {}", bracket, index),
+            Fix::RemovedTrailingComma { index }   => write!(f, "Removed trailing comma at byte {}", index),
+            Fix::QuotedKey { index }              => write!(f, "Quoted key at byte {}", index),
+        }
+    }
+}
+
+/// Parse `source`, fixing the common mistakes listed in `Fix` first.
+/// Returns the value along with the fixes that were needed, none for valid
+/// JSON.
+///
+/// Anything else that is wrong is left alone and reported as an error, with
+/// the position referring to the repaired text. Trailing commas and bare keys
+/// are recognized outside of strings only, strings are copied as they are.
+pub fn repair(source: &str) -> Result<(JsonValue, Vec<Fix>)> {
+    let bytes = source.as_bytes();
+    let mut out = Vec::with_capacity(source.len() + 8);
+    let mut fixes = Vec::new();
+
+    // Open brackets, and whether the next token is an object key
+    let mut stack = Vec::new();
+    let mut key = false;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let ch = bytes[index];
+        let start = index;
+        index += 1;
+
+        match ch {
+            b'"' => {
+                key = false;
+                index = skip_string(bytes, index);
+
+                if index > bytes.len() {
+                    // Unterminated, a dangling escape can't be closed
+                    let end = match source.ends_with('\\') && !escaped(bytes, bytes.len() - 1) {
+                        true  => bytes.len() - 1,
+                        false => bytes.len(),
+                    };
+
+                    out.extend_from_slice(&bytes[start .. end]);
+                    out.push(b'"');
+                    fixes.push(Fix::ClosedString { index: bytes.len() });
+                    index = bytes.len();
+                    continue;
+                }
+            },
+            b'[' | b'{' => {
+                stack.push(ch);
+                key = ch == b'{';
+            },
+            b']' | b'}' => {
+                if stack.last().map(|&open| closing(open)) == Some(ch) {
+                    stack.pop();
+                }
+                key = false;
+            },
+            b',' => {
+                let next = bytes[index ..].iter().position(|&ch| !is_whitespace(ch)).map(|skip| bytes[index + skip]);
+
+                if matches!(next, None | Some(b']') | Some(b'}')) {
+                    fixes.push(Fix::RemovedTrailingComma { index: start });
+                    continue;
+                }
+                key = stack.last() == Some(&b'{');
+            },
+            _ if is_whitespace(ch) => {},
+            _ if key && is_identifier(ch) => {
+                while index < bytes.len() && (is_identifier(bytes[index]) || bytes[index].is_ascii_digit()) {
+                    index += 1;
+                }
+
+                out.push(b'"');
+                out.extend_from_slice(&bytes[start .. index]);
+                out.push(b'"');
+                fixes.push(Fix::QuotedKey { index: start });
+                key = false;
+                continue;
+            },
+            _ => key = false,
+        }
+
+        out.extend_from_slice(&bytes[start .. index]);
+    }
+
+    while let Some(open) = stack.pop() {
+        let bracket = closing(open);
+
+        out.push(bracket);
+        fixes.push(Fix::ClosedBracket { index: bytes.len(), bracket: bracket as char });
+    }
+
+    // Source bytes are copied whole, only ASCII is added or removed
+    let out = String::from_utf8(out).expect("Repaired source must be UTF-8");
+
+    parse(&out).map(|value| (value, fixes))
+}
+
+// Index past the closing quote of a string starting before `index`, one
+// past the end of `bytes` if there is none.
+fn skip_string(bytes: &[u8], mut index: usize) -> usize {
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"'  => return index + 1,
+            b'\\' => index += 2,
+            _     => index += 1,
+        }
+    }
+
+    bytes.len() + 1
+}
+
+// Whether the byte at `index` is escaped by an odd number of backslashes.
+fn escaped(bytes: &[u8], index: usize) -> bool {
+    !bytes[.. index].iter().rev().take_while(|&&ch| ch == b'\\').count().is_multiple_of(2)
+}
+
+fn closing(open: u8) -> u8 {
+    match open {
+        b'[' => b']',
+        _    => b'}',
+    }
+}
+
+#[inline]
+fn is_whitespace(ch: u8) -> bool {
+    matches!(ch, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+#[inline]
+fn is_identifier(ch: u8) -> bool {
+    ch.is_ascii_alphabetic() || ch == b'_' || ch == b'$'
+}
@@ -0,0 +1,495 @@
+//! Validation against [JSON Type Definition](https://jsontypedef.com/)
+//! schemas, as specified by RFC 8927.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! use json::schema::jtd;
+//!
+//! let schema = object!{
+//!     "properties" => object!{
+//!         "name" => object!{ "type" => "string" },
+//!         "age" => object!{ "type" => "uint8" }
+//!     }
+//! };
+//!
+//! let errors = jtd::validate(&schema, &object!{ "name" => 7, "age" => 300 }).unwrap();
+//!
+//! assert_eq!(json::from(errors), array![
+//!     object!{ "instancePath" => "/name", "schemaPath" => "/properties/name/type" },
+//!     object!{ "instancePath" => "/age", "schemaPath" => "/properties/age/type" }
+//! ]);
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::JsonValue;
+use crate::object::Object;
+use crate::parser::DEPTH_LIMIT;
+use crate::util::pointer;
+use super::is_integer;
+
+/// Error preventing validation from being done.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The schema is not a valid JTD schema, the message says why.
+    InvalidSchema(&'static str),
+
+    /// Following `ref`s nested deeper than the depth limit, usually caused
+    /// by a definition referring to itself.
+    MaxDepthExceeded,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidSchema(message) => write!(f, "Invalid JTD schema: {}", message),
+            Error::MaxDepthExceeded       => write!(f, "Exceeded depth limit following refs"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A single validation error, in the standard error indicator form: JSON
+/// Pointers to the rejected part of the instance and to the part of the
+/// schema that rejected it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub instance_path: String,
+    pub schema_path: String,
+}
+
+impl From<ValidationError> for JsonValue {
+    fn from(error: ValidationError) -> JsonValue {
+        let mut object = Object::with_capacity(2);
+        object.insert("instancePath", error.instance_path.into());
+        object.insert("schemaPath", error.schema_path.into());
+        JsonValue::Object(object)
+    }
+}
+
+/// Validate `instance` against the JTD `schema`, returning all validation
+/// errors found. An empty list means the instance is valid.
+pub fn validate(schema: &JsonValue, instance: &JsonValue) -> Result<Vec<ValidationError>, Error> {
+    check(schema, &schema["definitions"], true)?;
+
+    let mut validator = Validator {
+        root: schema,
+        instance_path: String::new(),
+        schema_path: String::new(),
+        errors: Vec::new(),
+        depth: 0,
+    };
+
+    validator.validate(schema, instance, None)?;
+
+    Ok(validator.errors)
+}
+
+const TYPES: &[&str] = &[
+    "boolean", "string", "timestamp", "float32", "float64",
+    "int8", "uint8", "int16", "uint16", "int32", "uint32",
+];
+
+fn invalid<T>(message: &'static str) -> Result<T, Error> {
+    Err(Error::InvalidSchema(message))
+}
+
+fn has_properties(schema: &Object) -> bool {
+    schema.get("properties").is_some() || schema.get("optionalProperties").is_some()
+}
+
+// Checks the rules of RFC 8927 section 2 for a valid schema.
+fn check(schema: &JsonValue, definitions: &JsonValue, is_root: bool) -> Result<(), Error> {
+    let object = match *schema {
+        JsonValue::Object(ref object) => object,
+        _ => return invalid("Schema must be an object"),
+    };
+
+    for (key, value) in object.iter() {
+        match key {
+            "definitions" if is_root => match *value {
+                JsonValue::Object(ref definitions) => for (_, definition) in definitions.iter() {
+                    check(definition, &schema["definitions"], false)?;
+                },
+                _ => return invalid("definitions must be an object"),
+            },
+            "nullable" if !value.is_boolean() => return invalid("nullable must be a boolean"),
+            "metadata" if !value.is_object() => return invalid("metadata must be an object"),
+            "nullable" | "metadata" | "ref" | "type" | "enum" | "elements" |
+            "properties" | "optionalProperties" | "additionalProperties" |
+            "values" | "discriminator" | "mapping" => {},
+            "definitions" => return invalid("definitions are only allowed at the root"),
+            _ => return invalid("Unknown keyword"),
+        }
+    }
+
+    let forms = [
+        object.get("ref").is_some(),
+        object.get("type").is_some(),
+        object.get("enum").is_some(),
+        object.get("elements").is_some(),
+        has_properties(object),
+        object.get("values").is_some(),
+        object.get("discriminator").is_some(),
+    ];
+
+    if forms.iter().filter(|&&form| form).count() > 1 {
+        return invalid("Schema mixes keywords of different forms");
+    }
+    if object.get("additionalProperties").is_some() && !has_properties(object) {
+        return invalid("additionalProperties is only allowed with properties");
+    }
+    if object.get("discriminator").is_some() != object.get("mapping").is_some() {
+        return invalid("discriminator and mapping must be used together");
+    }
+
+    if let Some(reference) = object.get("ref") {
+        match reference.as_str() {
+            Some(name) if definitions.has_key(name) => {},
+            Some(_) => return invalid("ref to a missing definition"),
+            None    => return invalid("ref must be a string"),
+        }
+    }
+
+    if let Some(kind) = object.get("type") {
+        if !kind.as_str().is_some_and(|kind| TYPES.contains(&kind)) {
+            return invalid("Unknown type");
+        }
+    }
+
+    if let Some(values) = object.get("enum") {
+        let values = match *values {
+            JsonValue::Array(ref values) if !values.is_empty() => values,
+            _ => return invalid("enum must be a non-empty array"),
+        };
+        for (index, value) in values.iter().enumerate() {
+            if !value.is_string() {
+                return invalid("enum must only contain strings");
+            }
+            if values[.. index].contains(value) {
+                return invalid("enum must not contain duplicates");
+            }
+        }
+    }
+
+    if let Some(elements) = object.get("elements") {
+        check(elements, definitions, false)?;
+    }
+
+    if let Some(values) = object.get("values") {
+        check(values, definitions, false)?;
+    }
+
+    for &keyword in &["properties", "optionalProperties"] {
+        match object.get(keyword) {
+            None => {},
+            Some(JsonValue::Object(properties)) => {
+                for (key, property) in properties.iter() {
+                    if keyword == "optionalProperties" && schema["properties"].has_key(key) {
+                        return invalid("Property is both required and optional");
+                    }
+                    check(property, definitions, false)?;
+                }
+            },
+            Some(_) => return invalid("properties must be an object"),
+        }
+    }
+
+    if let Some(additional) = object.get("additionalProperties") {
+        if !additional.is_boolean() {
+            return invalid("additionalProperties must be a boolean");
+        }
+    }
+
+    if let Some(tag) = object.get("discriminator") {
+        let tag = match tag.as_str() {
+            Some(tag) => tag,
+            None      => return invalid("discriminator must be a string"),
+        };
+        let mapping = match object.get("mapping") {
+            Some(JsonValue::Object(mapping)) => mapping,
+            _ => return invalid("mapping must be an object"),
+        };
+
+        for (_, variant) in mapping.iter() {
+            check(variant, definitions, false)?;
+
+            let variant = match *variant {
+                JsonValue::Object(ref variant) if has_properties(variant) => variant,
+                _ => return invalid("mapping values must be of the properties form"),
+            };
+            if variant.get("nullable").and_then(JsonValue::as_bool) == Some(true) {
+                return invalid("mapping values must not be nullable");
+            }
+            if variant.get("properties").is_some_and(|p| p.has_key(tag)) ||
+               variant.get("optionalProperties").is_some_and(|p| p.has_key(tag)) {
+                return invalid("mapping values must not define the discriminator");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Validator<'a> {
+    root: &'a JsonValue,
+    instance_path: String,
+    schema_path: String,
+    errors: Vec<ValidationError>,
+    depth: usize,
+}
+
+impl<'a> Validator<'a> {
+    // Records an error at the current paths, extended by the optional
+    // instance key and the schema keywords.
+    fn report(&mut self, instance_key: Option<&str>, schema_keys: &[&str]) {
+        let mut instance_path = self.instance_path.clone();
+        if let Some(key) = instance_key {
+            pointer::push_key(&mut instance_path, key);
+        }
+
+        let mut schema_path = self.schema_path.clone();
+        for key in schema_keys {
+            pointer::push_key(&mut schema_path, key);
+        }
+
+        self.errors.push(ValidationError { instance_path, schema_path });
+    }
+
+    // Validates a nested value, `instance_key` and `schema_keys` are
+    // appended to the paths for the duration of the call.
+    fn nested(
+        &mut self,
+        schema: &'a JsonValue,
+        instance: &JsonValue,
+        instance_key: Option<&str>,
+        schema_keys: &[&str],
+        tag: Option<&str>,
+    ) -> Result<(), Error> {
+        let (instance_len, schema_len) = (self.instance_path.len(), self.schema_path.len());
+
+        if let Some(key) = instance_key {
+            pointer::push_key(&mut self.instance_path, key);
+        }
+        for key in schema_keys {
+            pointer::push_key(&mut self.schema_path, key);
+        }
+
+        let result = self.validate(schema, instance, tag);
+
+        self.instance_path.truncate(instance_len);
+        self.schema_path.truncate(schema_len);
+
+        result
+    }
+
+    // `tag` is the discriminator of the parent schema, which is exempt from
+    // the additional properties check.
+    fn validate(&mut self, schema: &'a JsonValue, instance: &JsonValue, tag: Option<&str>) -> Result<(), Error> {
+        if schema["nullable"].as_bool() == Some(true) && instance.is_null() {
+            return Ok(());
+        }
+
+        if let Some(name) = schema["ref"].as_str() {
+            if self.depth == DEPTH_LIMIT {
+                return Err(Error::MaxDepthExceeded);
+            }
+
+            // Errors inside of a definition point at the definition itself
+            let schema_path = std::mem::take(&mut self.schema_path);
+            self.depth += 1;
+
+            let result = self.nested(&self.root["definitions"][name], instance, None, &["definitions", name], None);
+
+            self.depth -= 1;
+            self.schema_path = schema_path;
+
+            return result;
+        }
+
+        if let Some(kind) = schema["type"].as_str() {
+            if !is_type(kind, instance) {
+                self.report(None, &["type"]);
+            }
+            return Ok(());
+        }
+
+        if let JsonValue::Array(ref values) = schema["enum"] {
+            if !instance.is_string() || !values.contains(instance) {
+                self.report(None, &["enum"]);
+            }
+            return Ok(());
+        }
+
+        if schema.has_key("elements") {
+            match *instance {
+                JsonValue::Array(ref items) => for (index, item) in items.iter().enumerate() {
+                    let index = index.to_string();
+                    self.nested(&schema["elements"], item, Some(&index), &["elements"], None)?;
+                },
+                _ => self.report(None, &["elements"]),
+            }
+            return Ok(());
+        }
+
+        if schema.has_key("properties") || schema.has_key("optionalProperties") {
+            let object = match *instance {
+                JsonValue::Object(ref object) => object,
+                _ => {
+                    let keyword = if schema.has_key("properties") { "properties" } else { "optionalProperties" };
+                    self.report(None, &[keyword]);
+                    return Ok(());
+                },
+            };
+
+            for (key, property) in schema["properties"].entries() {
+                match object.get(key) {
+                    Some(value) => self.nested(property, value, Some(key), &["properties", key], None)?,
+                    None        => self.report(None, &["properties", key]),
+                }
+            }
+
+            for (key, property) in schema["optionalProperties"].entries() {
+                if let Some(value) = object.get(key) {
+                    self.nested(property, value, Some(key), &["optionalProperties", key], None)?;
+                }
+            }
+
+            if schema["additionalProperties"].as_bool() != Some(true) {
+                for (key, _) in object.iter() {
+                    let known = schema["properties"].has_key(key) ||
+                                schema["optionalProperties"].has_key(key) ||
+                                tag == Some(key);
+                    if !known {
+                        self.report(Some(key), &[]);
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        if schema.has_key("values") {
+            match *instance {
+                JsonValue::Object(ref object) => for (key, value) in object.iter() {
+                    self.nested(&schema["values"], value, Some(key), &["values"], None)?;
+                },
+                _ => self.report(None, &["values"]),
+            }
+            return Ok(());
+        }
+
+        if let Some(tag) = schema["discriminator"].as_str() {
+            let object = match *instance {
+                JsonValue::Object(ref object) => object,
+                _ => {
+                    self.report(None, &["discriminator"]);
+                    return Ok(());
+                },
+            };
+
+            match object.get(tag).map(JsonValue::as_str) {
+                None              => self.report(None, &["discriminator"]),
+                Some(None)        => self.report(Some(tag), &["discriminator"]),
+                Some(Some(value)) => match schema["mapping"][value] {
+                    JsonValue::Null => self.report(Some(tag), &["mapping"]),
+                    ref variant     => self.nested(variant, instance, None, &["mapping", value], Some(tag))?,
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_type(kind: &str, instance: &JsonValue) -> bool {
+    let range = match kind {
+        "boolean"   => return instance.is_boolean(),
+        "string"    => return instance.is_string(),
+        "timestamp" => return instance.as_str().is_some_and(is_timestamp),
+        "float32" | "float64" => return instance.is_number(),
+        "int8"   => (i8::MIN as f64, i8::MAX as f64),
+        "uint8"  => (0.0, u8::MAX as f64),
+        "int16"  => (i16::MIN as f64, i16::MAX as f64),
+        "uint16" => (0.0, u16::MAX as f64),
+        "int32"  => (i32::MIN as f64, i32::MAX as f64),
+        "uint32" => (0.0, u32::MAX as f64),
+        _        => return false,
+    };
+
+    match instance.as_number() {
+        Some(number) if is_integer(&number) => {
+            let value = f64::from(number);
+            value >= range.0 && value <= range.1
+        },
+        _ => false,
+    }
+}
+
+// RFC 3339 `date-time`, such as `1985-04-12T23:20:50.52Z`.
+fn is_timestamp(timestamp: &str) -> bool {
+    let bytes = timestamp.as_bytes();
+
+    let number = |start: usize, len: usize| -> Option<u32> {
+        let digits = bytes.get(start .. start + len)?;
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        Some(digits.iter().fold(0, |acc, &digit| acc * 10 + (digit - b'0') as u32))
+    };
+
+    let separators = bytes.len() > 19 &&
+        bytes[4] == b'-' && bytes[7] == b'-' &&
+        (bytes[10] == b'T' || bytes[10] == b't') &&
+        bytes[13] == b':' && bytes[16] == b':';
+
+    if !separators {
+        return false;
+    }
+
+    let fields = (number(0, 4), number(5, 2), number(8, 2), number(11, 2), number(14, 2), number(17, 2));
+
+    let (year, month, day, hour, minute, second) = match fields {
+        (Some(y), Some(mo), Some(d), Some(h), Some(mi), Some(s)) => (y, mo, d, h, mi, s),
+        _ => return false,
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    if day < 1 || day > days || hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+
+    let mut index = 19;
+
+    if bytes[index] == b'.' {
+        index += 1;
+        let start = index;
+        while index < bytes.len() && bytes[index].is_ascii_digit() {
+            index += 1;
+        }
+        if index == start {
+            return false;
+        }
+    }
+
+    match bytes.get(index) {
+        Some(b'Z') | Some(b'z') => index + 1 == bytes.len(),
+        Some(b'+') | Some(b'-') => {
+            index + 6 == bytes.len() &&
+            bytes[index + 3] == b':' &&
+            number(index + 1, 2).is_some_and(|hours| hours <= 23) &&
+            number(index + 4, 2).is_some_and(|minutes| minutes <= 59)
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,173 @@
+//! Working with schemas describing the shape of JSON documents.
+
+pub mod jtd;
+mod shape;
+
+pub use self::shape::{ Shape, ObjectShape };
+
+use crate::JsonValue;
+use crate::number::Number;
+use crate::object::Object;
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Infer a [JSON Schema](https://json-schema.org/) accepting all `samples`.
+/// The schema lists the types seen at each position, the properties of
+/// objects with those present in every sample object marked as `required`,
+/// and the schema of array items.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// let schema = json::schema::infer(&[
+///     object!{ "id" => 1, "tags" => array!["a"] },
+///     object!{ "id" => 2, "tags" => array![], "note" => json::Null },
+/// ]);
+///
+/// assert_eq!(schema, object!{
+///     "$schema" => "https://json-schema.org/draft/2020-12/schema",
+///     "type" => "object",
+///     "properties" => object!{
+///         "id" => object!{ "type" => "integer" },
+///         "tags" => object!{ "type" => "array", "items" => object!{ "type" => "string" } },
+///         "note" => object!{ "type" => "null" }
+///     },
+///     "required" => array!["id", "tags"]
+/// });
+/// # }
+/// ```
+pub fn infer(samples: &[JsonValue]) -> JsonValue {
+    let mut shape = Inferred::default();
+
+    for sample in samples {
+        shape.add(sample);
+    }
+
+    let mut schema = Object::new();
+    schema.insert("$schema", DRAFT.into());
+
+    if let JsonValue::Object(inferred) = shape.to_schema() {
+        for (key, value) in inferred.iter() {
+            schema.insert(key, value.clone());
+        }
+    }
+
+    JsonValue::Object(schema)
+}
+
+// Everything seen at one position in the samples.
+#[derive(Default)]
+struct Inferred {
+    null: bool,
+    boolean: bool,
+    integer: bool,
+    number: bool,
+    string: bool,
+    array: bool,
+    object: bool,
+    items: Option<Box<Inferred>>,
+    // Properties in order of appearance, with the number of objects they
+    // appeared in.
+    properties: Vec<(String, usize, Inferred)>,
+    objects: usize,
+}
+
+impl Inferred {
+    fn add(&mut self, value: &JsonValue) {
+        match *value {
+            JsonValue::Null => self.null = true,
+            JsonValue::Boolean(_) => self.boolean = true,
+            JsonValue::Short(_) | JsonValue::String(_) | JsonValue::Binary(_) => self.string = true,
+            JsonValue::Number(ref number) => {
+                if is_integer(number) {
+                    self.integer = true;
+                } else {
+                    self.number = true;
+                }
+            },
+            JsonValue::Array(ref array) => {
+                self.array = true;
+                for item in array {
+                    self.items.get_or_insert_with(Default::default).add(item);
+                }
+            },
+            JsonValue::Object(ref object) => {
+                self.object = true;
+                self.objects += 1;
+
+                for (key, value) in object.iter() {
+                    let index = match self.properties.iter().position(|p| p.0 == key) {
+                        Some(index) => index,
+                        None => {
+                            self.properties.push((key.to_owned(), 0, Inferred::default()));
+                            self.properties.len() - 1
+                        },
+                    };
+
+                    let property = &mut self.properties[index];
+                    property.1 += 1;
+                    property.2.add(value);
+                }
+            },
+        }
+    }
+
+    fn to_schema(&self) -> JsonValue {
+        let mut types = Vec::new();
+
+        if self.object  { types.push("object"); }
+        if self.array   { types.push("array"); }
+        if self.string  { types.push("string"); }
+        // Integers are numbers too, no need to list both
+        if self.number  { types.push("number"); }
+        else if self.integer { types.push("integer"); }
+        if self.boolean { types.push("boolean"); }
+        if self.null    { types.push("null"); }
+
+        let mut schema = Object::new();
+
+        match types.len() {
+            0 => return JsonValue::Object(schema),
+            1 => schema.insert("type", types[0].into()),
+            _ => schema.insert("type", types.into()),
+        }
+
+        if self.object {
+            let mut properties = Object::with_capacity(self.properties.len());
+            let mut required = Vec::new();
+
+            for (key, count, shape) in &self.properties {
+                properties.insert(key, shape.to_schema());
+                if *count == self.objects {
+                    required.push(JsonValue::from(key.as_str()));
+                }
+            }
+
+            schema.insert("properties", JsonValue::Object(properties));
+            if !required.is_empty() {
+                schema.insert("required", JsonValue::Array(required));
+            }
+        }
+
+        if let Some(ref items) = self.items {
+            schema.insert("items", items.to_schema());
+        }
+
+        JsonValue::Object(schema)
+    }
+}
+
+fn is_integer(number: &Number) -> bool {
+    let (_, mut mantissa, mut exponent) = number.as_parts();
+
+    if number.is_nan() {
+        return false;
+    }
+
+    while exponent < 0 && mantissa % 10 == 0 && mantissa != 0 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+
+    exponent >= 0 || mantissa == 0
+}
@@ -0,0 +1,290 @@
+use crate::{ JsonValue, Error, Result };
+use crate::parser::{ self, Guide };
+use crate::util::pointer;
+
+/// Compact description of the expected structure of a document, used to
+/// validate it while it's being parsed. Documents that don't match are
+/// rejected as soon as the mismatch is found, without parsing the rest.
+///
+/// ```
+/// # #[macro_use] extern crate json;
+/// # fn main() {
+/// use json::schema::Shape;
+///
+/// let shape = Shape::object()
+///     .field("id", Shape::Number)
+///     .field("tags", Shape::array(Shape::String))
+///     .optional("parent", Shape::Number.nullable());
+///
+/// let user = shape.parse(r#"{ "id": 1, "tags": ["a"], "parent": null }"#).unwrap();
+/// assert_eq!(user, object!{ "id" => 1, "tags" => array!["a"], "parent" => json::Null });
+///
+/// assert_eq!(
+///     shape.parse(r#"{ "id": 1, "tags": ["a", 2] }"#),
+///     Err(json::Error::WrongType {
+///         expected: "string".into(),
+///         found: "number".into(),
+///         path: "/tags/1".into(),
+///     })
+/// );
+/// # }
+/// ```
+///
+/// Values of the wrong type and members not in the shape are reported as
+/// `Error::WrongType`, missing required members as `Error::MemberNotFound`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Any value at all, not checked.
+    Any,
+    Null,
+    Boolean,
+    Number,
+    String,
+
+    /// An array with items of the given shape.
+    Array(Box<Shape>),
+
+    /// An object, see `Shape::object`.
+    Object(ObjectShape),
+
+    /// Either `null` or a value of the given shape.
+    Nullable(Box<Shape>),
+}
+
+/// Members of an object `Shape`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectShape {
+    members: Vec<Member>,
+    additional: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Member {
+    key: String,
+    shape: Shape,
+    required: bool,
+}
+
+static ANY: Shape = Shape::Any;
+
+impl Shape {
+    /// An array with items of the shape `items`.
+    pub fn array(items: Shape) -> Shape {
+        Shape::Array(Box::new(items))
+    }
+
+    /// An object without members. Add them with `field` and `optional`.
+    /// Members not in the shape are rejected, unless `additional` is used.
+    pub fn object() -> Shape {
+        Shape::Object(ObjectShape {
+            members: Vec::new(),
+            additional: false,
+        })
+    }
+
+    /// Add a required member to an object shape.
+    ///
+    /// # Panics
+    ///
+    /// If `self` is not an object shape.
+    pub fn field(self, key: &str, shape: Shape) -> Shape {
+        self.member(key, shape, true)
+    }
+
+    /// Add an optional member to an object shape.
+    ///
+    /// # Panics
+    ///
+    /// If `self` is not an object shape.
+    pub fn optional(self, key: &str, shape: Shape) -> Shape {
+        self.member(key, shape, false)
+    }
+
+    /// Allow objects to have members not in the shape, which then aren't
+    /// checked.
+    ///
+    /// # Panics
+    ///
+    /// If `self` is not an object shape.
+    pub fn additional(mut self) -> Shape {
+        match self {
+            Shape::Object(ref mut object) => object.additional = true,
+            _ => panic!("Shape::additional called on a shape that is not an object"),
+        }
+        self
+    }
+
+    /// Allow `null` in place of a value of this shape.
+    pub fn nullable(self) -> Shape {
+        match self {
+            Shape::Nullable(_) | Shape::Any | Shape::Null => self,
+            _ => Shape::Nullable(Box::new(self)),
+        }
+    }
+
+    /// Parse `source`, checking it against this shape.
+    pub fn parse(&self, source: &str) -> Result<JsonValue> {
+        let mut guide = ShapeGuide {
+            next: self,
+            stack: Vec::new(),
+            path: String::new(),
+        };
+
+        parser::parse_guided(source, &mut guide)
+    }
+
+    fn member(mut self, key: &str, shape: Shape, required: bool) -> Shape {
+        match self {
+            Shape::Object(ref mut object) => {
+                object.members.retain(|member| member.key != key);
+                object.members.push(Member { key: key.into(), shape, required });
+            },
+            _ => panic!("Shape::field called on a shape that is not an object"),
+        }
+        self
+    }
+
+    fn accepts(&self, ch: u8) -> bool {
+        match *self {
+            Shape::Any         => true,
+            Shape::Null        => ch == b'n',
+            Shape::Boolean     => ch == b't' || ch == b'f',
+            Shape::Number      => ch == b'-' || ch.is_ascii_digit(),
+            Shape::String      => ch == b'"',
+            Shape::Array(_)    => ch == b'[',
+            Shape::Object(_)   => ch == b'{',
+            Shape::Nullable(ref shape) => ch == b'n' || shape.accepts(ch),
+        }
+    }
+
+    fn name(&self) -> String {
+        match *self {
+            Shape::Any         => "any value".into(),
+            Shape::Null        => "null".into(),
+            Shape::Boolean     => "boolean".into(),
+            Shape::Number      => "number".into(),
+            Shape::String      => "string".into(),
+            Shape::Array(_)    => "array".into(),
+            Shape::Object(_)   => "object".into(),
+            Shape::Nullable(ref shape) => format!("{} or null", shape.name()),
+        }
+    }
+
+    // The shape of containers, past `Nullable`.
+    fn container(&self) -> &Shape {
+        match *self {
+            Shape::Nullable(ref shape) => shape,
+            _ => self,
+        }
+    }
+}
+
+struct ShapeGuide<'s> {
+    // Shape of the value about to be parsed
+    next: &'s Shape,
+    stack: Vec<Frame<'s>>,
+    path: String,
+}
+
+struct Frame<'s> {
+    shape: &'s Shape,
+    path_len: usize,
+    index: usize,
+}
+
+impl<'s> ShapeGuide<'s> {
+    fn mismatch(&self, expected: String, found: &str) -> Error {
+        Error::WrongType {
+            expected,
+            found: found.into(),
+            path: self.path.clone(),
+        }
+    }
+}
+
+// Type of a value from its first character
+fn found(ch: u8) -> &'static str {
+    match ch {
+        b'n'        => "null",
+        b't' | b'f' => "boolean",
+        b'"'        => "string",
+        b'['        => "array",
+        b'{'        => "object",
+        _           => "number",
+    }
+}
+
+impl<'s> Guide for ShapeGuide<'s> {
+    fn value(&mut self, ch: u8) -> Result<()> {
+        if self.next.accepts(ch) {
+            Ok(())
+        } else {
+            Err(self.mismatch(self.next.name(), found(ch)))
+        }
+    }
+
+    fn open(&mut self) {
+        self.stack.push(Frame {
+            shape: self.next.container(),
+            path_len: self.path.len(),
+            index: 0,
+        });
+    }
+
+    fn item(&mut self) {
+        let frame = self.stack.last_mut().expect("Must have a frame");
+
+        self.path.truncate(frame.path_len);
+        pointer::push_index(&mut self.path, frame.index);
+        frame.index += 1;
+
+        self.next = match *frame.shape {
+            Shape::Array(ref items) => items,
+            _ => &ANY,
+        };
+    }
+
+    fn key(&mut self, key: &str) -> Result<()> {
+        let frame = self.stack.last().expect("Must have a frame");
+        let (shape, path_len) = (frame.shape, frame.path_len);
+
+        self.path.truncate(path_len);
+
+        self.next = match *shape {
+            Shape::Object(ref object) => {
+                match object.members.iter().find(|member| member.key == key) {
+                    Some(member) => &member.shape,
+                    None if object.additional => &ANY,
+                    None => return Err(self.mismatch(format!("object without member {:?}", key), "object")),
+                }
+            },
+            _ => &ANY,
+        };
+
+        pointer::push_key(&mut self.path, key);
+        Ok(())
+    }
+
+    fn close(&mut self, value: &JsonValue, nested: bool) -> Result<()> {
+        let shape = if nested {
+            let frame = self.stack.pop().expect("Must have a frame");
+            self.path.truncate(frame.path_len);
+            frame.shape
+        } else {
+            self.next.container()
+        };
+
+        if let Shape::Object(ref object) = *shape {
+            for member in &object.members {
+                if member.required && !value.has_key(&member.key) {
+                    return Err(Error::MemberNotFound {
+                        key: member.key.clone(),
+                        path: self.path.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
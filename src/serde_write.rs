@@ -0,0 +1,518 @@
+// Serialization of any `serde::Serialize` type straight to a writer, through
+// the generators of the `codegen` module, so no `JsonValue` is built on the
+// way.
+
+use std::fmt::{ self, Display };
+use std::io::{ self, Write };
+
+use serde::ser::{ self, Serialize };
+
+use crate::codegen::{ Generator, GeneratorOptions, WriterGenerator, PrettyWriterGenerator };
+use crate::number::Number;
+
+/// Writes `value` as compact JSON to `writer`, without building a
+/// `JsonValue` first. Requires the `serde` feature.
+///
+/// Maps need keys that serialize as strings, numbers, booleans or chars.
+/// Other keys fail with an `InvalidData` error, as do errors from the
+/// `Serialize` implementation of `value`.
+///
+/// ```
+/// # extern crate serde;
+/// # use serde::ser::{ Serialize, Serializer, SerializeStruct };
+/// struct User { name: &'static str, age: u32 }
+///
+/// impl Serialize for User {
+///     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         let mut user = serializer.serialize_struct("User", 2)?;
+///         user.serialize_field("name", self.name)?;
+///         user.serialize_field("age", &self.age)?;
+///         user.end()
+///     }
+/// }
+///
+/// let mut out = Vec::new();
+/// json::to_writer_serde(&User { name: "Ferris", age: 7 }, &mut out).unwrap();
+///
+/// assert_eq!(out, br#"{"name":"Ferris","age":7}"#);
+/// ```
+pub fn to_writer_serde<T, W>(value: &T, writer: &mut W) -> io::Result<()>
+where
+    T: Serialize + ?Sized,
+    W: Write,
+{
+    to_writer_serde_with(value, writer, &GeneratorOptions::default())
+}
+
+/// Same as `to_writer_serde`, but using custom `GeneratorOptions`. All of
+/// them apply, except for `redact`, `on_value` and `numbers_per_line`, which
+/// work on a `JsonValue`.
+///
+/// ```
+/// # use json::codegen::GeneratorOptions;
+/// # use json::Case;
+/// use std::collections::BTreeMap;
+///
+/// let options = GeneratorOptions {
+///     escape_slashes: true,
+///     key_case: Some(Case::CamelCase),
+///     ..GeneratorOptions::default()
+/// };
+///
+/// let mut tags = BTreeMap::new();
+/// tags.insert("page_title", "</script>");
+///
+/// let mut out = Vec::new();
+/// json::to_writer_serde_with(&tags, &mut out, &options).unwrap();
+///
+/// assert_eq!(out, br#"{"pageTitle":"<\/script>"}"#);
+/// ```
+pub fn to_writer_serde_with<T, W>(value: &T, writer: &mut W, options: &GeneratorOptions) -> io::Result<()>
+where
+    T: Serialize + ?Sized,
+    W: Write,
+{
+    write_document(&mut WriterGenerator::with_options(writer, *options), value)
+}
+
+/// Same as `to_writer_serde_with`, but pretty printed with `spaces` of
+/// indentation, like `JsonValue::write_pretty_with`.
+pub fn to_writer_serde_pretty_with<T, W>(value: &T, writer: &mut W, spaces: u16, options: &GeneratorOptions) -> io::Result<()>
+where
+    T: Serialize + ?Sized,
+    W: Write,
+{
+    write_document(&mut PrettyWriterGenerator::with_options(writer, spaces, *options), value)
+}
+
+fn write_document<G, T>(gen: &mut G, value: &T) -> io::Result<()>
+where
+    G: Generator,
+    T: Serialize + ?Sized,
+{
+    value.serialize(&mut Serializer { gen }).map_err(|Error(err)| err)?;
+
+    if gen.options().trailing_newline {
+        gen.write_char(b'\n')?;
+    }
+
+    Ok(())
+}
+
+// Errors of the serializer, which end up returned as the `io::Error` inside.
+#[derive(Debug)]
+struct Error(io::Error);
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error(err)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Error {
+        Error(io::Error::new(io::ErrorKind::InvalidData, msg.to_string()))
+    }
+}
+
+struct Serializer<'g, G> {
+    gen: &'g mut G,
+}
+
+impl<'g, G: Generator> Serializer<'g, G> {
+    fn number<N: Into<Number>>(&mut self, num: N) -> Result<(), Error> {
+        Ok(self.gen.write_number(&num.into())?)
+    }
+
+    // Open an array or object, `close` is written by `Compound::end`.
+    fn open(&mut self, open: u8, close: u8) -> Result<Compound<'_, 'g, G>, Error> {
+        self.gen.write_char(open)?;
+
+        Ok(Compound { ser: self, close, first: true, variant: false })
+    }
+
+    // Open an object holding `variant` as its only key, for the enum
+    // variants that carry values.
+    fn open_variant(&mut self, variant: &str, open: u8, close: u8) -> Result<Compound<'_, 'g, G>, Error> {
+        self.gen.write_char(b'{')?;
+        self.gen.indent();
+        self.gen.new_line()?;
+        self.gen.write_key(variant)?;
+        self.gen.write_key_separator()?;
+
+        let mut compound = self.open(open, close)?;
+        compound.variant = true;
+        Ok(compound)
+    }
+}
+
+// Array or object being written, see `Serializer::open`.
+struct Compound<'a, 'g, G> {
+    ser: &'a mut Serializer<'g, G>,
+    close: u8,
+    first: bool,
+
+    // Whether it's wrapped in an object by `Serializer::open_variant`
+    variant: bool,
+}
+
+impl<'a, 'g, G: Generator> Compound<'a, 'g, G> {
+    // Write the separator before the next member.
+    fn member(&mut self) -> Result<(), Error> {
+        let gen = &mut *self.ser.gen;
+
+        if self.first {
+            self.first = false;
+            gen.indent();
+        } else {
+            gen.write_char(b',')?;
+        }
+
+        Ok(gen.new_line()?)
+    }
+
+    fn key(&mut self, key: &str) -> Result<(), Error> {
+        self.member()?;
+        self.ser.gen.write_key(key)?;
+        Ok(self.ser.gen.write_key_separator()?)
+    }
+
+    fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        let gen = &mut *self.ser.gen;
+
+        if self.first {
+            gen.write_char(self.close)?;
+        } else {
+            close(gen, self.close)?;
+        }
+
+        if self.variant {
+            close(gen, b'}')?;
+        }
+
+        Ok(())
+    }
+}
+
+// Close a non-empty array or object.
+fn close<G: Generator>(gen: &mut G, close: u8) -> Result<(), Error> {
+    if gen.options().trailing_commas {
+        gen.write_char(b',')?;
+    }
+    gen.dedent();
+    gen.new_line()?;
+    Ok(gen.write_char(close)?)
+}
+
+impl<'a, 'g, G: Generator> ser::Serializer for &'a mut Serializer<'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a, 'g, G>;
+    type SerializeTuple = Compound<'a, 'g, G>;
+    type SerializeTupleStruct = Compound<'a, 'g, G>;
+    type SerializeTupleVariant = Compound<'a, 'g, G>;
+    type SerializeMap = Compound<'a, 'g, G>;
+    type SerializeStruct = Compound<'a, 'g, G>;
+    type SerializeStructVariant = Compound<'a, 'g, G>;
+
+    fn serialize_bool(self, value: bool) -> Result<(), Error> {
+        Ok(self.gen.write(if value { b"true" } else { b"false" })?)
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<(), Error> { self.number(value) }
+    fn serialize_i16(self, value: i16) -> Result<(), Error> { self.number(value) }
+    fn serialize_i32(self, value: i32) -> Result<(), Error> { self.number(value) }
+    fn serialize_i64(self, value: i64) -> Result<(), Error> { self.number(value) }
+    fn serialize_i128(self, value: i128) -> Result<(), Error> { self.number(value) }
+    fn serialize_u8(self, value: u8) -> Result<(), Error> { self.number(value) }
+    fn serialize_u16(self, value: u16) -> Result<(), Error> { self.number(value) }
+    fn serialize_u32(self, value: u32) -> Result<(), Error> { self.number(value) }
+    fn serialize_u64(self, value: u64) -> Result<(), Error> { self.number(value) }
+    fn serialize_u128(self, value: u128) -> Result<(), Error> { self.number(value) }
+    fn serialize_f32(self, value: f32) -> Result<(), Error> { self.number(value) }
+    fn serialize_f64(self, value: f64) -> Result<(), Error> { self.number(value) }
+
+    fn serialize_char(self, value: char) -> Result<(), Error> {
+        self.serialize_str(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), Error> {
+        Ok(self.gen.write_string(value)?)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), Error> {
+        Ok(self.gen.write_binary(value)?)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(self.gen.write(b"null")?)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(self, _: &'static str, _: u32, variant: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut compound = self.open(b'{', b'}')?;
+        compound.key(variant)?;
+        compound.value(value)?;
+        compound.end()
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        self.open(b'[', b']')
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        self.open(b'[', b']')
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, Error> {
+        self.open(b'[', b']')
+    }
+
+    fn serialize_tuple_variant(self, _: &'static str, _: u32, variant: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, Error> {
+        self.open_variant(variant, b'[', b']')
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        self.open(b'{', b'}')
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+        self.open(b'{', b'}')
+    }
+
+    fn serialize_struct_variant(self, _: &'static str, _: u32, variant: &'static str, _: usize) -> Result<Self::SerializeStructVariant, Error> {
+        self.open_variant(variant, b'{', b'}')
+    }
+}
+
+impl<'a, 'g, G: Generator> ser::SerializeSeq for Compound<'a, 'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.member()?;
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Compound::end(self)
+    }
+}
+
+impl<'a, 'g, G: Generator> ser::SerializeTuple for Compound<'a, 'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Compound::end(self)
+    }
+}
+
+impl<'a, 'g, G: Generator> ser::SerializeTupleStruct for Compound<'a, 'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Compound::end(self)
+    }
+}
+
+impl<'a, 'g, G: Generator> ser::SerializeTupleVariant for Compound<'a, 'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Compound::end(self)
+    }
+}
+
+impl<'a, 'g, G: Generator> ser::SerializeMap for Compound<'a, 'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = key.serialize(KeySerializer)?;
+        self.key(&key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Compound::end(self)
+    }
+}
+
+impl<'a, 'g, G: Generator> ser::SerializeStruct for Compound<'a, 'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.key(key)?;
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Compound::end(self)
+    }
+}
+
+impl<'a, 'g, G: Generator> ser::SerializeStructVariant for Compound<'a, 'g, G> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Compound::end(self)
+    }
+}
+
+// Turns map keys into the strings that JSON needs them to be.
+struct KeySerializer;
+
+fn key_error() -> Error {
+    ser::Error::custom("Map keys must be strings, numbers, booleans or chars")
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<String, Error>;
+    type SerializeTuple = ser::Impossible<String, Error>;
+    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = ser::Impossible<String, Error>;
+    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_i8(self, value: i8) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_i16(self, value: i16) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_i32(self, value: i32) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_i64(self, value: i64) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_i128(self, value: i128) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_u8(self, value: u8) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_u16(self, value: u16) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_u32(self, value: u32) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_u64(self, value: u64) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_u128(self, value: u128) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_f32(self, value: f32) -> Result<String, Error> { Ok(Number::from(value).to_string()) }
+    fn serialize_f64(self, value: f64) -> Result<String, Error> { Ok(Number::from(value).to_string()) }
+    fn serialize_char(self, value: char) -> Result<String, Error> { Ok(value.to_string()) }
+    fn serialize_str(self, value: &str) -> Result<String, Error> { Ok(value.into()) }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<String, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<String, Error> {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<String, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(key_error())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+        Err(key_error())
+    }
+
+    fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_error())
+    }
+}
@@ -0,0 +1,306 @@
+//! Read-only JSON values sharing identical subtrees, built with
+//! `dedup_subtrees`.
+//!
+//! A `JsonValue` owns all of its children, so a document repeating the same
+//! parts over and over, like the schemas of an API spec, holds a copy of each
+//! of them. A `SharedValue` keeps its strings, arrays and objects behind
+//! `Rc`s instead, and `dedup_subtrees` allocates every distinct one of them
+//! only once. Cloning a `SharedValue` is cheap, it only bumps a count.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let schema = object!{ "type" => "object", "required" => array!["id"] };
+//! let spec = object!{ "user" => schema.clone(), "order" => schema };
+//!
+//! let shared = json::dedup_subtrees(&spec);
+//!
+//! assert!(shared["user"].ptr_eq(&shared["order"]));
+//! assert_eq!(shared.to_json(), spec);
+//! # }
+//! ```
+//!
+//! For values that are only needed for a short while, `JsonArena::alloc_dedup`
+//! of the `arena` feature does the same with borrowed slices.
+
+use std::collections::{ HashMap, HashSet };
+use std::convert::TryInto;
+use std::ops::Index;
+use std::rc::Rc;
+use std::slice;
+
+use crate::JsonValue;
+use crate::number::Number;
+use crate::object::Object;
+
+/// JSON value sharing its strings, arrays and objects with other values.
+///
+/// Objects are slices of key value pairs in the order of the original
+/// object.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SharedValue {
+    Null,
+    String(Rc<str>),
+    Number(Number),
+    Boolean(bool),
+    Binary(Rc<[u8]>),
+    Object(Rc<[(Rc<str>, SharedValue)]>),
+    Array(Rc<[SharedValue]>),
+}
+
+/// Copy `value` into a `SharedValue`, allocating every distinct string,
+/// array and object only once. Identical subtrees of the result are the same
+/// `Rc`, which can be checked with `SharedValue::ptr_eq`.
+pub fn dedup_subtrees(value: &JsonValue) -> SharedValue {
+    Dedup::default().value(value)
+}
+
+impl SharedValue {
+    /// Name of the type of this value, same as `JsonValue::type_name`.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            SharedValue::Null       => "null",
+            SharedValue::String(_)  => "string",
+            SharedValue::Number(_)  => "number",
+            SharedValue::Boolean(_) => "boolean",
+            SharedValue::Binary(_)  => "binary",
+            SharedValue::Object(_)  => "object",
+            SharedValue::Array(_)   => "array",
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SharedValue::String(value) => Some(value),
+            _                          => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<Number> {
+        match *self {
+            SharedValue::Number(value) => Some(value),
+            _                          => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(|value| value.into())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            SharedValue::Boolean(value) => Some(value),
+            _                           => None,
+        }
+    }
+
+    /// Get the member of an object under `key`.
+    pub fn get(&self, key: &str) -> Option<&SharedValue> {
+        match self {
+            SharedValue::Object(entries) => {
+                entries.iter().find(|entry| &*entry.0 == key).map(|entry| &entry.1)
+            },
+            _ => None,
+        }
+    }
+
+    /// Length of an array or object, `0` for other types.
+    pub fn len(&self) -> usize {
+        match self {
+            SharedValue::Array(members)  => members.len(),
+            SharedValue::Object(entries) => entries.len(),
+            _                            => 0,
+        }
+    }
+
+    /// Same as `JsonValue::is_empty`, true for `null`, `""`, `0`, `false`
+    /// and empty arrays and objects.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            SharedValue::Null            => true,
+            SharedValue::String(value)   => value.is_empty(),
+            SharedValue::Number(value)   => value.is_empty(),
+            SharedValue::Boolean(value)  => !value,
+            SharedValue::Binary(bytes)   => bytes.is_empty(),
+            SharedValue::Array(members)  => members.is_empty(),
+            SharedValue::Object(entries) => entries.is_empty(),
+        }
+    }
+
+    /// Members of an array, empty for other types.
+    pub fn members(&self) -> slice::Iter<'_, SharedValue> {
+        match self {
+            SharedValue::Array(members) => members.iter(),
+            _                           => [].iter(),
+        }
+    }
+
+    /// Key value pairs of an object, empty for other types.
+    pub fn entries(&self) -> slice::Iter<'_, (Rc<str>, SharedValue)> {
+        match self {
+            SharedValue::Object(entries) => entries.iter(),
+            _                            => [].iter(),
+        }
+    }
+
+    /// True if both values are the same string, binary, array or object in
+    /// memory, not only equal to each other.
+    pub fn ptr_eq(&self, other: &SharedValue) -> bool {
+        match (self, other) {
+            (SharedValue::String(a), SharedValue::String(b)) => Rc::ptr_eq(a, b),
+            (SharedValue::Binary(a), SharedValue::Binary(b)) => Rc::ptr_eq(a, b),
+            (SharedValue::Object(a), SharedValue::Object(b)) => Rc::ptr_eq(a, b),
+            (SharedValue::Array(a), SharedValue::Array(b))   => Rc::ptr_eq(a, b),
+            _                                                  => false,
+        }
+    }
+
+    /// Copy this value into a regular `JsonValue`, with a copy of every
+    /// shared subtree.
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            SharedValue::Null           => JsonValue::Null,
+            SharedValue::String(value)  => (&**value).into(),
+            SharedValue::Number(value)  => JsonValue::Number(*value),
+            SharedValue::Boolean(value) => JsonValue::Boolean(*value),
+            SharedValue::Binary(bytes)  => JsonValue::Binary(bytes.to_vec()),
+            SharedValue::Array(members) => {
+                JsonValue::Array(members.iter().map(SharedValue::to_json).collect())
+            },
+            SharedValue::Object(entries) => {
+                let mut object = Object::with_capacity(entries.len());
+                for (key, value) in entries.iter() {
+                    object.insert(key, value.to_json());
+                }
+                JsonValue::Object(object)
+            },
+        }
+    }
+}
+
+impl From<SharedValue> for JsonValue {
+    fn from(value: SharedValue) -> JsonValue {
+        value.to_json()
+    }
+}
+
+/// Implements indexing by `usize` into arrays, returning `Null` for
+/// other types and out of bounds indexes.
+impl Index<usize> for SharedValue {
+    type Output = SharedValue;
+
+    fn index(&self, index: usize) -> &SharedValue {
+        match self {
+            SharedValue::Array(members) => members.get(index).unwrap_or(&SharedValue::Null),
+            _                           => &SharedValue::Null,
+        }
+    }
+}
+
+/// Implements indexing by `&str` into objects, returning `Null` for
+/// other types and missing keys.
+impl Index<&str> for SharedValue {
+    type Output = SharedValue;
+
+    fn index(&self, key: &str) -> &SharedValue {
+        self.get(key).unwrap_or(&SharedValue::Null)
+    }
+}
+
+// Builds shared values for `dedup_subtrees`. Subtrees are built bottom up,
+// so by the time an array or object is looked up all of its members are
+// deduplicated already, and can be compared by address.
+#[derive(Default)]
+struct Dedup {
+    strings: HashSet<Rc<str>>,
+    shared: HashMap<Shape, SharedValue>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Shape {
+    Array(Vec<Member>),
+    Object(Vec<(usize, Member)>),
+}
+
+// Value compared by identity rather than by contents, with `Rc`s as their
+// address. Everything they point to is kept alive by `Dedup`, so addresses
+// aren't reused while it lives.
+#[derive(PartialEq, Eq, Hash)]
+enum Member {
+    Null,
+    String(usize),
+    Number(bool, bool, u64, i16, Option<u128>),
+    Boolean(bool),
+    Binary(usize),
+    Object(usize),
+    Array(usize),
+}
+
+impl Member {
+    fn new(value: &SharedValue) -> Self {
+        match value {
+            SharedValue::Null            => Member::Null,
+            SharedValue::String(value)   => Member::String(Rc::as_ptr(value) as *const u8 as usize),
+            SharedValue::Number(value)   => {
+                let (positive, mantissa, exponent) = value.as_parts();
+                Member::Number(value.is_nan(), positive, mantissa, exponent, value.wide())
+            },
+            SharedValue::Boolean(value)  => Member::Boolean(*value),
+            SharedValue::Binary(bytes)   => Member::Binary(Rc::as_ptr(bytes) as *const u8 as usize),
+            SharedValue::Object(entries) => Member::Object(Rc::as_ptr(entries) as *const u8 as usize),
+            SharedValue::Array(members)  => Member::Array(Rc::as_ptr(members) as *const u8 as usize),
+        }
+    }
+}
+
+impl Dedup {
+    fn value(&mut self, value: &JsonValue) -> SharedValue {
+        match value {
+            JsonValue::Null           => SharedValue::Null,
+            JsonValue::Short(value)   => SharedValue::String(self.string(value)),
+            JsonValue::String(value)  => SharedValue::String(self.string(value)),
+            JsonValue::Number(value)  => SharedValue::Number(*value),
+            JsonValue::Boolean(value) => SharedValue::Boolean(*value),
+            JsonValue::Binary(bytes)  => SharedValue::Binary(bytes.as_slice().into()),
+            JsonValue::Array(vec)     => {
+                let members: Vec<_> = vec.iter().map(|member| self.value(member)).collect();
+                let shape = Shape::Array(members.iter().map(Member::new).collect());
+
+                self.share(shape, || SharedValue::Array(members.into()))
+            },
+            JsonValue::Object(object) => {
+                let entries: Vec<_> = object.iter().map(|(key, value)| (self.string(key), self.value(value))).collect();
+                let shape = Shape::Object(entries.iter().map(|(key, value)| {
+                    (Rc::as_ptr(key) as *const u8 as usize, Member::new(value))
+                }).collect());
+
+                self.share(shape, || SharedValue::Object(entries.into()))
+            },
+        }
+    }
+
+    fn string(&mut self, value: &str) -> Rc<str> {
+        if let Some(shared) = self.strings.get(value) {
+            return shared.clone();
+        }
+
+        let shared: Rc<str> = value.into();
+        self.strings.insert(shared.clone());
+        shared
+    }
+
+    fn share<F>(&mut self, shape: Shape, build: F) -> SharedValue
+    where
+        F: FnOnce() -> SharedValue,
+    {
+        self.shared.entry(shape).or_insert_with(build).clone()
+    }
+}
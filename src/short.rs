@@ -1,4 +1,6 @@
-use std::{ ptr, str, slice, fmt };
+use std::{ str, fmt };
+#[cfg(not(feature = "forbid-unsafe"))]
+use std::{ ptr, slice };
 use std::ops::Deref;
 
 pub const MAX_LEN: usize = 30;
@@ -20,6 +22,7 @@ impl Short {
     /// Typically you should avoid creating your own `Short`s, instead create a
     /// `JsonValue` (either using `"foo".into()` or `JsonValue::from("foo")`) out
     /// of a slice. This will automatically decide on `String` or `Short` for you.
+    #[cfg(not(feature = "forbid-unsafe"))]
     #[inline(always)]
     pub unsafe fn from_slice(slice: &str) -> Self {
         let mut short = Short {
@@ -32,7 +35,39 @@ impl Short {
         short
     }
 
+    /// Creates a `Short` from a `&str` slice. With the `forbid-unsafe` feature
+    /// this is a safe function, panicking if the length of the slice is larger
+    /// than `MAX_LEN`.
+    #[cfg(feature = "forbid-unsafe")]
+    #[inline(always)]
+    pub fn from_slice(slice: &str) -> Self {
+        Short::new(slice)
+    }
+
+    /// Creates a `Short` from a `&str` slice, panicking if the length of the
+    /// slice is larger than `MAX_LEN`. Unlike `from_slice` this can be used in
+    /// `const` contexts, where a slice that is too long is a compile error.
+    pub const fn new(slice: &str) -> Self {
+        let bytes = slice.as_bytes();
+
+        assert!(bytes.len() <= MAX_LEN, "Slice is too long for a Short");
+
+        let mut value = [0; MAX_LEN];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            value[i] = bytes[i];
+            i += 1;
+        }
+
+        Short {
+            value,
+            len: bytes.len() as u8,
+        }
+    }
+
     /// Cheaply obtain a `&str` slice out of the `Short`.
+    #[cfg(not(feature = "forbid-unsafe"))]
     #[inline]
     pub fn as_str(&self) -> &str {
         unsafe {
@@ -41,6 +76,14 @@ impl Short {
             )
         }
     }
+
+    /// Obtain a `&str` slice out of the `Short`. With the `forbid-unsafe`
+    /// feature the bytes are checked to be UTF-8 first.
+    #[cfg(feature = "forbid-unsafe")]
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.value[.. self.len as usize]).expect("Short is always UTF-8")
+    }
 }
 
 impl PartialEq for Short {
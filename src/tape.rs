@@ -0,0 +1,329 @@
+//! Two-stage parsing through a flat tape.
+//!
+//! `Tape::parse` is the first stage: it checks the source and records every
+//! value as one entry in a flat list, in source order. Arrays and objects
+//! know where their members end, so whole subtrees can be skipped over, and
+//! all strings share a single buffer. Reading values off the tape through
+//! `TapeValue` is the second, optional stage. Only the values that are
+//! looked at get converted, and `to_json` builds a `JsonValue` out of any
+//! part of the tape.
+//!
+//! ```
+//! # use json::tape::Tape;
+//! let tape = Tape::parse(r#"{"users":[{"name":"Jon","age":30},{"name":"Arya"}]}"#).unwrap();
+//!
+//! let users = tape.root().get("users").unwrap();
+//! let names: Vec<&str> = users.members().filter_map(|user| user.get("name")?.as_str()).collect();
+//!
+//! assert_eq!(names, ["Jon", "Arya"]);
+//! assert_eq!(users.at(0).unwrap().to_json().dump(), r#"{"name":"Jon","age":30}"#);
+//! ```
+
+use std::convert::TryInto;
+use std::fmt;
+
+use crate::{ JsonValue, Result };
+use crate::number::Number;
+use crate::object::Object;
+use crate::parser;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Entry {
+    Null,
+    Boolean(bool),
+    Number(Number),
+
+    // Range of the string in `Tape::strings`
+    String(usize, usize),
+
+    // Number of members, and index of the first entry past the last member.
+    // Members of objects take two entries each, a string for the key followed
+    // by the value.
+    Array(usize, usize),
+    Object(usize, usize),
+}
+
+/// Flat list of all values in a document, see the module docs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tape {
+    pub(crate) entries: Vec<Entry>,
+    pub(crate) strings: String,
+}
+
+impl Tape {
+    /// Parse `source` into a tape.
+    pub fn parse(source: &str) -> Result<Tape> {
+        let mut tape = Tape {
+            entries: Vec::with_capacity(source.len() / 8),
+            strings: String::new(),
+        };
+
+        parser::parse_tape(source, &mut tape)?;
+
+        Ok(tape)
+    }
+
+    /// The top-level value of the document.
+    pub fn root(&self) -> TapeValue<'_> {
+        TapeValue {
+            tape: self,
+            index: 0,
+        }
+    }
+
+    /// Number of entries on the tape, one for every value and object key.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no entries, never the case for a parsed tape.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Build a `JsonValue` of the whole document.
+    pub fn to_json(&self) -> JsonValue {
+        self.root().to_json()
+    }
+
+    pub(crate) fn push_str(&mut self, value: &str) {
+        let start = self.strings.len();
+        self.strings.push_str(value);
+        self.entries.push(Entry::String(start, self.strings.len()));
+    }
+
+    pub(crate) fn str_at(&self, index: usize) -> &str {
+        match self.entries[index] {
+            Entry::String(start, end) => &self.strings[start .. end],
+            _                         => "",
+        }
+    }
+}
+
+/// Value on a `Tape`. Getters convert the value as they are called, there is
+/// no work done upfront.
+#[derive(Clone, Copy)]
+pub struct TapeValue<'tape> {
+    tape: &'tape Tape,
+    index: usize,
+}
+
+impl<'tape> TapeValue<'tape> {
+    fn entry(&self) -> Entry {
+        self.tape.entries[self.index]
+    }
+
+    fn at_index(&self, index: usize) -> TapeValue<'tape> {
+        TapeValue {
+            tape: self.tape,
+            index,
+        }
+    }
+
+    // Index of the first entry past this value.
+    fn skip(&self) -> usize {
+        match self.entry() {
+            Entry::Array(_, end) | Entry::Object(_, end) => end,
+            _                                            => self.index + 1,
+        }
+    }
+
+    /// Name of the type of this value, same as `JsonValue::type_name`.
+    pub fn type_name(&self) -> &'static str {
+        match self.entry() {
+            Entry::Null         => "null",
+            Entry::Boolean(_)   => "boolean",
+            Entry::Number(_)    => "number",
+            Entry::String(..)   => "string",
+            Entry::Array(..)    => "array",
+            Entry::Object(..)   => "object",
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self.entry(), Entry::Null)
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self.entry(), Entry::String(..))
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self.entry(), Entry::Number(_))
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        matches!(self.entry(), Entry::Boolean(_))
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self.entry(), Entry::Object(..))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self.entry(), Entry::Array(..))
+    }
+
+    pub fn as_str(&self) -> Option<&'tape str> {
+        match self.entry() {
+            Entry::String(start, end) => Some(&self.tape.strings[start .. end]),
+            _                         => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<Number> {
+        match self.entry() {
+            Entry::Number(value) => Some(value),
+            _                    => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(|value| value.into())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.entry() {
+            Entry::Boolean(value) => Some(value),
+            _                     => None,
+        }
+    }
+
+    /// Length of an array or object, `0` for other types.
+    pub fn len(&self) -> usize {
+        match self.entry() {
+            Entry::Array(len, _) | Entry::Object(len, _) => len,
+            _                                            => 0,
+        }
+    }
+
+    /// Same as `JsonValue::is_empty`, true for `null`, `""`, `0`, `false`
+    /// and empty arrays and objects.
+    pub fn is_empty(&self) -> bool {
+        match self.entry() {
+            Entry::Null                                  => true,
+            Entry::Boolean(value)                        => !value,
+            Entry::Number(value)                         => value.is_empty(),
+            Entry::String(start, end)                    => start == end,
+            Entry::Array(len, _) | Entry::Object(len, _) => len == 0,
+        }
+    }
+
+    /// Get the member of an object under `key`, the last one if the key is
+    /// duplicated. Values of the other members are skipped over without
+    /// being read.
+    pub fn get(&self, key: &str) -> Option<TapeValue<'tape>> {
+        self.entries().filter(|&(name, _)| name == key).last().map(|(_, value)| value)
+    }
+
+    /// Get the member of an array at `index`. Members before it are skipped
+    /// over without being read.
+    pub fn at(&self, index: usize) -> Option<TapeValue<'tape>> {
+        self.members().nth(index)
+    }
+
+    /// Members of an array, empty for other types.
+    pub fn members(&self) -> Members<'tape> {
+        let (index, end) = match self.entry() {
+            Entry::Array(_, end) => (self.index + 1, end),
+            _                    => (0, 0),
+        };
+
+        Members {
+            value: self.at_index(index),
+            end,
+        }
+    }
+
+    /// Key value pairs of an object, empty for other types.
+    pub fn entries(&self) -> Entries<'tape> {
+        let (index, end) = match self.entry() {
+            Entry::Object(_, end) => (self.index + 1, end),
+            _                     => (0, 0),
+        };
+
+        Entries {
+            value: self.at_index(index),
+            end,
+        }
+    }
+
+    /// Build a `JsonValue` out of this value.
+    pub fn to_json(&self) -> JsonValue {
+        match self.entry() {
+            Entry::Null           => JsonValue::Null,
+            Entry::Boolean(value) => JsonValue::Boolean(value),
+            Entry::Number(value)  => JsonValue::Number(value),
+            Entry::String(..)     => self.as_str().unwrap_or("").into(),
+            Entry::Array(len, _)  => {
+                let mut array = Vec::with_capacity(len);
+                array.extend(self.members().map(|member| member.to_json()));
+                JsonValue::Array(array)
+            },
+            Entry::Object(len, _) => {
+                let mut object = Object::with_capacity(len);
+                for (key, value) in self.entries() {
+                    object.insert(key, value.to_json());
+                }
+                JsonValue::Object(object)
+            },
+        }
+    }
+}
+
+impl<'tape> fmt::Debug for TapeValue<'tape> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_json(), f)
+    }
+}
+
+/// Iterator over members of an array on a tape.
+pub struct Members<'tape> {
+    value: TapeValue<'tape>,
+    end: usize,
+}
+
+impl<'tape> Iterator for Members<'tape> {
+    type Item = TapeValue<'tape>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.value.index >= self.end {
+            return None;
+        }
+
+        let member = self.value;
+        self.value.index = member.skip();
+
+        Some(member)
+    }
+}
+
+/// Iterator over key value pairs of an object on a tape.
+pub struct Entries<'tape> {
+    value: TapeValue<'tape>,
+    end: usize,
+}
+
+impl<'tape> Iterator for Entries<'tape> {
+    type Item = (&'tape str, TapeValue<'tape>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.value.index >= self.end {
+            return None;
+        }
+
+        let key = self.value.tape.str_at(self.value.index);
+        let value = self.value.at_index(self.value.index + 1);
+        self.value.index = value.skip();
+
+        Some((key, value))
+    }
+}
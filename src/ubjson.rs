@@ -0,0 +1,354 @@
+//! Conversion between `JsonValue` and [UBJSON](https://ubjson.org/), a binary
+//! encoding with the same data model as JSON. Requires the `ubjson` feature.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let data = object!{ "id" => 7, "tags" => array!["a", "b"] };
+//!
+//! let bytes = json::ubjson::to_vec(&data);
+//!
+//! assert_eq!(bytes, b"{U\x02idU\x07U\x04tags[SU\x01aSU\x01b]}");
+//! assert_eq!(json::ubjson::from_slice(&bytes).unwrap(), data);
+//! # }
+//! ```
+//!
+//! Integers are written using the smallest integer type they fit in, falling
+//! back to the high-precision type for those that don't fit in `int64`. Other
+//! numbers are written as `float64`. The reader also accepts the optimized
+//! container format, with `$` type and `#` count markers.
+//!
+//! `JsonValue::Binary` is written as an optimized array of `uint8`, the way
+//! UBJSON stores binary data, and such arrays are read back as binary.
+
+use std::io::{ self, Read, Write };
+
+use crate::JsonValue;
+use crate::number::Number;
+use crate::object::Object;
+use crate::parser::DEPTH_LIMIT;
+
+/// Serialize `value` as UBJSON into `writer`.
+pub fn write<W: Write>(value: &JsonValue, writer: &mut W) -> io::Result<()> {
+    match *value {
+        JsonValue::Null               => writer.write_all(b"Z"),
+        JsonValue::Boolean(true)      => writer.write_all(b"T"),
+        JsonValue::Boolean(false)     => writer.write_all(b"F"),
+        JsonValue::Short(ref short)   => write_string(writer, short),
+        JsonValue::String(ref string) => write_string(writer, string),
+        JsonValue::Number(ref number) => write_number(writer, number),
+        JsonValue::Array(ref array)   => {
+            writer.write_all(b"[")?;
+            for item in array {
+                write(item, writer)?;
+            }
+            writer.write_all(b"]")
+        },
+        JsonValue::Binary(ref bytes)  => {
+            writer.write_all(b"[$U#")?;
+            write_integer(writer, bytes.len() as i64)?;
+            writer.write_all(bytes)
+        },
+        JsonValue::Object(ref object) => {
+            writer.write_all(b"{")?;
+            for (key, value) in object.iter() {
+                write_integer(writer, key.len() as i64)?;
+                writer.write_all(key.as_bytes())?;
+                write(value, writer)?;
+            }
+            writer.write_all(b"}")
+        },
+    }
+}
+
+/// Serialize `value` as UBJSON into a `Vec<u8>`.
+pub fn to_vec(value: &JsonValue) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write(value, &mut bytes).expect("Can't fail");
+    bytes
+}
+
+/// Read a single UBJSON value from `reader`. Wrap unbuffered readers, such
+/// as files, in a `BufReader`, as the input is read in small pieces.
+///
+/// Malformed input results in an `io::Error` of `InvalidData` kind.
+pub fn read<R: Read>(reader: &mut R) -> io::Result<JsonValue> {
+    let mut reader = Reader { reader, depth: 0 };
+
+    let marker = reader.marker()?;
+    reader.value(marker)
+}
+
+/// Read a UBJSON value from `bytes`, which must contain nothing else.
+pub fn from_slice(mut bytes: &[u8]) -> io::Result<JsonValue> {
+    let value = read(&mut bytes)?;
+
+    if !bytes.is_empty() {
+        return Err(invalid("Unexpected bytes after the UBJSON value"));
+    }
+
+    Ok(value)
+}
+
+fn write_string<W: Write>(writer: &mut W, string: &str) -> io::Result<()> {
+    writer.write_all(b"S")?;
+    write_integer(writer, string.len() as i64)?;
+    writer.write_all(string.as_bytes())
+}
+
+fn write_integer<W: Write>(writer: &mut W, num: i64) -> io::Result<()> {
+    if num >= 0 && num <= u8::MAX as i64 {
+        writer.write_all(&[b'U', num as u8])
+    } else if num >= i8::MIN as i64 && num < 0 {
+        writer.write_all(&[b'i', num as i8 as u8])
+    } else if num >= i16::MIN as i64 && num <= i16::MAX as i64 {
+        writer.write_all(b"I")?;
+        writer.write_all(&(num as i16).to_be_bytes())
+    } else if num >= i32::MIN as i64 && num <= i32::MAX as i64 {
+        writer.write_all(b"l")?;
+        writer.write_all(&(num as i32).to_be_bytes())
+    } else {
+        writer.write_all(b"L")?;
+        writer.write_all(&num.to_be_bytes())
+    }
+}
+
+fn write_number<W: Write>(writer: &mut W, number: &Number) -> io::Result<()> {
+    let (positive, mantissa, exponent) = number.as_parts();
+
+    // Integers wider than 64 bits only come out of `as_parts` rounded
+    let wide = number.wide().is_some();
+
+    if (exponent == 0 || wide) && !number.is_nan() {
+        if !wide && positive && mantissa <= i64::MAX as u64 {
+            return write_integer(writer, mantissa as i64);
+        }
+        if !wide && !positive && mantissa <= i64::MAX as u64 + 1 {
+            return write_integer(writer, (mantissa as i64).wrapping_neg());
+        }
+
+        return write_high_precision(writer, &number.to_string());
+    }
+
+    if number.is_nan() {
+        writer.write_all(b"D")?;
+        return writer.write_all(&f64::NAN.to_be_bytes());
+    }
+
+    // Parsing the digits rounds correctly, unlike `f64::from`. Numbers that
+    // a double can't hold exactly, such as the ones with more digits than it
+    // keeps, are written as their digits instead.
+    let digits = number.to_string();
+    let double: f64 = digits.parse().expect("Valid float");
+
+    if Number::from(double) != *number {
+        return write_high_precision(writer, &digits);
+    }
+
+    writer.write_all(b"D")?;
+    writer.write_all(&double.to_be_bytes())
+}
+
+fn write_high_precision<W: Write>(writer: &mut W, digits: &str) -> io::Result<()> {
+    writer.write_all(b"H")?;
+    write_integer(writer, digits.len() as i64)?;
+    writer.write_all(digits.as_bytes())
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+struct Reader<'a, R: Read> {
+    reader: &'a mut R,
+    depth: usize,
+}
+
+impl<'a, R: Read> Reader<'a, R> {
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> io::Result<u8> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    // Reads the next marker, skipping no-ops.
+    fn marker(&mut self) -> io::Result<u8> {
+        loop {
+            let marker = self.byte()?;
+            if marker != b'N' {
+                return Ok(marker);
+            }
+        }
+    }
+
+    fn integer(&mut self, marker: u8) -> io::Result<i64> {
+        Ok(match marker {
+            b'i' => self.byte()? as i8 as i64,
+            b'U' => self.byte()? as i64,
+            b'I' => i16::from_be_bytes(self.bytes()?) as i64,
+            b'l' => i32::from_be_bytes(self.bytes()?) as i64,
+            b'L' => i64::from_be_bytes(self.bytes()?),
+            _    => return Err(invalid("Expected an integer marker")),
+        })
+    }
+
+    fn length(&mut self, marker: u8) -> io::Result<usize> {
+        let length = self.integer(marker)?;
+
+        if length < 0 {
+            return Err(invalid("Negative length"));
+        }
+
+        Ok(length as usize)
+    }
+
+    fn string(&mut self, marker: u8) -> io::Result<String> {
+        let length = self.length(marker)?;
+
+        String::from_utf8(self.payload(length)?).map_err(|_| invalid("String is not valid UTF-8"))
+    }
+
+    fn payload(&mut self, length: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        // Not allocating `length` upfront, so that a corrupted length can't
+        // make us allocate more memory than the input has.
+        self.reader.by_ref().take(length as u64).read_to_end(&mut bytes)?;
+
+        if bytes.len() != length {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(bytes)
+    }
+
+    fn value(&mut self, marker: u8) -> io::Result<JsonValue> {
+        Ok(match marker {
+            b'Z' => JsonValue::Null,
+            b'T' => JsonValue::Boolean(true),
+            b'F' => JsonValue::Boolean(false),
+            b'i' | b'U' | b'I' | b'l' | b'L' => self.integer(marker)?.into(),
+            b'd' => f32::from_be_bytes(self.bytes()?).into(),
+            b'D' => f64::from_be_bytes(self.bytes()?).into(),
+            b'H' => {
+                let marker = self.byte()?;
+                let digits = self.string(marker)?;
+
+                match crate::parse(&digits) {
+                    Ok(number @ JsonValue::Number(_)) => number,
+                    _ => return Err(invalid("Invalid high-precision number")),
+                }
+            },
+            b'C' => {
+                let ch = self.byte()?;
+                if !ch.is_ascii() {
+                    return Err(invalid("Char is not ASCII"));
+                }
+                (ch as char).to_string().into()
+            },
+            b'S' => {
+                let marker = self.byte()?;
+                self.string(marker)?.into()
+            },
+            b'[' => self.nested(Reader::array)?,
+            b'{' => self.nested(Reader::object)?,
+            _    => return Err(invalid("Unknown marker")),
+        })
+    }
+
+    fn nested(&mut self, read: fn(&mut Self) -> io::Result<JsonValue>) -> io::Result<JsonValue> {
+        if self.depth == DEPTH_LIMIT {
+            return Err(invalid("Exceeded depth limit"));
+        }
+
+        self.depth += 1;
+        let value = read(self)?;
+        self.depth -= 1;
+
+        Ok(value)
+    }
+
+    // Reads the optional `$` type and `#` count of a container. Returns the
+    // first marker of the content instead if neither is present.
+    fn header(&mut self) -> io::Result<Header> {
+        match self.marker()? {
+            b'$' => {
+                let kind = self.byte()?;
+                if self.byte()? != b'#' {
+                    return Err(invalid("Expected a count after the container type"));
+                }
+                let marker = self.byte()?;
+                Ok(Header::Counted(Some(kind), self.length(marker)?))
+            },
+            b'#' => {
+                let marker = self.byte()?;
+                Ok(Header::Counted(None, self.length(marker)?))
+            },
+            marker => Ok(Header::Open(marker)),
+        }
+    }
+
+    fn array(&mut self) -> io::Result<JsonValue> {
+        let mut array = Vec::new();
+
+        match self.header()? {
+            Header::Counted(Some(b'U'), count) => return Ok(JsonValue::Binary(self.payload(count)?)),
+            Header::Counted(kind, count) => {
+                array.reserve(count.min(1024));
+
+                for _ in 0..count {
+                    let marker = match kind {
+                        Some(kind) => kind,
+                        None       => self.marker()?,
+                    };
+                    array.push(self.value(marker)?);
+                }
+            },
+            Header::Open(mut marker) => {
+                while marker != b']' {
+                    array.push(self.value(marker)?);
+                    marker = self.marker()?;
+                }
+            },
+        }
+
+        Ok(JsonValue::Array(array))
+    }
+
+    fn object(&mut self) -> io::Result<JsonValue> {
+        let mut object = Object::new();
+
+        match self.header()? {
+            Header::Counted(kind, count) => {
+                for _ in 0..count {
+                    let marker = self.marker()?;
+                    let key = self.string(marker)?;
+                    let marker = match kind {
+                        Some(kind) => kind,
+                        None       => self.marker()?,
+                    };
+                    object.insert(&key, self.value(marker)?);
+                }
+            },
+            Header::Open(mut marker) => {
+                while marker != b'}' {
+                    let key = self.string(marker)?;
+                    let marker_value = self.marker()?;
+                    object.insert(&key, self.value(marker_value)?);
+                    marker = self.marker()?;
+                }
+            },
+        }
+
+        Ok(JsonValue::Object(object))
+    }
+}
+
+enum Header {
+    Counted(Option<u8>, usize),
+    Open(u8),
+}
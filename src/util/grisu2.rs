@@ -18,7 +18,7 @@
 use crate::util::diyfp::{ self, DiyFp };
 
 #[inline]
-unsafe fn grisu_round(buffer: &mut u64, delta: u64, mut rest: u64, ten_kappa: u64, wp_w: u64) {
+fn grisu_round(buffer: &mut u64, delta: u64, mut rest: u64, ten_kappa: u64, wp_w: u64) {
     while rest < wp_w && delta - rest >= ten_kappa &&
            (rest + ten_kappa < wp_w || // closer
             wp_w - rest > rest + ten_kappa - wp_w) {
@@ -42,7 +42,7 @@ fn count_decimal_digit32(n: u32) -> i16 {
 }
 
 #[inline]
-unsafe fn digit_gen(w: DiyFp, mp: DiyFp, mut delta: u64, mut k: i16) -> (u64, i16) {
+fn digit_gen(w: DiyFp, mp: DiyFp, mut delta: u64, mut k: i16) -> (u64, i16) {
     static POW10: [u32; 10] = [ 1, 10, 100, 1000, 10000, 100000, 1000000, 10000000, 100000000, 1000000000 ];
     let one = DiyFp::new(1u64 << -mp.e, mp.e);
     let wp_w = mp - w;
@@ -101,16 +101,14 @@ pub fn convert(float: f64) -> (u64, i16) {
     if float == 0.0 {
         return (0, 0);
     }
-    unsafe {
-        let v = DiyFp::from_f64(float);
-        let (w_m, w_p) = v.normalized_boundaries();
-        let (c_mk, k) = diyfp::get_cached_power(w_p.e);
-        let w = v.normalize() * c_mk;
-        let mut wp = w_p * c_mk;
-        let mut wm = w_m * c_mk;
-        wm.f += 1;
-        wp.f -= 1;
+    let v = DiyFp::from_f64(float);
+    let (w_m, w_p) = v.normalized_boundaries();
+    let (c_mk, k) = diyfp::get_cached_power(w_p.e);
+    let w = v.normalize() * c_mk;
+    let mut wp = w_p * c_mk;
+    let mut wm = w_m * c_mk;
+    wm.f += 1;
+    wp.f -= 1;
 
-        digit_gen(w, wp, wp.f - wm.f, k as i16)
-    }
+    digit_gen(w, wp, wp.f - wm.f, k as i16)
 }
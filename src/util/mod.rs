@@ -1,3 +1,5 @@
 pub mod diyfp;
 pub mod grisu2;
 pub mod print_dec;
+pub mod pointer;
+pub mod path;
@@ -0,0 +1,54 @@
+// Dotted paths with wildcards, such as `users.*.address.city` and
+// `items[*].id`, for `JsonValue::select`.
+
+use crate::util::pointer;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment<'a> {
+    // Member of an object, or of an array if it is an index
+    Key(&'a str),
+    Index(usize),
+    Wildcard,
+}
+
+// Split a path into its segments. Segments are separated by `.`, and can be
+// followed by any number of `[index]` or `[*]`. `None` for empty segments,
+// unclosed brackets and anything but an index or `*` in brackets.
+pub fn segments(path: &str) -> Option<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+
+    if path.is_empty() {
+        return Some(segments);
+    }
+
+    for part in path.split('.') {
+        let (name, mut rest) = match part.find('[') {
+            Some(index) => part.split_at(index),
+            None        => (part, ""),
+        };
+
+        match name {
+            ""  if rest.is_empty() => return None,
+            ""  => {},
+            "*" => segments.push(Segment::Wildcard),
+            _   => segments.push(Segment::Key(name)),
+        }
+
+        while !rest.is_empty() {
+            let end = rest.find(']')?;
+
+            segments.push(match &rest[1 .. end] {
+                "*"   => Segment::Wildcard,
+                index => Segment::Index(pointer::index(index)?),
+            });
+
+            rest = &rest[end + 1 ..];
+
+            if !rest.is_empty() && !rest.starts_with('[') {
+                return None;
+            }
+        }
+    }
+
+    Some(segments)
+}
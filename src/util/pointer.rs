@@ -0,0 +1,47 @@
+// Helpers for building JSON Pointers (RFC 6901) while walking a tree.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+// Append a reference token to the pointer, escaping `~` and `/`.
+pub fn push_key(pointer: &mut String, key: &str) {
+    pointer.push('/');
+    for ch in key.chars() {
+        match ch {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            _   => pointer.push(ch),
+        }
+    }
+}
+
+pub fn push_index(pointer: &mut String, index: usize) {
+    write!(pointer, "/{}", index).expect("Can't fail");
+}
+
+// Split a pointer into its unescaped reference tokens. `None` if it is
+// neither empty nor starting with `/`.
+pub fn tokens(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return None;
+    }
+
+    Some(pointer.split('/').skip(1).map(unescape))
+}
+
+fn unescape(token: &str) -> Cow<'_, str> {
+    match token.contains('~') {
+        true  => Cow::Owned(token.replace("~1", "/").replace("~0", "~")),
+        false => Cow::Borrowed(token),
+    }
+}
+
+// Array index in a token, written without leading zeros.
+pub fn index(token: &str) -> Option<usize> {
+    let digits = !token.is_empty() && token.bytes().all(|ch| ch.is_ascii_digit());
+
+    match digits && (token == "0" || !token.starts_with('0')) {
+        true  => token.parse().ok(),
+        false => None,
+    }
+}
@@ -6,8 +6,11 @@
 // The algorithm here was modified from being able to just writing integers,
 // to printing decimal floating points.
 
-use std::{io, mem, ptr, slice};
+use std::io;
+#[cfg(not(any(feature = "forbid-unsafe", feature = "optimize-size")))]
+use std::{mem, ptr, slice};
 
+#[cfg(not(feature = "optimize-size"))]
 const DEC_DIGITS_LUT: &'static[u8] =
     b"0001020304050607080910111213141516171819\
       2021222324252627282930313233343536373839\
@@ -17,6 +20,7 @@ const DEC_DIGITS_LUT: &'static[u8] =
 
 const ZEROFILL: &'static [u8] = &[b'0'; 20];
 
+#[cfg(not(any(feature = "forbid-unsafe", feature = "optimize-size")))]
 #[inline(always)]
 unsafe fn write_num(n: &mut u64, curr: &mut isize, buf_ptr: *mut u8, lut_ptr: *const u8) {
     // eagerly decode 4 digits at a time
@@ -50,7 +54,16 @@ unsafe fn write_num(n: &mut u64, curr: &mut isize, buf_ptr: *mut u8, lut_ptr: *c
     }
 }
 
-pub unsafe fn write<W: io::Write>(wr: &mut W, positive: bool, mut n: u64, exponent: i16) -> io::Result<()> {
+// Write out a decimal number, switching to the `e` notation for numbers that
+// would be too long otherwise.
+#[cfg(not(any(feature = "forbid-unsafe", feature = "optimize-size")))]
+pub fn write<W: io::Write>(wr: &mut W, positive: bool, n: u64, exponent: i16) -> io::Result<()> {
+    // The buffer below fits all digits of a `u64` and a decimal point
+    unsafe { write_unchecked(wr, positive, n, exponent) }
+}
+
+#[cfg(not(any(feature = "forbid-unsafe", feature = "optimize-size")))]
+unsafe fn write_unchecked<W: io::Write>(wr: &mut W, positive: bool, mut n: u64, exponent: i16) -> io::Result<()> {
     if !positive {
         wr.write_all(b"-")?;
     }
@@ -238,6 +251,250 @@ pub unsafe fn write<W: io::Write>(wr: &mut W, positive: bool, mut n: u64, expone
     write(wr, true, e, 0)
 }
 
+// Same as above, with bounds checked slices in place of raw pointers.
+#[cfg(any(feature = "forbid-unsafe", feature = "optimize-size"))]
+pub fn write<W: io::Write>(wr: &mut W, positive: bool, n: u64, exponent: i16) -> io::Result<()> {
+    if !positive {
+        wr.write_all(b"-")?;
+    }
+
+    if n == 0 {
+        return wr.write_all(b"0");
+    }
+
+    let mut buf = [0; 20];
+    let digits = write_digits(&mut buf, n);
+    let printed = digits.len();
+
+    if exponent == 0 {
+        return wr.write_all(digits);
+    } else if exponent < 0 {
+        let mut e = safe_abs(exponent) as usize;
+
+        // Decimal number with a fraction that's fully printable
+        if e < 18 {
+            if printed > e {
+                wr.write_all(&digits[ .. printed - e])?;
+                wr.write_all(b".")?;
+                return wr.write_all(&digits[printed - e .. ]);
+            }
+
+            wr.write_all(b"0.")?;
+            write_zeroes(wr, e - printed)?;
+            return wr.write_all(digits);
+        }
+
+        // Not easily printable, write down the first digit, then the
+        // fraction, then the exponent
+        let mut exponent_positive = false;
+
+        wr.write_all(&digits[ .. 1])?;
+
+        if printed > 1 {
+            let fraction = printed - 1;
+
+            if fraction <= e {
+                e -= fraction;
+            } else {
+                e = fraction - e;
+                exponent_positive = true;
+            }
+
+            wr.write_all(b".")?;
+            wr.write_all(&digits[1 .. ])?;
+        }
+
+        if e == 0 {
+            return Ok(());
+        }
+        if exponent_positive {
+            wr.write_all(b"e+")?;
+        } else {
+            wr.write_all(b"e-")?;
+        }
+        return write(wr, true, e as u64, 0);
+    }
+
+    // No need for `e` notation, just print out zeroes
+    if (printed + exponent as usize) <= 20 {
+        wr.write_all(digits)?;
+
+        return wr.write_all(&ZEROFILL[ .. exponent as usize]);
+    }
+
+    let mut e = exponent as u64;
+
+    wr.write_all(&digits[ .. 1])?;
+
+    // More than one digit, turn into a fraction
+    if printed != 1 {
+        wr.write_all(b".")?;
+        wr.write_all(&digits[1 .. ])?;
+        e += (printed as u64) - 1;
+    }
+
+    wr.write_all(b"e")?;
+    write(wr, true, e, 0)
+}
+
+// Write the digits of `n` to the end of `buf`, returning the written part.
+#[cfg(all(feature = "forbid-unsafe", not(feature = "optimize-size")))]
+fn write_digits(buf: &mut [u8; 20], mut n: u64) -> &[u8] {
+    let mut curr = buf.len();
+
+    while n >= 100 {
+        let d = ((n % 100) << 1) as usize;
+        n /= 100;
+        curr -= 2;
+        buf[curr .. curr + 2].copy_from_slice(&DEC_DIGITS_LUT[d .. d + 2]);
+    }
+
+    if n < 10 {
+        curr -= 1;
+        buf[curr] = (n as u8) + b'0';
+    } else {
+        let d = (n << 1) as usize;
+        curr -= 2;
+        buf[curr .. curr + 2].copy_from_slice(&DEC_DIGITS_LUT[d .. d + 2]);
+    }
+
+    &buf[curr .. ]
+}
+
+// One digit at a time, without the look up table.
+#[cfg(feature = "optimize-size")]
+fn write_digits(buf: &mut [u8; 20], mut n: u64) -> &[u8] {
+    let mut curr = buf.len();
+
+    loop {
+        curr -= 1;
+        buf[curr] = (n % 10) as u8 + b'0';
+        n /= 10;
+
+        if n == 0 {
+            return &buf[curr .. ];
+        }
+    }
+}
+
+// Write out an integral number in full, padding it with zeroes instead of
+// switching to the `e` notation.
+pub fn write_integral<W: io::Write>(wr: &mut W, positive: bool, n: u64, exponent: u16) -> io::Result<()> {
+    write(wr, positive, n, 0)?;
+
+    if n == 0 {
+        return Ok(());
+    }
+
+    write_zeroes(wr, exponent as usize)
+}
+
+// Write out a number in plain decimal notation if `10^min <= |n| < 10^max`,
+// otherwise use the `e` notation. This is slower than `write`, but allows the
+// exact cut-off points to be configured.
+pub fn write_with_thresholds<W: io::Write>(
+    wr: &mut W,
+    positive: bool,
+    mut n: u64,
+    exponent: i16,
+    min: i16,
+    max: i16,
+) -> io::Result<()> {
+    if n == 0 {
+        return write(wr, positive, 0, 0);
+    }
+
+    let mut exponent = exponent as i32;
+
+    // Strip trailing zeroes, they are accounted for in the exponent
+    while n.is_multiple_of(10) {
+        n /= 10;
+        exponent += 1;
+    }
+
+    let mut buf = Vec::with_capacity(20);
+    write(&mut buf, true, n, 0)?;
+
+    write_digits_with_thresholds(wr, positive, &buf, exponent, min, max)
+}
+
+// Same as `write_with_thresholds`, for the integers too wide to fit a `u64`
+// mantissa. All digits are kept, none are rounded off.
+pub fn write_wide_with_thresholds<W: io::Write>(
+    wr: &mut W,
+    positive: bool,
+    mut n: u128,
+    min: i16,
+    max: i16,
+) -> io::Result<()> {
+    if n == 0 {
+        return write(wr, positive, 0, 0);
+    }
+
+    let mut exponent = 0;
+
+    while n.is_multiple_of(10) {
+        n /= 10;
+        exponent += 1;
+    }
+
+    write_digits_with_thresholds(wr, positive, n.to_string().as_bytes(), exponent, min, max)
+}
+
+// Write out the decimal `buf` digits, with no trailing zeroes, scaled by
+// `10^exponent`.
+fn write_digits_with_thresholds<W: io::Write>(
+    wr: &mut W,
+    positive: bool,
+    buf: &[u8],
+    exponent: i32,
+    min: i16,
+    max: i16,
+) -> io::Result<()> {
+    if !positive {
+        wr.write_all(b"-")?;
+    }
+
+    let digits = buf.len() as i32;
+
+    // Exponent of the leading digit
+    let leading = exponent + digits - 1;
+
+    if leading < min as i32 || leading >= max as i32 {
+        wr.write_all(&buf[ .. 1])?;
+        if digits > 1 {
+            wr.write_all(b".")?;
+            wr.write_all(&buf[1 .. ])?;
+        }
+        wr.write_all(if leading < 0 { b"e-" } else { b"e+" })?;
+        return write(wr, true, leading.unsigned_abs() as u64, 0);
+    }
+
+    if exponent >= 0 {
+        wr.write_all(buf)?;
+        write_zeroes(wr, exponent as usize)
+    } else if leading >= 0 {
+        let point = (leading + 1) as usize;
+        wr.write_all(&buf[ .. point])?;
+        wr.write_all(b".")?;
+        wr.write_all(&buf[point .. ])
+    } else {
+        wr.write_all(b"0.")?;
+        write_zeroes(wr, (-leading - 1) as usize)?;
+        wr.write_all(buf)
+    }
+}
+
+fn write_zeroes<W: io::Write>(wr: &mut W, mut zeroes: usize) -> io::Result<()> {
+    while zeroes > 0 {
+        let len = zeroes.min(ZEROFILL.len());
+        wr.write_all(&ZEROFILL[ .. len])?;
+        zeroes -= len;
+    }
+
+    Ok(())
+}
+
 fn safe_abs(x : i16) -> u16 {
     if let Some(y) = x.checked_abs() {
         y as u16
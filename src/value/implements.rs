@@ -1,12 +1,20 @@
-// This is a private module that contains `PartialEq` and `From` trait
-// implementations for `JsonValue`.
+// This is a private module that contains `PartialEq`, `From`, `TryFrom` and
+// `Extend` trait implementations for `JsonValue`.
 
 use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
 
 use crate::short::{self, Short};
 use crate::number::Number;
 use crate::object::Object;
 use crate::value::JsonValue;
+use crate::Error;
+#[cfg(feature = "datetime")]
+use chrono::{ DateTime, FixedOffset, NaiveDate, SecondsFormat, TimeZone };
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
 
 macro_rules! implement_eq {
     ($to:ident, $from:ty) => {
@@ -60,10 +68,36 @@ macro_rules! implement {
     }
 }
 
+// Converts with one of the `as_*` methods. Numbers out of range of the type
+// are reported with their value as `found`.
+macro_rules! implement_try_from {
+    ($to:ty, $as:ident, $expected:expr) => {
+        impl<'a> TryFrom<&'a JsonValue> for $to {
+            type Error = Error;
+
+            fn try_from(val: &'a JsonValue) -> Result<$to, Error> {
+                val.$as().ok_or_else(|| match *val {
+                    JsonValue::Number(_) => Error::WrongType {
+                        expected: $expected.into(),
+                        found: val.dump(),
+                        path: String::new(),
+                    },
+                    _ => Error::wrong_type("number", val),
+                })
+            }
+        }
+    }
+}
+
 impl<'a> From<&'a str> for JsonValue {
     fn from(val: &'a str) -> JsonValue {
         if val.len() <= short::MAX_LEN {
-            JsonValue::Short(unsafe { Short::from_slice(val) })
+            #[cfg(not(feature = "forbid-unsafe"))]
+            let short = unsafe { Short::from_slice(val) };
+            #[cfg(feature = "forbid-unsafe")]
+            let short = Short::from_slice(val);
+
+            JsonValue::Short(short)
         } else {
             JsonValue::String(val.into())
         }
@@ -103,6 +137,33 @@ impl<K: AsRef<str>, V: Into<JsonValue>> From<BTreeMap<K, V>> for JsonValue {
     }
 }
 
+/// Pushes the values onto an array. Like indexing with `usize`, this turns
+/// any other value into an empty array first.
+impl Extend<JsonValue> for JsonValue {
+    fn extend<I: IntoIterator<Item=JsonValue>>(&mut self, iter: I) {
+        match *self {
+            JsonValue::Array(ref mut members) => members.extend(iter),
+            _ => {
+                *self = JsonValue::Array(iter.into_iter().collect());
+            }
+        }
+    }
+}
+
+/// Inserts the entries into an object, replacing the values of keys it
+/// already has. Like indexing with `&str`, this turns any other value into an
+/// empty object first.
+impl<K: AsRef<str>, V: Into<JsonValue>> Extend<(K, V)> for JsonValue {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        match *self {
+            JsonValue::Object(ref mut object) => object.extend(iter),
+            _ => {
+                *self = JsonValue::Object(iter.into_iter().collect());
+            }
+        }
+    }
+}
+
 impl<'a> PartialEq<&'a str> for JsonValue {
     fn eq(&self, other: &&str) -> bool {
         match *self {
@@ -150,12 +211,110 @@ implement!(Number, i8 as num);
 implement!(Number, i16 as num);
 implement!(Number, i32 as num);
 implement!(Number, i64 as num);
+implement!(Number, i128 as num);
 implement!(Number, u8 as num);
 implement!(Number, u16 as num);
 implement!(Number, u32 as num);
 implement!(Number, u64 as num);
+implement!(Number, u128 as num);
 implement!(Number, f32 as num);
 implement!(Number, f64 as num);
 implement!(Number, Number);
+#[cfg(feature = "decimal")]
+implement!(Number, Decimal as num);
 implement!(Object, Object);
 implement!(Boolean, bool);
+
+impl<'a> TryFrom<&'a JsonValue> for &'a str {
+    type Error = Error;
+
+    fn try_from(val: &'a JsonValue) -> Result<&'a str, Error> {
+        val.as_str().ok_or_else(|| Error::wrong_type("string", val))
+    }
+}
+
+impl<'a> TryFrom<&'a JsonValue> for String {
+    type Error = Error;
+
+    fn try_from(val: &'a JsonValue) -> Result<String, Error> {
+        <&str>::try_from(val).map(Into::into)
+    }
+}
+
+impl<'a> TryFrom<&'a JsonValue> for bool {
+    type Error = Error;
+
+    fn try_from(val: &'a JsonValue) -> Result<bool, Error> {
+        val.as_bool().ok_or_else(|| Error::wrong_type("boolean", val))
+    }
+}
+
+implement_try_from!(Number, as_number, "number");
+implement_try_from!(f64, as_f64, "number");
+implement_try_from!(f32, as_f32, "number");
+implement_try_from!(u128, as_u128, "u128");
+implement_try_from!(u64, as_u64, "u64");
+implement_try_from!(u32, as_u32, "u32");
+implement_try_from!(u16, as_u16, "u16");
+implement_try_from!(u8, as_u8, "u8");
+implement_try_from!(usize, as_usize, "usize");
+implement_try_from!(i128, as_i128, "i128");
+implement_try_from!(i64, as_i64, "i64");
+implement_try_from!(i32, as_i32, "i32");
+implement_try_from!(i16, as_i16, "i16");
+implement_try_from!(i8, as_i8, "i8");
+implement_try_from!(isize, as_isize, "isize");
+#[cfg(feature = "decimal")]
+implement_try_from!(Decimal, as_decimal, "decimal");
+
+// Types stored as strings, such as dates. Strings that don't hold a valid
+// value are reported with their value as `found`.
+#[cfg(any(feature = "datetime", feature = "uuid"))]
+macro_rules! implement_string_type {
+    ($to:ty, $as:ident, $expected:expr) => {
+        impl<'a> TryFrom<&'a JsonValue> for $to {
+            type Error = Error;
+
+            fn try_from(val: &'a JsonValue) -> Result<$to, Error> {
+                val.$as().ok_or_else(|| match val.as_str() {
+                    Some(_) => Error::WrongType {
+                        expected: $expected.into(),
+                        found: val.dump(),
+                        path: String::new(),
+                    },
+                    None => Error::wrong_type($expected, val),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(feature = "datetime")]
+implement_string_type!(NaiveDate, as_date, "date");
+#[cfg(feature = "datetime")]
+implement_string_type!(DateTime<FixedOffset>, as_datetime, "date-time");
+#[cfg(feature = "uuid")]
+implement_string_type!(Uuid, as_uuid, "UUID");
+
+/// Writes the timestamp in RFC 3339 form, with `Z` for UTC and as many
+/// digits of the fraction as needed, in groups of three.
+#[cfg(feature = "datetime")]
+impl<Tz: TimeZone> From<DateTime<Tz>> for JsonValue where Tz::Offset: std::fmt::Display {
+    fn from(val: DateTime<Tz>) -> JsonValue {
+        val.to_rfc3339_opts(SecondsFormat::AutoSi, true).as_str().into()
+    }
+}
+
+#[cfg(feature = "datetime")]
+impl From<NaiveDate> for JsonValue {
+    fn from(val: NaiveDate) -> JsonValue {
+        val.to_string().as_str().into()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<Uuid> for JsonValue {
+    fn from(val: Uuid) -> JsonValue {
+        val.to_string().as_str().into()
+    }
+}
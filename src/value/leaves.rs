@@ -0,0 +1,92 @@
+use std::iter::Enumerate;
+use std::slice;
+
+use crate::JsonValue;
+use crate::object;
+use crate::util::pointer;
+
+/// Iterator over the scalar values in a `JsonValue` tree along with their
+/// JSON Pointers, see `JsonValue::iter_leaves`.
+pub struct Leaves<'a> {
+    // Value to start from, until the first call to `next`
+    root: Option<&'a JsonValue>,
+    stack: Vec<Frame<'a>>,
+    pointer: String,
+}
+
+struct Frame<'a> {
+    children: Children<'a>,
+    pointer_len: usize,
+}
+
+enum Children<'a> {
+    Members(Enumerate<slice::Iter<'a, JsonValue>>),
+    Entries(object::Iter<'a>),
+}
+
+impl<'a> Leaves<'a> {
+    pub(crate) fn new(root: &'a JsonValue) -> Self {
+        Leaves {
+            root: Some(root),
+            stack: Vec::new(),
+            pointer: String::new(),
+        }
+    }
+
+    // Descend into `value` if it is an array or an object, returning it
+    // otherwise.
+    fn enter(&mut self, value: &'a JsonValue) -> Option<&'a JsonValue> {
+        let children = match *value {
+            JsonValue::Array(ref vec)     => Children::Members(vec.iter().enumerate()),
+            JsonValue::Object(ref object) => Children::Entries(object.iter()),
+            _                             => return Some(value),
+        };
+
+        self.stack.push(Frame {
+            children,
+            pointer_len: self.pointer.len(),
+        });
+
+        None
+    }
+}
+
+impl<'a> Iterator for Leaves<'a> {
+    type Item = (String, &'a JsonValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            if let Some(leaf) = self.enter(root) {
+                return Some((String::new(), leaf));
+            }
+        }
+
+        loop {
+            let frame = self.stack.last_mut()?;
+
+            self.pointer.truncate(frame.pointer_len);
+
+            let child = match frame.children {
+                Children::Members(ref mut members) => members.next().map(|(index, member)| {
+                    pointer::push_index(&mut self.pointer, index);
+                    member
+                }),
+                Children::Entries(ref mut entries) => entries.next().map(|(key, value)| {
+                    pointer::push_key(&mut self.pointer, key);
+                    value
+                }),
+            };
+
+            match child {
+                Some(child) => {
+                    if let Some(leaf) = self.enter(child) {
+                        return Some((self.pointer.clone(), leaf));
+                    }
+                },
+                None => {
+                    self.stack.pop();
+                },
+            }
+        }
+    }
+}
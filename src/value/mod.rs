@@ -1,16 +1,36 @@
-use std::ops::{Index, IndexMut, Deref};
-use std::convert::TryInto;
-use std::{fmt, mem, usize, u8, u16, u32, u64, isize, i8, i16, i32, i64, f32};
+use std::ops::{Index, IndexMut, Deref, Bound, RangeBounds};
+use std::convert::{ TryFrom, TryInto };
+use std::{cmp, fmt, mem, result, usize, u8, u16, u32, u64, isize, i8, i16, i32, i64, f32};
 use std::io::{self, Write};
 
-use crate::{Result, Error};
+use crate::{Result, Error, Case};
 use crate::short::Short;
 use crate::number::Number;
 use crate::object::Object;
-use crate::iterators::{ Members, MembersMut, Entries, EntriesMut };
-use crate::codegen::{ Generator, PrettyGenerator, DumpGenerator, WriterGenerator, PrettyWriterGenerator };
+use crate::util::pointer;
+use crate::util::path::{ self, Segment };
+use crate::iterators::{ Members, MembersMut, Entries, EntriesMut, Keys, Values, ValuesMut };
+use crate::codegen;
+use crate::codegen::{ Generator, GeneratorOptions, PrettyGenerator, DumpGenerator, WriterGenerator, PrettyWriterGenerator, LengthGenerator };
+#[cfg(feature = "color")]
+use crate::codegen::ColoredGenerator;
+#[cfg(feature = "datetime")]
+use chrono::{ DateTime, FixedOffset, NaiveDate };
+#[cfg(feature = "base64")]
+use crate::base64;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
 
 mod implements;
+mod leaves;
+
+pub use self::leaves::Leaves;
+
+// Most `null`s `set_pointer` pads an array with to reach an index past its
+// end, so that a stray huge index can't allocate gigabytes.
+const MAX_POINTER_PADDING: usize = 1024;
 
 // These are convenience macros for converting `f64` to the `$unsigned` type.
 // The macros check that the numbers are representable the target type.
@@ -43,6 +63,7 @@ pub enum JsonValue {
     Boolean(bool),
     Object(Object),
     Array(Vec<JsonValue>),
+    Binary(Vec<u8>),
 }
 
 impl PartialEq for JsonValue {
@@ -58,6 +79,7 @@ impl PartialEq for JsonValue {
             (&Boolean(ref a), &Boolean(ref b)) => a == b,
             (&Object(ref a), &Object(ref b)) => a == b,
             (&Array(ref a), &Array(ref b)) => a == b,
+            (Binary(a), Binary(b)) => a == b,
             _ => false,
         }
     }
@@ -65,6 +87,15 @@ impl PartialEq for JsonValue {
 
 impl Eq for JsonValue {}
 
+/// The default value is `JsonValue::Null`, so that `#[derive(Default)]`
+/// works for structs with `JsonValue` fields, and `mem::take` leaves `Null`
+/// behind like `JsonValue::take` does.
+impl Default for JsonValue {
+    fn default() -> JsonValue {
+        JsonValue::Null
+    }
+}
+
 /// Implements formatting
 ///
 /// ```
@@ -96,16 +127,64 @@ static NULL: JsonValue = JsonValue::Null;
 impl JsonValue {
     /// Create an empty `JsonValue::Object` instance.
     /// When creating an object with data, consider using the `object!` macro.
-    pub fn new_object() -> JsonValue {
+    /// Like all other constructors without data, this can be used in `const`
+    /// contexts, see `JsonValue::from_static`.
+    pub const fn new_object() -> JsonValue {
         JsonValue::Object(Object::new())
     }
 
     /// Create an empty `JsonValue::Array` instance.
     /// When creating array with data, consider using the `array!` macro.
-    pub fn new_array() -> JsonValue {
+    pub const fn new_array() -> JsonValue {
         JsonValue::Array(Vec::new())
     }
 
+    /// Create an empty `JsonValue::Object` with room for `capacity` entries
+    /// before it has to reallocate.
+    pub fn object_with_capacity(capacity: usize) -> JsonValue {
+        JsonValue::Object(Object::with_capacity(capacity))
+    }
+
+    /// Create an empty `JsonValue::Array` with room for `capacity` members
+    /// before it has to reallocate.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// let mut data = JsonValue::array_with_capacity(100);
+    ///
+    /// data.extend((0 .. 100).map(JsonValue::from));
+    ///
+    /// assert_eq!(data.len(), 100);
+    /// ```
+    pub fn array_with_capacity(capacity: usize) -> JsonValue {
+        JsonValue::Array(Vec::with_capacity(capacity))
+    }
+
+    /// Create a `JsonValue::Short` string in a `const` context, so that
+    /// default values can live in `static`s.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// # use json::number::Number;
+    /// static DEFAULT_NAME: JsonValue = JsonValue::from_static("anonymous");
+    /// static DEFAULT_LIMIT: JsonValue = JsonValue::Number(Number::from_parts(true, 25, 0));
+    /// static DEFAULT_TAGS: JsonValue = JsonValue::new_array();
+    /// static ENABLED: JsonValue = JsonValue::Boolean(true);
+    ///
+    /// assert_eq!(DEFAULT_NAME, "anonymous");
+    /// assert_eq!(DEFAULT_LIMIT, 25);
+    /// assert!(DEFAULT_TAGS.is_empty());
+    /// assert_eq!(ENABLED, true);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `value` is longer than `short::MAX_LEN` bytes, which in a `const`
+    /// context is a compile error.
+    pub const fn from_static(value: &'static str) -> JsonValue {
+        JsonValue::Short(Short::new(value))
+    }
+
     /// Prints out the value as JSON string.
     pub fn dump(&self) -> String {
         let mut gen = DumpGenerator::new();
@@ -113,6 +192,28 @@ impl JsonValue {
         gen.consume()
     }
 
+    /// Compact JSON preview of the value for logs, cut down to `max_len`
+    /// bytes. Long strings end with `…`, and members of arrays and objects
+    /// that don't fit are replaced with a count, such as `… 3 more`, so the
+    /// result is only valid JSON if nothing had to be left out. Values too
+    /// short to be cut down, like numbers or a final bracket, can make the
+    /// preview a few bytes longer than `max_len`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "id" => 7, "log" => "connection reset by peer", "items" => array![1, 2, 3, 4, 5, 6] };
+    ///
+    /// assert_eq!(data.preview(100), data.dump());
+    /// assert_eq!(data.preview(40), r#"{"id":7,"log":"connectio…",… 1 more}"#);
+    /// # }
+    /// ```
+    pub fn preview(&self, max_len: usize) -> String {
+        let mut out = String::new();
+        codegen::preview(&mut out, self, max_len);
+        out
+    }
+
     /// Pretty prints out the value as JSON string. Takes an argument that's
     /// number of spaces to indent new blocks with.
     pub fn pretty(&self, spaces: u16) -> String {
@@ -121,6 +222,16 @@ impl JsonValue {
         gen.consume()
     }
 
+    /// Pretty prints out the value with 2 spaces of indentation, highlighting
+    /// keys, strings, numbers, literals and punctuation with ANSI color codes
+    /// for display in a terminal. Requires the `color` feature.
+    #[cfg(feature = "color")]
+    pub fn dump_colored(&self) -> String {
+        let mut gen = ColoredGenerator::new(2);
+        gen.write_json(self).expect("Can't fail");
+        gen.consume()
+    }
+
     /// Writes the JSON as byte stream into an implementor of `std::io::Write`.
     ///
     /// This method is deprecated as it will panic on io errors, use `write` instead.
@@ -142,6 +253,131 @@ impl JsonValue {
         gen.write_json(self)
     }
 
+    /// Same as `dump`, but the members of a top-level array are serialized
+    /// on the `rayon` thread pool, for exporting huge arrays faster than a
+    /// single core can. That is the global pool, unless called inside of
+    /// `ThreadPool::install`. Small arrays and other values are dumped on the
+    /// calling thread. Requires the `parallel` feature.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// let data: JsonValue = (0 .. 10_000).collect::<Vec<i32>>().into();
+    ///
+    /// assert_eq!(data.dump_parallel(), data.dump());
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn dump_parallel(&self) -> String {
+        match *self {
+            JsonValue::Array(ref members) => codegen::dump_parallel(members),
+            _                             => self.dump(),
+        }
+    }
+
+    /// Same as `write`, serializing the members of a top-level array on
+    /// multiple threads like `dump_parallel`. Requires the `parallel`
+    /// feature.
+    #[cfg(feature = "parallel")]
+    pub fn write_parallel<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match *self {
+            JsonValue::Array(ref members) => codegen::write_parallel(members, writer),
+            _                             => self.write(writer),
+        }
+    }
+
+    /// SHA-256 digest of the value written in the JSON Canonicalization
+    /// Scheme of RFC 8785, with sorted keys and no whitespace, so equal
+    /// values have the same digest however they were formatted. Requires the
+    /// `digest` feature.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let a = json::parse(r#"{ "b": [1, 2.50], "a": "x" }"#).unwrap();
+    /// let b = object!{ "a" => "x", "b" => array![1.0, 2.5] };
+    ///
+    /// let digest = a.digest_sha256().unwrap();
+    /// let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    ///
+    /// assert_eq!(b.digest_sha256(), Some(digest));
+    /// assert_eq!(hex, "66efddae6a97500318e4c6cdc4bc04149f340a165a7ef2d830393048b67b7a31");
+    /// # }
+    /// ```
+    ///
+    /// As in the scheme, numbers are hashed as doubles, so integers past
+    /// 2^53 that round to the same double have the same digest. Values
+    /// holding NaN or numbers too large for a double have no canonical form
+    /// and return `None`. Binary data is hashed as a base64 string.
+    #[cfg(feature = "digest")]
+    pub fn digest_sha256(&self) -> Option<[u8; 32]> {
+        crate::digest::sha256(self)
+    }
+
+    /// Same as `dump`, but using custom `GeneratorOptions`.
+    ///
+    /// Panics if the value contains a NaN and `options.non_finite` is set to
+    /// `NonFinite::Error`, use `write_with` to handle that case.
+    pub fn dump_with(&self, options: &GeneratorOptions) -> String {
+        let mut gen = DumpGenerator::with_options(*options);
+        gen.write_document(self).expect("NaN can't be represented in JSON");
+        gen.consume()
+    }
+
+    /// Same as `pretty`, but using custom `GeneratorOptions`.
+    ///
+    /// Panics if the value contains a NaN and `options.non_finite` is set to
+    /// `NonFinite::Error`, use `write_pretty_with` to handle that case.
+    pub fn pretty_with(&self, spaces: u16, options: &GeneratorOptions) -> String {
+        let mut gen = PrettyGenerator::with_options(spaces, *options);
+        gen.write_document(self).expect("NaN can't be represented in JSON");
+        gen.consume()
+    }
+
+    /// Same as `write`, but using custom `GeneratorOptions`.
+    pub fn write_with<W: Write>(&self, writer: &mut W, options: &GeneratorOptions) -> io::Result<()> {
+        let mut gen = WriterGenerator::with_options(writer, *options);
+        gen.write_document(self)
+    }
+
+    /// Same as `write_pretty`, but using custom `GeneratorOptions`.
+    pub fn write_pretty_with<W: Write>(&self, writer: &mut W, spaces: u16, options: &GeneratorOptions) -> io::Result<()> {
+        let mut gen = PrettyWriterGenerator::with_options(writer, spaces, *options);
+        gen.write_document(self)
+    }
+
+    /// Exact length in bytes of the string `dump` would produce, computed
+    /// without allocating it. Useful for setting `Content-Length` before
+    /// streaming the value out with `write`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "name" => "Ferris", "tags" => array![1.5, true] };
+    ///
+    /// assert_eq!(data.serialized_len(), data.dump().len());
+    /// # }
+    /// ```
+    pub fn serialized_len(&self) -> usize {
+        let mut gen = LengthGenerator::new();
+        gen.write_json(self).expect("Can't fail");
+        gen.count()
+    }
+
+    /// Name of the type of this value as used in error messages: `"null"`,
+    /// `"boolean"`, `"number"`, `"string"`, `"array"`, `"object"` or
+    /// `"binary"`.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            JsonValue::Null       => "null",
+            JsonValue::Short(_)   => "string",
+            JsonValue::String(_)  => "string",
+            JsonValue::Number(_)  => "number",
+            JsonValue::Boolean(_) => "boolean",
+            JsonValue::Object(_)  => "object",
+            JsonValue::Array(_)   => "array",
+            JsonValue::Binary(_)  => "binary",
+        }
+    }
+
     pub fn is_string(&self) -> bool {
         match *self {
             JsonValue::Short(_)  => true,
@@ -185,6 +421,10 @@ impl JsonValue {
         }
     }
 
+    pub fn is_binary(&self) -> bool {
+        matches!(*self, JsonValue::Binary(_))
+    }
+
     /// Checks whether the value is empty. Returns true for:
     ///
     /// - empty string (`""`)
@@ -193,6 +433,7 @@ impl JsonValue {
     /// - null
     /// - empty array (`array![]`)
     /// - empty object (`object!{}`)
+    /// - empty binary
     pub fn is_empty(&self) -> bool {
         match *self {
             JsonValue::Null               => true,
@@ -202,6 +443,7 @@ impl JsonValue {
             JsonValue::Boolean(ref value) => !value,
             JsonValue::Array(ref value)   => value.is_empty(),
             JsonValue::Object(ref value)  => value.is_empty(),
+            JsonValue::Binary(ref value)  => value.is_empty(),
         }
     }
 
@@ -213,6 +455,13 @@ impl JsonValue {
         }
     }
 
+    pub fn as_binary(&self) -> Option<&[u8]> {
+        match *self {
+            JsonValue::Binary(ref value) => Some(value),
+            _                            => None
+        }
+    }
+
     pub fn as_number(&self) -> Option<Number> {
         match *self {
             JsonValue::Number(value) => Some(value),
@@ -228,6 +477,11 @@ impl JsonValue {
         self.as_number().map(|value| value.into())
     }
 
+    /// Integers of any size this crate can represent exactly, up to 128 bits.
+    pub fn as_u128(&self) -> Option<u128> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
     pub fn as_u64(&self) -> Option<u64> {
         self.as_number().and_then(|value| {
             value.try_into().ok()
@@ -250,6 +504,11 @@ impl JsonValue {
         self.as_u64().and_then(|value| number_to_unsigned!(usize, value, u64))
     }
 
+    /// Integers of any size this crate can represent exactly, up to 128 bits.
+    pub fn as_i128(&self) -> Option<i128> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
     pub fn as_i64(&self) -> Option<i64> {
         self.as_number().and_then(|value| value.try_into().ok())
     }
@@ -270,6 +529,24 @@ impl JsonValue {
         self.as_i64().and_then(|value| number_to_signed!(isize, value, i64))
     }
 
+    /// The number as an exact `Decimal`, `None` if it's out of range of
+    /// one. Numbers with up to 19 significant digits parse exactly, as do
+    /// integers of up to 128 bits. Requires the `decimal` feature.
+    ///
+    /// ```
+    /// # use json::rust_decimal::Decimal;
+    /// let order = json::parse(r#"{"price": 19.90, "quantity": 3}"#).unwrap();
+    /// let price = order["price"].as_decimal().unwrap();
+    ///
+    /// assert_eq!(price, Decimal::new(1990, 2));
+    /// assert_eq!(price.to_string(), "19.90");
+    /// assert_eq!(json::JsonValue::from(price).dump(), "19.90");
+    /// ```
+    #[cfg(feature = "decimal")]
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        self.as_number().and_then(|value| value.try_into().ok())
+    }
+
     pub fn as_bool(&self) -> Option<bool> {
         match *self {
             JsonValue::Boolean(ref value) => Some(*value),
@@ -277,6 +554,114 @@ impl JsonValue {
         }
     }
 
+    /// Parse a string holding an RFC 3339 timestamp, such as
+    /// `2024-02-29T13:45:00Z`, keeping its offset from UTC. Requires the
+    /// `datetime` feature.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// # use json::chrono::{ Timelike, Utc };
+    /// let value = JsonValue::from("2024-02-29T13:45:00.25+01:00");
+    /// let time = value.as_datetime().unwrap();
+    ///
+    /// assert_eq!((time.hour(), time.minute()), (13, 45));
+    /// assert_eq!(time.offset().local_minus_utc(), 3600);
+    /// assert_eq!(time.timestamp(), 1709210700);
+    ///
+    /// assert_eq!(JsonValue::from(time), "2024-02-29T13:45:00.250+01:00");
+    /// assert_eq!(JsonValue::from(time.with_timezone(&Utc)), "2024-02-29T12:45:00.250Z");
+    /// ```
+    #[cfg(feature = "datetime")]
+    pub fn as_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        self.as_str().and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+    }
+
+    /// Parse a string holding an RFC 3339 date, such as `2024-02-29`.
+    /// Requires the `datetime` feature.
+    #[cfg(feature = "datetime")]
+    pub fn as_date(&self) -> Option<NaiveDate> {
+        // `%Y` also takes signed and shorter years, `full-date` doesn't
+        self.as_str()
+            .filter(|value| value.len() == 10 && value.starts_with(|ch: char| ch.is_ascii_digit()))
+            .and_then(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+    }
+
+    /// Parse a string holding a hyphenated UUID, such as
+    /// `67e55044-10b1-426f-9247-bb680e5fe0c8`, in either case. Requires the
+    /// `uuid` feature.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// let value = JsonValue::from("67E55044-10B1-426F-9247-BB680E5FE0C8");
+    /// let id = value.as_uuid().unwrap();
+    ///
+    /// assert_eq!(id.get_version_num(), 4);
+    /// assert_eq!(JsonValue::from(id), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    /// ```
+    #[cfg(feature = "uuid")]
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        // The other forms `Uuid` parses, such as the braced one, are all of
+        // different lengths
+        self.as_str()
+            .filter(|value| value.len() == 36)
+            .and_then(|value| Uuid::try_parse(value).ok())
+    }
+
+    /// Decode a string holding base64 data, in the standard alphabet with
+    /// or without `=` padding, or the bytes of a `JsonValue::Binary`.
+    /// Requires the `base64` feature.
+    ///
+    /// ```
+    /// # use json::JsonValue;
+    /// let blob = JsonValue::from_bytes_base64(b"\x00binary\xff");
+    ///
+    /// assert_eq!(blob, "AGJpbmFyef8=");
+    /// assert_eq!(blob.as_bytes_base64(), Some(b"\x00binary\xff".to_vec()));
+    /// assert_eq!(JsonValue::from("not base64!").as_bytes_base64(), None);
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn as_bytes_base64(&self) -> Option<Vec<u8>> {
+        match *self {
+            JsonValue::Binary(ref bytes) => Some(bytes.clone()),
+            _                            => self.as_str().and_then(base64::decode),
+        }
+    }
+
+    /// Create a string holding `bytes` encoded as base64, in the standard
+    /// alphabet with padding. Requires the `base64` feature.
+    #[cfg(feature = "base64")]
+    pub fn from_bytes_base64(bytes: &[u8]) -> JsonValue {
+        base64::encode(bytes).as_str().into()
+    }
+
+    pub fn as_object(&self) -> Option<&Object> {
+        match *self {
+            JsonValue::Object(ref object) => Some(object),
+            _                             => None
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Object> {
+        match *self {
+            JsonValue::Object(ref mut object) => Some(object),
+            _                                 => None
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<JsonValue>> {
+        match *self {
+            JsonValue::Array(ref vec) => Some(vec),
+            _                         => None
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<JsonValue>> {
+        match *self {
+            JsonValue::Array(ref mut vec) => Some(vec),
+            _                             => None
+        }
+    }
+
     /// Obtain an integer at a fixed decimal point. This is useful for
     /// converting monetary values and doing arithmetic on them without
     /// rounding errors introduced by floating point operations.
@@ -380,6 +765,34 @@ impl JsonValue {
         None
     }
 
+    /// Move the `Object` out of this value without cloning it. Values of
+    /// other types are handed back as the error.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let object = object!{ "a" => 1 }.into_object().unwrap();
+    /// assert_eq!(object.get("a"), Some(&1.into()));
+    ///
+    /// assert_eq!(array![1].into_object(), Err(array![1]));
+    /// # }
+    /// ```
+    pub fn into_object(self) -> result::Result<Object, JsonValue> {
+        match self {
+            JsonValue::Object(object) => Ok(object),
+            value                     => Err(value),
+        }
+    }
+
+    /// Move the members out of an array without cloning them. Values of other
+    /// types are handed back as the error.
+    pub fn into_array(self) -> result::Result<Vec<JsonValue>, JsonValue> {
+        match self {
+            JsonValue::Array(vec) => Ok(vec),
+            value                 => Err(value),
+        }
+    }
+
     /// Works on `JsonValue::Array` - pushes a new value to the array.
     pub fn push<T>(&mut self, value: T) -> Result<()>
     where T: Into<JsonValue> {
@@ -388,7 +801,7 @@ impl JsonValue {
                 vec.push(value.into());
                 Ok(())
             },
-            _ => Err(Error::wrong_type("Array"))
+            _ => Err(Error::wrong_type("array", self))
         }
     }
 
@@ -403,74 +816,615 @@ impl JsonValue {
         }
     }
 
-    /// Works on `JsonValue::Array` - checks if the array contains a value
-    pub fn contains<T>(&self, item: T) -> bool where T: PartialEq<JsonValue> {
+    /// Works on `JsonValue::Array` - insert a new value at `index`, moving
+    /// the members after it up. Indexes past the end append the value.
+    pub fn insert_at<T>(&mut self, index: usize, value: T) -> Result<()>
+    where T: Into<JsonValue> {
         match *self {
-            JsonValue::Array(ref vec) => vec.iter().any(|member| item == *member),
-            _                         => false
+            JsonValue::Array(ref mut vec) => {
+                vec.insert(index.min(vec.len()), value.into());
+                Ok(())
+            },
+            _ => Err(Error::wrong_type("array", self))
         }
     }
 
-    /// Works on `JsonValue::Object` - checks if the object has a key
-    pub fn has_key(&self, key: &str) -> bool {
+    /// Works on `JsonValue::Array` - replace the members in `range` with
+    /// `replacement`, returning the members that were removed. Ranges
+    /// reaching past the end are cut short at the end.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::JsonValue;
+    /// # fn main() {
+    /// let mut list = array![1, 2, 3, 4];
+    ///
+    /// let removed = list.splice(1 .. 3, vec!["two", "three", "and a half"]).unwrap();
+    ///
+    /// assert_eq!(removed, vec![JsonValue::from(2), JsonValue::from(3)]);
+    /// assert_eq!(list, array![1, "two", "three", "and a half", 4]);
+    /// # }
+    /// ```
+    pub fn splice<R, I>(&mut self, range: R, replacement: I) -> Result<Vec<JsonValue>>
+    where R: RangeBounds<usize>, I: IntoIterator, I::Item: Into<JsonValue> {
         match *self {
-            JsonValue::Object(ref object) => object.get(key).is_some(),
-            _                             => false
+            JsonValue::Array(ref mut vec) => {
+                let end = match range.end_bound() {
+                    Bound::Included(&end) => end.saturating_add(1),
+                    Bound::Excluded(&end) => end,
+                    Bound::Unbounded      => vec.len(),
+                }.min(vec.len());
+
+                let start = match range.start_bound() {
+                    Bound::Included(&start) => start,
+                    Bound::Excluded(&start) => start.saturating_add(1),
+                    Bound::Unbounded        => 0,
+                }.min(end);
+
+                Ok(vec.splice(start .. end, replacement.into_iter().map(Into::into)).collect())
+            },
+            _ => Err(Error::wrong_type("array", self))
         }
     }
 
-    /// Returns length of array or object (number of keys), defaults to `0` for
-    /// other types.
-    pub fn len(&self) -> usize {
-        match *self {
-            JsonValue::Array(ref vec) => {
-                vec.len()
-            },
-            JsonValue::Object(ref object) => {
-                object.len()
+    /// Works on `JsonValue::Array` - move all members of the array `other` to
+    /// the end of this one, such as when stitching pages of results
+    /// together. Fails if either value isn't an array.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut results = array![1, 2];
+    ///
+    /// for page in vec![array![3, 4], array![], array![5]] {
+    ///     results.concat(page).unwrap();
+    /// }
+    ///
+    /// assert_eq!(results, array![1, 2, 3, 4, 5]);
+    /// # }
+    /// ```
+    pub fn concat(&mut self, other: JsonValue) -> Result<()> {
+        match (&mut *self, other) {
+            (JsonValue::Array(vec), JsonValue::Array(mut other)) => {
+                vec.append(&mut other);
+                Ok(())
             },
-            _ => 0
+            (JsonValue::Array(_), other) => Err(Error::wrong_type("array", &other)),
+            _                            => Err(Error::wrong_type("array", self)),
         }
     }
 
-    /// Works on `JsonValue::Array` - returns an iterator over members.
-    /// Will return an empty iterator if called on non-array types.
-    pub fn members(&self) -> Members {
-        match *self {
-            JsonValue::Array(ref vec) => {
-                vec.iter()
+    /// Works on `JsonValue::Array` - same as `concat`, cloning the members of
+    /// `other` instead of moving them.
+    pub fn concat_cloned(&mut self, other: &JsonValue) -> Result<()> {
+        match (&mut *self, other) {
+            (JsonValue::Array(vec), JsonValue::Array(other)) => {
+                vec.extend_from_slice(other);
+                Ok(())
             },
-            _ => [].iter()
+            (JsonValue::Array(_), other) => Err(Error::wrong_type("array", other)),
+            _                            => Err(Error::wrong_type("array", self)),
         }
     }
 
-    /// Works on `JsonValue::Array` - returns a mutable iterator over members.
-    /// Will return an empty iterator if called on non-array types.
-    pub fn members_mut(&mut self) -> MembersMut {
-        match *self {
-            JsonValue::Array(ref mut vec) => {
-                vec.iter_mut()
-            },
-            _ => [].iter_mut()
+    /// Works on `JsonValue::Array` - group the objects in the array by the
+    /// value of their `field`, into an object of arrays keyed by that value.
+    /// Strings are used as keys as they are, other values in their JSON
+    /// form. Members that lack the field, or aren't objects, are left out.
+    /// Groups are in the order of their first member. Other types give an
+    /// empty object.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let users = array![
+    ///     object!{ "name" => "Jon", "house" => "Stark" },
+    ///     object!{ "name" => "Cersei", "house" => "Lannister" },
+    ///     object!{ "name" => "Arya", "house" => "Stark" }
+    /// ];
+    ///
+    /// assert_eq!(users.group_by("house"), object!{
+    ///     "Stark" => array![users[0].clone(), users[2].clone()],
+    ///     "Lannister" => array![users[1].clone()]
+    /// });
+    /// # }
+    /// ```
+    pub fn group_by(&self, field: &str) -> JsonValue {
+        let mut groups = Object::new();
+
+        for (key, member) in self.keyed_by(field) {
+            match groups.get_mut(&key) {
+                Some(&mut JsonValue::Array(ref mut group)) => group.push(member.clone()),
+                _ => groups.insert(&key, JsonValue::Array(vec![member.clone()])),
+            }
         }
+
+        JsonValue::Object(groups)
     }
 
-    /// Works on `JsonValue::Object` - returns an iterator over key value pairs.
-    /// Will return an empty iterator if called on non-object types.
-    pub fn entries(&self) -> Entries {
-        match *self {
-            JsonValue::Object(ref object) => {
-                object.iter()
-            },
-            _ => Entries::empty()
+    /// Works on `JsonValue::Array` - make an object of the objects in the
+    /// array, keyed by the value of their `field` like with `group_by`. When
+    /// the value is repeated the last object wins.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let users = array![
+    ///     object!{ "id" => 7, "name" => "Jon" },
+    ///     object!{ "id" => 9, "name" => "Arya" }
+    /// ];
+    ///
+    /// assert_eq!(users.index_by("id")["9"]["name"], "Arya");
+    /// # }
+    /// ```
+    pub fn index_by(&self, field: &str) -> JsonValue {
+        let mut index = Object::new();
+
+        for (key, member) in self.keyed_by(field) {
+            index.insert(&key, member.clone());
         }
+
+        JsonValue::Object(index)
     }
 
-    /// Works on `JsonValue::Object` - returns a mutable iterator over
-    /// key value pairs.
-    /// Will return an empty iterator if called on non-object types.
-    pub fn entries_mut(&mut self) -> EntriesMut {
-        match *self {
+    // Objects in an array with the value of their `field` as a key.
+    fn keyed_by<'a>(&'a self, field: &'a str) -> impl Iterator<Item = (String, &'a JsonValue)> {
+        self.members().filter_map(move |member| {
+            let value = match *member {
+                JsonValue::Object(ref object) => object.get(field)?,
+                _                             => return None,
+            };
+
+            let key = match value.as_str() {
+                Some(key) => key.to_string(),
+                None      => value.dump(),
+            };
+
+            Some((key, member))
+        })
+    }
+
+    /// Works on `JsonValue::Array` - checks if the array contains a value
+    pub fn contains<T>(&self, item: T) -> bool where T: PartialEq<JsonValue> {
+        match *self {
+            JsonValue::Array(ref vec) => vec.iter().any(|member| item == *member),
+            _                         => false
+        }
+    }
+
+    /// Works on `JsonValue::Object` - checks if the object has a key
+    pub fn has_key(&self, key: &str) -> bool {
+        match *self {
+            JsonValue::Object(ref object) => object.get(key).is_some(),
+            _                             => false
+        }
+    }
+
+    /// Checks if `subset` is structurally contained in this value. Objects
+    /// contain an object if they have all of its keys, with values that in
+    /// turn contain its values. Arrays contain an array if each of its
+    /// members is contained in some member of theirs, in any order. Other
+    /// values only contain values equal to them, in the sense of `==`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let response = object!{
+    ///     "id" => 17,
+    ///     "user" => object!{ "name" => "Sam", "roles" => array!["admin", "dev"] },
+    ///     "created" => "2024-02-29T13:45:00Z"
+    /// };
+    ///
+    /// assert!(response.contains_value(&object!{ "user" => object!{ "roles" => array!["dev"] } }));
+    /// assert!(response.contains_value(&object!{ "id" => 17.0 }));
+    /// assert!(!response.contains_value(&object!{ "id" => 17, "deleted" => false }));
+    /// # }
+    /// ```
+    pub fn contains_value(&self, subset: &JsonValue) -> bool {
+        match (self, subset) {
+            (JsonValue::Object(object), JsonValue::Object(subset)) => {
+                subset.iter().all(|(key, value)| match object.get(key) {
+                    Some(member) => member.contains_value(value),
+                    None         => false,
+                })
+            },
+            (JsonValue::Array(vec), JsonValue::Array(subset)) => {
+                subset.iter().all(|value| vec.iter().any(|member| member.contains_value(value)))
+            },
+            _ => self == subset,
+        }
+    }
+
+    /// Works on `JsonValue::Object` - get the value of a member, without
+    /// silently falling back to `Null` on failure like indexing does.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "name" => "Jon", "tags" => array![] };
+    ///
+    /// assert_eq!(data.try_get("name").unwrap(), "Jon");
+    /// assert_eq!(data.try_get("age").unwrap_err().to_string(), "No member \"age\"");
+    /// assert_eq!(data["tags"].try_get("x").unwrap_err().to_string(), "Not an object");
+    /// # }
+    /// ```
+    pub fn try_get(&self, key: &str) -> Result<&JsonValue> {
+        self.try_get_path(&[key])
+    }
+
+    /// Works on `JsonValue::Object` - follow `keys` through nested objects,
+    /// like chaining `try_get` calls. Errors carry the JSON Pointer of the
+    /// object the lookup failed at.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "user" => object!{ "address" => object!{} } };
+    ///
+    /// let err = data.try_get_path(&["user", "address", "city"]).unwrap_err();
+    ///
+    /// assert_eq!(err, json::Error::MemberNotFound {
+    ///     key: "city".into(),
+    ///     path: "/user/address".into(),
+    /// });
+    /// # }
+    /// ```
+    pub fn try_get_path(&self, keys: &[&str]) -> Result<&JsonValue> {
+        let mut current = self;
+        let mut path = String::new();
+
+        for &key in keys {
+            current = match *current {
+                JsonValue::Object(ref object) => match object.get(key) {
+                    Some(value) => value,
+                    None => return Err(Error::MemberNotFound {
+                        key: key.into(),
+                        path,
+                    }),
+                },
+                _ => return Err(Error::NotAnObject { path }),
+            };
+
+            pointer::push_key(&mut path, key);
+        }
+
+        Ok(current)
+    }
+
+    /// Works on `JsonValue::Object` - follow `keys` like `try_get_path`, then
+    /// convert the value found using its `TryFrom<&JsonValue>` implementation.
+    /// `WrongType` errors of the conversion carry the JSON Pointer of the
+    /// value, followed by the path inside of it the conversion failed at.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "item" => object!{ "price" => "12" } };
+    ///
+    /// let err = data.try_get_path_as::<f64>(&["item", "price"]).unwrap_err();
+    ///
+    /// assert_eq!(err.to_string(), "Wrong type, expected number, found string at /item/price");
+    /// # }
+    /// ```
+    pub fn try_get_path_as<'a, T>(&'a self, keys: &[&str]) -> Result<T>
+    where T: TryFrom<&'a JsonValue, Error = Error> {
+        let value = self.try_get_path(keys)?;
+
+        T::try_from(value).map_err(|error| match error {
+            Error::WrongType { expected, found, path: inner } => {
+                let mut path = String::new();
+                for key in keys {
+                    pointer::push_key(&mut path, key);
+                }
+                path.push_str(&inner);
+                Error::WrongType { expected, found, path }
+            },
+            error => error,
+        })
+    }
+
+    /// Get all values at `path`, a dotted path of keys such as
+    /// `users.address.city`, in the order they are in. Keys also index into
+    /// arrays when they are valid indexes, and can be followed by brackets
+    /// with an index like `items[0]`. A `*` key or `[*]` matches every member
+    /// of an array or object. An empty path matches the value itself, paths
+    /// that can't be parsed match nothing.
+    ///
+    /// Keys containing `.` or `[` can't be written in a path, use `pointer`
+    /// to get at those.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{
+    ///     "users" => object!{
+    ///         "jon" => object!{ "address" => object!{ "city" => "Winterfell" } },
+    ///         "sam" => object!{ "address" => object!{ "city" => "Oldtown" } }
+    ///     },
+    ///     "items" => array![object!{ "id" => 1 }, object!{ "id" => 2 }]
+    /// };
+    ///
+    /// assert_eq!(data.select("users.*.address.city"), ["Winterfell", "Oldtown"]);
+    /// assert_eq!(data.select("items[*].id"), [1, 2]);
+    /// assert_eq!(data.select("items[1].id"), [2]);
+    /// assert!(data.select("items[*].name").is_empty());
+    /// # }
+    /// ```
+    pub fn select(&self, path: &str) -> Vec<&JsonValue> {
+        let segments = match path::segments(path) {
+            Some(segments) => segments,
+            None           => return Vec::new(),
+        };
+
+        let mut current = vec![self];
+
+        for segment in segments {
+            let mut next = Vec::with_capacity(current.len());
+
+            for value in current {
+                match (segment, value) {
+                    (Segment::Wildcard, JsonValue::Array(vec))     => next.extend(vec.iter()),
+                    (Segment::Wildcard, JsonValue::Object(object)) => next.extend(object.values()),
+                    (Segment::Key(key), JsonValue::Object(object)) => next.extend(object.get(key)),
+                    (Segment::Key(key), JsonValue::Array(vec))     => {
+                        next.extend(pointer::index(key).and_then(|index| vec.get(index)));
+                    },
+                    (Segment::Index(index), JsonValue::Array(vec)) => next.extend(vec.get(index)),
+                    _ => {},
+                }
+            }
+
+            current = next;
+        }
+
+        current
+    }
+
+    /// Get the value at the JSON Pointer (RFC 6901) `pointer`, such as
+    /// `/users/0/name`. The empty pointer refers to the value itself.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "users" => array![object!{ "name" => "Jon" }] };
+    ///
+    /// assert_eq!(data.pointer("/users/0/name").and_then(|name| name.as_str()), Some("Jon"));
+    /// assert_eq!(data.pointer("/users/1"), None);
+    /// # }
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonValue> {
+        let mut current = self;
+
+        for token in pointer::tokens(pointer)? {
+            current = match *current {
+                JsonValue::Object(ref object) => object.get(&token)?,
+                JsonValue::Array(ref array)   => array.get(pointer::index(&token)?)?,
+                _                             => return None,
+            };
+        }
+
+        Some(current)
+    }
+
+    /// Mutable version of `pointer`.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue> {
+        let mut current = self;
+
+        for token in pointer::tokens(pointer)? {
+            current = match *current {
+                JsonValue::Object(ref mut object) => object.get_mut(&token)?,
+                JsonValue::Array(ref mut array)   => array.get_mut(pointer::index(&token)?)?,
+                _                                 => return None,
+            };
+        }
+
+        Some(current)
+    }
+
+    /// Remove the value at the JSON Pointer `pointer` out of its object or
+    /// array and return it, `None` if there is no value there. Members of
+    /// arrays after the one removed shift down. The empty pointer refers to
+    /// the value itself, which can't be removed, so it also gives `None`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut data = object!{
+    ///     "users" => array![object!{ "name" => "Jon", "password" => "hunter2" }]
+    /// };
+    ///
+    /// assert_eq!(data.remove_pointer("/users/0/password"), Some("hunter2".into()));
+    /// assert_eq!(data.remove_pointer("/users/0/password"), None);
+    /// assert_eq!(data, object!{ "users" => array![object!{ "name" => "Jon" }] });
+    /// # }
+    /// ```
+    pub fn remove_pointer(&mut self, pointer: &str) -> Option<JsonValue> {
+        let split = pointer.rfind('/')?;
+        let token = pointer::tokens(&pointer[split ..])?.next()?;
+
+        match *self.pointer_mut(&pointer[.. split])? {
+            JsonValue::Object(ref mut object) => object.remove(&token),
+            JsonValue::Array(ref mut array)   => {
+                let index = pointer::index(&token).filter(|&index| index < array.len())?;
+                Some(array.remove(index))
+            },
+            _ => None,
+        }
+    }
+
+    /// Set the value at the JSON Pointer `pointer`, creating the objects and
+    /// arrays leading up to it where they are missing or `null`. Tokens that
+    /// are array indexes, or `-`, create arrays and any other tokens create
+    /// objects. Like `set` of lodash, an index past the end of an array pads
+    /// it with `null`s up to the index, and `-` appends to it.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut value = object!{ "name" => "app" };
+    ///
+    /// value.set_pointer("/a/b/3/c", "deep").unwrap();
+    /// value.set_pointer("/a/b/-", "last").unwrap();
+    ///
+    /// assert_eq!(value, object!{
+    ///     "name" => "app",
+    ///     "a" => object!{
+    ///         "b" => array![json::Null, json::Null, json::Null, object!{ "c" => "deep" }, "last"]
+    ///     }
+    /// });
+    /// assert!(value.set_pointer("/a/b/99999", "far").is_err());
+    /// # }
+    /// ```
+    ///
+    /// Fails on pointers that don't start with `/`, on running into a value
+    /// other than an object, array or `null` before the end of the pointer,
+    /// on tokens that aren't indexes for arrays, and on indexes that would
+    /// pad an array with more than 1024 `null`s. The value is left unchanged
+    /// when that happens.
+    pub fn set_pointer<T>(&mut self, pointer: &str, value: T) -> Result<()>
+    where T: Into<JsonValue> {
+        let tokens = pointer::tokens(pointer).ok_or_else(|| Error::InvalidPointer {
+            pointer: pointer.into(),
+        })?;
+
+        self.check_pointer(pointer)?;
+
+        let mut current = self;
+
+        // All checks are done, so nothing can fail past the first change
+        for token in tokens {
+            if current.is_null() {
+                *current = match token == "-" || pointer::index(&token).is_some() {
+                    true  => JsonValue::new_array(),
+                    false => JsonValue::new_object(),
+                };
+            }
+
+            current = match *current {
+                JsonValue::Object(ref mut object) => &mut object[&*token],
+                JsonValue::Array(ref mut array)   => {
+                    let index = pointer::index(&token).unwrap_or(array.len());
+
+                    if index >= array.len() {
+                        array.resize(index + 1, JsonValue::Null);
+                    }
+                    &mut array[index]
+                },
+                _ => unreachable!("Checked by `check_pointer`"),
+            };
+        }
+
+        *current = value.into();
+
+        Ok(())
+    }
+
+    // Fails where `set_pointer` would, without changing anything.
+    fn check_pointer(&self, pointer: &str) -> Result<()> {
+        let mut current = Some(self);
+        let mut path = String::new();
+
+        for token in pointer::tokens(pointer).into_iter().flatten() {
+            let index = pointer::index(&token);
+
+            current = match current {
+                // Created by `set_pointer`, arrays start out empty
+                None | Some(JsonValue::Null) => {
+                    match index {
+                        Some(index) if index > MAX_POINTER_PADDING => {
+                            return Err(Error::IndexOutOfBounds { index, len: 0, path });
+                        },
+                        Some(index)              => pointer::push_index(&mut path, index),
+                        None if token == "-"     => pointer::push_index(&mut path, 0),
+                        None                     => pointer::push_key(&mut path, &token),
+                    }
+                    None
+                },
+                Some(JsonValue::Object(object)) => {
+                    pointer::push_key(&mut path, &token);
+                    object.get(&token)
+                },
+                Some(JsonValue::Array(array)) => {
+                    let index = match index {
+                        Some(index)          => index,
+                        None if token == "-" => array.len(),
+                        None                 => return Err(Error::WrongType {
+                            expected: "object".into(),
+                            found: "array".into(),
+                            path,
+                        }),
+                    };
+
+                    if index.saturating_sub(array.len()) > MAX_POINTER_PADDING {
+                        return Err(Error::IndexOutOfBounds { index, len: array.len(), path });
+                    }
+
+                    pointer::push_index(&mut path, index);
+                    array.get(index)
+                },
+                Some(other) => return Err(Error::WrongType {
+                    expected: "object or array".into(),
+                    found: other.type_name().into(),
+                    path,
+                }),
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Returns length of array or object (number of keys), defaults to `0` for
+    /// other types.
+    pub fn len(&self) -> usize {
+        match *self {
+            JsonValue::Array(ref vec) => {
+                vec.len()
+            },
+            JsonValue::Object(ref object) => {
+                object.len()
+            },
+            _ => 0
+        }
+    }
+
+    /// Works on `JsonValue::Array` - returns an iterator over members.
+    /// Will return an empty iterator if called on non-array types.
+    pub fn members(&self) -> Members {
+        match *self {
+            JsonValue::Array(ref vec) => {
+                vec.iter()
+            },
+            _ => [].iter()
+        }
+    }
+
+    /// Works on `JsonValue::Array` - returns a mutable iterator over members.
+    /// Will return an empty iterator if called on non-array types.
+    pub fn members_mut(&mut self) -> MembersMut {
+        match *self {
+            JsonValue::Array(ref mut vec) => {
+                vec.iter_mut()
+            },
+            _ => [].iter_mut()
+        }
+    }
+
+    /// Works on `JsonValue::Object` - returns an iterator over key value pairs.
+    /// Will return an empty iterator if called on non-object types.
+    pub fn entries(&self) -> Entries {
+        match *self {
+            JsonValue::Object(ref object) => {
+                object.iter()
+            },
+            _ => Entries::empty()
+        }
+    }
+
+    /// Works on `JsonValue::Object` - returns a mutable iterator over
+    /// key value pairs.
+    /// Will return an empty iterator if called on non-object types.
+    pub fn entries_mut(&mut self) -> EntriesMut {
+        match *self {
             JsonValue::Object(ref mut object) => {
                 object.iter_mut()
             },
@@ -478,6 +1432,40 @@ impl JsonValue {
         }
     }
 
+    /// Works on `JsonValue::Object` - returns an iterator over keys.
+    /// Will return an empty iterator if called on non-object types.
+    pub fn keys(&self) -> Keys<'_> {
+        match *self {
+            JsonValue::Object(ref object) => {
+                object.keys()
+            },
+            _ => Keys::empty()
+        }
+    }
+
+    /// Works on `JsonValue::Object` - returns an iterator over values.
+    /// Will return an empty iterator if called on non-object types.
+    pub fn values(&self) -> Values<'_> {
+        match *self {
+            JsonValue::Object(ref object) => {
+                object.values()
+            },
+            _ => Values::empty()
+        }
+    }
+
+    /// Works on `JsonValue::Object` - returns a mutable iterator over
+    /// values.
+    /// Will return an empty iterator if called on non-object types.
+    pub fn values_mut(&mut self) -> ValuesMut<'_> {
+        match *self {
+            JsonValue::Object(ref mut object) => {
+                object.values_mut()
+            },
+            _ => ValuesMut::empty()
+        }
+    }
+
     /// Works on `JsonValue::Object` - inserts a new entry, or override an existing
     /// one into the object. Note that `key` has to be a `&str` slice and not an owned
     /// `String`. The internals of `Object` will handle the heap allocation of the key
@@ -489,7 +1477,374 @@ impl JsonValue {
                 object.insert(key, value.into());
                 Ok(())
             },
-            _ => Err(Error::wrong_type("Object"))
+            _ => Err(Error::wrong_type("object", self))
+        }
+    }
+
+    /// Works on `JsonValue::Object` - move all entries of the object `other`
+    /// into this one, overriding existing keys, see `Object::merge_in`.
+    /// Fails if either value isn't an object.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut config = object!{ "host" => "localhost", "port" => 80, "tls" => object!{ "on" => false } };
+    ///
+    /// config.merge_in(object!{ "port" => 8080, "tls" => object!{ "cert" => "a.pem" } }).unwrap();
+    ///
+    /// assert_eq!(config, object!{
+    ///     "host" => "localhost",
+    ///     "port" => 8080,
+    ///     "tls" => object!{ "cert" => "a.pem" }
+    /// });
+    /// # }
+    /// ```
+    pub fn merge_in(&mut self, other: JsonValue) -> Result<()> {
+        match (&mut *self, other) {
+            (JsonValue::Object(object), JsonValue::Object(other)) => {
+                object.merge_in(other);
+                Ok(())
+            },
+            (JsonValue::Object(_), other) => Err(Error::wrong_type("object", &other)),
+            _                             => Err(Error::wrong_type("object", self)),
+        }
+    }
+
+    /// Works on `JsonValue::Object` - rename the entry under `old` to `new`,
+    /// keeping its position, see `Object::rename`. Returns `false` if there
+    /// is no such entry, or the value isn't an object.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut user = object!{ "id" => 1, "userName" => "jon", "admin" => false };
+    ///
+    /// assert!(user.rename_key("userName", "user_name"));
+    /// assert!(!user.rename_key("email", "mail"));
+    ///
+    /// assert_eq!(user.dump(), r#"{"id":1,"user_name":"jon","admin":false}"#);
+    /// # }
+    /// ```
+    pub fn rename_key(&mut self, old: &str, new: &str) -> bool {
+        match *self {
+            JsonValue::Object(ref mut object) => object.rename(old, new),
+            _                                 => false,
+        }
+    }
+
+    /// Convert the keys of all objects in the value to `case`, see `Case`
+    /// for how words are found. When two keys of an object convert to the
+    /// same key, the entry is kept at the position of the first one with the
+    /// value of the last one.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # use json::Case;
+    /// # fn main() {
+    /// let mut data = object!{ "user_id" => 1, "tags" => array![object!{ "tag_name" => "a" }] };
+    ///
+    /// data.transform_keys(Case::CamelCase);
+    ///
+    /// assert_eq!(data, object!{ "userId" => 1, "tags" => array![object!{ "tagName" => "a" }] });
+    /// # }
+    /// ```
+    pub fn transform_keys(&mut self, case: Case) {
+        match *self {
+            JsonValue::Object(ref mut object) => {
+                let mut converted = Object::with_capacity(object.len());
+
+                for (key, value) in object.iter_mut() {
+                    value.transform_keys(case);
+                    converted.insert(&case.convert(key), value.take());
+                }
+
+                *object = converted;
+            },
+            JsonValue::Array(ref mut vec) => {
+                for member in vec.iter_mut() {
+                    member.transform_keys(case);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Sort the entries of all objects in the value by their keys, for
+    /// comparing or writing out documents the same way regardless of the
+    /// order they were built in.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut data = object!{ "b" => object!{ "d" => 1, "c" => 2 }, "a" => array![object!{ "f" => 3, "e" => 4 }] };
+    ///
+    /// data.sort_keys();
+    ///
+    /// assert_eq!(data.dump(), r#"{"a":[{"e":4,"f":3}],"b":{"c":2,"d":1}}"#);
+    /// # }
+    /// ```
+    pub fn sort_keys(&mut self) {
+        self.sort_keys_with(&mut str::cmp);
+    }
+
+    /// Same as `sort_keys`, ordering the keys with `compare`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let mut data = object!{ "id" => 1, "name" => "Jon", "age" => 30 };
+    ///
+    /// data.sort_keys_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+    ///
+    /// assert_eq!(data.dump(), r#"{"id":1,"age":30,"name":"Jon"}"#);
+    /// # }
+    /// ```
+    pub fn sort_keys_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&str, &str) -> cmp::Ordering,
+    {
+        self.sort_keys_with(&mut compare);
+    }
+
+    fn sort_keys_with(&mut self, compare: &mut dyn FnMut(&str, &str) -> cmp::Ordering) {
+        match *self {
+            JsonValue::Object(ref mut object) => {
+                let mut sorted = Object::with_capacity(object.len());
+                let mut entries: Vec<_> = object.iter_mut().collect();
+
+                entries.sort_by(|a, b| compare(a.0, b.0));
+
+                for (key, value) in entries {
+                    value.sort_keys_with(compare);
+                    sorted.insert(key, value.take());
+                }
+
+                *object = sorted;
+            },
+            JsonValue::Array(ref mut vec) => {
+                for member in vec.iter_mut() {
+                    member.sort_keys_with(compare);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Summarized copy of the value, small enough for log lines. Arrays and
+    /// objects nested more than `max_depth` levels deep are replaced with
+    /// `"…"`, ones with more than `max_items` members keep the first ones
+    /// followed by a `"…"` member, and strings longer than `max_string_len`
+    /// characters are cut short with a `…`.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let payload = object!{
+    ///     "id" => 1,
+    ///     "data" => "aaaaaaaaaaaaaaaa",
+    ///     "items" => array![1, 2, 3, 4, 5],
+    ///     "meta" => object!{ "trace" => array![1] }
+    /// };
+    ///
+    /// assert_eq!(
+    ///     payload.truncated(2, 3, 4).dump(),
+    ///     r#"{"id":1,"data":"aaaa…","items":[1,2,3,"…"],"…":"…"}"#
+    /// );
+    /// # }
+    /// ```
+    pub fn truncated(&self, max_depth: usize, max_items: usize, max_string_len: usize) -> JsonValue {
+        const MARKER: &str = "…";
+
+        match *self {
+            JsonValue::Array(ref vec) if !vec.is_empty() => {
+                if max_depth == 0 {
+                    return MARKER.into();
+                }
+
+                let mut array: Vec<_> = vec.iter()
+                                           .take(max_items)
+                                           .map(|member| member.truncated(max_depth - 1, max_items, max_string_len))
+                                           .collect();
+
+                if vec.len() > max_items {
+                    array.push(MARKER.into());
+                }
+
+                JsonValue::Array(array)
+            },
+            JsonValue::Object(ref object) if !object.is_empty() => {
+                if max_depth == 0 {
+                    return MARKER.into();
+                }
+
+                let mut truncated = Object::with_capacity(cmp::min(object.len(), max_items + 1));
+
+                for (key, value) in object.iter().take(max_items) {
+                    truncated.insert(key, value.truncated(max_depth - 1, max_items, max_string_len));
+                }
+
+                if object.len() > max_items {
+                    truncated.insert(MARKER, MARKER.into());
+                }
+
+                JsonValue::Object(truncated)
+            },
+            JsonValue::Short(_) | JsonValue::String(_) => {
+                let string = self.as_str().unwrap_or("");
+
+                match string.char_indices().nth(max_string_len) {
+                    Some((index, _)) => format!("{}{}", &string[.. index], MARKER).into(),
+                    None             => self.clone(),
+                }
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Iterate over all values in the tree that are neither arrays nor
+    /// objects, along with their JSON Pointers, in the order they are in.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{ "name" => "Jon", "tags" => array!["a", array![]], "a/b" => true };
+    ///
+    /// let pointers: Vec<String> = data.iter_leaves().map(|(pointer, _)| pointer).collect();
+    ///
+    /// assert_eq!(pointers, ["/name", "/tags/0", "/a~1b"]);
+    /// # }
+    /// ```
+    pub fn iter_leaves(&self) -> Leaves<'_> {
+        Leaves::new(self)
+    }
+
+    /// Find all values in the tree, including this one, arrays and objects,
+    /// for which `predicate` returns `true`. The predicate is given the JSON
+    /// Pointer of every value along with the value, and matches are returned
+    /// the same way, in the order they are in.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{
+    ///     "owner" => "jon@example.com",
+    ///     "members" => array![object!{ "name" => "Arya", "email" => "arya@example.com" }]
+    /// };
+    ///
+    /// let emails = data.find_all(|_, value| value.as_str().is_some_and(|text| text.contains('@')));
+    ///
+    /// assert_eq!(emails, vec![
+    ///     ("/owner".to_string(), &data["owner"]),
+    ///     ("/members/0/email".to_string(), &data["members"][0]["email"]),
+    /// ]);
+    /// # }
+    /// ```
+    pub fn find_all<F>(&self, mut predicate: F) -> Vec<(String, &JsonValue)>
+    where
+        F: FnMut(&str, &JsonValue) -> bool,
+    {
+        let mut found = Vec::new();
+
+        self.find_all_with(&mut String::new(), &mut predicate, &mut found);
+
+        found
+    }
+
+    fn find_all_with<'a>(
+        &'a self,
+        path: &mut String,
+        predicate: &mut dyn FnMut(&str, &JsonValue) -> bool,
+        found: &mut Vec<(String, &'a JsonValue)>,
+    ) {
+        if predicate(path, self) {
+            found.push((path.clone(), self));
+        }
+
+        let len = path.len();
+
+        match *self {
+            JsonValue::Array(ref vec) => {
+                for (index, member) in vec.iter().enumerate() {
+                    pointer::push_index(path, index);
+                    member.find_all_with(path, predicate, found);
+                    path.truncate(len);
+                }
+            },
+            JsonValue::Object(ref object) => {
+                for (key, value) in object.iter() {
+                    pointer::push_key(path, key);
+                    value.find_all_with(path, predicate, found);
+                    path.truncate(len);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Works on `JsonValue::Object` - copy of the object with only the
+    /// entries under `keys`, in the order they are in the object. Values of
+    /// other types are cloned as they are.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let user = object!{ "id" => 1, "name" => "Jon", "password" => "hunter2" };
+    ///
+    /// assert_eq!(user.pick(&["name", "id", "email"]), object!{ "id" => 1, "name" => "Jon" });
+    /// assert_eq!(user.omit(&["password"]), object!{ "id" => 1, "name" => "Jon" });
+    /// # }
+    /// ```
+    pub fn pick(&self, keys: &[&str]) -> JsonValue {
+        self.project(keys, true, false)
+    }
+
+    /// Works on `JsonValue::Object` - copy of the object without the entries
+    /// under `keys`. Values of other types are cloned as they are.
+    pub fn omit(&self, keys: &[&str]) -> JsonValue {
+        self.project(keys, false, false)
+    }
+
+    /// Same as `pick`, for all objects nested in the value as well, inside
+    /// both the entries kept and arrays.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate json;
+    /// # fn main() {
+    /// let data = object!{
+    ///     "users" => array![object!{ "name" => "Jon", "password" => "hunter2" }],
+    ///     "debug" => true
+    /// };
+    ///
+    /// assert_eq!(data.pick_deep(&["users", "name"]), object!{ "users" => array![object!{ "name" => "Jon" }] });
+    /// assert_eq!(data.omit_deep(&["password", "debug"]), object!{ "users" => array![object!{ "name" => "Jon" }] });
+    /// # }
+    /// ```
+    pub fn pick_deep(&self, keys: &[&str]) -> JsonValue {
+        self.project(keys, true, true)
+    }
+
+    /// Same as `omit`, for all objects nested in the value as well.
+    pub fn omit_deep(&self, keys: &[&str]) -> JsonValue {
+        self.project(keys, false, true)
+    }
+
+    // Copy of the value with the entries whose keys are in `keys` kept or
+    // left out, descending into nested values when `deep` is set.
+    fn project(&self, keys: &[&str], keep: bool, deep: bool) -> JsonValue {
+        let nested = |value: &JsonValue| match deep {
+            true  => value.project(keys, keep, deep),
+            false => value.clone(),
+        };
+
+        match *self {
+            JsonValue::Object(ref object) => JsonValue::Object(
+                object.iter()
+                      .filter(|&(key, _)| keys.contains(&key) == keep)
+                      .map(|(key, value)| (key, nested(value)))
+                      .collect()
+            ),
+            JsonValue::Array(ref vec) if deep => JsonValue::Array(vec.iter().map(nested).collect()),
+            _ => self.clone(),
         }
     }
 
@@ -0,0 +1,307 @@
+use std::io::{ self, Write };
+
+use crate::JsonValue;
+use crate::number::Number;
+use crate::codegen::{ self, Generator, GeneratorOptions };
+
+/// Streaming writer with a push API. Values are written out to the underlying
+/// `std::io::Write` as soon as they are pushed, so large documents can be
+/// produced without building a `JsonValue` tree in memory first.
+///
+/// ```
+/// # use json::JsonWriter;
+/// let mut writer = JsonWriter::new(Vec::new());
+///
+/// writer.begin_object().unwrap();
+/// writer.key("name").unwrap();
+/// writer.string("Stream").unwrap();
+/// writer.key("sizes").unwrap();
+/// writer.begin_array().unwrap();
+/// writer.number(1).unwrap();
+/// writer.number(2.5).unwrap();
+/// writer.end_array().unwrap();
+/// writer.end_object().unwrap();
+///
+/// let out = writer.finish().unwrap();
+///
+/// assert_eq!(out, br#"{"name":"Stream","sizes":[1,2.5]}"#);
+/// ```
+///
+/// Pushing tokens out of order, such as a value where an object key is
+/// expected, results in an `io::Error` of `InvalidInput` kind.
+pub struct JsonWriter<W: Write> {
+    sink: Sink<W>,
+    stack: Vec<Frame>,
+    done: bool,
+}
+
+enum Frame {
+    // `first` is true until the first member has been written, `key` is true
+    // after a key has been written, but before its value.
+    Object { first: bool, key: bool },
+    Array { first: bool },
+}
+
+impl<W: Write> JsonWriter<W> {
+    /// Create a writer producing compact JSON.
+    pub fn new(writer: W) -> Self {
+        JsonWriter::with_options(writer, None, GeneratorOptions::default())
+    }
+
+    /// Create a writer producing pretty printed JSON, indenting new blocks
+    /// with `spaces` number of spaces.
+    pub fn pretty(writer: W, spaces: u16) -> Self {
+        JsonWriter::with_options(writer, Some(spaces), GeneratorOptions::default())
+    }
+
+    /// Create a writer with custom `GeneratorOptions`. Pass `Some(spaces)`
+    /// as `pretty` to pretty print the output.
+    pub fn with_options(writer: W, pretty: Option<u16>, options: GeneratorOptions) -> Self {
+        JsonWriter {
+            sink: Sink {
+                writer,
+                dent: 0,
+                closing: false,
+                pretty,
+                options,
+            },
+            stack: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Start writing an object.
+    pub fn begin_object(&mut self) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write_char(b'{')?;
+        self.sink.indent();
+        self.stack.push(Frame::Object { first: true, key: false });
+        Ok(())
+    }
+
+    /// Write a key inside of an object. Must be followed by a value.
+    pub fn key(&mut self, key: &str) -> io::Result<()> {
+        match self.stack.last_mut() {
+            Some(&mut Frame::Object { ref mut first, key: ref mut has_key }) if !*has_key => {
+                if !*first {
+                    self.sink.write_char(b',')?;
+                }
+                *first = false;
+                *has_key = true;
+            },
+            _ => return Err(invalid("Unexpected object key")),
+        }
+
+        self.sink.new_line()?;
+        self.sink.write_key(key)?;
+        self.sink.write_key_separator()
+    }
+
+    /// Finish writing an object.
+    pub fn end_object(&mut self) -> io::Result<()> {
+        match self.stack.last() {
+            Some(&Frame::Object { first, key: false }) => self.end(first, b'}'),
+            _ => Err(invalid("Unexpected end of object")),
+        }
+    }
+
+    /// Start writing an array.
+    pub fn begin_array(&mut self) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write_char(b'[')?;
+        self.sink.indent();
+        self.stack.push(Frame::Array { first: true });
+        Ok(())
+    }
+
+    /// Finish writing an array.
+    pub fn end_array(&mut self) -> io::Result<()> {
+        match self.stack.last() {
+            Some(&Frame::Array { first }) => self.end(first, b']'),
+            _ => Err(invalid("Unexpected end of array")),
+        }
+    }
+
+    /// Write a complete `JsonValue`.
+    pub fn value(&mut self, value: &JsonValue) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write_json(value)?;
+        self.after_value()
+    }
+
+    /// Write a string value.
+    pub fn string(&mut self, value: &str) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write_string(value)?;
+        self.after_value()
+    }
+
+    /// Write a number value.
+    pub fn number<N: Into<Number>>(&mut self, value: N) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write_number(&value.into())?;
+        self.after_value()
+    }
+
+    /// Write a boolean value.
+    pub fn boolean(&mut self, value: bool) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write(if value { b"true" } else { b"false" })?;
+        self.after_value()
+    }
+
+    /// Write a `null`.
+    pub fn null(&mut self) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write(b"null")?;
+        self.after_value()
+    }
+
+    /// Write an already serialized value, such as a cached blob, as it is.
+    /// The fragment isn't parsed or checked, it must be a single valid JSON
+    /// value for the output to be valid, and it keeps its own formatting
+    /// when pretty printing.
+    ///
+    /// ```
+    /// # use json::JsonWriter;
+    /// let cached = r#"{"id":7,"tags":["a"]}"#;
+    /// let mut writer = JsonWriter::new(Vec::new());
+    ///
+    /// writer.begin_object().unwrap();
+    /// writer.key("status").unwrap();
+    /// writer.string("ok").unwrap();
+    /// writer.key("data").unwrap();
+    /// writer.raw(cached).unwrap();
+    /// writer.end_object().unwrap();
+    ///
+    /// assert_eq!(writer.finish().unwrap(), br#"{"status":"ok","data":{"id":7,"tags":["a"]}}"#);
+    /// ```
+    pub fn raw(&mut self, fragment: &str) -> io::Result<()> {
+        self.before_value()?;
+        self.sink.write(fragment.as_bytes())?;
+        self.after_value()
+    }
+
+    /// Check that the document is complete, flush and return the
+    /// underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.done {
+            return Err(invalid("Incomplete JSON document"));
+        }
+        self.sink.writer.flush()?;
+        Ok(self.sink.writer)
+    }
+
+    // Writes separators required before a new value and checks that a value
+    // is allowed in the current position.
+    fn before_value(&mut self) -> io::Result<()> {
+        match self.stack.last_mut() {
+            None if self.done => Err(invalid("JSON document is already complete")),
+            None => Ok(()),
+            Some(&mut Frame::Object { key: true, .. }) => Ok(()),
+            Some(&mut Frame::Object { .. }) => Err(invalid("Expected an object key")),
+            Some(&mut Frame::Array { ref mut first }) => {
+                if !*first {
+                    self.sink.write_char(b',')?;
+                }
+                *first = false;
+                self.sink.new_line()
+            },
+        }
+    }
+
+    fn after_value(&mut self) -> io::Result<()> {
+        match self.stack.last_mut() {
+            None => {
+                self.done = true;
+                if self.sink.options.trailing_newline {
+                    self.sink.write_char(b'\n')?;
+                }
+            },
+            Some(&mut Frame::Object { ref mut key, .. }) => *key = false,
+            Some(&mut Frame::Array { .. }) => {},
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, empty: bool, ch: u8) -> io::Result<()> {
+        if !empty && self.sink.options.trailing_commas {
+            self.sink.write_char(b',')?;
+        }
+        self.stack.pop();
+        self.sink.dedent();
+        if !empty {
+            self.sink.new_line()?;
+        }
+        self.sink.write_char(ch)?;
+        self.after_value()
+    }
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+// Generator owning its writer, pretty printing when `pretty` is set.
+struct Sink<W: Write> {
+    writer: W,
+    dent: u16,
+    closing: bool,
+    pretty: Option<u16>,
+    options: GeneratorOptions,
+}
+
+impl<W: Write> Generator for Sink<W> {
+    type T = W;
+
+    #[inline(always)]
+    fn get_writer(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    #[inline(always)]
+    fn options(&self) -> &GeneratorOptions {
+        &self.options
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: u8) -> io::Result<()> {
+        self.closing = false;
+        self.writer.write_all(&[ch])
+    }
+
+    #[inline(always)]
+    fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()> {
+        match self.pretty {
+            Some(_) if !codegen::collapsed(&self.options, self.dent, false) => self.writer.write_all(slice),
+            _                                                               => self.writer.write_all(&[min]),
+        }
+    }
+
+    fn new_line(&mut self) -> io::Result<()> {
+        if codegen::collapsed(&self.options, self.dent, self.closing) {
+            return Ok(());
+        }
+        if let Some(spaces) = self.pretty {
+            self.write_char(b'\n')?;
+            match self.options.indent {
+                Some(indent) => for _ in 0..self.dent {
+                    self.writer.write_all(indent.as_bytes())?;
+                },
+                None => for _ in 0..(self.dent * spaces) {
+                    self.write_char(b' ')?;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn indent(&mut self) {
+        self.dent += 1;
+    }
+
+    fn dedent(&mut self) {
+        self.dent -= 1;
+        self.closing = true;
+    }
+}
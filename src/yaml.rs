@@ -0,0 +1,165 @@
+//! Conversion between `JsonValue` and YAML, for programs accepting both
+//! formats. Requires the `yaml` feature.
+//!
+//! ```
+//! # #[macro_use] extern crate json;
+//! # fn main() {
+//! let config = json::yaml::parse("
+//! name: app
+//! ports:
+//!   - 80
+//!   - 443
+//! debug: false
+//! ").unwrap();
+//!
+//! assert_eq!(config, object!{
+//!     "name" => "app",
+//!     "ports" => array![80, 443],
+//!     "debug" => false
+//! });
+//!
+//! assert_eq!(json::yaml::stringify(&config), "name: app\nports:\n  - 80\n  - 443\ndebug: false\n");
+//! # }
+//! ```
+
+use std::fmt;
+
+use yaml_rust2::{ Yaml, YamlLoader, YamlEmitter };
+use yaml_rust2::yaml::Hash;
+
+use crate::JsonValue;
+use crate::base64;
+use crate::object::Object;
+use crate::number::Number;
+
+/// Error returned when a YAML document can't be converted to `JsonValue`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The document is not valid YAML.
+    Syntax(String),
+
+    /// The document uses a YAML feature that has no JSON counterpart, such
+    /// as sequences or mappings used as mapping keys.
+    Unsupported(&'static str),
+
+    /// The source contains more than one YAML document.
+    MultipleDocuments,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Syntax(ref message)     => write!(f, "Invalid YAML: {}", message),
+            Error::Unsupported(feature)    => write!(f, "Unsupported YAML: {}", feature),
+            Error::MultipleDocuments       => write!(f, "Expected a single YAML document"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Parse a single YAML document. An empty document is `null`.
+///
+/// Mapping keys that are numbers, booleans or `null` are converted to their
+/// string form, YAML floats such as `.inf` that JSON can't represent become
+/// NaN.
+pub fn parse(source: &str) -> Result<JsonValue, Error> {
+    let mut documents = YamlLoader::load_from_str(source)
+        .map_err(|err| Error::Syntax(err.to_string()))?;
+
+    if documents.len() > 1 {
+        return Err(Error::MultipleDocuments);
+    }
+
+    match documents.pop() {
+        Some(document) => from_yaml(document),
+        None           => Ok(JsonValue::Null),
+    }
+}
+
+/// Serialize `value` as a YAML document, ending with a newline.
+pub fn stringify(value: &JsonValue) -> String {
+    let mut out = String::new();
+
+    YamlEmitter::new(&mut out).dump(&to_yaml(value)).expect("Can't fail");
+
+    // Drop the document start marker, it's optional for single documents
+    let mut out = match out.strip_prefix("---\n") {
+        Some(body) => body.to_owned(),
+        None       => out,
+    };
+    out.push('\n');
+    out
+}
+
+fn from_yaml(yaml: Yaml) -> Result<JsonValue, Error> {
+    Ok(match yaml {
+        Yaml::Null            => JsonValue::Null,
+        Yaml::Boolean(value)  => value.into(),
+        Yaml::Integer(value)  => value.into(),
+        Yaml::String(value)   => value.into(),
+        Yaml::Real(ref value) => match crate::parse(value) {
+            Ok(number @ JsonValue::Number(_)) => number,
+            _ => match yaml.as_f64() {
+                Some(value) => value.into(),
+                None        => return Err(Error::Syntax(format!("Invalid number: {}", value))),
+            },
+        },
+        Yaml::Array(array) => {
+            JsonValue::Array(array.into_iter().map(from_yaml).collect::<Result<_, _>>()?)
+        },
+        Yaml::Hash(hash) => {
+            let mut object = Object::with_capacity(hash.len());
+
+            for (key, value) in hash {
+                let key = match key {
+                    Yaml::String(key)  => key,
+                    Yaml::Integer(key) => key.to_string(),
+                    Yaml::Real(key)    => key,
+                    Yaml::Boolean(key) => key.to_string(),
+                    Yaml::Null         => "null".to_owned(),
+                    _ => return Err(Error::Unsupported("Mapping keys must be scalars")),
+                };
+                object.insert(&key, from_yaml(value)?);
+            }
+
+            JsonValue::Object(object)
+        },
+        Yaml::Alias(_)  => return Err(Error::Unsupported("Aliases")),
+        Yaml::BadValue  => return Err(Error::Unsupported("Invalid value")),
+    })
+}
+
+fn to_yaml(value: &JsonValue) -> Yaml {
+    match *value {
+        JsonValue::Null               => Yaml::Null,
+        JsonValue::Boolean(value)     => Yaml::Boolean(value),
+        JsonValue::Short(ref short)   => Yaml::String(short.to_string()),
+        JsonValue::String(ref string) => Yaml::String(string.clone()),
+        JsonValue::Number(number)     => number_to_yaml(number),
+        JsonValue::Binary(ref bytes)  => Yaml::String(base64::encode(bytes)),
+        JsonValue::Array(ref array)   => Yaml::Array(array.iter().map(to_yaml).collect()),
+        JsonValue::Object(ref object) => {
+            let mut hash = Hash::new();
+            for (key, value) in object.iter() {
+                hash.insert(Yaml::String(key.to_owned()), to_yaml(value));
+            }
+            Yaml::Hash(hash)
+        },
+    }
+}
+
+fn number_to_yaml(number: Number) -> Yaml {
+    if number.is_nan() {
+        return Yaml::Real(".nan".to_owned());
+    }
+
+    let (positive, mantissa, exponent) = number.as_parts();
+
+    if exponent == 0 && mantissa <= i64::MAX as u64 {
+        let value = mantissa as i64;
+        return Yaml::Integer(if positive { value } else { -value });
+    }
+
+    Yaml::Real(number.to_string())
+}
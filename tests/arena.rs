@@ -0,0 +1,125 @@
+#![cfg(feature = "arena")]
+
+extern crate json;
+
+use json::{ JsonArena, ArenaValue, Error };
+
+#[test]
+fn arena_parse_matches_parse() {
+    let sources = [
+        "null",
+        "[]",
+        "{}",
+        r#""esc\"aped\né""#,
+        "-1.5e-3",
+        r#"[1,[2,[3,[]],{}],"x",{"a":[true,false,null]}]"#,
+        r#"{"a":{"b":{"c":[1,2,{"d":"e"}]},"f":[]},"g":-0,"h":"😀"}"#,
+    ];
+
+    let arena = JsonArena::new();
+
+    for source in sources.iter() {
+        let value = arena.parse(source).unwrap();
+
+        assert_eq!(value.to_json(), json::parse(source).unwrap(), "{}", source);
+    }
+}
+
+#[test]
+fn arena_lookups() {
+    let arena = JsonArena::new();
+    let data = arena.parse(r#"{"name":"Jon","tags":["a","b"],"age":30,"admin":true,"name":"Snow"}"#).unwrap();
+
+    assert!(data.is_object());
+    assert_eq!(data.len(), 5);
+    assert_eq!(data["name"].as_str(), Some("Snow"));
+    assert_eq!(data["tags"][1], ArenaValue::String("b"));
+    assert_eq!(data["tags"][2], ArenaValue::Null);
+    assert_eq!(data["age"].as_u64(), Some(30));
+    assert_eq!(data["age"].as_f64(), Some(30.0));
+    assert_eq!(data["admin"].as_bool(), Some(true));
+    assert_eq!(data["missing"]["nested"], ArenaValue::Null);
+    assert_eq!(data.get("tags").map(ArenaValue::type_name), Some("array"));
+
+    let keys: Vec<&str> = data.entries().map(|&(key, _)| key).collect();
+    assert_eq!(keys, ["name", "tags", "age", "admin", "name"]);
+
+    let tags: Vec<&str> = data["tags"].members().filter_map(ArenaValue::as_str).collect();
+    assert_eq!(tags, ["a", "b"]);
+
+    assert_eq!(data.to_json().dump(), r#"{"name":"Snow","tags":["a","b"],"age":30,"admin":true}"#);
+}
+
+#[test]
+fn arena_parse_errors() {
+    let arena = JsonArena::new();
+
+    assert_eq!(arena.parse("[1,2"), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(arena.parse(r#"{"a" 1}"#), Err(json::parse(r#"{"a" 1}"#).unwrap_err()));
+    assert_eq!(arena.parse("[1] x"), Err(json::parse("[1] x").unwrap_err()));
+
+    let source = format!("[0,[{}]]", r#"{"a":["#.repeat(300));
+    let error = arena.parse(&source).unwrap_err();
+
+    assert_eq!(error, json::parse(&source).unwrap_err());
+    assert_eq!(error.without_path(), &Error::ExceededDepthLimit);
+}
+
+#[test]
+fn arena_reset_reuses_memory() {
+    let mut arena = JsonArena::with_capacity(64);
+    let long = format!("\"{}\"", "x".repeat(100_000));
+
+    assert_eq!(arena.parse(&long).unwrap().as_str().map(str::len), Some(100_000));
+
+    let allocated = arena.allocated_bytes();
+    assert!(allocated >= 100_000);
+
+    arena.reset();
+    assert!(arena.allocated_bytes() >= 100_000);
+    assert!(arena.allocated_bytes() <= allocated);
+
+    for _ in 0 .. 100 {
+        let data = arena.parse(r#"{"id":1,"tags":["a","b","c"]}"#).unwrap();
+        assert_eq!(data["tags"].len(), 3);
+    }
+
+    let before = arena.allocated_bytes();
+    arena.reset();
+    arena.parse(r#"{"id":1,"tags":["a","b","c"]}"#).unwrap();
+    assert_eq!(arena.allocated_bytes(), before);
+}
+
+#[test]
+fn arena_alloc_dedup() {
+    let source = r#"{
+        "paths": [
+            {"get": {"name": "users", "schema": {"type": "object", "properties": {"id": {"type": "integer"}}}}},
+            {"get": {"name": "orders", "schema": {"type": "object", "properties": {"id": {"type": "integer"}}}}},
+            {"get": {"name": "items", "schema": {"type": "object", "properties": {"id": {"type": "integer"}}}}}
+        ],
+        "empty": [[], {}, [], {}],
+        "numbers": [1, 1.0, -0, 0]
+    }"#;
+    let value = json::parse(source).unwrap();
+
+    let parsed = JsonArena::new();
+    let parsed = parsed.parse(source).unwrap();
+
+    let arena = JsonArena::new();
+    let shared = arena.alloc_dedup(&value);
+
+    assert_eq!(shared, parsed);
+    assert_eq!(shared.to_json(), value);
+
+    let schemas: Vec<_> = shared["paths"].members().map(|path| path["get"]["schema"].entries().as_slice()).collect();
+
+    assert!(std::ptr::eq(schemas[0], schemas[1]));
+    assert!(std::ptr::eq(schemas[0], schemas[2]));
+    assert!(!std::ptr::eq(shared["paths"][0]["get"].entries().as_slice(), shared["paths"][1]["get"].entries().as_slice()));
+    assert!(std::ptr::eq(shared["paths"][0].entries().as_slice()[0].0, shared["paths"][1].entries().as_slice()[0].0));
+
+    assert!(shared["empty"][0].is_array());
+    assert!(shared["empty"][1].is_object());
+    assert_eq!(shared["numbers"].to_json().dump(), "[1,1.0,-0,0]");
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "async")]
+
+#[macro_use]
+extern crate json;
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ Context, Poll, Wake, Waker };
+
+use json::{ write_async, AsyncWrite, JsonValue };
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+// Accepts at most `limit` bytes per write, and every other poll isn't ready
+#[derive(Default)]
+struct Throttled {
+    out: Vec<u8>,
+    limit: usize,
+    polls: usize,
+    largest: usize,
+    flushed: bool,
+}
+
+impl AsyncWrite for Throttled {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.polls += 1;
+        if self.polls % 2 == 1 {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let count = buf.len().min(self.limit);
+        self.out.extend_from_slice(&buf[.. count]);
+        self.largest = self.largest.max(buf.len());
+        Poll::Ready(Ok(count))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.flushed = true;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn write_async_values() {
+    let values = vec![
+        object!{ "a" => array![1, "x\"y", JsonValue::Null], "b" => object!{}, "c\n" => 2.5 },
+        array![true, array![], object!{ "k" => false }],
+        array![],
+        "string".into(),
+        JsonValue::Null,
+    ];
+
+    for value in values {
+        let mut out = Vec::new();
+        block_on(write_async(&value, &mut out)).unwrap();
+
+        assert_eq!(out, value.dump().as_bytes());
+    }
+}
+
+#[test]
+fn write_async_chunks() {
+    let value: JsonValue = (0 .. 5000).map(|id| object!{ "id" => id, "name" => format!("item {}", id) }).collect::<Vec<_>>().into();
+    let mut writer = Throttled { limit: 1000, ..Throttled::default() };
+
+    block_on(write_async(&value, &mut writer)).unwrap();
+
+    assert_eq!(writer.out, value.dump().as_bytes());
+    assert!(writer.flushed);
+
+    // Written out in chunks while serializing, not all at the end
+    assert!(writer.largest < writer.out.len() / 4);
+}
+
+#[test]
+fn write_async_write_zero() {
+    let mut writer = Throttled { limit: 0, ..Throttled::default() };
+    let err = block_on(write_async(&array![1], &mut writer)).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    assert!(!writer.flushed);
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "base64")]
+
+extern crate json;
+
+use json::JsonValue;
+
+#[test]
+fn base64_round_trip() {
+    let cases: &[(&[u8], &str)] = &[
+        (b"", ""),
+        (b"f", "Zg=="),
+        (b"fo", "Zm8="),
+        (b"foo", "Zm9v"),
+        (b"foob", "Zm9vYg=="),
+        (b"fooba", "Zm9vYmE="),
+        (b"foobar", "Zm9vYmFy"),
+        (b"\xfb\xff\xfe", "+//+"),
+    ];
+
+    for &(bytes, encoded) in cases {
+        let value = JsonValue::from_bytes_base64(bytes);
+
+        assert_eq!(value, encoded);
+        assert_eq!(value.as_bytes_base64().as_deref(), Some(bytes));
+    }
+
+    let bytes: Vec<u8> = (0 ..= 255).collect();
+    assert_eq!(JsonValue::from_bytes_base64(&bytes).as_bytes_base64(), Some(bytes));
+}
+
+#[test]
+fn base64_decoding() {
+    let decode = |source: &str| JsonValue::from(source).as_bytes_base64();
+
+    assert_eq!(decode("Zg"), Some(b"f".to_vec()));
+    assert_eq!(decode("Zm8"), Some(b"fo".to_vec()));
+
+    for invalid in &["Z", "Zg=", "Zg===", "Z===", "Zh==", "Zm9=", "Zm9v\n", "Zm 9v", "Zm9v-_", "Z=g=", "=Zg="] {
+        assert_eq!(decode(invalid), None, "{}", invalid);
+    }
+
+    assert_eq!(JsonValue::from(3).as_bytes_base64(), None);
+    assert_eq!(JsonValue::Binary(vec![0xff]).as_bytes_base64(), Some(vec![0xff]));
+}
@@ -0,0 +1,97 @@
+#[macro_use]
+extern crate json;
+
+use std::env;
+use std::fs;
+use std::io;
+
+use json::config::Layers;
+
+#[test]
+fn config_layers() {
+    let config = Layers::new()
+        .layer("defaults", object!{
+            "name" => "app",
+            "hosts" => array!["a", "b"],
+            "db" => object!{ "host" => "localhost", "port" => 5432, "tls" => object!{ "verify" => true } },
+            "extra" => object!{}
+        })
+        .layer("site", object!{
+            "hosts" => array!["c"],
+            "db" => object!{ "port" => 6432, "tls" => json::Null },
+            "debug" => true
+        })
+        .load();
+
+    assert_eq!(config.value, object!{
+        "name" => "app",
+        "hosts" => array!["c"],
+        "db" => object!{ "host" => "localhost", "port" => 6432, "tls" => json::Null },
+        "extra" => object!{},
+        "debug" => true
+    });
+
+    let sources: Vec<_> = config.sources.iter().map(|(path, name)| (path.as_str(), name.as_str())).collect();
+    assert_eq!(sources, [
+        ("/db/host", "defaults"),
+        ("/db/port", "site"),
+        ("/db/tls", "site"),
+        ("/debug", "site"),
+        ("/extra", "defaults"),
+        ("/hosts", "site"),
+        ("/name", "defaults"),
+    ]);
+
+    assert_eq!(config.source("/hosts/0"), Some("site"));
+    assert_eq!(config.source("/db"), None);
+    assert_eq!(config.source("/missing"), None);
+}
+
+#[test]
+fn config_files() {
+    let dir = env::temp_dir().join(format!("json-config-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("app.json");
+    fs::write(&path, r#"{ "db": { "host": "db.internal" } }"#).unwrap();
+
+    let config = Layers::new()
+        .layer("defaults", object!{ "db" => object!{ "host" => "localhost", "port" => 5432 } })
+        .file(&path).unwrap()
+        .optional_file(dir.join("missing.json")).unwrap()
+        .load();
+
+    assert_eq!(config.value, object!{ "db" => object!{ "host" => "db.internal", "port" => 5432 } });
+    assert_eq!(config.source("/db/host"), Some(path.display().to_string().as_str()));
+
+    let err = Layers::new().file(dir.join("missing.json")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    fs::write(&path, "{ nope").unwrap();
+    let err = Layers::new().optional_file(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_env() {
+    env::set_var("JSON_CONFIG_TEST_DB__HOST", "db.internal");
+    env::set_var("JSON_CONFIG_TEST_DB__PORT", "6432");
+    env::set_var("JSON_CONFIG_TEST_FEATURES", "[\"a\", \"b\"]");
+    env::set_var("JSON_CONFIG_TEST_NAME", "not json");
+
+    let config = Layers::new()
+        .layer("defaults", object!{ "db" => object!{ "host" => "localhost", "user" => "app" }, "name" => "app" })
+        .env("JSON_CONFIG_TEST_")
+        .load();
+
+    assert_eq!(config.value, object!{
+        "db" => object!{ "host" => "db.internal", "user" => "app", "port" => 6432 },
+        "name" => "not json",
+        "features" => array!["a", "b"]
+    });
+    assert_eq!(config.source("/db/host"), Some("env"));
+    assert_eq!(config.source("/db/user"), Some("defaults"));
+    assert_eq!(config.source("/features/1"), Some("env"));
+}
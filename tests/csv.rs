@@ -0,0 +1,76 @@
+#![cfg(feature = "csv")]
+
+#[macro_use]
+extern crate json;
+
+use std::io;
+use json::{ to_csv, from_csv, Null };
+
+fn csv(value: &json::JsonValue) -> io::Result<String> {
+    let mut out = Vec::new();
+    to_csv(value, &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn csv_write() {
+    let data = array![
+        object!{ "id" => 1, "name" => "a", "note" => Null },
+        object!{ "id" => 2.5, "name" => "line\nbreak", "note" => "say \"hi\"" },
+        object!{ "name" => "c", "extra" => false }
+    ];
+
+    assert_eq!(csv(&data).unwrap(), concat!(
+        "id,name,note,extra\r\n",
+        "1,a,,\r\n",
+        "2.5,\"line\nbreak\",\"say \"\"hi\"\"\",\r\n",
+        ",c,,false\r\n"
+    ));
+}
+
+#[test]
+fn csv_write_empty() {
+    assert_eq!(csv(&array![]).unwrap(), "");
+}
+
+#[test]
+fn csv_write_invalid() {
+    assert_eq!(csv(&object!{}).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(csv(&array![1]).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(csv(&array![object!{ "a" => array![] }]).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn csv_read() {
+    let source = "a,b\r\n1,\"x,y\"\r\n\"\",\"multi\nline\"\n3,4";
+
+    assert_eq!(from_csv(source.as_bytes()).unwrap(), array![
+        object!{ "a" => "1", "b" => "x,y" },
+        object!{ "a" => "", "b" => "multi\nline" },
+        object!{ "a" => "3", "b" => "4" }
+    ]);
+}
+
+#[test]
+fn csv_read_empty() {
+    assert_eq!(from_csv(&b""[..]).unwrap(), array![]);
+    assert_eq!(from_csv(&b"a,b\n"[..]).unwrap(), array![]);
+}
+
+#[test]
+fn csv_read_invalid() {
+    assert_eq!(from_csv(&b"a,b\n1\n"[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    assert_eq!(from_csv(&b"a\n\"open\n"[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    assert_eq!(from_csv(&b"a\n\"x\"y\n"[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    assert_eq!(from_csv(&b"a\n\xFF\n"[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn csv_round_trip() {
+    let data = array![
+        object!{ "k" => "v, \"quoted\"", "n" => "\r\n" },
+        object!{ "k" => "", "n" => "ż" }
+    ];
+
+    assert_eq!(from_csv(csv(&data).unwrap().as_bytes()).unwrap(), data);
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "datetime")]
+
+extern crate json;
+
+use std::convert::TryFrom;
+
+use json::chrono::{ DateTime, Datelike, FixedOffset, NaiveDate, Timelike, Utc };
+use json::{ Error, JsonValue };
+
+fn date(source: &str) -> Option<NaiveDate> {
+    JsonValue::from(source).as_date()
+}
+
+fn datetime(source: &str) -> Option<DateTime<FixedOffset>> {
+    JsonValue::from(source).as_datetime()
+}
+
+#[test]
+fn parse_dates() {
+    assert_eq!(date("2024-02-29"), NaiveDate::from_ymd_opt(2024, 2, 29));
+    assert_eq!(date("2000-02-29").map(|date| date.day()), Some(29));
+    assert_eq!(date("1900-02-29"), None);
+    assert_eq!(date("2023-04-31"), None);
+    assert_eq!(date("2023-13-01"), None);
+    assert_eq!(date("2023-00-01"), None);
+    assert_eq!(date("2023-1-01"), None);
+    assert_eq!(date("2023-01-01T00:00:00Z"), None);
+    assert_eq!(date("+023-01-01"), None);
+}
+
+#[test]
+fn parse_datetimes() {
+    let time = datetime("1985-04-12T23:20:50.52Z").unwrap();
+
+    assert_eq!((time.year(), time.month(), time.day()), (1985, 4, 12));
+    assert_eq!((time.hour(), time.minute(), time.second()), (23, 20, 50));
+    assert_eq!(time.nanosecond(), 520_000_000);
+    assert_eq!(time.offset().local_minus_utc(), 0);
+    assert_eq!(time.timestamp(), 482196050);
+
+    let time = datetime("1996-12-19t16:39:57-08:00").unwrap();
+    assert_eq!(time.offset().local_minus_utc(), -8 * 3600);
+    assert_eq!(time, datetime("1996-12-20T00:39:57Z").unwrap());
+
+    assert_eq!(datetime("1969-12-31T23:59:59Z").map(|time| time.timestamp()), Some(-1));
+    assert_eq!(datetime("2024-01-01T00:00:00.1234567891Z").map(|time| time.nanosecond()), Some(123_456_789));
+
+    for invalid in &[
+        "2024-01-01T00:00:00",
+        "2024-01-01T24:00:00Z",
+        "2024-01-01T00:60:00Z",
+        "2024-01-01T00:00:61Z",
+        "2024-01-01T00:00:00.Z",
+        "2024-01-01T00:00:00+0100",
+        "2024-01-01T00:00:00+24:00",
+        "2024-01-01T00:00:00Zx",
+        "2024-02-30T00:00:00Z",
+    ] {
+        assert_eq!(datetime(invalid), None, "{}", invalid);
+    }
+}
+
+#[test]
+fn format_datetimes() {
+    for source in &[
+        "1985-04-12T23:20:50.520Z",
+        "1996-12-19T16:39:57-08:00",
+        "2024-02-29T13:45:00.000000001+05:30",
+        "0001-01-01T00:00:00Z",
+    ] {
+        assert_eq!(JsonValue::from(datetime(source).unwrap()), *source);
+    }
+
+    assert_eq!(JsonValue::from(datetime("2024-01-01t00:00:00.100-00:00").unwrap()), "2024-01-01T00:00:00.100Z");
+    assert_eq!(JsonValue::from(DateTime::<Utc>::from_timestamp(1709210700, 0).unwrap()), "2024-02-29T12:45:00Z");
+}
+
+#[test]
+fn json_conversions() {
+    let data = json::parse(r#"{"at":"2024-02-29T13:45:00Z","on":"2024-02-29","bad":"yesterday","n":1}"#).unwrap();
+
+    assert_eq!(data["at"].as_datetime().map(|time| time.hour()), Some(13));
+    assert_eq!(data["on"].as_date(), NaiveDate::from_ymd_opt(2024, 2, 29));
+    assert_eq!(data["on"].as_datetime(), None);
+    assert_eq!(data["bad"].as_date(), None);
+    assert_eq!(data["n"].as_datetime(), None);
+
+    let time = data["at"].as_datetime().unwrap();
+    assert_eq!(JsonValue::from(time), data["at"]);
+    assert_eq!(JsonValue::from(time.date_naive()), data["on"]);
+
+    assert_eq!(DateTime::try_from(&data["at"]), Ok(time));
+    assert_eq!(NaiveDate::try_from(&data["bad"]), Err(Error::WrongType {
+        expected: "date".into(),
+        found: r#""yesterday""#.into(),
+        path: String::new(),
+    }));
+    assert_eq!(data.try_get_path_as::<DateTime<FixedOffset>>(&["n"]).unwrap_err().to_string(), "Wrong type, expected date-time, found number at /n");
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "decimal")]
+
+#[macro_use]
+extern crate json;
+
+use std::convert::TryFrom;
+
+use json::rust_decimal::Decimal;
+use json::number::Number;
+use json::{ Error, JsonValue };
+
+fn decimal(source: &str) -> Decimal {
+    source.parse().unwrap_or_else(|_| panic!("{}", source))
+}
+
+fn parsed(source: &str) -> Option<Decimal> {
+    json::parse(source).unwrap().as_decimal()
+}
+
+#[test]
+fn parse_decimals() {
+    assert_eq!(parsed("12.50").map(|value| (value.mantissa(), value.scale())), Some((1250, 2)));
+    assert_eq!(parsed("-0.05").unwrap().to_string(), "-0.05");
+    assert_eq!(parsed("1.5e-3").unwrap().to_string(), "0.0015");
+    assert_eq!(parsed("1.5E+3").unwrap().to_string(), "1500");
+    assert_eq!(parsed("0e-40").unwrap().to_string(), "0.0000000000000000000000000000");
+    assert_eq!(parsed("79228162514264337593543950335").unwrap().to_string(), "79228162514264337593543950335");
+
+    for out_of_range in &[
+        "79228162514264337593543950336",
+        "0.00000000000000000000000000001",
+        "1e29",
+        "1e9223372036854775807",
+    ] {
+        assert_eq!(parsed(out_of_range), None, "{}", out_of_range);
+    }
+}
+
+#[test]
+fn decimal_values() {
+    let data = json::parse(r#"{"price": 19.90, "fee": -0.05, "count": 3, "big": 170141183460469231731687303715884105727, "tiny": 1e-30}"#).unwrap();
+
+    assert_eq!(data["price"].as_decimal(), Some(Decimal::new(1990, 2)));
+    assert_eq!(data["price"].as_decimal().unwrap().to_string(), "19.90");
+    assert_eq!(data["fee"].as_decimal(), Some(Decimal::new(-5, 2)));
+    assert_eq!(data["count"].as_decimal(), Some(Decimal::new(3, 0)));
+    assert_eq!(data["big"].as_decimal(), None);
+    assert_eq!(data["tiny"].as_decimal(), None);
+    assert_eq!(JsonValue::from("1.5").as_decimal(), None);
+    assert_eq!(JsonValue::from(f64::NAN).as_decimal(), None);
+    assert_eq!(json::parse("0.1").unwrap().as_decimal().unwrap(), decimal("0.1"));
+
+    let total = object!{ "total" => decimal("1234.560"), "big" => decimal("-79228162514264337593543950335") };
+    assert_eq!(total.dump(), r#"{"total":1234.560,"big":-79228162514264337593543950335}"#);
+    assert_eq!(total["total"], decimal("1234.56"));
+
+    assert_eq!(Number::from(decimal("0.3")), Number::from_parts(true, 3, -1));
+    assert_eq!(Number::from(decimal("79228162514264337593543950.335")), Number::from_parts(true, 7922816251426433759, 7));
+
+    assert_eq!(Decimal::try_from(&data["price"]).unwrap(), Decimal::new(199, 1));
+    assert!(matches!(
+        Decimal::try_from(&data["big"]),
+        Err(Error::WrongType { ref expected, .. }) if expected == "decimal"
+    ));
+    assert!(matches!(
+        Decimal::try_from(&JsonValue::from("1.5")),
+        Err(Error::WrongType { ref expected, .. }) if expected == "number"
+    ));
+}
@@ -0,0 +1,175 @@
+#[macro_use]
+extern crate json;
+
+use std::panic;
+
+use json::{ diff, compare, merge3, Conflict, Difference, JsonValue, Null };
+use json::diff::CompareOpts;
+
+fn describe(left: &JsonValue, right: &JsonValue) -> Vec<String> {
+    diff(left, right).iter().map(ToString::to_string).collect()
+}
+
+fn panic_message<F: FnOnce() + panic::UnwindSafe>(f: F) -> String {
+    let error = panic::catch_unwind(f).unwrap_err();
+
+    match error.downcast::<String>() {
+        Ok(message) => *message,
+        Err(_)      => panic!("no message"),
+    }
+}
+
+#[test]
+fn diff_values() {
+    let left = json::parse(r#"{"a": 1, "b": [1, 2, 3], "c": {"d": null}, "e": "x"}"#).unwrap();
+    let right = json::parse(r#"{"e": "x", "a": 1.0, "b": [1, 5], "c": {"d": false}, "f": []}"#).unwrap();
+
+    assert_eq!(describe(&left, &left), Vec::<String>::new());
+    assert_eq!(describe(&left, &right), [
+        "/b/1: 2 != 5",
+        "/b/2: 3 != (missing)",
+        "/c/d: null != false",
+        "/f: (missing) != []",
+    ]);
+
+    let differences = diff(&left, &right);
+    assert_eq!(differences[0], Difference {
+        pointer: "/b/1".into(),
+        left: Some(&left["b"][1]),
+        right: Some(&right["b"][1]),
+    });
+
+    assert_eq!(describe(&array![1], &object!{}), ["(root): [1] != {}"]);
+    assert_eq!(describe(&"a".into(), &"a".to_string().into()), Vec::<String>::new());
+    assert_eq!(
+        describe(&object!{ "a/b" => object!{ "~" => 1 } }, &object!{ "a/b" => object!{ "~" => 2 } }),
+        ["/a~1b/~0: 1 != 2"]
+    );
+
+    let long = JsonValue::from("x".repeat(100));
+    assert_eq!(describe(&long, &Null), [format!(r#"(root): "{}…" != null"#, "x".repeat(60))]);
+}
+
+#[test]
+fn compare_with_options() {
+    let describe = |left: &JsonValue, right: &JsonValue, opts| -> Vec<String> {
+        compare(left, right, opts).iter().map(ToString::to_string).collect()
+    };
+
+    let left = json::parse(r#"{"id": "a1", "items": [{"n": 1, "at": 5}, {"n": 2}, {"n": 2}], "total": 0.3}"#).unwrap();
+    let right = json::parse(r#"{"id": "f7", "items": [{"n": 2}, {"n": 1, "at": 9}, {"n": 3}], "total": 0.30000001}"#).unwrap();
+
+    assert_eq!(compare(&left, &right, CompareOpts::default()), diff(&left, &right));
+
+    let opts = CompareOpts {
+        ignore_array_order: true,
+        ignore_keys: &["id", "at"],
+        numeric_epsilon: 1e-6,
+    };
+
+    assert_eq!(describe(&left, &right, opts), [
+        r#"/items/2: {"n":2} != (missing)"#,
+        r#"/items/2: (missing) != {"n":3}"#,
+    ]);
+
+    let opts = CompareOpts { ignore_keys: &["id", "at"], ..CompareOpts::default() };
+
+    assert_eq!(describe(&left, &right, opts), [
+        "/items/0/n: 1 != 2",
+        "/items/1/n: 2 != 1",
+        "/items/2/n: 2 != 3",
+        "/total: 0.3 != 0.30000001",
+    ]);
+
+    let opts = CompareOpts { numeric_epsilon: 0.5, ..CompareOpts::default() };
+
+    assert!(describe(&array![1, 2.25], &array![1.5, 2], opts).is_empty());
+    assert_eq!(describe(&array![1], &array![2], opts), ["/0: 1 != 2"]);
+    assert_eq!(describe(&array![1], &array!["1"], opts), [r#"/0: 1 != "1""#]);
+
+    let opts = CompareOpts { ignore_array_order: true, ..CompareOpts::default() };
+
+    assert!(describe(&array![array![1, 2], 3], &array![3, array![2, 1]], opts).is_empty());
+    assert_eq!(describe(&array![1, 1, 2], &array![1, 2, 2], opts), ["/1: 1 != (missing)", "/2: (missing) != 2"]);
+}
+
+#[test]
+fn assert_json_eq_passes() {
+    let value = json::parse(r#"{"id": 7, "tags": ["new"]}"#).unwrap();
+
+    assert_json_eq!(value, object!{ "tags" => array!["new"], "id" => 7 });
+    assert_json_eq!(&value, &value.clone());
+    assert_json_eq!(value["id"], 7.0);
+    assert_json_eq!(value["tags"][0], "new", "with a {}", "message");
+    assert_json_eq!(Null, value["missing"]);
+}
+
+#[test]
+fn assert_json_eq_failures() {
+    let message = panic_message(|| {
+        assert_json_eq!(object!{ "a" => 1, "b" => array![true] }, object!{ "a" => 2, "b" => array![] });
+    });
+
+    assert_eq!(message, "assertion `left == right` failed\n    /a: 1 != 2\n    /b/0: true != (missing)");
+
+    let message = panic_message(|| {
+        assert_json_eq!(array![1], array![2], "request {}", 3);
+    });
+
+    assert_eq!(message, "assertion `left == right` failed: request 3\n    /0: 1 != 2");
+}
+
+#[test]
+fn merge3_values() {
+    let base = json::parse(r#"{"name": "app", "port": 80, "tags": ["a"], "db": {"host": "h", "pool": 4}, "old": 1, "gone": 2}"#).unwrap();
+    let ours = json::parse(r#"{"name": "app", "port": 8080, "tags": ["a", "b"], "db": {"host": "h", "pool": 8}, "gone": 2, "new": true}"#).unwrap();
+    let theirs = json::parse(r#"{"name": "api", "port": 81, "tags": ["a", "b"], "db": {"host": "h2", "pool": 4}, "old": 1, "added": {"x": 1}}"#).unwrap();
+
+    let merge = merge3(&base, &ours, &theirs);
+
+    assert_eq!(merge.value, json::parse(
+        r#"{"name": "api", "port": 8080, "tags": ["a", "b"], "db": {"host": "h2", "pool": 8}, "new": true, "added": {"x": 1}}"#
+    ).unwrap());
+    assert_eq!(merge.conflicts, [Conflict {
+        pointer: "/port".into(),
+        base: Some(&base["port"]),
+        ours: Some(&ours["port"]),
+        theirs: Some(&theirs["port"]),
+    }]);
+
+    let clean = merge3(&base, &ours, &base);
+    assert_eq!(clean.value, ours);
+    assert!(clean.conflicts.is_empty());
+}
+
+#[test]
+fn merge3_conflicts() {
+    let describe = |base: JsonValue, ours: JsonValue, theirs: JsonValue| -> (JsonValue, Vec<String>) {
+        let merge = merge3(&base, &ours, &theirs);
+        let conflicts = merge.conflicts.iter().map(ToString::to_string).collect();
+
+        (merge.value, conflicts)
+    };
+
+    // Removed on one side, changed on the other
+    assert_eq!(
+        describe(object!{ "a" => 1 }, object!{}, object!{ "a" => 2 }),
+        (object!{}, vec!["/a: (missing) != 2 (base: 1)".to_string()])
+    );
+
+    // Added on both sides, merged where they agree
+    assert_eq!(
+        describe(object!{}, object!{ "a" => object!{ "x" => 1, "y" => 2 } }, object!{ "a" => object!{ "x" => 1, "y" => 3, "z" => 4 } }),
+        (object!{ "a" => object!{ "x" => 1, "y" => 2, "z" => 4 } }, vec!["/a/y: 2 != 3 (base: (missing))".to_string()])
+    );
+
+    // Arrays and values of different types conflict as a whole
+    assert_eq!(
+        describe(array![1, 2], array![0, 2], array![1, 3]),
+        (array![0, 2], vec!["(root): [0,2] != [1,3] (base: [1,2])".to_string()])
+    );
+    assert_eq!(
+        describe(object!{ "a" => 1 }, array![], Null),
+        (array![], vec![r#"(root): [] != null (base: {"a":1})"#.to_string()])
+    );
+}
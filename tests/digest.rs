@@ -0,0 +1,72 @@
+#![cfg(feature = "digest")]
+
+#[macro_use]
+extern crate json;
+
+use json::JsonValue;
+
+fn hex(value: &JsonValue) -> String {
+    value.digest_sha256().unwrap().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn digest_canonical_form() {
+    let cases = [
+        // [1e+21,100000000000000000000,0.000001,1e-7,333333333.3333333,0,4.5,0.002,1e-27,-1.5e+300,9007199254740992]
+        (
+            "[1e21, 1e20, 0.000001, 1e-7, 333333333.33333329, -0, 4.50, 2e-3, 1e-27, -1.5e300, 9007199254740993]",
+            "e44950ab014f91bb688f69ead7e3d6a736d4f2b9fba4580eeb4f4467121e9716",
+        ),
+        // "€$\u000f\nA'B\"\\\\\"/\b\u001f"
+        (
+            r#""\u0080€$\u000F\nA'B\"\\\\\"\/\b\u001f""#,
+            "bc75ab102a6e4792f4727d8c133cb91a9423359d4d7c949877defb70d9de25b4",
+        ),
+        // {"\r":2,"1":4,"\u0080":6,"ö":7,"€":1,"😀":5,"דּ":3}, sorted by UTF-16 code units
+        (
+            r#"{"€": 1, "\r": 2, "דּ": 3, "1": 4, "😀": 5, "\u0080": 6, "ö": 7}"#,
+            "0d922ac8e15a6d17d5d50fab064983e86009ebae7b5998cbfc4ed172e6ff74a9",
+        ),
+        // {"a":[],"b":{"y":[true,false,{}],"z":null}}
+        (
+            r#"{ "b" : { "z": null, "y": [true, false, {}] }, "a": [] }"#,
+            "a70911caa48507dce6c4fc8395c035047b0759b144f2e97f142d92012ef72360",
+        ),
+    ];
+
+    for (source, digest) in cases.iter() {
+        assert_eq!(hex(&json::parse(source).unwrap()), *digest, "{}", source);
+    }
+}
+
+#[test]
+fn digest_block_boundaries() {
+    let cases = [
+        (53, "2ae89a8121a3f9d2709899b414da4c60234316951093ce35f41ce954a09533f4"),
+        (54, "9b68496ab8c784a9ed22d25a7e3aada1736d7097061bb3149f3d66f1e22ceeef"),
+        (62, "1cd1b6277a35426076612315059afa72e6903b84575ebdd1507d73697be9dbd3"),
+        (117, "9b40e0ad78bc52a3b16ada42ba7bb8f00e1ba071356abbcc1a159715b57f7ff8"),
+    ];
+
+    for (len, digest) in cases.iter() {
+        assert_eq!(hex(&"a".repeat(*len).into()), *digest);
+    }
+}
+
+#[test]
+fn digest_ignores_formatting() {
+    let a = json::parse("{\"id\": 1, \"tags\": [\"x\"], \"meta\": {\"b\": 2.0, \"a\": null}}").unwrap();
+    let b = json::parse("{\"meta\":{\"a\":null,\"b\":2},\"tags\":[\"x\"],\"id\":1e0}").unwrap();
+
+    assert_eq!(a.digest_sha256(), b.digest_sha256());
+    assert_ne!(a.digest_sha256(), object!{ "id" => 2 }.digest_sha256());
+    assert_ne!(array![1, 2].digest_sha256(), array![2, 1].digest_sha256());
+}
+
+#[test]
+fn digest_non_finite() {
+    assert_eq!(JsonValue::from(f64::NAN).digest_sha256(), None);
+    assert_eq!(array![1, f64::INFINITY].digest_sha256(), None);
+    assert_eq!(json::parse(r#"{"a":[1e400]}"#).unwrap().digest_sha256(), None);
+    assert!(JsonValue::Null.digest_sha256().is_some());
+}
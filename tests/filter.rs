@@ -0,0 +1,173 @@
+#[macro_use]
+extern crate json;
+
+use json::{ Filter, Error, JsonValue };
+
+fn run(value: &JsonValue, filter: &str) -> String {
+    match json::filter(value, filter) {
+        Ok(values) => values.iter().map(JsonValue::dump).collect::<Vec<_>>().join(" "),
+        Err(error) => panic!("{}: {}", filter, error),
+    }
+}
+
+fn data() -> JsonValue {
+    json::parse(r#"{
+        "store": "corner",
+        "items": [
+            {"name": "pen", "price": 5, "tags": ["office"]},
+            {"name": "book", "price": 12, "tags": ["office", "gift"]},
+            {"name": "lamp", "price": 30, "tags": [], "stock": null}
+        ],
+        "odd key": {"a.b": true}
+    }"#).unwrap()
+}
+
+#[test]
+fn filter_paths() {
+    let data = data();
+
+    assert_eq!(run(&data, "."), data.dump());
+    assert_eq!(run(&data, ".store"), r#""corner""#);
+    assert_eq!(run(&data, r#"."odd key"."a.b""#), "true");
+    assert_eq!(run(&data, r#".["odd key"]["a.b"]"#), "true");
+    assert_eq!(run(&data, ".items[0].name"), r#""pen""#);
+    assert_eq!(run(&data, ".items[-1].name"), r#""lamp""#);
+    assert_eq!(run(&data, ".items[5]"), "null");
+    assert_eq!(run(&data, ".items[].name"), r#""pen" "book" "lamp""#);
+    assert_eq!(run(&data, ".items[].tags[]"), r#""office" "office" "gift""#);
+    assert_eq!(run(&data, ".items | .[1] | .price"), "12");
+    assert_eq!(run(&data, ".items[.items[0].price - 4].name"), r#""book""#);
+    assert_eq!(run(&data, ".missing.deeper"), "null");
+    assert_eq!(run(&data, ".items[2].stock.count"), "null");
+    assert_eq!(run(&data, "[.. | .price? | select(. != null)]"), "[5,12,30]");
+}
+
+#[test]
+fn filter_construction() {
+    let data = data();
+
+    assert_eq!(run(&data, "[.items[] | .price]"), "[5,12,30]");
+    assert_eq!(run(&data, "[]"), "[]");
+    assert_eq!(run(&data, "{}"), "{}");
+    assert_eq!(run(&data, ".store, .items[0].price"), r#""corner" 5"#);
+    assert_eq!(run(&data, ".items[0] | {name, cost: .price, \"first tag\": .tags[0]}"), r#"{"name":"pen","cost":5,"first tag":"office"}"#);
+    assert_eq!(run(&data, "{(.store): 1}"), r#"{"corner":1}"#);
+    assert_eq!(run(&data, "{a: (1, 2), b: (3, 4)}"), r#"{"a":1,"b":3} {"a":1,"b":4} {"a":2,"b":3} {"a":2,"b":4}"#);
+    assert_eq!(run(&data, "{a: .store | length}"), r#"{"a":6}"#);
+    assert_eq!(run(&data, r#"[1, "a", null, true, -2.5]"#), r#"[1,"a",null,true,-2.5]"#);
+}
+
+#[test]
+fn filter_operators() {
+    let data = data();
+
+    assert_eq!(run(&data, ".items[] | select(.price > 10) | .name"), r#""book" "lamp""#);
+    assert_eq!(run(&data, ".items[] | select(.price >= 12 and (.tags | length) > 0) | .name"), r#""book""#);
+    assert_eq!(run(&data, ".items[] | select(.name == \"pen\" or .price == 30) | .name"), r#""pen" "lamp""#);
+    assert_eq!(run(&data, ".items[] | select(.price == 5 | not) | .name"), r#""book" "lamp""#);
+    assert_eq!(run(&data, "1 + 2 * 3 - 4 / 2"), "5");
+    assert_eq!(run(&data, "7 % 3, -(1 + 1), (1, 2) + (10, 20)"), "1 -2 11 12 21 22");
+    assert_eq!(run(&data, r#""a" + "b", null + 1, [1, 2] + [3], [1, 2, 1, 3] - [1]"#), r#""ab" 1 [1,2,3] [2,3]"#);
+    assert_eq!(run(&data, r#"{a: 1, b: 2} + {b: 3}"#), r#"{"a":1,"b":3}"#);
+    assert_eq!(run(&data, r#"null < false, false < true, true < 0, 0 < "", "" < [], [] < {}"#), "true true true true true true");
+    assert_eq!(run(&data, r#"[2] < [10], "b" > "a", {a: 1, b: 2} == {b: 2, a: 1}, 1 == 1.0"#), "true true true true");
+    assert_eq!(run(&data, "false and 1 / 0, true or 1 / 0, null and true"), "false true false");
+}
+
+#[test]
+fn filter_builtins() {
+    let data = data();
+
+    assert_eq!(run(&data, ".items | map(.price) | add"), "47");
+    assert_eq!(run(&data, ".items | map(.tags[]) | sort"), r#"["gift","office","office"]"#);
+    assert_eq!(run(&data, ".items[0] | keys"), r#"["name","price","tags"]"#);
+    assert_eq!(run(&data, ".items | keys"), "[0,1,2]");
+    assert_eq!(run(&data, ".items[2] | has(\"stock\"), has(\"weight\")"), "true false");
+    assert_eq!(run(&data, ".items | has(2), has(3)"), "true false");
+    assert_eq!(run(&data, ".store, .items, .items[0], null, -3 | length"), "6 3 3 0 3");
+    assert_eq!(run(&data, ".store, .items, 1, true, null | type"), r#""string" "array" "number" "boolean" "null""#);
+    assert_eq!(run(&data, ".items | first.name, last.name"), r#""pen" "lamp""#);
+    assert_eq!(run(&data, ".items[0].price | tostring"), r#""5""#);
+    assert_eq!(run(&data, r#""12.5" | tonumber"#), "12.5");
+    assert_eq!(run(&data, "[.items[] | empty]"), "[]");
+    assert_eq!(run(&data, "[] | add"), "null");
+}
+
+#[test]
+fn filter_runtime_errors() {
+    let data = data();
+
+    assert_eq!(json::filter(&data, ".items.name"), Err(Error::wrong_type("object", &data["items"])));
+    assert_eq!(json::filter(&data, ".store[0]"), Err(Error::wrong_type("array", &data["store"])));
+    assert_eq!(json::filter(&data, ".store[]"), Err(Error::wrong_type("array or object", &data["store"])));
+    assert_eq!(json::filter(&data, ".store + 1"), Err(Error::wrong_type("string", &1.into())));
+    assert_eq!(json::filter(&data, "1 / 0").unwrap_err().to_string(), "Wrong type, expected non-zero number, found number");
+    assert_eq!(json::filter(&data, "5 % 0.5").unwrap_err().to_string(), "Wrong type, expected non-zero number, found number");
+    assert_eq!(json::filter(&data, r#""x" | tonumber"#).unwrap_err().to_string(), "Wrong type, expected number or numeric string, found string");
+
+    assert_eq!(run(&data, ".items.name?"), "");
+    assert_eq!(run(&data, "[.items[] | .tags[0]?]"), r#"["office","office",null]"#);
+    assert_eq!(run(&data, "[.store, .items[0] | .[]?]"), r#"["pen",5,["office"]]"#);
+}
+
+#[test]
+fn filter_syntax_errors() {
+    let cases = [
+        (".items[", "unexpected end of filter", 7),
+        (".items | ", "unexpected end of filter", 9),
+        (".a )", "unexpected `)`", 3),
+        ("map(.a", "unexpected end of filter", 6),
+        ("select", "unknown function `select/0`", 0),
+        (".a | length(1)", "unknown function `length/1`", 5),
+        ("\"open", "unterminated string", 0),
+        (".a # b", "unexpected `#`", 3),
+        ("{a: 1, }", "unexpected `}`", 7),
+        ("{1: 2}", "unexpected `1`", 1),
+        (". and", "unexpected end of filter", 5),
+        ("and", "unexpected `and`", 0),
+        ("1 2", "unexpected `2`", 2),
+        (". .", "unexpected `.`", 2),
+    ];
+
+    for &(filter, message, position) in cases.iter() {
+        assert_eq!(
+            Filter::parse(filter).unwrap_err(),
+            Error::InvalidFilter { message: message.into(), position },
+            "{}",
+            filter
+        );
+    }
+
+    assert_eq!(
+        Filter::parse("(.a").unwrap_err().to_string(),
+        "Invalid filter: unexpected end of filter at 3"
+    );
+
+    let deep = format!("{}.{}", "[".repeat(100), "]".repeat(100));
+    assert_eq!(Filter::parse(&deep).unwrap_err(), Error::ExceededDepthLimit);
+
+    let nested = format!("{}.{}", "[".repeat(60), "]".repeat(60));
+    assert!(Filter::parse(&nested).is_ok());
+
+    let negated = "-".repeat(20_000) + "1";
+    assert_eq!(json::filter(&JsonValue::Null, &negated).unwrap_err(), Error::ExceededDepthLimit);
+    assert_eq!(json::filter(&JsonValue::Null, &("-".repeat(10) + "1")).unwrap(), [1]);
+
+    let long = vec!["1"; 1000].join(" + ");
+    assert_eq!(Filter::parse(&long).unwrap_err(), Error::ExceededDepthLimit);
+
+    let listed = format!("[{}] | length", vec!["1"; 1000].join(", "));
+    assert_eq!(json::filter(&JsonValue::Null, &listed).unwrap(), [1000]);
+
+    let chained = vec!["."; 60].join(" | ");
+    assert_eq!(json::filter(&JsonValue::from(1), &chained).unwrap(), [1]);
+}
+
+#[test]
+fn filter_reuse() {
+    let filter = Filter::parse(".id").unwrap();
+
+    assert_eq!(format!("{:?}", filter), r#"Filter(".id")"#);
+    assert_eq!(filter.apply(&object!{ "id" => 1 }).unwrap(), [1]);
+    assert_eq!(filter.apply(&object!{ "id" => "x" }).unwrap(), ["x"]);
+}
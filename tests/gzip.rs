@@ -0,0 +1,96 @@
+#![cfg(feature = "gzip")]
+
+#[macro_use]
+extern crate json;
+
+use std::io::{ self, Write };
+
+use json::{ parse_from_reader, JsonValue };
+
+// `{"ok": true, "n": [1, 2, 3]}` with fixed Huffman codes
+const FIXED: &[u8] = &[
+    0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFF, 0xAB, 0x56, 0xCA, 0xCF, 0x56, 0xB2, 0x52, 0x28, 0x29,
+    0x2A, 0x4D, 0xD5, 0x51, 0x50, 0xCA, 0x03, 0x32, 0xA3, 0x0D, 0x75, 0x14, 0x8C, 0x74, 0x14, 0x8C, 0x63, 0x6B, 0x01,
+    0xB0, 0x52, 0x2E, 0xD2, 0x1C, 0x00, 0x00, 0x00,
+];
+
+// The same, stored uncompressed with a file name in the header
+const STORED: &[u8] = &[
+    0x1F, 0x8B, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x64, 0x61, 0x74, 0x61, 0x2E, 0x6A, 0x73, 0x6F, 0x6E,
+    0x00, 0x01, 0x1C, 0x00, 0xE3, 0xFF, 0x7B, 0x22, 0x6F, 0x6B, 0x22, 0x3A, 0x20, 0x74, 0x72, 0x75, 0x65, 0x2C, 0x20,
+    0x22, 0x6E, 0x22, 0x3A, 0x20, 0x5B, 0x31, 0x2C, 0x20, 0x32, 0x2C, 0x20, 0x33, 0x5D, 0x7D, 0xB0, 0x52, 0x2E, 0xD2,
+    0x1C, 0x00, 0x00, 0x00,
+];
+
+// `[{"id": 0, "name": "item 0"}, ...]` up to 8, with dynamic Huffman codes
+const DYNAMIC: &[u8] = &[
+    0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFF, 0x5D, 0xC9, 0x2B, 0x0E, 0x80, 0x30, 0x10, 0x05, 0xC0,
+    0xAB, 0x6C, 0x56, 0x57, 0xF4, 0xDF, 0x86, 0xAB, 0x10, 0x44, 0x13, 0x2A, 0x2A, 0x8A, 0xC2, 0x91, 0xDE, 0x1D, 0x04,
+    0xE2, 0xE5, 0xB9, 0x49, 0x66, 0x7F, 0x74, 0x9C, 0xBA, 0x89, 0x35, 0xA2, 0x57, 0x9B, 0xFD, 0xA3, 0x8E, 0xBB, 0x4F,
+    0xB1, 0xBA, 0x8C, 0xFC, 0xE9, 0x38, 0x1D, 0xA4, 0xE7, 0xF4, 0x90, 0x81, 0x33, 0x40, 0x46, 0xCE, 0x08, 0x99, 0x38,
+    0x13, 0x64, 0xE6, 0xCC, 0x90, 0x85, 0xB3, 0x40, 0x56, 0xCE, 0xAA, 0xEB, 0x78, 0x01, 0xD2, 0x23, 0x27, 0x98, 0x05,
+    0x01, 0x00, 0x00,
+];
+
+// `[1, ` and `2]` as two members
+const MEMBERS: &[u8] = &[
+    0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFF, 0x8B, 0x36, 0xD4, 0x51, 0x00, 0x00, 0x7E, 0x93, 0xBE,
+    0x1A, 0x04, 0x00, 0x00, 0x00, 0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFF, 0x33, 0x8A, 0x05, 0x00,
+    0xC7, 0x6B, 0x73, 0xB9, 0x02, 0x00, 0x00, 0x00,
+];
+
+#[test]
+fn gzip_blocks() {
+    let expected = object!{ "ok" => true, "n" => array![1, 2, 3] };
+
+    assert_eq!(parse_from_reader(FIXED).unwrap(), expected);
+    assert_eq!(parse_from_reader(STORED).unwrap(), expected);
+
+    let items: Vec<JsonValue> = (0 .. 9).map(|id| object!{ "id" => id, "name" => format!("item {}", id) }).collect();
+    assert_eq!(parse_from_reader(DYNAMIC).unwrap(), JsonValue::Array(items));
+
+    assert_eq!(parse_from_reader(MEMBERS).unwrap(), array![1, 2]);
+}
+
+#[test]
+fn gzip_corrupt() {
+    let error = |data: &[u8]| parse_from_reader(data).unwrap_err();
+
+    let mut crc = FIXED.to_vec();
+    crc[FIXED.len() - 8] ^= 1;
+    assert_eq!(error(&crc).kind(), io::ErrorKind::InvalidData);
+
+    assert_eq!(error(&FIXED[.. 30]).kind(), io::ErrorKind::InvalidData);
+    assert_eq!(error(&DYNAMIC[.. 60]).kind(), io::ErrorKind::InvalidData);
+    assert_eq!(error(&MEMBERS[.. 30]).kind(), io::ErrorKind::InvalidData);
+
+    // Decompressed data must still be valid JSON
+    let err = error(&MEMBERS[.. 24]);
+    assert_eq!(*err.into_inner().unwrap().downcast::<json::Error>().unwrap(), json::Error::UnexpectedEndOfJson);
+}
+
+#[test]
+fn gzip_large() {
+    let value: JsonValue = (0 .. 10000).map(|id| object!{ "id" => id, "tags" => array!["a", "b"] }).collect::<Vec<_>>().into();
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(value.dump().as_bytes()).unwrap();
+
+    assert_eq!(parse_from_reader(&encoder.finish().unwrap()[..]).unwrap(), value);
+}
+
+#[test]
+fn zstd_frames() {
+    let value = object!{ "ok" => true, "n" => array![1, 2, 3] };
+    let frame = zstd::encode_all(value.dump().as_bytes(), 3).unwrap();
+
+    assert_eq!(parse_from_reader(&frame[..]).unwrap(), value);
+
+    // Frames are read one after another, like gzip members
+    let mut frames = zstd::encode_all(&b"[1, "[..], 3).unwrap();
+    frames.extend(zstd::encode_all(&b"2]"[..], 3).unwrap());
+    assert_eq!(parse_from_reader(&frames[..]).unwrap(), array![1, 2]);
+
+    let err = parse_from_reader(&frame[.. frame.len() - 3]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
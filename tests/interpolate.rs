@@ -0,0 +1,58 @@
+#[macro_use]
+extern crate json;
+
+use json::{ interpolate, JsonValue, Null };
+
+fn vars(name: &str) -> Option<JsonValue> {
+    match name {
+        "NAME"  => Some("app".into()),
+        "PORT"  => Some(8080.into()),
+        "DEBUG" => Some(true.into()),
+        "TAGS"  => Some(array!["a", "b"]),
+        "EMPTY" => Some("".into()),
+        "NULL"  => Some(Null),
+        _       => None,
+    }
+}
+
+#[test]
+fn interpolate_whole_strings() {
+    let mut data = array!["${PORT}", "${DEBUG}", "${TAGS}", "${NULL}", JsonValue::String("${NAME}".into())];
+
+    assert!(interpolate(&mut data, vars).is_empty());
+    assert_eq!(data, array![8080, true, array!["a", "b"], Null, "app"]);
+}
+
+#[test]
+fn interpolate_inside_strings() {
+    let mut data = object!{
+        "${NAME}" => "${NAME}-${PORT}.log",
+        "nested" => array![object!{ "flags" => "debug=${DEBUG} tags=${TAGS}" }],
+        "empty" => "[${EMPTY}]",
+        "both" => "${NAME}${NAME}",
+        "number" => 5
+    };
+
+    assert!(interpolate(&mut data, vars).is_empty());
+    assert_eq!(data, object!{
+        "${NAME}" => "app-8080.log",
+        "nested" => array![object!{ "flags" => r#"debug=true tags=["a","b"]"# }],
+        "empty" => "[]",
+        "both" => "appapp",
+        "number" => 5
+    });
+}
+
+#[test]
+fn interpolate_leftovers() {
+    let mut data = array!["${HOME}", "${HOME}/${NAME}/${USER}", "$${PORT} $$ $5 ${ ${} ${NAME", "${A{B}", "cost: $"];
+    let missing = interpolate(&mut data, vars);
+
+    assert_eq!(missing, ["HOME", "HOME", "USER"]);
+    assert_eq!(data, array!["${HOME}", "${HOME}/app/${USER}", "${PORT} $$ $5 ${ ${} ${NAME", "${A{B}", "cost: $"]);
+
+    let mut calls = 0;
+    let mut data = JsonValue::from("no placeholders");
+    interpolate(&mut data, |_| { calls += 1; None });
+    assert_eq!(calls, 0);
+}
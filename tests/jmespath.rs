@@ -0,0 +1,202 @@
+#![cfg(feature = "jmespath")]
+
+#[macro_use]
+extern crate json;
+
+use json::jmespath::{ self, Expression };
+use json::{ Error, JsonValue };
+
+fn run(value: &JsonValue, expression: &str) -> String {
+    match jmespath::search(value, expression) {
+        Ok(result) => result.dump(),
+        Err(error) => panic!("{}: {}", expression, error),
+    }
+}
+
+fn syntax_error(expression: &str) -> (String, usize) {
+    match Expression::compile(expression) {
+        Err(Error::InvalidFilter { message, position }) => (message, position),
+        other => panic!("{}: {:?}", expression, other),
+    }
+}
+
+fn data() -> JsonValue {
+    json::parse(r#"{
+        "store": "corner",
+        "items": [
+            {"name": "pen", "price": 5, "tags": ["office"]},
+            {"name": "book", "price": 12, "tags": ["office", "gift"]},
+            {"name": "lamp", "price": 30, "tags": [], "stock": null}
+        ],
+        "sizes": {"s": 1, "m": 2, "l": 3},
+        "nested": [[1, 2], [3], 4, [[5]]],
+        "odd key": {"a.b": true}
+    }"#).unwrap()
+}
+
+#[test]
+fn jmespath_paths() {
+    let data = data();
+
+    assert_eq!(run(&data, "@"), data.dump());
+    assert_eq!(run(&data, "store"), r#""corner""#);
+    assert_eq!(run(&data, r#""odd key"."a.b""#), "true");
+    assert_eq!(run(&data, "items[0].name"), r#""pen""#);
+    assert_eq!(run(&data, "items[-1].name"), r#""lamp""#);
+    assert_eq!(run(&data, "items[5]"), "null");
+    assert_eq!(run(&data, "missing.deeper"), "null");
+    assert_eq!(run(&data, "store[0]"), "null");
+    assert_eq!(run(&data, "items | [1] | price"), "12");
+}
+
+#[test]
+fn jmespath_projections() {
+    let data = data();
+
+    assert_eq!(run(&data, "items[*].name"), r#"["pen","book","lamp"]"#);
+    assert_eq!(run(&data, "items[*].stock"), "[]");
+    assert_eq!(run(&data, "items[].tags[]"), r#"["office","office","gift"]"#);
+    assert_eq!(run(&data, "items[*].tags[0]"), r#"["office","office"]"#);
+    assert_eq!(run(&data, "sizes.*"), "[1,2,3]");
+    assert_eq!(run(&data, "nested[]"), "[1,2,3,4,[5]]");
+    assert_eq!(run(&data, "nested[][]"), "[1,2,3,4,5]");
+    assert_eq!(run(&data, "items[*].name | [0]"), r#""pen""#);
+    assert_eq!(run(&data, "items[*].name[0]"), "[]");
+    assert_eq!(run(&data, "store[*]"), "null");
+
+    assert_eq!(run(&data, "items[?price > `10`].name"), r#"["book","lamp"]"#);
+    assert_eq!(run(&data, "items[?tags[?@ == 'gift']].name"), r#"["book"]"#);
+    assert_eq!(run(&data, "items[?name == 'pen' || price == `30`].name"), r#"["pen","lamp"]"#);
+    assert_eq!(run(&data, "items[?!tags].name"), r#"["lamp"]"#);
+    assert_eq!(run(&data, "items[?contains(tags, 'office')] | length(@)"), "2");
+}
+
+#[test]
+fn jmespath_slices() {
+    let data = json::parse("[0, 1, 2, 3, 4, 5]").unwrap();
+
+    assert_eq!(run(&data, "[1:3]"), "[1,2]");
+    assert_eq!(run(&data, "[:2]"), "[0,1]");
+    assert_eq!(run(&data, "[-2:]"), "[4,5]");
+    assert_eq!(run(&data, "[::2]"), "[0,2,4]");
+    assert_eq!(run(&data, "[::-1]"), "[5,4,3,2,1,0]");
+    assert_eq!(run(&data, "[4:1:-2]"), "[4,2]");
+    assert_eq!(run(&data, "[10:]"), "[]");
+    assert_eq!(run(&object!{ "a" => 1 }, "[1:]"), "null");
+}
+
+#[test]
+fn jmespath_multi_select_and_literals() {
+    let data = data();
+
+    assert_eq!(run(&data, "items[*].[name, price]"), r#"[["pen",5],["book",12],["lamp",30]]"#);
+    assert_eq!(run(&data, "items[0].{n: name, \"first tag\": tags[0]}"), r#"{"n":"pen","first tag":"office"}"#);
+    assert_eq!(run(&data, "missing.[a, b]"), "null");
+    assert_eq!(run(&data, "`{\"a\": [1, 2]}`.a[1]"), "2");
+    assert_eq!(run(&data, r"'it\'s a \\ \n'"), r#""it's a \\ \\n""#);
+    assert_eq!(run(&data, "`\"back\\`tick\"`"), r#""back`tick""#);
+    assert_eq!(run(&data, "missing || store"), r#""corner""#);
+    assert_eq!(run(&data, "store && sizes.m"), "2");
+    assert_eq!(run(&data, "`[]` && store"), "[]");
+    assert_eq!(run(&data, "!`0`"), "false");
+    assert_eq!(run(&data, "sizes.m == `2.0`"), "true");
+    assert_eq!(run(&data, "sizes == `{\"l\": 3, \"m\": 2, \"s\": 1}`"), "true");
+    assert_eq!(run(&data, "store < `1`"), "null");
+    assert_eq!(run(&data, "sizes.s <= sizes.m"), "true");
+}
+
+#[test]
+fn jmespath_functions() {
+    let data = data();
+
+    assert_eq!(run(&data, "length(items)"), "3");
+    assert_eq!(run(&data, "length('héllo')"), "5");
+    assert_eq!(run(&data, "keys(sizes)"), r#"["s","m","l"]"#);
+    assert_eq!(run(&data, "values(sizes)"), "[1,2,3]");
+    assert_eq!(run(&data, "sum(items[*].price)"), "47");
+    assert_eq!(run(&data, "avg(values(sizes))"), "2");
+    assert_eq!(run(&data, "avg(`[]`)"), "null");
+    assert_eq!(run(&data, "max(items[*].price)"), "30");
+    assert_eq!(run(&data, "min(items[*].name)"), r#""book""#);
+    assert_eq!(run(&data, "max_by(items, &price).name"), r#""lamp""#);
+    assert_eq!(run(&data, "min_by(items, &price).name"), r#""pen""#);
+    assert_eq!(run(&data, "sort_by(items, &name)[*].name"), r#"["book","lamp","pen"]"#);
+    assert_eq!(run(&data, "sort(items[*].price)"), "[5,12,30]");
+    assert_eq!(run(&data, "map(&length(tags), items)"), "[1,2,0]");
+    assert_eq!(run(&data, "join(', ', items[*].name)"), r#""pen, book, lamp""#);
+    assert_eq!(run(&data, "reverse(store)"), r#""renroc""#);
+    assert_eq!(run(&data, "reverse(sizes.*)"), "[3,2,1]");
+    assert_eq!(run(&data, "starts_with(store, 'cor')"), "true");
+    assert_eq!(run(&data, "ends_with(store, 'x')"), "false");
+    assert_eq!(run(&data, "contains(store, 'rn')"), "true");
+    assert_eq!(run(&data, "abs(`-2.5`)"), "2.5");
+    assert_eq!(run(&data, "[ceil(`1.2`), floor(`1.8`)]"), "[2,1]");
+    assert_eq!(run(&data, "not_null(missing, items[2].stock, store)"), r#""corner""#);
+    assert_eq!(run(&data, "merge(sizes, `{\"m\": 20, \"xl\": 4}`)"), r#"{"s":1,"m":20,"l":3,"xl":4}"#);
+    assert_eq!(run(&data, "[to_array(store), to_array(nested[1])]"), r#"[["corner"],[3]]"#);
+    assert_eq!(run(&data, "[to_string(sizes.s), to_string(store)]"), r#"["1","corner"]"#);
+    assert_eq!(run(&data, "[to_number('1.5'), to_number('one'), to_number(`3`)]"), "[1.5,null,3]");
+    assert_eq!(run(&data, "[type(store), type(items), type(missing)]"), r#"["string","array","null"]"#);
+}
+
+#[test]
+fn jmespath_runtime_errors() {
+    let data = data();
+
+    assert!(matches!(
+        jmespath::search(&data, "abs(store)"),
+        Err(Error::WrongType { ref expected, .. }) if expected == "number"
+    ));
+    assert!(matches!(
+        jmespath::search(&data, "sort(items)"),
+        Err(Error::WrongType { .. })
+    ));
+    assert!(matches!(
+        jmespath::search(&data, "sort_by(items, &tags)"),
+        Err(Error::WrongType { .. })
+    ));
+    assert!(matches!(
+        jmespath::search(&data, "max(`[1, \"a\"]`)"),
+        Err(Error::WrongType { ref expected, .. }) if expected == "number"
+    ));
+    assert!(matches!(
+        jmespath::search(&data, "keys(items)"),
+        Err(Error::WrongType { ref expected, .. }) if expected == "object"
+    ));
+}
+
+#[test]
+fn jmespath_syntax_errors() {
+    assert_eq!(syntax_error("foo."), ("unexpected end of expression".into(), 4));
+    assert_eq!(syntax_error("foo[0"), ("unexpected end of expression".into(), 5));
+    assert_eq!(syntax_error("foo]"), ("unexpected `]`".into(), 3));
+    assert_eq!(syntax_error("a ++ b"), ("unexpected `+`".into(), 2));
+    assert_eq!(syntax_error("'open"), ("unterminated string".into(), 0));
+    assert_eq!(syntax_error("`{nope}`"), ("invalid literal".into(), 0));
+    assert_eq!(syntax_error("[::0]"), ("slice step can't be 0".into(), 3));
+    assert_eq!(syntax_error("a | nope(@)"), ("unknown function `nope`".into(), 4));
+    assert_eq!(syntax_error("length(a, b)"), ("`length` takes 1 arguments, found 2".into(), 0));
+    assert_eq!(syntax_error("merge()"), ("`merge` takes at least 1 arguments, found 0".into(), 0));
+    assert_eq!(
+        syntax_error("sort_by(items, name)"),
+        ("argument 2 of `sort_by` has to be an expression reference".into(), 0)
+    );
+
+    let deep = format!("{}a{}", "[".repeat(100), "]".repeat(100));
+    assert!(matches!(Expression::compile(&deep), Err(Error::ExceededDepthLimit)));
+
+    let fine = format!("{}a{}", "[".repeat(30), "]".repeat(30));
+    assert!(Expression::compile(&fine).is_ok());
+}
+
+#[test]
+fn jmespath_compiled_reuse() {
+    let expression = Expression::compile("items[*].name").unwrap();
+
+    assert_eq!(expression.as_str(), "items[*].name");
+    assert_eq!(format!("{:?}", expression), r#"Expression("items[*].name")"#);
+
+    assert_eq!(expression.search(&data()).unwrap(), array!["pen", "book", "lamp"]);
+    assert_eq!(expression.search(&object!{ "items" => array![] }).unwrap(), array![]);
+    assert_eq!(expression.search(&json::Null).unwrap(), json::Null);
+}
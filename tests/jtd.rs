@@ -0,0 +1,187 @@
+#[macro_use]
+extern crate json;
+
+use json::schema::jtd::{ self, Error, ValidationError };
+use json::{ JsonValue, Null };
+
+fn errors(schema: JsonValue, instance: JsonValue) -> Vec<(String, String)> {
+    jtd::validate(&schema, &instance)
+        .unwrap()
+        .into_iter()
+        .map(|ValidationError { instance_path, schema_path }| (instance_path, schema_path))
+        .collect()
+}
+
+fn e(instance_path: &str, schema_path: &str) -> (String, String) {
+    (instance_path.to_owned(), schema_path.to_owned())
+}
+
+#[test]
+fn jtd_empty_form_accepts_anything() {
+    assert!(errors(object!{}, array![1, "a", Null]).is_empty());
+    assert!(errors(object!{ "metadata" => object!{ "doc" => "x" } }, Null).is_empty());
+}
+
+#[test]
+fn jtd_type_form() {
+    let schema = || object!{ "type" => "uint8" };
+
+    assert!(errors(schema(), 255.into()).is_empty());
+    assert!(errors(schema(), json::parse("1e2").unwrap()).is_empty());
+    assert_eq!(errors(schema(), 256.into()), vec![e("", "/type")]);
+    assert_eq!(errors(schema(), 1.5.into()), vec![e("", "/type")]);
+    assert_eq!(errors(schema(), (-1).into()), vec![e("", "/type")]);
+    assert_eq!(errors(object!{ "type" => "int8" }, (-128).into()), vec![]);
+    assert_eq!(errors(object!{ "type" => "float32" }, 1.5.into()), vec![]);
+    assert_eq!(errors(object!{ "type" => "boolean" }, "true".into()), vec![e("", "/type")]);
+    assert_eq!(errors(object!{ "type" => "string" }, Null), vec![e("", "/type")]);
+}
+
+#[test]
+fn jtd_nullable() {
+    assert!(errors(object!{ "type" => "string", "nullable" => true }, Null).is_empty());
+    assert_eq!(errors(object!{ "type" => "string", "nullable" => false }, Null), vec![e("", "/type")]);
+}
+
+#[test]
+fn jtd_timestamps() {
+    let schema = || object!{ "type" => "timestamp" };
+
+    for valid in &["1985-04-12T23:20:50.52Z", "1990-12-31T15:59:60-08:00", "2020-02-29t00:00:00z"] {
+        assert!(errors(schema(), (*valid).into()).is_empty(), "{}", valid);
+    }
+    for invalid in &["1985-04-12", "2019-02-29T00:00:00Z", "1985-04-12T24:00:00Z", "1985-04-12T23:20:50.Z", "1985-04-12T23:20:50+0100"] {
+        assert_eq!(errors(schema(), (*invalid).into()), vec![e("", "/type")], "{}", invalid);
+    }
+}
+
+#[test]
+fn jtd_enum_form() {
+    let schema = || object!{ "enum" => array!["PENDING", "DONE"] };
+
+    assert!(errors(schema(), "DONE".into()).is_empty());
+    assert_eq!(errors(schema(), "done".into()), vec![e("", "/enum")]);
+    assert_eq!(errors(schema(), 1.into()), vec![e("", "/enum")]);
+}
+
+#[test]
+fn jtd_elements_form() {
+    let schema = || object!{ "elements" => object!{ "type" => "string" } };
+
+    assert!(errors(schema(), array!["a", "b"]).is_empty());
+    assert_eq!(errors(schema(), array!["a", 1, "b", Null]), vec![e("/1", "/elements/type"), e("/3", "/elements/type")]);
+    assert_eq!(errors(schema(), "a".into()), vec![e("", "/elements")]);
+}
+
+#[test]
+fn jtd_properties_form() {
+    let schema = || object!{
+        "properties" => object!{ "name" => object!{ "type" => "string" }, "age" => object!{ "type" => "uint8" } },
+        "optionalProperties" => object!{ "phones" => object!{ "elements" => object!{ "type" => "string" } } }
+    };
+
+    assert!(errors(schema(), object!{ "name" => "a", "age" => 1 }).is_empty());
+    assert_eq!(
+        errors(schema(), object!{ "age" => "x", "phones" => array![1], "extra/key" => true }),
+        vec![
+            e("", "/properties/name"),
+            e("/age", "/properties/age/type"),
+            e("/phones/0", "/optionalProperties/phones/elements/type"),
+            e("/extra~1key", ""),
+        ]
+    );
+    assert_eq!(errors(schema(), array![]), vec![e("", "/properties")]);
+    assert_eq!(errors(object!{ "optionalProperties" => object!{} }, 1.into()), vec![e("", "/optionalProperties")]);
+
+    let open = object!{ "properties" => object!{}, "additionalProperties" => true };
+    assert!(errors(open, object!{ "anything" => 1 }).is_empty());
+}
+
+#[test]
+fn jtd_values_form() {
+    let schema = || object!{ "values" => object!{ "type" => "uint32" } };
+
+    assert!(errors(schema(), object!{ "a" => 1, "b" => 2 }).is_empty());
+    assert_eq!(errors(schema(), object!{ "a" => 1, "b" => "x" }), vec![e("/b", "/values/type")]);
+    assert_eq!(errors(schema(), array![]), vec![e("", "/values")]);
+}
+
+#[test]
+fn jtd_discriminator_form() {
+    let schema = || object!{
+        "discriminator" => "kind",
+        "mapping" => object!{
+            "circle" => object!{ "properties" => object!{ "radius" => object!{ "type" => "float64" } } },
+            "square" => object!{ "properties" => object!{ "side" => object!{ "type" => "float64" } } }
+        }
+    };
+
+    assert!(errors(schema(), object!{ "kind" => "circle", "radius" => 1.5 }).is_empty());
+    assert_eq!(errors(schema(), object!{ "kind" => "square", "radius" => 1 }), vec![
+        e("", "/mapping/square/properties/side"),
+        e("/radius", "/mapping/square"),
+    ]);
+    assert_eq!(errors(schema(), object!{ "radius" => 1 }), vec![e("", "/discriminator")]);
+    assert_eq!(errors(schema(), object!{ "kind" => 1 }), vec![e("/kind", "/discriminator")]);
+    assert_eq!(errors(schema(), object!{ "kind" => "triangle" }), vec![e("/kind", "/mapping")]);
+    assert_eq!(errors(schema(), 1.into()), vec![e("", "/discriminator")]);
+}
+
+#[test]
+fn jtd_ref_form() {
+    let schema = || object!{
+        "definitions" => object!{
+            "node" => object!{
+                "properties" => object!{
+                    "value" => object!{ "type" => "string" },
+                    "next" => object!{ "ref" => "node", "nullable" => true }
+                }
+            }
+        },
+        "ref" => "node"
+    };
+
+    let list = object!{ "value" => "a", "next" => object!{ "value" => 1, "next" => Null } };
+
+    assert_eq!(errors(schema(), list), vec![e("/next/value", "/definitions/node/properties/value/type")]);
+}
+
+#[test]
+fn jtd_ref_cycle() {
+    let schema = object!{ "definitions" => object!{ "a" => object!{ "ref" => "a" } }, "ref" => "a" };
+
+    assert_eq!(jtd::validate(&schema, &Null), Err(Error::MaxDepthExceeded));
+}
+
+#[test]
+fn jtd_invalid_schemas() {
+    let invalid = |schema: JsonValue| match jtd::validate(&schema, &Null) {
+        Err(Error::InvalidSchema(_)) => true,
+        _ => false,
+    };
+
+    assert!(invalid("string".into()));
+    assert!(invalid(object!{ "type" => "int64" }));
+    assert!(invalid(object!{ "ref" => "missing" }));
+    assert!(invalid(object!{ "type" => "string", "enum" => array!["a"] }));
+    assert!(invalid(object!{ "enum" => array![] }));
+    assert!(invalid(object!{ "enum" => array!["a", "a"] }));
+    assert!(invalid(object!{ "nullable" => "yes" }));
+    assert!(invalid(object!{ "unknown" => 1 }));
+    assert!(invalid(object!{ "elements" => object!{ "definitions" => object!{} } }));
+    assert!(invalid(object!{ "additionalProperties" => true }));
+    assert!(invalid(object!{
+        "properties" => object!{ "a" => object!{} },
+        "optionalProperties" => object!{ "a" => object!{} }
+    }));
+    assert!(invalid(object!{ "discriminator" => "kind" }));
+    assert!(invalid(object!{ "discriminator" => "kind", "mapping" => object!{ "x" => object!{ "type" => "string" } } }));
+    assert!(invalid(object!{
+        "discriminator" => "kind",
+        "mapping" => object!{ "x" => object!{ "properties" => object!{ "kind" => object!{} } } }
+    }));
+    assert!(invalid(object!{
+        "discriminator" => "kind",
+        "mapping" => object!{ "x" => object!{ "properties" => object!{}, "nullable" => true } }
+    }));
+}
@@ -0,0 +1,79 @@
+extern crate json;
+
+use json::lazy::LazyValue;
+use json::Error;
+
+#[test]
+fn lazy_lookups() {
+    let lazy = LazyValue::new(r#" {"name":"Jon", "tags" : ["a", "b"],"age":30,"admin":true,"name":"Snow","n":null} "#);
+
+    assert!(lazy.is_object());
+    assert_eq!(lazy.len(), 6);
+    assert_eq!(lazy["name"].as_str(), Some("Snow"));
+    assert_eq!(lazy["tags"][1].as_str(), Some("b"));
+    assert!(lazy["tags"][2].is_null());
+    assert_eq!(lazy["age"].as_u64(), Some(30));
+    assert_eq!(lazy["age"].as_f64(), Some(30.0));
+    assert_eq!(lazy["admin"].as_bool(), Some(true));
+    assert!(lazy["n"].is_null());
+    assert!(lazy["missing"]["nested"][0].is_null());
+    assert!(lazy.at(0).is_none());
+
+    let keys: Vec<&str> = lazy.entries().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["name", "tags", "age", "admin", "name", "n"]);
+
+    let tags: Vec<&str> = lazy["tags"].members().filter_map(LazyValue::as_str).collect();
+    assert_eq!(tags, ["a", "b"]);
+
+    assert_eq!(lazy["tags"].raw(), r#"["a", "b"]"#);
+}
+
+#[test]
+fn lazy_escaped_keys() {
+    let lazy = LazyValue::new(r#"{"a\"b":1,"é":[{"x\\":"y\n"}]}"#);
+
+    assert_eq!(lazy["a\"b"].as_u64(), Some(1));
+    assert_eq!(lazy["é"][0]["x\\"].as_str(), Some("y\n"));
+}
+
+#[test]
+fn lazy_to_json_matches_parse() {
+    let sources = [
+        "null",
+        " [ ] ",
+        "{}",
+        r#""esc\"aped\né""#,
+        "-1.5e-3",
+        r#"[1,[2,[3,[]],{}],"x",{"a":[true,false,null]}]"#,
+        r#"{"a":{"b":{"c":[1,2,{"d":"e]}"}]},"f":[]},"g":-0,"h":"😀"}"#,
+    ];
+
+    for source in sources.iter() {
+        let lazy = LazyValue::new(source);
+
+        assert_eq!(lazy.to_json(), json::parse(source), "{}", source);
+        assert!(lazy.error().is_none(), "{}", source);
+    }
+}
+
+#[test]
+fn lazy_errors() {
+    let source = r#"{"ok":[1,2],"bad":[1,,2],"worse":{"a" 1},"num":1x}"#;
+    let lazy = LazyValue::new(source);
+
+    assert!(lazy.error().is_none());
+    assert_eq!(lazy["ok"][1].as_u64(), Some(2));
+
+    // Positions are in the whole document
+    assert_eq!(lazy["bad"].error(), json::parse(source).err().as_ref().map(Error::without_path));
+    assert!(lazy["bad"][0].is_null());
+
+    assert!(matches!(lazy["worse"].error(), Some(&Error::UnexpectedCharacter { ch: '1', .. })));
+    assert!(matches!(lazy["num"].error(), Some(&Error::UnexpectedCharacter { ch: 'x', .. })));
+    assert!(lazy.to_json().is_err());
+
+    assert_eq!(LazyValue::new("[1,2").error(), Some(&Error::UnexpectedEndOfJson));
+    assert_eq!(LazyValue::new(r#"{"a":"b"#).error(), Some(&Error::UnexpectedEndOfJson));
+    assert!(LazyValue::new("[1] x").error().is_some());
+    assert!(LazyValue::new("").error().is_some());
+}
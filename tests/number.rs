@@ -66,6 +66,12 @@ fn eq_normalize_right_negative() {
     );
 }
 
+#[test]
+fn from_min_signed() {
+    assert_eq!(Number::from(i64::MIN), Number::from_parts(false, 1 << 63, 0));
+    assert_eq!(Number::from(i8::MIN), Number::from_parts(false, 128, 0));
+}
+
 #[test]
 fn from_small_float() {
     assert_eq!(Number::from(0.05), unsafe { Number::from_parts_unchecked(true, 5, -2) });
@@ -125,3 +131,58 @@ fn as_fixed_point_i64() {
 fn convert_f64_precision() {
     assert_eq!(unsafe { Number::from_parts_unchecked(true, 4750000000000001, -18) }, 0.004750000000000001);
 }
+
+#[test]
+fn wide_integers() {
+    use std::convert::TryFrom;
+
+    let sources = [
+        "18446744073709551616",
+        "-18446744073709551616",
+        "170141183460469231731687303715884105727",
+        "-170141183460469231731687303715884105728",
+        "340282366920938463463374607431768211455",
+    ];
+
+    for source in sources.iter() {
+        let value = json::parse(source).unwrap();
+
+        assert_eq!(value.dump(), *source);
+        assert_eq!(value.as_number().unwrap().to_string(), *source);
+    }
+
+    let max = json::parse("170141183460469231731687303715884105727").unwrap();
+    assert_eq!(max.as_i128(), Some(i128::MAX));
+    assert_eq!(max.as_u128(), Some(i128::MAX as u128));
+    assert_eq!(max.as_u64(), None);
+    assert_eq!(max.as_f64(), Some(1.7014118346046923e38));
+    assert_eq!(max, i128::MAX);
+    assert!(max != json::parse("170141183460469231731687303715884105726").unwrap());
+
+    let min = json::parse("-170141183460469231731687303715884105728").unwrap();
+    assert_eq!(min.as_i128(), Some(i128::MIN));
+    assert_eq!(min.as_u128(), None);
+    assert_eq!(json::parse("-170141183460469231731687303715884105729").unwrap().as_i128(), None);
+
+    assert_eq!(json::parse("340282366920938463463374607431768211455").unwrap().as_u128(), Some(u128::MAX));
+    assert_eq!(json::parse("340282366920938463463374607431768211455").unwrap().as_i128(), None);
+
+    // Past 128 bits numbers are rounded as before
+    assert_eq!(json::parse("340282366920938463463374607431768211456").unwrap().dump(), "3.402823669209384634e38");
+    assert_eq!(json::parse("1.5").unwrap().as_i128(), None);
+
+    // Integers are the same however they are written
+    assert_eq!(Number::from_parts(true, 18446744073709551, 3), Number::from(18446744073709551000u128));
+    assert_eq!(json::parse("1e20").unwrap(), json::parse("100000000000000000000").unwrap());
+    assert_eq!(json::parse("1e20").unwrap().as_u128(), Some(100000000000000000000));
+    assert_eq!(json::parse("-0").unwrap().as_u128(), Some(0));
+    assert_eq!(i128::try_from(Number::from(-5i128)).ok(), Some(-5));
+    assert_eq!(u128::try_from(Number::from(2.5)).ok(), None);
+
+    let wide = Number::from(1u128 << 100);
+    assert_eq!(-(-wide), wide);
+    assert_eq!((-wide).to_string(), "-1267650600228229401496703205376");
+    assert_eq!(wide.as_parts(), (true, 12676506002282294014, 11));
+    assert!(!wide.is_zero());
+    assert_eq!(Number::from(0u128), 0);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "parallel")]
+
+#[macro_use]
+extern crate json;
+
+use json::JsonValue;
+
+#[test]
+fn dump_parallel_matches_dump() {
+    let mut data = JsonValue::new_array();
+
+    for id in 0 .. 5_000 {
+        data.push(object!{
+            "id" => id,
+            "name" => format!("user \"{}\" 😀", id),
+            "score" => id as f64 / 7.0,
+            "tags" => array!["a", JsonValue::Null, true],
+        }).unwrap();
+    }
+
+    let dumped = data.dump();
+    assert_eq!(data.dump_parallel(), dumped);
+
+    let mut written = Vec::new();
+    data.write_parallel(&mut written).unwrap();
+    assert_eq!(written, dumped.as_bytes());
+}
+
+#[test]
+fn dump_parallel_small_values() {
+    let values = [
+        array![],
+        array![1, 2, 3],
+        object!{ "a" => array![1, 2] },
+        JsonValue::from("text"),
+        JsonValue::Null,
+    ];
+
+    for value in values.iter() {
+        assert_eq!(value.dump_parallel(), value.dump());
+
+        let mut written = Vec::new();
+        value.write_parallel(&mut written).unwrap();
+        assert_eq!(written, value.dump().as_bytes());
+    }
+}
+
+#[test]
+fn dump_parallel_in_thread_pool() {
+    let data: JsonValue = (0 .. 20_000).map(|id| object!{ "id" => id }).collect::<Vec<_>>().into();
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+
+    assert_eq!(pool.install(|| data.dump_parallel()), data.dump());
+}
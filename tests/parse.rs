@@ -349,7 +349,17 @@ fn parse_error_after_depth_limit() {
         text.push_str("}]");
     }
 
-    assert_eq!(parse(&text), Err(json::Error::ExceededDepthLimit));
+    let err = parse(&text).unwrap_err();
+
+    assert_eq!(err.without_path(), &json::Error::ExceededDepthLimit);
+    assert_eq!(err.path(), Some("/0/a".repeat(256).as_str()));
+    assert_eq!(err.to_string(), format!("Exceeded depth limit at {}", "/0/a".repeat(256)));
+
+    let text = format!(r#"{{"a":{{"b":{}}}}}"#, "[".repeat(600));
+    let err = parse(&text).unwrap_err();
+
+    assert_eq!(err.without_path(), &json::Error::ExceededDepthLimit);
+    assert_eq!(err.path(), Some(format!("/a/b{}", "/0".repeat(510)).as_str()));
 }
 
 #[test]
@@ -378,3 +388,373 @@ fn does_not_panic_on_huge_numbers() {
 
     let _ = json::parse(&string);
 }
+
+#[test]
+fn parse_with_key_interner() {
+    let mut interner = json::object::KeyInterner::new();
+
+    let first = r#"{"a_key_long_enough_to_be_heap_allocated":{"another_key_long_enough_to_be_heap_allocated":[1]}}"#;
+    let second = r#"{"a_key_long_enough_to_be_heap_allocated":2,"a_third_key_long_enough_to_be_heap_allocated":3}"#;
+
+    let first_data = interner.parse(first).unwrap();
+    let second_data = interner.parse(second).unwrap();
+
+    assert_eq!(interner.len(), 3);
+    assert_eq!(first_data, parse(first).unwrap());
+    assert_eq!(second_data, parse(second).unwrap());
+
+    // Shared keys outlive the interner and the values they were copied from
+    let copy = first_data.clone();
+    drop(interner);
+    drop(first_data);
+
+    assert_eq!(copy.dump(), first);
+}
+
+#[test]
+fn reusable_parser() {
+    let mut parser = json::Parser::new();
+
+    let sources = [
+        r#"{"a\nb":["c\"d",{"eé":1}]}"#,
+        r#"[{"a":[1,{"b":"unterminated"#,
+        r#"{"x\ty":"z\\"}"#,
+        "[1,2,,3]",
+        r#"["😀",true,null]"#,
+    ];
+
+    for source in sources.iter() {
+        assert_eq!(parser.parse(source), parse(source));
+    }
+
+    for source in sources.iter() {
+        parser.reset(source);
+        assert_eq!(parser.document(), parse(source));
+        assert_eq!(parser.document(), parse(source));
+    }
+
+    assert_eq!(json::Parser::new().document(), Err(json::Error::UnexpectedEndOfJson));
+}
+
+#[test]
+fn array_capacity_follows_lengths() {
+    fn capacities(value: &JsonValue) -> Vec<usize> {
+        match *value {
+            JsonValue::Array(ref rows) => rows.iter().map(|row| match *row {
+                JsonValue::Array(ref row) => row.capacity(),
+                _                         => 0,
+            }).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    let row = format!("[{}]", (0 .. 12).map(|i| i.to_string()).collect::<Vec<_>>().join(","));
+    let rows = format!("[{}]", vec![row.as_str(); 8].join(","));
+
+    let data = parse(&rows).unwrap();
+    assert_eq!(data, parse(&rows).unwrap());
+    assert_eq!(capacities(&data).last(), Some(&12));
+
+    // The parser keeps the capacity between documents
+    let mut parser = json::Parser::new();
+    parser.parse(&rows).unwrap();
+    assert_eq!(capacities(&parser.parse(&format!("[{}]", row)).unwrap()), [12]);
+
+    let mut parser = json::Parser::with_array_capacity(12);
+    assert_eq!(capacities(&parser.parse(&format!("[{}]", row)).unwrap()), [12]);
+}
+
+#[test]
+fn parse_precision_loss() {
+    use std::cell::RefCell;
+    use json::{ parse_with, Error, ParseOptions, PrecisionLoss };
+
+    thread_local! {
+        static WARNINGS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    }
+
+    fn warn(path: &str, number: &str) {
+        WARNINGS.with(|warnings| warnings.borrow_mut().push(format!("{} {}", path, number)));
+    }
+
+    let strict = ParseOptions {
+        precision_loss: PrecisionLoss::Error,
+        ..ParseOptions::default()
+    };
+
+    let exact = [
+        "0", "-0", "9007199254740992", "-9007199254740992", "9007199254740994",
+        "1152921504606846976", "10000000000000000000000", "1e400", "9007199254740993.5",
+        "18014398509481985e0", "-12.75",
+    ];
+
+    for source in exact.iter() {
+        assert_eq!(parse_with(source, &strict), parse(source), "{}", source);
+    }
+
+    let lossy = [
+        "9007199254740993", "-9007199254740993", "18446744073709551615",
+        "100000000000000000000000", "340282366920938463463374607431768211457",
+    ];
+
+    for source in lossy.iter() {
+        assert_eq!(parse_with(source, &strict), Err(Error::PrecisionLoss {
+            number: source.to_string(),
+            line: 1,
+            column: 1,
+        }), "{}", source);
+    }
+
+    let err = parse_with("{\n  \"a\": [1,\n    12345678901234567]\n}", &strict).unwrap_err();
+    assert_eq!(err.path(), Some("/a/1"));
+    assert_eq!(err.to_string(), "Number 12345678901234567 can't be represented exactly by f64 at (3:5) at /a/1");
+    assert!(std::error::Error::source(&err).is_none());
+    assert_eq!(err.without_path(), &Error::PrecisionLoss {
+        number: "12345678901234567".into(),
+        line: 3,
+        column: 5,
+    });
+
+    assert_eq!(parse_with("[12345678901234567]", &ParseOptions::default()), parse("[12345678901234567]"));
+
+    let warned = ParseOptions {
+        precision_loss: PrecisionLoss::Warn(warn),
+        ..ParseOptions::default()
+    };
+
+    let data = parse_with(r#"{"ids":[9007199254740993,2],"big":-18446744073709551615}"#, &warned).unwrap();
+    assert_eq!(data["ids"][0].as_u64(), Some(9007199254740993));
+
+    WARNINGS.with(|warnings| {
+        assert_eq!(*warnings.borrow(), ["/ids/0 9007199254740993", "/big -18446744073709551615"]);
+    });
+}
+
+#[test]
+fn parse_lenient_numbers() {
+    use json::{ parse_with, Error, ParseOptions };
+
+    let lenient = ParseOptions {
+        lenient_numbers: true,
+        ..ParseOptions::default()
+    };
+
+    let relaxed = [
+        ("+1", "1"), (".5", "0.5"), ("5.", "5"), ("007", "7"), ("-007.50", "-7.5"),
+        ("+.5e+2", "50"), ("-.25E1", "-2.5"), ("00", "0"), ("+0.", "0"),
+    ];
+
+    for &(source, expected) in relaxed.iter() {
+        assert_eq!(parse_with(source, &lenient).unwrap(), parse(expected).unwrap(), "{}", source);
+        assert!(parse(source).is_err(), "{}", source);
+    }
+
+    for &source in ["1", "-0", "-1.5e3", "[1, 2.5, -0.1]", "18446744073709551616"].iter() {
+        assert_eq!(parse_with(source, &lenient), parse(source), "{}", source);
+    }
+
+    assert_eq!(parse_with("[+1,.5 , 5.]", &lenient).unwrap(), array![1, 0.5, 5]);
+
+    assert_eq!(parse_with("[.]", &lenient), Err(Error::UnexpectedCharacter { ch: ']', line: 1, column: 3, expected: &["a digit"] }));
+    assert_eq!(parse_with("[+ 1]", &lenient), Err(Error::UnexpectedCharacter { ch: ' ', line: 1, column: 3, expected: &["a digit"] }));
+    assert_eq!(parse_with("[1.5ex]", &lenient), Err(Error::UnexpectedCharacter { ch: 'x', line: 1, column: 6, expected: &["a digit"] }));
+    assert_eq!(parse_with("1.5e", &lenient), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(parse_with("-", &lenient), Err(Error::UnexpectedEndOfJson));
+
+    assert_eq!(parse("+1"), Err(Error::UnexpectedCharacter { ch: '+', line: 1, column: 1, expected: &["a value"] }));
+    assert_eq!(parse(".5"), Err(Error::UnexpectedCharacter { ch: '.', line: 1, column: 1, expected: &["a value"] }));
+}
+
+#[test]
+fn parse_raw_control_characters() {
+    use json::{ parse_with, Error, ParseOptions };
+
+    let lenient = ParseOptions {
+        raw_control_characters: true,
+        ..ParseOptions::default()
+    };
+
+    let data = parse_with("{\"a\tb\": \"one\ttwo\r\nthree\", \"c\": \"\\\"quoted\\\"\n\x01\"}", &lenient).unwrap();
+
+    assert_eq!(data["a\tb"], "one\ttwo\r\nthree");
+    assert_eq!(data["c"], "\"quoted\"\n\x01");
+    assert_eq!(data.dump(), r#"{"a\tb":"one\ttwo\r\nthree","c":"\"quoted\"\n\u0001"}"#);
+
+    assert_eq!(parse_with("[\"tab\there\"]", &ParseOptions::default()), parse("[\"tab\there\"]"));
+    assert_eq!(parse("[\"tab\there\"]"), Err(Error::UnexpectedCharacter { ch: '\t', line: 1, column: 6, expected: &["an escaped control character"] }));
+    assert_eq!(parse_with("[\"open\n", &lenient), Err(Error::UnexpectedEndOfJson));
+}
+
+#[test]
+fn parse_bytes_lossy() {
+    use json::Error;
+
+    let data = json::parse_bytes_lossy(b"{\"k\xc3\": [\"ok\", \"bad \xf0\x9f\x98 end\", \"\xff\xfe\"], \"n\": 1}").unwrap();
+
+    assert_eq!(data["k\u{FFFD}"][0], "ok");
+    assert_eq!(data["k\u{FFFD}"][1], "bad \u{FFFD} end");
+    assert_eq!(data["k\u{FFFD}"][2], "\u{FFFD}\u{FFFD}");
+    assert_eq!(data["n"], 1);
+
+    assert_eq!(json::parse_bytes_lossy(b"[\"fine\"]"), parse("[\"fine\"]"));
+    assert_eq!(json::parse_bytes_lossy(b"[1, \xff]"), Err(Error::UnexpectedCharacter { ch: '\u{FFFD}', line: 1, column: 5, expected: &["a value"] }));
+}
+
+#[test]
+fn parse_hex_escapes() {
+    use json::{ parse_with, Error, ParseOptions };
+
+    let lenient = ParseOptions {
+        hex_escapes: true,
+        ..ParseOptions::default()
+    };
+
+    let data = parse_with(r#"{"caf\xe9": ["\x00\x7F\xff", "\x4a\x53ON", "\\x41"]}"#, &lenient).unwrap();
+
+    assert_eq!(data["café"][0], "\u{0}\u{7f}ÿ");
+    assert_eq!(data["café"][1], "JSON");
+    assert_eq!(data["café"][2], "\\x41");
+
+    assert_eq!(parse_with(r#"["\x4"]"#, &lenient), Err(Error::UnexpectedCharacter { ch: '"', line: 1, column: 6, expected: &["a hex digit"] }));
+    assert_eq!(parse_with(r#"["\xg0"]"#, &lenient), Err(Error::UnexpectedCharacter { ch: 'g', line: 1, column: 5, expected: &["a hex digit"] }));
+    assert_eq!(parse(r#"["\x41"]"#), Err(Error::UnexpectedCharacter { ch: 'x', line: 1, column: 4, expected: &["\"", "\\", "/", "b", "f", "n", "r", "t", "u"] }));
+}
+
+#[test]
+fn parse_with_reviver() {
+    let mut visited = Vec::new();
+
+    let data = json::parse_with_reviver(r#"{"a": [1, "drop", {"b~/": null}], "c": "x"}"#, |path, value| {
+        visited.push(path.to_string());
+
+        match value {
+            JsonValue::Number(_) => Some((value.as_f64().unwrap() * 10.0).into()),
+            ref value if *value == "drop" => None,
+            JsonValue::Null => Some("was null".into()),
+            value => Some(value),
+        }
+    }).unwrap();
+
+    assert_eq!(visited, ["/a/0", "/a/1", "/a/2/b~0~1", "/a/2", "/a", "/c", ""]);
+    assert_eq!(data, object!{ "a" => array![10, object!{ "b~/" => "was null" }], "c" => "x" });
+
+    assert_eq!(json::parse_with_reviver("[1, 2]", |_, _| None), Ok(Null));
+    assert_eq!(json::parse_with_reviver("true", |path, value| Some(array![path, value])), Ok(array!["", true]));
+    assert!(json::parse_with_reviver("[1,", |_, value| Some(value)).is_err());
+}
+
+#[test]
+fn parse_exponent_overflow() {
+    use json::{ parse_with, Error, ParseOptions, ExponentOverflow };
+
+    let options = |exponent_overflow| ParseOptions { exponent_overflow, ..ParseOptions::default() };
+    let source = "[1e400, -12.5e999, 1e308, 0e9999, 1e-400]";
+
+    let data = parse_with(source, &options(ExponentOverflow::Infinity)).unwrap();
+    assert_eq!(data, parse(source).unwrap());
+    assert_eq!(data[0].as_f64(), Some(f64::INFINITY));
+    assert_eq!(data[1].as_f64(), Some(f64::NEG_INFINITY));
+    assert_eq!(data.dump(), "[1e400,-1.25e1000,1e308,0,1e-400]");
+
+    let data = parse_with(source, &options(ExponentOverflow::Clamp)).unwrap();
+    assert_eq!(data.dump(), "[1.7976931348623157e308,-1.7976931348623157e308,1e308,0,1e-400]");
+    assert_eq!(data[0].as_f64(), Some(f64::MAX));
+    assert_eq!(data[1].as_f64(), Some(f64::MIN));
+
+    let err = parse_with("{\"a\": [1, \n  -1e400]}", &options(ExponentOverflow::Error)).unwrap_err();
+    assert_eq!(err.path(), Some("/a/1"));
+    assert_eq!(err.without_path(), &Error::NumberOutOfRange { number: "-1e400".into(), line: 2, column: 3 });
+    assert_eq!(err.to_string(), "Number -1e400 is out of range of f64 at (2:3) at /a/1");
+
+    assert!(parse_with("[1e308, 1e-400]", &options(ExponentOverflow::Error)).is_ok());
+    assert_eq!(
+        parse_with("1E+309", &options(ExponentOverflow::Error)),
+        Err(Error::NumberOutOfRange { number: "1E+309".into(), line: 1, column: 1 })
+    );
+}
+
+#[test]
+fn parse_trailing_characters() {
+    use json::Error;
+
+    let source = "[1, 2] \n {\"next\": true}";
+    let err = parse(source).unwrap_err();
+
+    assert_eq!(err, Error::TrailingCharacters { index: 9 });
+    assert_eq!(parse(&source[.. 9]).unwrap(), array![1, 2]);
+    assert_eq!(parse(&source[9 ..]).unwrap(), object!{ "next" => true });
+    assert_eq!(err.to_string(), "Unexpected characters after the value at byte 9");
+    assert_eq!(err.format_with_source(source), "\
+Unexpected characters after the value at byte 9
+  |
+2 |  {\"next\": true}
+  |  ^");
+
+    assert_eq!(parse("1 x"), Err(Error::TrailingCharacters { index: 2 }));
+    assert_eq!(parse("\"é\"é"), Err(Error::TrailingCharacters { index: 4 }));
+    assert_eq!(json::Parser::new().parse("{} []"), Err(Error::TrailingCharacters { index: 3 }));
+    assert_eq!(parse("[1] \t\r\n "), Ok(array![1]));
+    assert_eq!(parse("[1 x]"), Err(Error::UnexpectedCharacter { ch: 'x', line: 1, column: 4, expected: &[",", "]"] }));
+}
+
+#[test]
+fn parse_require_container() {
+    use json::{ parse_with, Error, ParseOptions };
+
+    let options = ParseOptions { require_container: true, ..ParseOptions::default() };
+
+    assert_eq!(parse_with(" \n[1]", &options), Ok(array![1]));
+    assert_eq!(parse_with("{}", &options), Ok(object!{}));
+
+    assert_eq!(parse_with("\n  42", &options), Err(Error::UnexpectedCharacter { ch: '4', line: 2, column: 3, expected: &["{", "["] }));
+    assert_eq!(parse_with("null", &options), Err(Error::UnexpectedCharacter { ch: 'n', line: 1, column: 1, expected: &["{", "["] }));
+    assert_eq!(parse_with("\"[]\"", &options), Err(Error::UnexpectedCharacter { ch: '"', line: 1, column: 1, expected: &["{", "["] }));
+    assert_eq!(parse_with("  ", &options), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(parse_with("[1", &options), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(parse_with("42", &ParseOptions::default()), Ok(42.into()));
+}
+
+#[test]
+fn parse_expected_tokens() {
+    use json::Error;
+
+    fn expected(source: &str) -> &'static [&'static str] {
+        match parse(source) {
+            Err(Error::UnexpectedCharacter { expected, .. }) => expected,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    assert_eq!(expected("{\"a\": 1 \"b\": 2}"), &[",", "}"]);
+    assert_eq!(expected("{\"a\": 1, b: 2}"), &["a string"]);
+    assert_eq!(expected("{a: 1}"), &["a string", "}"]);
+    assert_eq!(expected("{\"a\" 1}"), &[":"]);
+    assert_eq!(expected("[1, fals]"), &["false"]);
+    assert_eq!(expected("[-x]"), &["a digit"]);
+    assert_eq!(expected("\"\\u12G4\""), &["a hex digit"]);
+    assert_eq!(expected("\"\\uD83D.\""), &["\\u"]);
+    assert_eq!(json::tape::Tape::parse("{\"a\": [1 }").unwrap_err(), parse("{\"a\": [1 }").unwrap_err());
+
+    assert_eq!(parse("{\"a\" 1}").unwrap_err().to_string(), "Unexpected character: 1 at (1:6), expected `:`");
+    assert_eq!(parse("{a: 1}").unwrap_err().to_string(), "Unexpected character: a at (1:2), expected a string or `}`");
+    assert_eq!(
+        parse("\"\\q\"").unwrap_err().to_string(),
+        "Unexpected character: q at (1:3), expected `\"`, `\\`, `/`, `b`, `f`, `n`, `r`, `t` or `u`"
+    );
+}
+
+#[test]
+fn parse_from_reader() {
+    use std::io;
+
+    assert_eq!(json::parse_from_reader(&b" [1, {\"a\": null}] "[..]).unwrap(), array![1, object!{ "a" => Null }]);
+
+    let kind = |source: &[u8]| json::parse_from_reader(source).unwrap_err().kind();
+
+    assert_eq!(kind(b"[1,]"), io::ErrorKind::InvalidData);
+    assert_eq!(kind(b"[\"\xFF\"]"), io::ErrorKind::InvalidData);
+    assert_eq!(kind(&[0x28, 0xB5, 0x2F, 0xFD, 0x00]), io::ErrorKind::InvalidData);
+
+    let err = json::parse_from_reader(&b"{\"a\" 1}"[..]).unwrap_err();
+    assert_eq!(*err.into_inner().unwrap().downcast::<json::Error>().unwrap(), parse("{\"a\" 1}").unwrap_err());
+}
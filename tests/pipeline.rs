@@ -0,0 +1,198 @@
+extern crate json;
+
+use std::io;
+
+use json::{ Error, JsonWriter };
+use json::pipeline::{ Event, Events, Pipeline };
+
+fn run(pipeline: &mut Pipeline, source: &str) -> io::Result<String> {
+    let out = pipeline.run(source.as_bytes(), JsonWriter::new(Vec::new()))?;
+
+    Ok(String::from_utf8(out).unwrap())
+}
+
+fn json_error(err: io::Error) -> Error {
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    *err.into_inner().unwrap().downcast::<Error>().unwrap()
+}
+
+#[test]
+fn events_roundtrip() {
+    let source = r#" {"a": [1, -2.5e3, "x\"\u00e9\ud83d\ude00", true, false, null], "b": {}, "c": []} "#;
+
+    assert_eq!(run(&mut Pipeline::new(), source).unwrap(), json::parse(source).unwrap().dump());
+
+    let events: Vec<Event> = Events::new(&b"[\"\\n\", 10]"[..]).map(Result::unwrap).collect();
+    assert_eq!(events, [Event::BeginArray, Event::String("\n".into()), Event::Number(10.into()), Event::EndArray]);
+}
+
+#[test]
+fn events_errors() {
+    let error = |source: &str| json_error(Events::new(source.as_bytes()).find_map(Result::err).unwrap());
+
+    assert_eq!(error("[1 2]"), Error::UnexpectedCharacter { ch: '2', line: 1, column: 4, expected: &[",", "]"] });
+    assert_eq!(error("{\"a\" 1}"), Error::UnexpectedCharacter { ch: '1', line: 1, column: 6, expected: &[":"] });
+    assert_eq!(error("{\n  1}"), Error::UnexpectedCharacter { ch: '1', line: 2, column: 3, expected: &["a string", "}"] });
+    assert_eq!(error("[01]"), Error::UnexpectedCharacter { ch: '1', line: 1, column: 3, expected: &[",", "]"] });
+    assert_eq!(error("\"\\uD800x\""), Error::UnexpectedCharacter { ch: 'x', line: 1, column: 8, expected: &["\\u"] });
+    assert_eq!(error("[] ł"), Error::UnexpectedCharacter { ch: 'ł', line: 1, column: 4, expected: &[] });
+    assert_eq!(error("[tru"), Error::UnexpectedEndOfJson);
+    assert_eq!(error(&"[".repeat(1000)), Error::ExceededDepthLimit);
+
+    let mut events = Events::new(&b"[x, 1]"[..]);
+    assert_eq!(events.next().unwrap().unwrap(), Event::BeginArray);
+    assert!(events.next().unwrap().is_err());
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn pipeline_stages() {
+    let source = r#"{"id": 7, "secret": {"deep": [1, 2]}, "items": [{"name": "a", "drop": true}, {"name": "b"}], "n": null}"#;
+
+    let mut pipeline = Pipeline::new()
+        .stage(|path: &str, event: Event| match event {
+            Event::Key(ref key) if key == "name" => Some(Event::Key("title".into())),
+            _ if path == "/secret" || path == "/items/0/drop" => None,
+            event => Some(event),
+        })
+        .stage(|path: &str, event: Event| match event {
+            // Paths are those of the source, before the renaming
+            Event::String(value) if path.ends_with("/name") => Some(Event::String(value.to_uppercase())),
+            // Dropping a value drops its key too
+            Event::Null => None,
+            event => Some(event),
+        });
+
+    assert_eq!(run(&mut pipeline, source).unwrap(), r#"{"id":7,"items":[{"title":"A"},{"title":"B"}]}"#);
+}
+
+#[test]
+fn pipeline_paths() {
+    let mut paths = Vec::new();
+
+    run(&mut Pipeline::new().stage(|path: &str, event: Event| {
+        paths.push(path.to_string());
+        Some(event)
+    }), r#"{"a": [1, {"b~/": 2}], "c": 3}"#).unwrap();
+
+    assert_eq!(paths, ["", "/a", "/a", "/a/0", "/a/1", "/a/1/b~0~1", "/a/1/b~0~1", "/a/1", "/a", "/c", "/c", ""]);
+}
+
+#[test]
+fn pipeline_errors() {
+    assert_eq!(json_error(run(&mut Pipeline::new(), "[1,]").unwrap_err()), Error::UnexpectedCharacter {
+        ch: ']',
+        line: 1,
+        column: 4,
+        expected: &["a value"],
+    });
+
+    let mut broken = Pipeline::new().stage(|_: &str, event: Event| match event {
+        Event::String(_) => Some(Event::Key("x".into())),
+        event => Some(event),
+    });
+
+    assert_eq!(run(&mut broken, r#"["a"]"#).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+    let mut dropped = Pipeline::new().stage(|_: &str, _: Event| None);
+
+    assert_eq!(run(&mut dropped, "[1]").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn project_paths() {
+    let source = r#"{"meta": {"v": 2, "tags": ["a"]}, "items": [{"id": 1, "blob": {"x": [1]}}, {"blob": 2}, {"id": 3}], "n": 1, "a/b": 4}"#;
+    let project = |paths: &[&str]| json::project_value(source.as_bytes(), paths).unwrap();
+
+    assert_eq!(project(&["/meta", "/items/*/id"]), json::parse(r#"{"meta": {"v": 2, "tags": ["a"]}, "items": [{"id": 1}, {}, {"id": 3}]}"#).unwrap());
+    assert_eq!(project(&["/items/1/blob", "/a~1b"]), json::parse(r#"{"items": [{"blob": 2}], "a/b": 4}"#).unwrap());
+    assert_eq!(project(&["/*/v"]), json::parse(r#"{"meta": {"v": 2}, "items": []}"#).unwrap());
+    assert_eq!(project(&[""]), json::parse(source).unwrap());
+    assert_eq!(project(&[]), json::object!{});
+    assert_eq!(json::project_value(&b"[1, 2]"[..], &["/1"]).unwrap(), json::array![2]);
+    assert_eq!(json::project_value(&b"7"[..], &["/x"]).unwrap(), 7);
+
+    let out = json::project(source.as_bytes(), &["/n"], JsonWriter::pretty(Vec::new(), 2)).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "{\n  \"n\": 1\n}");
+}
+
+#[test]
+fn project_errors() {
+    let err = json::project_value(&b"{}"[..], &["meta"]).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), "Invalid JSON Pointer \"meta\"");
+
+    assert_eq!(json_error(json::project_value(&b"{\"a\": [1,]}"[..], &["/b"]).unwrap_err()), Error::UnexpectedCharacter {
+        ch: ']',
+        line: 1,
+        column: 10,
+        expected: &["a value"],
+    });
+}
+
+#[test]
+fn pipeline_to_value() {
+    let mut pipeline = Pipeline::new().stage(|_: &str, event: Event| match event {
+        Event::Number(_) => Some(Event::Null),
+        event => Some(event),
+    });
+
+    assert_eq!(pipeline.run_to_value(&b"{\"a\": [1, {\"b\": 2}], \"c\": \"d\"}"[..]).unwrap(), json::parse(r#"{"a": [null, {"b": null}], "c": "d"}"#).unwrap());
+
+    let mut broken = Pipeline::new().stage(|_: &str, event: Event| match event {
+        Event::EndArray => Some(Event::EndObject),
+        event => Some(event),
+    });
+
+    assert_eq!(broken.run_to_value(&b"[1]"[..]).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn split_array_chunks() {
+    use json::pipeline::Chunks;
+
+    let source = r#"[1, {"a": [2, 3]}, [], "x", null]"#;
+    let chunks = |size| Chunks::new(source.as_bytes(), size).collect::<io::Result<Vec<_>>>().unwrap();
+
+    assert_eq!(chunks(2), [json::array![1, json::object!{ "a" => json::array![2, 3] }], json::array![json::array![], "x"], json::array![json::Null]]);
+    assert_eq!(chunks(5).len(), 1);
+    assert_eq!(chunks(100), [json::parse(source).unwrap()]);
+    assert_eq!(Chunks::new(&b" [ ] "[..], 3).count(), 0);
+
+    let mut broken = Chunks::new(&b"[1, 2, 3 x]"[..], 2);
+    assert_eq!(broken.next().unwrap().unwrap(), json::array![1, 2]);
+    assert!(broken.next().unwrap().is_err());
+    assert!(broken.next().is_none());
+
+    let err = Chunks::new(&b"{}"[..], 2).next().unwrap().unwrap_err();
+    assert_eq!(json_error(err).to_string(), "Wrong type, expected array, found object");
+    assert!(Chunks::new(&b"[1] 2"[..], 2).next().unwrap().is_err());
+}
+
+#[test]
+fn split_array_files() {
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("json-split-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let source = r#"[{"id": 1}, {"id": 2, "tags": ["a"]}, {"id": 3}]"#;
+    let count = json::split_array(source.as_bytes(), 2, |chunk| {
+        Ok(JsonWriter::new(fs::File::create(dir.join(format!("{}.json", chunk)))?))
+    }).unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(fs::read_to_string(dir.join("0.json")).unwrap(), r#"[{"id":1},{"id":2,"tags":["a"]}]"#);
+    assert_eq!(fs::read_to_string(dir.join("1.json")).unwrap(), r#"[{"id":3}]"#);
+
+    let mut opened = 0;
+    assert_eq!(json::split_array(&b"[]"[..], 2, |_| { opened += 1; Ok(JsonWriter::new(io::sink())) }).unwrap(), 0);
+    assert_eq!(opened, 0);
+
+    let err = json::split_array(&b"[1, 2"[..], 1, |_| Ok(JsonWriter::new(io::sink()))).unwrap_err();
+    assert_eq!(json_error(err), Error::UnexpectedEndOfJson);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,80 @@
+#[macro_use]
+extern crate json;
+
+use json::query;
+use json::JsonValue;
+
+#[test]
+fn query_parse_flat() {
+    assert_eq!(query::parse("a=1&b=two+words&c="), object!{ "a" => "1", "b" => "two words", "c" => "" });
+    assert_eq!(query::parse("flag&&x=%41%2b%zz%4"), object!{ "flag" => "", "x" => "A+%zz%4" });
+    assert_eq!(query::parse("name=%C5%BC%C3%B3%C5%82w"), object!{ "name" => "żółw" });
+    assert_eq!(query::parse(""), JsonValue::new_object());
+}
+
+#[test]
+fn query_parse_nested() {
+    assert_eq!(
+        query::parse("a[b][0]=1&a[b][1]=2&a[c]=3&c=2"),
+        object!{ "a" => object!{ "b" => array!["1", "2"], "c" => "3" }, "c" => "2" }
+    );
+    assert_eq!(
+        query::parse("items[][id]=1&items[][id]=2"),
+        object!{ "items" => array![object!{ "id" => "1" }, object!{ "id" => "2" }] }
+    );
+    assert_eq!(query::parse("a%5Bb%5D=1"), object!{ "a" => object!{ "b" => "1" } });
+}
+
+#[test]
+fn query_parse_repeated_keys() {
+    assert_eq!(query::parse("a=1&a=2&a=3"), object!{ "a" => array!["1", "2", "3"] });
+    assert_eq!(query::parse("a[]=1&a[]=2"), object!{ "a" => array!["1", "2"] });
+}
+
+#[test]
+fn query_parse_sparse_index_appends() {
+    assert_eq!(query::parse("a[5]=x&a[99999999999]=y"), object!{ "a" => array!["x", "y"] });
+}
+
+#[test]
+fn query_parse_malformed_keys() {
+    assert_eq!(query::parse("[a]=1"), object!{ "[a]" => "1" });
+    assert_eq!(query::parse("a[b=1"), object!{ "a" => object!{ "[b" => "1" } });
+    assert_eq!(query::parse("a[b]c=1"), object!{ "a" => object!{ "b" => object!{ "c" => "1" } } });
+}
+
+#[test]
+fn query_parse_index_into_object() {
+    assert_eq!(query::parse("a[x]=1&a[0]=2"), object!{ "a" => object!{ "x" => "1", "0" => "2" } });
+}
+
+#[test]
+fn query_stringify() {
+    let data = object!{
+        "q" => "rust & json",
+        "page" => 2,
+        "exact" => true,
+        "empty" => JsonValue::Null,
+        "filter" => object!{ "tags" => array!["a/b", "ż"], "none" => array![] }
+    };
+
+    assert_eq!(
+        query::stringify(&data),
+        "q=rust+%26+json&page=2&exact=true&empty=&filter[tags][0]=a%2Fb&filter[tags][1]=%C5%BC"
+    );
+}
+
+#[test]
+fn query_stringify_non_object() {
+    assert_eq!(query::stringify(&array![1, 2]), "");
+}
+
+#[test]
+fn query_round_trip() {
+    let data = object!{
+        "user" => object!{ "name" => "A B", "roles" => array!["x", "y"] },
+        "weird key=&" => "v"
+    };
+
+    assert_eq!(query::parse(&query::stringify(&data)), data);
+}
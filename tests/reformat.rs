@@ -0,0 +1,104 @@
+extern crate json;
+
+use std::io;
+use json::{ reformat, minify, Style, Error };
+
+fn reformat_str(source: &str, style: Style) -> io::Result<String> {
+    let mut out = Vec::new();
+    reformat(source.as_bytes(), &mut out, style)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+fn reformat_error(source: &str) -> Error {
+    let err = reformat_str(source, Style::Compact).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    *err.into_inner().unwrap().downcast::<Error>().unwrap()
+}
+
+#[test]
+fn reformat_compact() {
+    let source = " {\n \"a\" : [ 1 , -2.50e+3 , 0 ] ,\t\"b\\n\\u00e9\" : { } , \"c\": [], \"d\": null, \"e\": [true, false] }\r\n";
+
+    assert_eq!(
+        reformat_str(source, Style::Compact).unwrap(),
+        r#"{"a":[1,-2.50e+3,0],"b\n\u00e9":{},"c":[],"d":null,"e":[true,false]}"#
+    );
+}
+
+#[test]
+fn reformat_pretty() {
+    let source = r#"{"a":[1,{"b":"zażółć"}],"c":{}}"#;
+
+    assert_eq!(
+        reformat_str(source, Style::Pretty(2)).unwrap(),
+        "{\n  \"a\": [\n    1,\n    {\n      \"b\": \"zażółć\"\n    }\n  ],\n  \"c\": {}\n}"
+    );
+}
+
+#[test]
+fn reformat_pretty_matches_parse_and_pretty() {
+    let source = r#"[{"id":1,"tags":["a","b"],"nested":{"ok":true,"list":[[],{}]}},"x",null]"#;
+
+    assert_eq!(
+        reformat_str(source, Style::Pretty(4)).unwrap(),
+        json::parse(source).unwrap().pretty(4)
+    );
+}
+
+#[test]
+fn reformat_scalars() {
+    assert_eq!(reformat_str(" 10 ", Style::Compact).unwrap(), "10");
+    assert_eq!(reformat_str("\"a\"", Style::Pretty(2)).unwrap(), "\"a\"");
+    assert_eq!(reformat_str("false", Style::Compact).unwrap(), "false");
+}
+
+#[test]
+fn reformat_reads_in_chunks() {
+    let source = format!("[{}]", vec!["\"zażółć gęślą jaźń\""; 2000].join(" , "));
+    let expected = format!("[{}]", vec!["\"zażółć gęślą jaźń\""; 2000].join(","));
+
+    assert_eq!(reformat_str(&source, Style::Compact).unwrap(), expected);
+}
+
+#[test]
+fn reformat_invalid() {
+    assert_eq!(reformat_error(""), Error::UnexpectedEndOfJson);
+    assert_eq!(reformat_error("[1,"), Error::UnexpectedEndOfJson);
+    assert_eq!(reformat_error("\"abc"), Error::UnexpectedEndOfJson);
+    assert_eq!(reformat_error("[1,]"), Error::UnexpectedCharacter { ch: ']', line: 1, column: 4, expected: &["a value"] });
+    assert_eq!(reformat_error("{\"a\" 1}"), Error::UnexpectedCharacter { ch: '1', line: 1, column: 6, expected: &[":"] });
+    assert_eq!(reformat_error("[01]"), Error::UnexpectedCharacter { ch: '1', line: 1, column: 3, expected: &[",", "]"] });
+    assert_eq!(reformat_error("1.e5"), Error::UnexpectedCharacter { ch: 'e', line: 1, column: 3, expected: &["a digit"] });
+    assert_eq!(reformat_error("[\n  tru ]"), Error::UnexpectedCharacter { ch: ' ', line: 2, column: 6, expected: &["true"] });
+    assert_eq!(reformat_error("\"\\x\""), Error::UnexpectedCharacter { ch: 'x', line: 1, column: 3, expected: &["\"", "\\", "/", "b", "f", "n", "r", "t", "u"] });
+    assert_eq!(reformat_error("{} ł"), Error::UnexpectedCharacter { ch: 'ł', line: 1, column: 4, expected: &[] });
+    assert_eq!(reformat_error("\"a\tb\""), Error::UnexpectedCharacter { ch: '\t', line: 1, column: 3, expected: &["an escaped control character"] });
+}
+
+#[test]
+fn reformat_invalid_utf8() {
+    let mut out = Vec::new();
+    let err = reformat(&b"\"\xED\xA0\x80\""[..], &mut out, Style::Compact).unwrap_err();
+
+    assert_eq!(*err.into_inner().unwrap().downcast::<Error>().unwrap(), Error::FailedUtf8Parsing);
+}
+
+#[test]
+fn reformat_depth_limit() {
+    let deep = format!("{}{}", "[".repeat(600), "]".repeat(600));
+    assert_eq!(reformat_error(&deep), Error::ExceededDepthLimit);
+
+    let ok = format!("{}{}", "[".repeat(512), "]".repeat(512));
+    assert_eq!(reformat_str(&ok, Style::Compact).unwrap(), ok);
+    assert!(json::parse(&ok).is_ok());
+}
+
+#[test]
+fn minify_str() {
+    let source = "{\n  \"a\": [ 1, 2.50 ],\n  \"b\": \"x y\\\" \"\n}\n";
+
+    assert_eq!(minify(source).unwrap(), r#"{"a":[1,2.50],"b":"x y\" "}"#);
+    assert_eq!(minify("[1 2]"), Err(Error::UnexpectedCharacter { ch: '2', line: 1, column: 4, expected: &[",", "]"] }));
+    assert_eq!(minify("  "), Err(Error::UnexpectedEndOfJson));
+}
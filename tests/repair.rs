@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate json;
+
+use json::{ repair, Error, Fix, JsonValue };
+
+fn repaired(source: &str) -> JsonValue {
+    repair(source).unwrap_or_else(|err| panic!("{}: {}", source, err)).0
+}
+
+#[test]
+fn repair_valid_json() {
+    let source = r#"{"a": [1, "x,]", {"b": null}], "c": "\"{"}"#;
+
+    assert_eq!(repair(source), Ok((json::parse(source).unwrap(), vec![])));
+}
+
+#[test]
+fn repair_truncated() {
+    assert_eq!(repair("[1, [2, 3"), Ok((array![1, array![2, 3]], vec![
+        Fix::ClosedBracket { index: 9, bracket: ']' },
+        Fix::ClosedBracket { index: 9, bracket: ']' },
+    ])));
+    assert_eq!(repair(r#"{"log": "disk ful"#), Ok((object!{ "log" => "disk ful" }, vec![
+        Fix::ClosedString { index: 17 },
+        Fix::ClosedBracket { index: 17, bracket: '}' },
+    ])));
+    assert_eq!(repaired(r#"["a\"#), array!["a"]);
+    assert_eq!(repaired(r#"["a\\"#), array!["a\\"]);
+    assert_eq!(repaired(r#"["żółw"#), array!["żółw"]);
+    assert_eq!(repair("[1, 2,"), Ok((array![1, 2], vec![
+        Fix::RemovedTrailingComma { index: 5 },
+        Fix::ClosedBracket { index: 6, bracket: ']' },
+    ])));
+}
+
+#[test]
+fn repair_trailing_commas() {
+    assert_eq!(repair("[1, 2, ]"), Ok((array![1, 2], vec![Fix::RemovedTrailingComma { index: 5 }])));
+    assert_eq!(repaired("{\"a\": [1,],\n}"), object!{ "a" => array![1] });
+    assert_eq!(repaired(r#"["a,]", "b",]"#), array!["a,]", "b"]);
+}
+
+#[test]
+fn repair_bare_keys() {
+    assert_eq!(repair("{id: 1, $ref_2: true}"), Ok((object!{ "id" => 1, "$ref_2" => true }, vec![
+        Fix::QuotedKey { index: 1 },
+        Fix::QuotedKey { index: 8 },
+    ])));
+    assert_eq!(repaired(r#"{"a": {b: [{c: null}]}}"#), object!{ "a" => object!{ "b" => array![object!{ "c" => JsonValue::Null }] } });
+
+    // Only keys are quoted
+    assert!(matches!(repair("[yes]"), Err(Error::UnexpectedCharacter { ch: 'y', .. })));
+    assert!(matches!(repair("{a: yes}"), Err(Error::UnexpectedCharacter { ch: 'y', .. })));
+}
+
+#[test]
+fn repair_leaves_other_errors() {
+    assert_eq!(repair(r#"{"a": 1, "b""#), Err(Error::UnexpectedCharacter { ch: '}', line: 1, column: 13, expected: &[":"] }));
+    assert_eq!(repair("[1}"), Err(Error::UnexpectedCharacter { ch: '}', line: 1, column: 3, expected: &[",", "]"] }));
+    assert_eq!(repair(""), Err(Error::UnexpectedEndOfJson));
+    assert_eq!(Fix::ClosedBracket { index: 4, bracket: ']' }.to_string(), "Added ] at byte 4");
+}
@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate json;
+
+use json::schema;
+use json::Null;
+
+#[test]
+fn infer_empty() {
+    assert_eq!(schema::infer(&[]), object!{ "$schema" => "https://json-schema.org/draft/2020-12/schema" });
+}
+
+#[test]
+fn infer_scalars() {
+    let inferred = schema::infer(&[1.into(), 2.5.into(), Null, 3e2.into()]);
+
+    assert_eq!(inferred["type"], array!["number", "null"]);
+
+    assert_eq!(schema::infer(&[10.into(), json::parse("1.0").unwrap()])["type"], "integer");
+    assert_eq!(schema::infer(&["a".into(), true.into()])["type"], array!["string", "boolean"]);
+}
+
+#[test]
+fn infer_optional_properties() {
+    let inferred = schema::infer(&[
+        object!{ "id" => 1, "name" => "a" },
+        object!{ "id" => 2 },
+        object!{ "id" => 3, "extra" => object!{ "deep" => true } }
+    ]);
+
+    assert_eq!(inferred["required"], array!["id"]);
+    assert_eq!(inferred["properties"]["name"], object!{ "type" => "string" });
+    assert_eq!(inferred["properties"]["extra"], object!{
+        "type" => "object",
+        "properties" => object!{ "deep" => object!{ "type" => "boolean" } },
+        "required" => array!["deep"]
+    });
+}
+
+#[test]
+fn infer_array_items() {
+    let inferred = schema::infer(&[array![
+        object!{ "a" => 1 },
+        object!{ "a" => "x", "b" => Null }
+    ]]);
+
+    assert_eq!(inferred["items"], object!{
+        "type" => "object",
+        "properties" => object!{
+            "a" => object!{ "type" => array!["string", "integer"] },
+            "b" => object!{ "type" => "null" }
+        },
+        "required" => array!["a"]
+    });
+
+    assert!(schema::infer(&[array![]])["items"].is_null());
+}
+
+#[test]
+fn infer_mixed_object_and_scalar() {
+    let inferred = schema::infer(&[object!{ "a" => 1 }, "text".into()]);
+
+    assert_eq!(inferred["type"], array!["object", "string"]);
+    assert_eq!(inferred["required"], array!["a"]);
+}
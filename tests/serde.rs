@@ -0,0 +1,159 @@
+#![cfg(feature = "serde")]
+
+#[macro_use]
+extern crate json;
+extern crate serde;
+
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::Serialize;
+
+use json::codegen::{ GeneratorOptions, IntegerStrings, NonFinite };
+use json::{ to_writer_serde, to_writer_serde_with, to_writer_serde_pretty_with, Case, JsonValue };
+
+#[derive(Serialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Line(i32, i32),
+    Rect { width: u32, height: u32 },
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Id(u64);
+
+#[derive(Serialize)]
+struct Drawing {
+    drawing_name: String,
+    id: Id,
+    shapes: Vec<Shape>,
+    tags: Vec<&'static str>,
+    owner: Option<&'static str>,
+    pair: (bool, char),
+    unit: Unit,
+    counts: BTreeMap<u32, i64>,
+}
+
+fn drawing() -> Drawing {
+    let mut counts = BTreeMap::new();
+    counts.insert(1, -1);
+    counts.insert(20, 9007199254740993);
+
+    Drawing {
+        drawing_name: "a \"quoted\"\n</name> é".into(),
+        id: Id(7),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Line(-1, 2),
+            Shape::Rect { width: 3, height: 4 },
+        ],
+        tags: vec![],
+        owner: None,
+        pair: (true, 'x'),
+        unit: Unit,
+        counts,
+    }
+}
+
+// The same as `drawing`, built as a `JsonValue`
+fn drawing_value() -> JsonValue {
+    object!{
+        "drawing_name" => "a \"quoted\"\n</name> é",
+        "id" => 7,
+        "shapes" => array![
+            "Empty",
+            object!{ "Circle" => 1.5 },
+            object!{ "Line" => array![-1, 2] },
+            object!{ "Rect" => object!{ "width" => 3, "height" => 4 } }
+        ],
+        "tags" => array![],
+        "owner" => JsonValue::Null,
+        "pair" => array![true, "x"],
+        "unit" => JsonValue::Null,
+        "counts" => object!{ "1" => -1, "20" => 9007199254740993u64 }
+    }
+}
+
+fn serde_dump<T: Serialize>(value: &T, options: &GeneratorOptions) -> String {
+    let mut out = Vec::new();
+    to_writer_serde_with(value, &mut out, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn serde_pretty<T: Serialize>(value: &T, spaces: u16, options: &GeneratorOptions) -> String {
+    let mut out = Vec::new();
+    to_writer_serde_pretty_with(value, &mut out, spaces, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn serde_matches_dump() {
+    let mut out = Vec::new();
+    to_writer_serde(&drawing(), &mut out).unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), drawing_value().dump());
+}
+
+#[test]
+fn serde_matches_pretty() {
+    let options = GeneratorOptions::default();
+
+    assert_eq!(serde_pretty(&drawing(), 2, &options), drawing_value().pretty(2));
+    assert_eq!(serde_pretty(&drawing(), 4, &GeneratorOptions::JSON5), drawing_value().pretty_with(4, &GeneratorOptions::JSON5));
+    assert_eq!(serde_pretty(&Vec::<u8>::new(), 2, &options), "[]");
+}
+
+#[test]
+fn serde_generator_options() {
+    let options = GeneratorOptions {
+        escape_slashes: true,
+        escape_unicode: true,
+        key_case: Some(Case::CamelCase),
+        integer_strings: IntegerStrings::Unsafe,
+        trailing_newline: true,
+        ..GeneratorOptions::default()
+    };
+
+    assert_eq!(serde_dump(&drawing(), &options), drawing_value().dump_with(&options));
+    assert!(serde_dump(&drawing(), &options).ends_with("}\n"));
+
+    let options = GeneratorOptions {
+        max_pretty_depth: Some(2),
+        trailing_commas: true,
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(serde_pretty(&drawing(), 2, &options), drawing_value().pretty_with(2, &options));
+}
+
+#[test]
+fn serde_non_finite() {
+    let floats = [1.0, f64::NAN, f64::INFINITY];
+
+    assert_eq!(serde_dump(&floats, &GeneratorOptions::default()), "[1,null,null]");
+
+    let options = GeneratorOptions {
+        non_finite: NonFinite::Error,
+        ..GeneratorOptions::default()
+    };
+    let mut out = Vec::new();
+    let err = to_writer_serde_with(&floats, &mut out, &options).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn serde_invalid_keys() {
+    let mut map = BTreeMap::new();
+    map.insert(vec![1], 2);
+
+    let mut out = Vec::new();
+    let err = to_writer_serde(&map, &mut out).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "Map keys must be strings, numbers, booleans or chars");
+}
@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate json;
+
+use json::schema::Shape;
+use json::{ Error, Null };
+
+fn wrong_type(expected: &str, found: &str, path: &str) -> Error {
+    Error::WrongType {
+        expected: expected.into(),
+        found: found.into(),
+        path: path.into(),
+    }
+}
+
+fn user() -> Shape {
+    Shape::object()
+        .field("name", Shape::String)
+        .field("roles", Shape::array(Shape::String))
+        .optional("manager", Shape::object().field("name", Shape::String).nullable())
+}
+
+#[test]
+fn shape_accepts_matching_documents() {
+    let source = r#"{ "name": "Jon", "roles": [], "manager": { "name": "Ned" } }"#;
+
+    assert_eq!(user().parse(source).unwrap(), object!{
+        "name" => "Jon",
+        "roles" => array![],
+        "manager" => object!{ "name" => "Ned" }
+    });
+
+    assert!(user().parse(r#"{ "roles": ["a"], "name": "Jon", "manager": null }"#).is_ok());
+}
+
+#[test]
+fn shape_rejects_wrong_types() {
+    assert_eq!(
+        user().parse(r#"{ "name": 1, "roles": [] }"#),
+        Err(wrong_type("string", "number", "/name"))
+    );
+    assert_eq!(
+        user().parse(r#"{ "name": "a", "roles": ["x", "y", false] }"#),
+        Err(wrong_type("string", "boolean", "/roles/2"))
+    );
+    assert_eq!(
+        user().parse(r#"{ "name": "a", "roles": [], "manager": [] }"#),
+        Err(wrong_type("object or null", "array", "/manager"))
+    );
+    assert_eq!(user().parse("[]"), Err(wrong_type("object", "array", "")));
+}
+
+#[test]
+fn shape_rejects_before_parsing_the_rest() {
+    // The syntax error after the mismatch is never reached
+    assert_eq!(
+        user().parse(r#"{ "name": true, "roles": [ garbage"#),
+        Err(wrong_type("string", "boolean", "/name"))
+    );
+}
+
+#[test]
+fn shape_missing_and_unknown_members() {
+    assert_eq!(
+        user().parse(r#"{ "name": "a" }"#),
+        Err(Error::MemberNotFound { key: "roles".into(), path: "".into() })
+    );
+    assert_eq!(
+        user().parse(r#"{ "name": "a", "roles": [], "manager": {} }"#),
+        Err(Error::MemberNotFound { key: "name".into(), path: "/manager".into() })
+    );
+    assert_eq!(
+        user().parse(r#"{ "name": "a", "age": 3, "roles": [] }"#),
+        Err(wrong_type("object without member \"age\"", "object", ""))
+    );
+    assert_eq!(
+        user().additional().parse(r#"{ "name": "a", "age": [3], "roles": [] }"#).unwrap()["age"],
+        array![3]
+    );
+}
+
+#[test]
+fn shape_nested_arrays() {
+    let matrix = Shape::array(Shape::array(Shape::Number));
+
+    assert_eq!(matrix.parse("[[1, 2], [], [-3]]").unwrap(), array![array![1, 2], array![], array![-3]]);
+    assert_eq!(matrix.parse("[[1], [2, \"3\"]]"), Err(wrong_type("number", "string", "/1/1")));
+}
+
+#[test]
+fn shape_any_and_scalars() {
+    assert_eq!(Shape::Any.parse("{\"a\": [1]}").unwrap(), object!{ "a" => array![1] });
+    assert_eq!(Shape::Null.parse("null").unwrap(), Null);
+    assert_eq!(Shape::Boolean.parse("1"), Err(wrong_type("boolean", "number", "")));
+    assert_eq!(Shape::Number.nullable().parse("null").unwrap(), Null);
+    assert_eq!(
+        Shape::array(Shape::Any).parse("[1, {\"x\": []}]").unwrap(),
+        array![1, object!{ "x" => array![] }]
+    );
+}
+
+#[test]
+fn shape_keys_are_escaped_in_paths() {
+    let shape = Shape::object().field("a/b", Shape::object().field("c", Shape::Null));
+
+    assert_eq!(shape.parse(r#"{ "a/b": { "c": 1 } }"#), Err(wrong_type("null", "number", "/a~1b/c")));
+}
+
+#[test]
+fn shape_syntax_errors_still_reported() {
+    assert_eq!(Shape::Any.parse("[1,]"), Err(Error::UnexpectedCharacter { ch: ']', line: 1, column: 4, expected: &["a value"] }));
+}
@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate json;
+
+use json::{ dedup_subtrees, SharedValue };
+
+#[test]
+fn dedup_subtrees_shares_identical_parts() {
+    let source = r#"{
+        "paths": [
+            {"get": {"name": "users", "schema": {"type": "object", "properties": {"id": {"type": "integer"}}}}},
+            {"get": {"name": "orders", "schema": {"type": "object", "properties": {"id": {"type": "integer"}}}}},
+            {"get": {"name": "items", "schema": {"type": "object", "properties": {"id": {"type": "integer"}}}}}
+        ],
+        "empty": [[], {}, [], {}],
+        "numbers": [1, 1.0, -0, 0, 12345678901234567890123]
+    }"#;
+    let value = json::parse(source).unwrap();
+    let shared = dedup_subtrees(&value);
+
+    assert_eq!(shared.to_json(), value);
+    assert_eq!(shared["numbers"].to_json().dump(), "[1,1.0,-0,0,12345678901234567890123]");
+
+    let paths = &shared["paths"];
+    assert!(paths[0]["get"]["schema"].ptr_eq(&paths[1]["get"]["schema"]));
+    assert!(paths[0]["get"]["schema"].ptr_eq(&paths[2]["get"]["schema"]));
+    assert!(!paths[0]["get"].ptr_eq(&paths[1]["get"]));
+    assert!(paths[0].entries().next().unwrap().0.as_ptr() == paths[1].entries().next().unwrap().0.as_ptr());
+
+    assert!(shared["empty"][0].ptr_eq(&shared["empty"][2]));
+    assert!(!shared["empty"][0].ptr_eq(&shared["empty"][1]));
+    assert_eq!(shared["empty"][1].type_name(), "object");
+}
+
+#[test]
+fn shared_value_accessors() {
+    let shared = dedup_subtrees(&object!{ "name" => "Ferris", "age" => 7, "tags" => array![true] });
+
+    assert_eq!(shared["name"].as_str(), Some("Ferris"));
+    assert_eq!(shared["age"].as_u64(), Some(7));
+    assert_eq!(shared["tags"][0].as_bool(), Some(true));
+    assert_eq!(shared["tags"].len(), 1);
+    assert_eq!(shared["missing"], SharedValue::Null);
+    assert_eq!(shared["tags"][5], SharedValue::Null);
+    assert_eq!(shared.members().count(), 0);
+    assert!(shared["tags"][5].is_empty() && !shared["tags"].is_empty());
+    assert_eq!(json::JsonValue::from(shared.clone()), shared.to_json());
+}
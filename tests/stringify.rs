@@ -3,7 +3,8 @@ extern crate json;
 
 use std::collections::{ HashMap, BTreeMap };
 use std::f64;
-use json::{ parse, stringify, stringify_pretty, JsonValue, Null };
+use json::{ parse, stringify, stringify_pretty, JsonValue, Null, Case };
+use json::codegen::{ GeneratorOptions, ExponentThresholds, NonFinite, Redact, Serializer };
 
 #[test]
 fn stringify_null() {
@@ -221,6 +222,362 @@ fn stringify_dont_escape_forward_slash() {
     assert_eq!(stringify("foo/bar"), r#""foo/bar""#);
 }
 
+#[test]
+fn stringify_escape_forward_slash_with_options() {
+    let options = GeneratorOptions {
+        escape_slashes: true,
+        ..GeneratorOptions::default()
+    };
+    let data = object!{ "a/b" => "http://example.com/" };
+
+    assert_eq!(data.dump_with(&options), r#"{"a\/b":"http:\/\/example.com\/"}"#);
+    assert_eq!(data.dump(), r#"{"a/b":"http://example.com/"}"#);
+}
+
+#[test]
+fn stringify_integral_as_integer() {
+    let options = GeneratorOptions {
+        integral_as_integer: true,
+        ..GeneratorOptions::default()
+    };
+    let data = parse("[3.0, 3e0, 1.5e1, 300e-2, 1e21, 2.50, -0.0, 0]").unwrap();
+
+    assert_eq!(data.dump(), "[3.0,3,15,3.00,1e21,2.50,-0,0]");
+    assert_eq!(data.dump_with(&options), "[3,3,15,3,1000000000000000000000,2.50,-0,0]");
+}
+
+#[test]
+fn stringify_exponent_thresholds() {
+    let options = GeneratorOptions {
+        exponent_thresholds: Some(ExponentThresholds { min: -2, max: 3 }),
+        ..GeneratorOptions::default()
+    };
+    let data = parse("[0, 1, 0.01, 0.001, 999, 1000, 12.5, 1234.5, -0.0123, 2e-20]").unwrap();
+
+    assert_eq!(data.dump_with(&options), "[0,1,0.01,1e-3,999,1e+3,12.5,1.2345e+3,-0.0123,2e-20]");
+}
+
+#[test]
+fn stringify_exponent_thresholds_javascript() {
+    let options = GeneratorOptions {
+        exponent_thresholds: Some(ExponentThresholds::JAVASCRIPT),
+        ..GeneratorOptions::default()
+    };
+    let data = array![1e21, 123456789012345680000.0, 0.0000001, 0.000001, 3.0, 1.5];
+
+    assert_eq!(data.dump_with(&options), "[1e+21,123456789012345680000,1e-7,0.000001,3,1.5]");
+}
+
+#[test]
+fn stringify_exponent_thresholds_wide() {
+    let options = GeneratorOptions {
+        exponent_thresholds: Some(ExponentThresholds::JAVASCRIPT),
+        ..GeneratorOptions::default()
+    };
+    let data = parse("[12345678901234567890123, -100000000000000000000000, 123456789012345678901]").unwrap();
+
+    assert_eq!(data.dump(), "[12345678901234567890123,-100000000000000000000000,123456789012345678901]");
+    assert_eq!(data.dump_with(&options), "[1.2345678901234567890123e+22,-1e+23,123456789012345678901]");
+
+    let options = GeneratorOptions {
+        integral_as_integer: true,
+        ..options
+    };
+    assert_eq!(data.dump_with(&options), "[12345678901234567890123,-100000000000000000000000,123456789012345678901]");
+}
+
+#[test]
+fn stringify_non_finite_policy() {
+    let data = array![1, f64::NAN, f64::INFINITY];
+
+    let options = GeneratorOptions {
+        non_finite: NonFinite::String,
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(data.dump_with(&options), r#"[1,"NaN","NaN"]"#);
+
+    let options = GeneratorOptions {
+        non_finite: NonFinite::Error,
+        ..GeneratorOptions::default()
+    };
+    let mut out = Vec::new();
+    let err = data.write_with(&mut out, &options).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    assert_eq!(data.dump(), "[1,null,null]");
+}
+
+#[test]
+fn stringify_pretty_number_rows() {
+    let options = GeneratorOptions {
+        numbers_per_line: Some(4),
+        ..GeneratorOptions::default()
+    };
+    let data = object!{
+        "vertices" => array![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        "empty" => array![],
+        "mixed" => array![1, "two"]
+    };
+
+    assert_eq!(
+        data.pretty_with(2, &options),
+        "{\n  \"vertices\": [\n    0, 1, 2, 3,\n    4, 5, 6, 7,\n    8, 9\n  ],\n  \"empty\": [],\n  \"mixed\": [\n    1,\n    \"two\"\n  ]\n}"
+    );
+    assert_eq!(data.dump_with(&options), data.dump());
+}
+
+#[test]
+fn stringify_key_case() {
+    let data = object!{
+        "user_id" => 1,
+        "homeAddress" => object!{ "ZIPCode" => "00-950", "street-name" => "Main" },
+        "list" => array![object!{ "first_name" => "snake_value" }]
+    };
+
+    let options = GeneratorOptions {
+        key_case: Some(Case::CamelCase),
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(
+        data.dump_with(&options),
+        r#"{"userId":1,"homeAddress":{"zipCode":"00-950","streetName":"Main"},"list":[{"firstName":"snake_value"}]}"#
+    );
+
+    let options = GeneratorOptions {
+        key_case: Some(Case::SnakeCase),
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(
+        data.dump_with(&options),
+        r#"{"user_id":1,"home_address":{"zip_code":"00-950","street_name":"Main"},"list":[{"first_name":"snake_value"}]}"#
+    );
+
+    let options = GeneratorOptions {
+        key_case: Some(Case::KebabCase),
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(
+        data.dump_with(&options),
+        r#"{"user-id":1,"home-address":{"zip-code":"00-950","street-name":"Main"},"list":[{"first-name":"snake_value"}]}"#
+    );
+}
+
+#[test]
+fn stringify_redact() {
+    fn redact(path: &str, key: &str) -> Redact {
+        match (path, key) {
+            (_, "password")         => Redact::Mask,
+            ("/users/1", "email")   => Redact::Drop,
+            ("", "debug")           => Redact::Drop,
+            _                       => Redact::Keep,
+        }
+    }
+
+    let options = GeneratorOptions {
+        redact: Some(redact),
+        ..GeneratorOptions::default()
+    };
+    let data = object!{
+        "debug" => true,
+        "users" => array![
+            object!{ "email" => "a@example.com", "password" => "secret" },
+            object!{ "email" => "b@example.com", "password" => object!{ "hash" => "x" } },
+            object!{}
+        ],
+        "empty" => array![]
+    };
+
+    assert_eq!(
+        data.dump_with(&options),
+        r#"{"users":[{"email":"a@example.com","password":"***"},{"password":"***"},{}],"empty":[]}"#
+    );
+    assert_eq!(
+        data["users"][1].pretty_with(2, &options),
+        "{\n  \"email\": \"b@example.com\",\n  \"password\": \"***\"\n}"
+    );
+    assert_eq!(object!{ "debug" => 1 }.dump_with(&options), "{}");
+}
+
+#[test]
+fn stringify_trailing_newline() {
+    let options = GeneratorOptions {
+        trailing_newline: true,
+        ..GeneratorOptions::default()
+    };
+    let data = object!{ "a" => array![1] };
+
+    assert_eq!(data.dump_with(&options), "{\"a\":[1]}\n");
+    assert_eq!(data.pretty_with(2, &options), "{\n  \"a\": [\n    1\n  ]\n}\n");
+
+    let mut out = Vec::new();
+    data.write_with(&mut out, &options).unwrap();
+    assert_eq!(out, b"{\"a\":[1]}\n");
+}
+
+#[test]
+fn stringify_pretty_custom_indent() {
+    let data = object!{ "a" => array![1, object!{ "b" => Null }] };
+
+    let options = GeneratorOptions {
+        indent: Some("\t"),
+        ..GeneratorOptions::default()
+    };
+    assert_eq!(
+        data.pretty_with(4, &options),
+        "{\n\t\"a\": [\n\t\t1,\n\t\t{\n\t\t\t\"b\": null\n\t\t}\n\t]\n}"
+    );
+
+    let options = GeneratorOptions {
+        key_separator: ":",
+        ..GeneratorOptions::default()
+    };
+    let mut out = Vec::new();
+    data.write_pretty_with(&mut out, 1, &options).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "{\n \"a\":[\n  1,\n  {\n   \"b\":null\n  }\n ]\n}");
+
+    assert_eq!(data.dump_with(&options), r#"{"a":[1,{"b":null}]}"#);
+}
+
+#[test]
+fn stringify_write_lines() {
+    let values = vec![
+        object!{ "text" => "multi\nline", "list" => array![1, 2] },
+        Null,
+        JsonValue::from("end"),
+    ];
+
+    let mut out = Vec::new();
+    json::write_lines(&values, &mut out).unwrap();
+    json::write_lines(values.into_iter().take(1), &mut out).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{\"text\":\"multi\\nline\",\"list\":[1,2]}\nnull\n\"end\"\n{\"text\":\"multi\\nline\",\"list\":[1,2]}\n"
+    );
+}
+
+#[test]
+fn stringify_write_seq() {
+    let mut out = Vec::new();
+
+    json::write_seq(vec![JsonValue::from(1), JsonValue::from("a\u{1e}b")], &mut out).unwrap();
+
+    assert_eq!(out, b"\x1E1\n\x1E\"a\\u001eb\"\n");
+}
+
+#[test]
+fn stringify_serialized_len() {
+    let data = object!{
+        "esc\"aped\n" => "\u{1}ünïcödé\t",
+        "numbers" => array![0, -1, 3.14, 1e300, 123456789012345u64, f64::NAN],
+        "nested" => object!{ "empty" => array![], "obj" => object!{}, "b" => false },
+        "long" => "a".repeat(100)
+    };
+
+    assert_eq!(data.serialized_len(), data.dump().len());
+    assert_eq!(JsonValue::from("x").serialized_len(), 3);
+    assert_eq!(Null.serialized_len(), 4);
+}
+
+#[test]
+fn stringify_reusable_serializer() {
+    let mut ser = Serializer::with_options(GeneratorOptions {
+        non_finite: NonFinite::Error,
+        ..GeneratorOptions::default()
+    });
+
+    ser.write(&array!["x".repeat(500)]).unwrap();
+    let capacity = ser.capacity();
+
+    ser.clear();
+    assert!(ser.is_empty());
+
+    ser.write(&object!{ "a" => 1 }).unwrap();
+    ser.write(&JsonValue::from(true)).unwrap();
+    assert!(ser.write(&array![1, f64::NAN]).is_err());
+
+    assert_eq!(ser.as_str(), r#"{"a":1}true"#);
+    assert_eq!(ser.as_bytes().len(), ser.len());
+    assert_eq!(ser.capacity(), capacity);
+}
+
+#[test]
+fn stringify_minimal_escaping_by_default() {
+    let data = object!{ "ключ" => "日本語\t\"ok\"\u{1}" };
+
+    assert_eq!(data.dump(), "{\"ключ\":\"日本語\\t\\\"ok\\\"\\u0001\"}");
+}
+
+#[test]
+fn stringify_escape_unicode() {
+    let options = GeneratorOptions {
+        escape_unicode: true,
+        ..GeneratorOptions::default()
+    };
+
+    let data = object!{ "ключ" => "a\"é𝄞\n" };
+
+    assert_eq!(data.dump_with(&options), r#"{"\u043a\u043b\u044e\u0447":"a\"\u00e9\ud834\udd1e\n"}"#);
+    assert_eq!(json::parse(&data.dump_with(&options)).unwrap(), data);
+}
+
+#[test]
+fn stringify_json5() {
+    let data = object!{
+        "name" => "app",
+        "$ref" => Null,
+        "2fa" => true,
+        "empty" => array![],
+        "nested" => object!{ "ok" => array![1, 2] }
+    };
+
+    assert_eq!(data.dump_with(&GeneratorOptions::JSON5), r#"{name:"app",$ref:null,"2fa":true,empty:[],nested:{ok:[1,2,],},}"#);
+    assert_eq!(data.pretty_with(2, &GeneratorOptions::JSON5), r#"{
+  name: "app",
+  $ref: null,
+  "2fa": true,
+  empty: [],
+  nested: {
+    ok: [
+      1,
+      2,
+    ],
+  },
+}"#);
+}
+
+#[test]
+fn stringify_unquoted_keys_after_case_conversion() {
+    let options = GeneratorOptions {
+        unquoted_keys: true,
+        key_case: Some(Case::KebabCase),
+        ..GeneratorOptions::default()
+    };
+
+    let data = object!{ "userId" => 1, "id" => 2 };
+
+    assert_eq!(data.dump_with(&options), r#"{"user-id":1,id:2}"#);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn stringify_colored() {
+    let data = object!{ "a" => array![1, "b", Null], "c" => object!{} };
+
+    let expected = concat!(
+        "\x1b[37m{\x1b[0m\n",
+        "  \x1b[1;34m\"a\"\x1b[0m\x1b[37m:\x1b[0m \x1b[37m[\x1b[0m\n",
+        "    \x1b[36m1\x1b[0m\x1b[37m,\x1b[0m\n",
+        "    \x1b[32m\"b\"\x1b[0m\x1b[37m,\x1b[0m\n",
+        "    \x1b[35mnull\x1b[0m\n",
+        "  \x1b[37m]\x1b[0m\x1b[37m,\x1b[0m\n",
+        "  \x1b[1;34m\"c\"\x1b[0m\x1b[37m:\x1b[0m \x1b[37m{\x1b[0m\x1b[37m}\x1b[0m\n",
+        "\x1b[37m}\x1b[0m"
+    );
+
+    assert_eq!(data.dump_colored(), expected);
+}
+
 #[test]
 fn stringify_escaped() {
     assert_eq!(stringify("http://www.google.com/\t"), r#""http://www.google.com/\t""#);
@@ -247,3 +604,185 @@ fn stringify_pretty_object() {
     assert_eq!(object.pretty(2), expected);
     assert_eq!(stringify_pretty(object, 2), expected);
 }
+
+#[test]
+fn stringify_binary() {
+    use json::codegen::BinaryFormat;
+
+    let data = object!{ "blob" => JsonValue::Binary(b"\x00binary\xff".to_vec()), "empty" => JsonValue::Binary(vec![]) };
+
+    assert_eq!(data.dump(), r#"{"blob":"AGJpbmFyef8=","empty":""}"#);
+    assert_eq!(data.to_string(), data.dump());
+
+    let hex = GeneratorOptions { binary: BinaryFormat::Hex, ..GeneratorOptions::default() };
+    assert_eq!(data.dump_with(&hex), r#"{"blob":"0062696e617279ff","empty":""}"#);
+
+    let array = GeneratorOptions { binary: BinaryFormat::Array, ..GeneratorOptions::default() };
+    assert_eq!(data.dump_with(&array), r#"{"blob":[0,98,105,110,97,114,121,255],"empty":[]}"#);
+    assert_eq!(data.pretty_with(2, &array), "{\n  \"blob\": [\n    0,\n    98,\n    105,\n    110,\n    97,\n    114,\n    121,\n    255\n  ],\n  \"empty\": []\n}");
+}
+
+#[test]
+fn stringify_integer_strings() {
+    use json::codegen::IntegerStrings;
+
+    let options = |integer_strings| GeneratorOptions { integer_strings, ..GeneratorOptions::default() };
+    let data = array![
+        9007199254740992u64,
+        9007199254740993u64,
+        -9007199254740993i64,
+        u64::MAX,
+        i64::MIN,
+        parse("340282366920938463463374607431768211456").unwrap(),
+        parse("1e400").unwrap(),
+        parse("12345678901234567.5").unwrap(),
+        42,
+        parse("3.0").unwrap(),
+        0.5,
+        f64::NAN
+    ];
+
+    assert_eq!(data.dump_with(&options(IntegerStrings::Never)), data.dump());
+    assert_eq!(data.dump_with(&options(IntegerStrings::Unsafe)), concat!(
+        r#"[9007199254740992,"9007199254740993","-9007199254740993","18446744073709551615","-9223372036854775808","#,
+        r#""3.402823669209384634e38","1e400","12345678901234567.5",42,3.0,0.5,null]"#,
+    ));
+    assert_eq!(data.dump_with(&options(IntegerStrings::Always)), concat!(
+        r#"["9007199254740992","9007199254740993","-9007199254740993","18446744073709551615","-9223372036854775808","#,
+        r#""3.402823669209384634e38","1e400","12345678901234567.5","42","3.0",0.5,null]"#,
+    ));
+
+    let mut out = Vec::new();
+    json::JsonWriter::with_options(&mut out, Some(2), options(IntegerStrings::Always)).number(7).unwrap();
+    assert_eq!(out, b"\"7\"");
+}
+
+#[test]
+fn stringify_on_value() {
+    use json::codegen::Emit;
+
+    fn hook(path: &str, value: &JsonValue) -> Emit {
+        match *value {
+            _ if path == "/meta"  => Emit::Raw("\"hidden\"".into()),
+            JsonValue::Number(_) if path.starts_with("/totals/") => Emit::Raw(format!("{:.2}", value.as_f64().unwrap())),
+            JsonValue::Boolean(flag) => Emit::Raw(if flag { "1" } else { "0" }.into()),
+            _ => Emit::Default,
+        }
+    }
+
+    let options = GeneratorOptions {
+        on_value: Some(hook),
+        ..GeneratorOptions::default()
+    };
+    let data = object!{
+        "meta" => object!{ "a" => 1 },
+        "totals" => array![12.5, 3, "n/a"],
+        "flags" => object!{ "on" => true, "off" => false },
+        "n" => 7
+    };
+
+    assert_eq!(data.dump_with(&options), r#"{"meta":"hidden","totals":[12.50,3.00,"n/a"],"flags":{"on":1,"off":0},"n":7}"#);
+    assert_eq!(JsonValue::from(true).dump_with(&options), "1");
+
+    let rows = GeneratorOptions { numbers_per_line: Some(2), ..options };
+    assert_eq!(data["totals"].pretty_with(2, &rows), "[\n  12.5,\n  3,\n  \"n/a\"\n]");
+    assert_eq!(object!{ "totals" => array![1, 2, 3] }.pretty_with(2, &rows), "{\n  \"totals\": [\n    1.00, 2.00,\n    3.00\n  ]\n}");
+
+    fn root(path: &str, _: &JsonValue) -> Emit {
+        match path {
+            "" => Emit::Raw("[]".into()),
+            _  => Emit::Default,
+        }
+    }
+    assert_eq!(data.dump_with(&GeneratorOptions { on_value: Some(root), ..GeneratorOptions::default() }), "[]");
+}
+
+#[test]
+fn stringify_max_pretty_depth() {
+    let options = |depth| GeneratorOptions { max_pretty_depth: Some(depth), ..GeneratorOptions::default() };
+    let data = object!{
+        "a" => object!{ "b" => array![1, object!{ "c" => true }], "d" => object!{} },
+        "e" => array![array![], "x"],
+        "f" => 3
+    };
+
+    assert_eq!(data.pretty_with(2, &options(0)), data.dump());
+    assert_eq!(
+        data.pretty_with(2, &options(1)),
+        "{\n  \"a\": {\"b\":[1,{\"c\":true}],\"d\":{}},\n  \"e\": [[],\"x\"],\n  \"f\": 3\n}"
+    );
+    assert_eq!(
+        data.pretty_with(2, &options(2)),
+        "{\n  \"a\": {\n    \"b\": [1,{\"c\":true}],\n    \"d\": {}\n  },\n  \"e\": [\n    [],\n    \"x\"\n  ],\n  \"f\": 3\n}"
+    );
+    assert_eq!(data.pretty_with(2, &options(3)), data.pretty(2).replace("{\n        \"c\": true\n      }", "{\"c\":true}"));
+    assert_eq!(data.pretty_with(2, &options(4)), data.pretty(2));
+
+    let mut out = Vec::new();
+    data.write_pretty_with(&mut out, 2, &options(1)).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), data.pretty_with(2, &options(1)));
+
+    let mut writer = json::JsonWriter::with_options(Vec::new(), Some(2), options(1));
+    writer.begin_array().unwrap();
+    writer.begin_object().unwrap();
+    writer.key("k").unwrap();
+    writer.value(&array![1, 2]).unwrap();
+    writer.end_object().unwrap();
+    writer.number(1).unwrap();
+    writer.end_array().unwrap();
+    assert_eq!(writer.finish().unwrap(), b"[\n  {\"k\":[1,2]},\n  1\n]");
+
+    // Objects left empty by redaction still close on their own line
+    fn redact(_: &str, key: &str) -> Redact {
+        match key {
+            "c" => Redact::Drop,
+            _   => Redact::Keep,
+        }
+    }
+    let redacted = GeneratorOptions { redact: Some(redact), ..options(2) };
+    assert_eq!(
+        array![array![object!{ "c" => 1 }, 2], 3].pretty_with(2, &redacted),
+        "[\n  [\n    {},\n    2\n  ],\n  3\n]"
+    );
+}
+
+#[test]
+fn stringify_preview() {
+    let data = object!{
+        "user" => object!{ "name" => "Zażółć \"gęślą\" jaźń", "id" => 12345 },
+        "items" => (0 .. 100).collect::<Vec<i32>>(),
+        "ok" => true
+    };
+
+    assert_eq!(data.preview(usize::MAX), data.dump());
+    assert_eq!(data.preview(45), r#"{"user":{"name":"…",… 1 more},… 2 more}"#);
+    assert_eq!(data.preview(60), r#"{"user":{"name":"Zażółć \"g…",… 1 more},… 2 more}"#);
+    assert_eq!(data.preview(100), r#"{"user":{"name":"Zażółć \"gęślą\" jaźń","id":12345},"items":[0,1,2,… 97 more],… 1 more}"#);
+
+    // Notes on what was left out may not fit into tiny budgets
+    assert_eq!(data.preview(30), r#"{"user":{… 2 more},… 2 more}"#);
+
+    assert_eq!(array![1, 2, 3].preview(7), "[1,2,3]");
+    assert_eq!(array![1, 2, 3].preview(6), "[… 3 more]");
+    assert_eq!(JsonValue::from("\n\n\n\n").preview(8), r#""\n…""#);
+    assert_eq!(JsonValue::from(12345).preview(3), "…");
+    assert_eq!(array![].preview(0), "[]");
+}
+
+#[test]
+fn stringify_escape_predicate() {
+    fn html(ch: char) -> bool {
+        matches!(ch, '<' | '>' | '&' | '\n' | '\u{2028}' | '\u{2029}')
+    }
+
+    let options = GeneratorOptions { escape: Some(html), ..GeneratorOptions::default() };
+    let data = object!{ "<b>" => "a\u{2028}b & \"c\"\n</b> żółw" };
+
+    assert_eq!(data.dump_with(&options), r#"{"\u003cb\u003e":"a\u2028b \u0026 \"c\"\n\u003c/b\u003e żółw"}"#);
+    assert_eq!(data.pretty_with(2, &options), "{\n  \"\\u003cb\\u003e\": \"a\\u2028b \\u0026 \\\"c\\\"\\n\\u003c/b\\u003e żółw\"\n}");
+
+    let both = GeneratorOptions { escape_unicode: true, escape_slashes: true, ..options };
+    assert_eq!(data.dump_with(&both), r#"{"\u003cb\u003e":"a\u2028b \u0026 \"c\"\n\u003c\/b\u003e \u017c\u00f3\u0142w"}"#);
+
+    assert_eq!(parse(&data.dump_with(&options)).unwrap(), data);
+}
@@ -0,0 +1,68 @@
+extern crate json;
+
+use json::tape::Tape;
+
+#[test]
+fn tape_to_json_matches_parse() {
+    let sources = [
+        "null",
+        "[]",
+        "{}",
+        r#""esc\"aped\né""#,
+        "-1.5e-3",
+        r#"[1,[2,[3,[]],{}],"x",{"a":[true,false,null]}]"#,
+        r#"{"a":{"b":{"c":[1,2,{"d":"e"}]},"f":[]},"g":-0,"h":"😀","a":1}"#,
+    ];
+
+    for source in sources.iter() {
+        let tape = Tape::parse(source).unwrap();
+
+        assert_eq!(tape.to_json(), json::parse(source).unwrap(), "{}", source);
+    }
+}
+
+#[test]
+fn tape_navigation() {
+    let tape = Tape::parse(r#"{"skip":{"deep":[[1,2],{"x":[3]}]},"tags":["a","b"],"age":30,"ok":true,"tags":["c"]}"#).unwrap();
+    let root = tape.root();
+
+    assert_eq!(tape.len(), 23);
+    assert!(root.is_object());
+    assert_eq!(root.len(), 5);
+    assert_eq!(root.get("age").and_then(|age| age.as_u64()), Some(30));
+    assert_eq!(root.get("ok").and_then(|ok| ok.as_bool()), Some(true));
+    assert_eq!(root.get("tags").map(|tags| tags.len()), Some(1));
+    assert!(root.get("missing").is_none());
+    assert!(root.at(0).is_none());
+
+    let deep = root.get("skip").and_then(|skip| skip.get("deep")).unwrap();
+    assert_eq!(deep.type_name(), "array");
+    assert_eq!(deep.at(1).and_then(|x| x.get("x")).and_then(|x| x.at(0)).and_then(|x| x.as_f64()), Some(3.0));
+    assert!(deep.at(2).is_none());
+
+    let keys: Vec<&str> = root.entries().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["skip", "tags", "age", "ok", "tags"]);
+
+    let values: Vec<String> = deep.members().map(|member| member.to_json().dump()).collect();
+    assert_eq!(values, ["[1,2]", r#"{"x":[3]}"#]);
+}
+
+#[test]
+fn tape_parse_errors() {
+    let sources = [
+        "[1,2",
+        r#"{"a" 1}"#,
+        "[1] x",
+        "[1,]",
+        r#"{"a":1]"#,
+        "[1}",
+    ];
+
+    for source in sources.iter() {
+        assert_eq!(Tape::parse(source), Err(json::parse(source).unwrap_err()), "{}", source);
+    }
+
+    let source = format!("[0,[{}]]", r#"{"a":["#.repeat(300));
+
+    assert_eq!(Tape::parse(&source), Err(json::parse(&source).unwrap_err()));
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "ubjson")]
+
+#[macro_use]
+extern crate json;
+
+use std::convert::TryInto;
+use std::io;
+use json::ubjson;
+use json::number::Number;
+use json::{ JsonValue, Null };
+
+#[test]
+fn ubjson_scalars() {
+    assert_eq!(ubjson::to_vec(&Null), b"Z");
+    assert_eq!(ubjson::to_vec(&true.into()), b"T");
+    assert_eq!(ubjson::to_vec(&false.into()), b"F");
+    assert_eq!(ubjson::to_vec(&"hi".into()), b"SU\x02hi");
+}
+
+#[test]
+fn ubjson_integers_use_smallest_type() {
+    assert_eq!(ubjson::to_vec(&(-5).into()), b"i\xFB");
+    assert_eq!(ubjson::to_vec(&5.into()), b"U\x05");
+    assert_eq!(ubjson::to_vec(&200.into()), b"U\xC8");
+    assert_eq!(ubjson::to_vec(&(-300).into()), b"I\xFE\xD4");
+    assert_eq!(ubjson::to_vec(&70000.into()), b"l\x00\x01\x11\x70");
+    assert_eq!(ubjson::to_vec(&i64::MIN.into()), b"L\x80\x00\x00\x00\x00\x00\x00\x00");
+    assert_eq!(ubjson::to_vec(&u64::MAX.into()), b"HU\x1418446744073709551615");
+    assert_eq!(ubjson::to_vec(&1.5.into()), b"D\x3F\xF8\x00\x00\x00\x00\x00\x00");
+}
+
+#[test]
+fn ubjson_round_trip() {
+    let data = object!{
+        "null" => Null,
+        "bool" => array![true, false],
+        "numbers" => array![0, -1, 255, 65536, i64::MAX, u64::MAX, 0.25, -1e100],
+        "long" => "x".repeat(300),
+        "unicode" => "zażółć",
+        "nested" => object!{ "empty" => array![], "object" => object!{} }
+    };
+
+    let bytes = ubjson::to_vec(&data);
+
+    assert_eq!(ubjson::from_slice(&bytes).unwrap(), data);
+}
+
+#[test]
+fn ubjson_round_trip_floats() {
+    for source in &[
+        "-6.3090395500865768e41",
+        "2.2250738585072014e-308",
+        "0.30000000000000004",
+        "1.7976931348623157e308",
+        "5e-324",
+        "123456789.12345678",
+        "12345678901234567.5",
+    ] {
+        let number = json::parse(source).unwrap();
+        let bytes = ubjson::to_vec(&number);
+
+        assert_eq!(ubjson::from_slice(&bytes).unwrap(), number, "{}", source);
+    }
+
+    let bytes = ubjson::to_vec(&json::parse("-6.3090395500865768e41").unwrap());
+    assert_eq!(bytes[0], b'D');
+    assert_eq!(f64::from_be_bytes(bytes[1 ..].try_into().unwrap()), -6.3090395500865768e41);
+
+    // More digits than a double keeps
+    let number = json::parse("0.123456789012345678").unwrap();
+    let bytes = ubjson::to_vec(&number);
+
+    assert_eq!(bytes[0], b'H');
+    assert_eq!(ubjson::from_slice(&bytes).unwrap(), number);
+}
+
+#[test]
+fn ubjson_read_optimized_containers() {
+    // Array of three `int8` with type and count
+    assert_eq!(ubjson::from_slice(b"[$i#U\x03\x01\x02\x03").unwrap(), array![1, 2, 3]);
+
+    // Object with count only, and a no-op before the first key
+    assert_eq!(
+        ubjson::from_slice(b"{#i\x02NU\x01aTU\x01bC!").unwrap(),
+        object!{ "a" => true, "b" => "!" }
+    );
+
+    // Empty counted array
+    assert_eq!(ubjson::from_slice(b"[#i\x00").unwrap(), array![]);
+}
+
+#[test]
+fn ubjson_read_floats() {
+    assert_eq!(ubjson::from_slice(b"d\x3F\xC0\x00\x00").unwrap(), 1.5);
+    assert_eq!(
+        ubjson::from_slice(b"HU\x0512.50").unwrap(),
+        JsonValue::Number(Number::from_parts(true, 1250, -2))
+    );
+}
+
+#[test]
+fn ubjson_read_stream() {
+    let mut stream = &b"i\x01SU\x01aZ"[..];
+
+    assert_eq!(ubjson::read(&mut stream).unwrap(), 1);
+    assert_eq!(ubjson::read(&mut stream).unwrap(), "a");
+    assert_eq!(ubjson::read(&mut stream).unwrap(), Null);
+    assert!(stream.is_empty());
+}
+
+#[test]
+fn ubjson_read_invalid() {
+    let err = |bytes: &[u8]| ubjson::from_slice(bytes).unwrap_err().kind();
+
+    assert_eq!(err(b"X"), io::ErrorKind::InvalidData);
+    assert_eq!(err(b"SU\x05abc"), io::ErrorKind::UnexpectedEof);
+    assert_eq!(err(b"S\xFF"), io::ErrorKind::InvalidData);
+    assert_eq!(err(b"Si\xFFabc"), io::ErrorKind::InvalidData);
+    assert_eq!(err(b"SU\x01\xFF"), io::ErrorKind::InvalidData);
+    assert_eq!(err(b"[i\x01"), io::ErrorKind::UnexpectedEof);
+    assert_eq!(err(b"HU\x03abc"), io::ErrorKind::InvalidData);
+    assert_eq!(err(b"ZZ"), io::ErrorKind::InvalidData);
+    assert_eq!(err(&[b'['; 600]), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn ubjson_binary() {
+    let data = object!{ "blob" => JsonValue::Binary(vec![0, 1, 255]), "empty" => JsonValue::Binary(Vec::new()) };
+    let bytes = ubjson::to_vec(&data);
+
+    assert_eq!(bytes, b"{U\x04blob[$U#U\x03\x00\x01\xFFU\x05empty[$U#U\x00}");
+    assert_eq!(ubjson::from_slice(&bytes).unwrap(), data);
+
+    assert_eq!(ubjson::from_slice(b"[$i#U\x02\x01\x02").unwrap(), array![1, 2]);
+    assert_eq!(ubjson::from_slice(b"[#U\x02U\x01U\x02").unwrap(), array![1, 2]);
+    assert_eq!(ubjson::from_slice(b"[$U#U\x05ab").unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+}
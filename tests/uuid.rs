@@ -0,0 +1,49 @@
+#![cfg(feature = "uuid")]
+
+extern crate json;
+
+use std::convert::TryFrom;
+
+use json::uuid::Uuid;
+use json::{ Error, JsonValue };
+
+#[test]
+fn parse_uuids() {
+    let id = JsonValue::from("00112233-4455-6677-8899-AaBbCcDdEeFf").as_uuid().unwrap();
+
+    assert_eq!(id.as_bytes(), &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff]);
+    assert_eq!(id.get_version_num(), 6);
+    assert_eq!(JsonValue::from(id), "00112233-4455-6677-8899-aabbccddeeff");
+    assert_eq!(JsonValue::from("00000000-0000-0000-0000-000000000000").as_uuid(), Some(Uuid::nil()));
+
+    for invalid in &[
+        "",
+        "00112233445566778899aabbccddeeff",
+        "{00112233-4455-6677-8899-aabbccddeeff}",
+        "urn:uuid:00112233-4455-6677-8899-aabbccddeeff",
+        "00112233-4455-6677-8899-aabbccddeef",
+        "00112233-4455-6677-8899-aabbccddeeffa",
+        "0011223-34455-6677-8899-aabbccddeeff",
+        "00112233-4455-6677-8899-aabbccddeefg",
+        "+0112233-4455-6677-8899-aabbccddeeff",
+        "00112233-4455-6677-8899-aabbccddeeé",
+    ] {
+        assert_eq!(JsonValue::from(*invalid).as_uuid(), None, "{}", invalid);
+    }
+}
+
+#[test]
+fn json_conversions() {
+    let data = json::parse(r#"{"id":"67e55044-10b1-426f-9247-bb680e5fe0c8","bad":"1234","n":1}"#).unwrap();
+    let id = data["id"].as_uuid().unwrap();
+
+    assert_eq!(JsonValue::from(id), data["id"]);
+    assert_eq!(Uuid::try_from(&data["id"]), Ok(id));
+    assert_eq!(data["bad"].as_uuid(), None);
+    assert_eq!(Uuid::try_from(&data["bad"]), Err(Error::WrongType {
+        expected: "UUID".into(),
+        found: r#""1234""#.into(),
+        path: String::new(),
+    }));
+    assert_eq!(Uuid::try_from(&data["n"]).unwrap_err().to_string(), "Wrong type, expected UUID, found number");
+}
@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate json;
 
-use json::{ parse, JsonValue, JsonError, Null };
+use json::{ parse, JsonValue, JsonError, Null, Case };
 
 #[test]
 fn is_as_string() {
@@ -111,6 +111,25 @@ fn is_null() {
     assert!(null.is_null());
 }
 
+#[test]
+fn default_is_null() {
+    #[derive(Default)]
+    struct Settings {
+        extra: JsonValue,
+    }
+
+    assert!(JsonValue::default().is_null());
+    assert!(Settings::default().extra.is_null());
+
+    let mut data = array![1, object!{ "a" => true }];
+    let taken = std::mem::take(&mut data[1]);
+
+    assert_eq!(taken, object!{ "a" => true });
+    assert_eq!(data, array![1, Null]);
+    assert_eq!(JsonValue::new_array(), array![]);
+    assert_eq!(JsonValue::new_object(), object!{});
+}
+
 #[test]
 fn is_empty() {
     assert!(Null.is_empty());
@@ -148,6 +167,35 @@ fn array_contains() {
     assert!(!data.contains("bar"));
 }
 
+#[test]
+fn as_object_and_array() {
+    let mut data = object!{ "list" => array![1] };
+
+    assert_eq!(data.as_object().map(|object| object.len()), Some(1));
+    assert_eq!(data["list"].as_array(), Some(&vec![JsonValue::from(1)]));
+    assert!(data.as_array().is_none());
+    assert!(data["list"].as_object().is_none());
+
+    data["list"].as_array_mut().unwrap().push(2.into());
+    data.as_object_mut().unwrap().insert("b", true.into());
+
+    assert_eq!(data, object!{ "list" => array![1, 2], "b" => true });
+    assert!(Null.as_object_mut().is_none());
+    assert!(JsonValue::from("x").as_array_mut().is_none());
+}
+
+#[test]
+fn into_object_and_array() {
+    let object = object!{ "a" => array![1, 2] }.into_object().unwrap();
+    let array = object.get("a").cloned().unwrap().into_array().unwrap();
+
+    assert_eq!(array, vec![JsonValue::from(1), JsonValue::from(2)]);
+
+    assert_eq!(JsonValue::from("a").into_object(), Err(JsonValue::from("a")));
+    assert_eq!(object!{ "b" => 1 }.into_array(), Err(object!{ "b" => 1 }));
+    assert_eq!(Null.into_array(), Err(Null));
+}
+
 #[test]
 fn array_push() {
     let mut data = array![1, 2];
@@ -157,6 +205,53 @@ fn array_push() {
     assert_eq!(data, array![1, 2, 3]);
 }
 
+#[test]
+fn array_with_capacity_extend() {
+    let mut data = JsonValue::array_with_capacity(10);
+
+    assert_eq!(data, array![]);
+
+    data.extend(vec![json::from(1), json::from("two")]);
+    data.extend((3 .. 5).map(JsonValue::from));
+
+    assert_eq!(data, array![1, "two", 3, 4]);
+
+    let mut other = json::from("not an array");
+    other.extend(vec![Null]);
+
+    assert_eq!(other, array![Null]);
+}
+
+#[test]
+fn object_with_capacity_extend() {
+    let mut data = JsonValue::object_with_capacity(2);
+
+    assert_eq!(data, object!{});
+
+    data.extend(vec![("a".to_string(), json::from(1)), ("b".to_string(), json::from(2))]);
+
+    // Grows past the capacity, with keys both short and long
+    data.extend((0 .. 50).map(|n| (format!("key number {}", n).repeat(n % 3 + 1), n)));
+    data.extend(vec![("a", 10)]);
+
+    assert_eq!(data.len(), 52);
+    assert_eq!(data["a"], 10);
+    assert_eq!(data["b"], 2);
+    assert_eq!(data["key number 7key number 7"], 7);
+    assert_eq!(data["key number 48"], 48);
+
+    let mut object = json::object::Object::new();
+    object.insert("x", 1.into());
+    object.extend(vec![("y", true)]);
+
+    assert_eq!(JsonValue::Object(object), object!{ "x" => 1, "y" => true });
+
+    let mut other = JsonValue::Null;
+    other.extend(vec![("k", "v")]);
+
+    assert_eq!(other, object!{ "k" => "v" });
+}
+
 #[test]
 fn array_pop() {
     let mut data = array![1, 2, 3];
@@ -175,6 +270,90 @@ fn array_remove() {
     assert_eq!(data.array_remove(2), JsonValue::Null);
 }
 
+#[test]
+fn array_insert_at() {
+    let mut data = array![1, 3];
+
+    data.insert_at(1, 2).unwrap();
+    data.insert_at(0, 0).unwrap();
+    data.insert_at(10, 4).unwrap();
+
+    assert_eq!(data, array![0, 1, 2, 3, 4]);
+    assert!(JsonValue::new_object().insert_at(0, 1).is_err());
+}
+
+#[test]
+fn array_splice() {
+    let mut data = array![1, 2, 3, 4, 5];
+
+    assert_eq!(data.splice(1 ..= 2, vec!["a"]).unwrap(), vec![JsonValue::from(2), JsonValue::from(3)]);
+    assert_eq!(data, array![1, "a", 4, 5]);
+
+    assert_eq!(data.splice(.. 0, vec![true, false]).unwrap(), Vec::<JsonValue>::new());
+    assert_eq!(data, array![true, false, 1, "a", 4, 5]);
+
+    assert_eq!(data.splice(4 .. 100, Vec::<JsonValue>::new()).unwrap(), vec![JsonValue::from(4), JsonValue::from(5)]);
+    assert_eq!(data.splice(10 .., vec![Null]).unwrap(), Vec::<JsonValue>::new());
+    assert_eq!(data, array![true, false, 1, "a", Null]);
+
+    assert_eq!(data.splice(.., vec![0]).unwrap().len(), 5);
+    assert_eq!(data, array![0]);
+
+    assert!(JsonValue::from("text").splice(.., vec![1]).is_err());
+}
+
+#[test]
+fn array_concat() {
+    let mut data = array![1];
+    let page = array![2, array![3]];
+
+    data.concat_cloned(&page).unwrap();
+    data.concat(page).unwrap();
+    data.concat(array![]).unwrap();
+
+    assert_eq!(data, array![1, 2, array![3], 2, array![3]]);
+
+    assert!(data.concat(JsonValue::from(4)).is_err());
+    assert!(data.concat_cloned(&object!{}).is_err());
+    assert!(JsonValue::Null.concat(array![1]).is_err());
+    assert_eq!(data.len(), 5);
+}
+
+#[test]
+fn array_group_by() {
+    let data = array![
+        object!{ "id" => 1, "kind" => "a" },
+        object!{ "id" => 2, "kind" => 5 },
+        object!{ "id" => 3, "kind" => "a" },
+        object!{ "id" => 4 },
+        object!{ "id" => 5, "kind" => Null },
+        "not an object",
+        object!{ "id" => 6, "kind" => 5 }
+    ];
+
+    let groups = data.group_by("kind");
+
+    assert_eq!(groups.dump(), r#"{"a":[{"id":1,"kind":"a"},{"id":3,"kind":"a"}],"5":[{"id":2,"kind":5},{"id":6,"kind":5}],"null":[{"id":5,"kind":null}]}"#);
+    assert_eq!(JsonValue::from(1).group_by("kind"), object!{});
+}
+
+#[test]
+fn array_index_by() {
+    let data = array![
+        object!{ "id" => 1, "v" => "first" },
+        object!{ "id" => "x", "v" => "second" },
+        object!{ "v" => "no id" },
+        object!{ "id" => 1, "v" => "third" }
+    ];
+
+    let index = data.index_by("id");
+
+    assert_eq!(index.keys().collect::<Vec<_>>(), ["1", "x"]);
+    assert_eq!(index["1"]["v"], "third");
+    assert_eq!(index["x"]["v"], "second");
+    assert_eq!(object!{ "id" => 1 }.index_by("id"), object!{});
+}
+
 #[test]
 fn array_members() {
     let data = array![1, "foo"];
@@ -252,6 +431,342 @@ fn object_remove() {
     assert_eq!(data, object!{ "answer" => 42 });
 }
 
+#[test]
+fn object_merge_in() {
+    let mut data = object!{
+        "a" => 1,
+        "nested" => object!{ "x" => 1, "y" => 2 },
+        "b" => 2
+    };
+
+    data.merge_in(object!{ "c" => 3, "a" => "one", "nested" => object!{ "z" => 3 } }).unwrap();
+
+    assert_eq!(data.dump(), r#"{"a":"one","nested":{"z":3},"b":2,"c":3}"#);
+
+    assert!(data.merge_in(array![1]).is_err());
+    assert!(JsonValue::Null.merge_in(object!{}).is_err());
+}
+
+#[test]
+fn object_rename_key() {
+    let mut data = object!{ "a" => 1, "b" => 2, "c" => 3 };
+
+    assert!(data.rename_key("b", "bee"));
+    assert_eq!(data.dump(), r#"{"a":1,"bee":2,"c":3}"#);
+    assert_eq!(data["bee"], 2);
+    assert!(data["b"].is_null());
+
+    // Renaming over an existing key drops it
+    assert!(data.rename_key("c", "a"));
+    assert_eq!(data.dump(), r#"{"bee":2,"a":3}"#);
+
+    assert!(data.rename_key("a", "a"));
+    assert!(!data.rename_key("missing", "x"));
+    assert!(!array!["a"].rename_key("a", "b"));
+    assert_eq!(data.dump(), r#"{"bee":2,"a":3}"#);
+}
+
+#[test]
+fn transform_keys() {
+    let mut data = object!{
+        "userId" => 1,
+        "user_id" => 2,
+        "Profile Data" => object!{ "displayName" => "Jon" },
+        "list" => array![object!{ "item-id" => 3 }, "keyLike"]
+    };
+
+    data.transform_keys(Case::SnakeCase);
+
+    assert_eq!(data.dump(), r#"{"user_id":2,"profile_data":{"display_name":"Jon"},"list":[{"item_id":3},"keyLike"]}"#);
+
+    data.transform_keys(Case::KebabCase);
+
+    assert_eq!(data.dump(), r#"{"user-id":2,"profile-data":{"display-name":"Jon"},"list":[{"item-id":3},"keyLike"]}"#);
+
+    let mut text = JsonValue::from("user_id");
+    text.transform_keys(Case::CamelCase);
+    assert_eq!(text, "user_id");
+}
+
+#[test]
+fn sort_keys() {
+    let mut data = object!{
+        "zeta" => 1,
+        "Beta" => array![object!{ "y" => 1, "x" => 2 }, 3],
+        "alpha" => object!{ "b" => Null, "a" => object!{ "d" => 4, "c" => 5 } }
+    };
+
+    data.sort_keys();
+
+    assert_eq!(data.dump(), r#"{"Beta":[{"x":2,"y":1},3],"alpha":{"a":{"c":5,"d":4},"b":null},"zeta":1}"#);
+    assert_eq!(data["alpha"]["a"]["c"], 5);
+
+    data.sort_keys_by(|a, b| b.to_lowercase().cmp(&a.to_lowercase()));
+
+    assert_eq!(data.dump(), r#"{"zeta":1,"Beta":[{"y":1,"x":2},3],"alpha":{"b":null,"a":{"d":4,"c":5}}}"#);
+}
+
+#[test]
+fn truncated() {
+    let data = object!{
+        "name" => "Jon Snow",
+        "emoji" => "😀😀😀",
+        "list" => array![1, array![2, array![3]], object!{}, array![]],
+        "nested" => object!{ "a" => object!{ "b" => 1 } },
+        "last" => true
+    };
+
+    assert_eq!(data.truncated(10, 10, 100), data);
+    assert_eq!(
+        data.truncated(2, 4, 3).dump(),
+        r#"{"name":"Jon…","emoji":"😀😀😀","list":[1,"…",{},[]],"nested":{"a":"…"},"…":"…"}"#
+    );
+    assert_eq!(data.truncated(0, 4, 3), "…");
+    assert_eq!(data.truncated(1, 0, 0).dump(), r#"{"…":"…"}"#);
+    assert_eq!(array![1, 2, 3].truncated(1, 2, 0), array![1, 2, "…"]);
+    assert_eq!(JsonValue::from("long").truncated(0, 0, 0), "…");
+    assert_eq!(JsonValue::from(12345).truncated(0, 0, 0), 12345);
+}
+
+#[test]
+fn iter_leaves() {
+    let data = object!{
+        "id" => 1,
+        "users" => array![object!{ "name" => "Jon", "tags" => array![] }, JsonValue::Null, object!{}],
+        "m~n" => object!{ "a/b" => false }
+    };
+
+    let leaves: Vec<(String, &JsonValue)> = data.iter_leaves().collect();
+
+    assert_eq!(leaves, vec![
+        ("/id".to_string(), &JsonValue::from(1)),
+        ("/users/0/name".to_string(), &JsonValue::from("Jon")),
+        ("/users/1".to_string(), &JsonValue::Null),
+        ("/m~0n/a~1b".to_string(), &JsonValue::from(false)),
+    ]);
+
+    for (pointer, value) in data.iter_leaves() {
+        assert_eq!(data.pointer(&pointer), Some(value));
+    }
+
+    assert_eq!(JsonValue::from("text").iter_leaves().collect::<Vec<_>>(), vec![(String::new(), &JsonValue::from("text"))]);
+    assert_eq!(array![array![], object!{}].iter_leaves().count(), 0);
+}
+
+#[test]
+fn find_all() {
+    let data = object!{
+        "id" => 1,
+        "items" => array![object!{ "id" => 2, "sub" => object!{ "id" => 3 } }, 4],
+        "a/b" => object!{ "id" => "x" }
+    };
+
+    let ids: Vec<String> = data.find_all(|path, _| path.ends_with("/id")).into_iter().map(|(path, _)| path).collect();
+
+    assert_eq!(ids, ["/id", "/items/0/id", "/items/0/sub/id", "/a~1b/id"]);
+
+    let objects = data.find_all(|_, value| value.is_object());
+
+    assert_eq!(objects.len(), 4);
+    assert_eq!(objects[0], (String::new(), &data));
+    assert_eq!(objects[2].0, "/items/0/sub");
+
+    let numbers = data.find_all(|_, value| value.as_u64().is_some_and(|number| number > 2));
+
+    assert_eq!(numbers, vec![
+        ("/items/0/sub/id".to_string(), &JsonValue::from(3)),
+        ("/items/1".to_string(), &JsonValue::from(4)),
+    ]);
+
+    assert!(data.find_all(|_, _| false).is_empty());
+}
+
+#[test]
+fn select_paths() {
+    let data = object!{
+        "users" => object!{
+            "jon" => object!{ "tags" => array!["a", "b"], "age" => 30 },
+            "sam" => object!{ "tags" => array!["c"] },
+            "ghost" => "not an object"
+        },
+        "grid" => array![array![1, 2], array![3, 4]],
+        "0" => "key"
+    };
+
+    assert_eq!(data.select(""), [&data]);
+    assert_eq!(data.select("users.jon.age"), [30]);
+    assert_eq!(data.select("users.*.age"), [30]);
+    assert_eq!(data.select("users.*.tags[*]"), ["a", "b", "c"]);
+    assert_eq!(data.select("users.*.tags.0"), ["a", "c"]);
+    assert_eq!(data.select("users.*.tags[1]"), ["b"]);
+    assert_eq!(data.select("grid[*][1]"), [2, 4]);
+    assert_eq!(data.select("grid.1.*"), [3, 4]);
+    assert_eq!(data.select("*").len(), 3);
+    assert_eq!(data.select("0"), ["key"]);
+    assert_eq!(data["grid"].select("[0][0]"), [1]);
+
+    assert!(data.select("users.nobody").is_empty());
+    assert!(data.select("grid.01").is_empty());
+
+    for path in &["users..jon", "users.", "grid[0", "grid[x]", "grid[0]x", "grid[-1]", "."] {
+        assert!(data.select(path).is_empty(), "{}", path);
+    }
+}
+
+#[test]
+fn contains_value() {
+    let data = json::parse(r#"{
+        "id": 17,
+        "user": {"name": "Sam", "roles": ["admin", "dev"]},
+        "items": [{"sku": "a", "qty": 1}, {"sku": "b", "qty": 2}],
+        "note": null
+    }"#).unwrap();
+
+    assert!(data.contains_value(&data));
+    assert!(data.contains_value(&object!{}));
+    assert!(data.contains_value(&object!{ "note" => Null }));
+    assert!(data.contains_value(&object!{ "id" => 17.0 }));
+    assert!(data.contains_value(&object!{ "user" => object!{ "roles" => array![] } }));
+    assert!(data.contains_value(&object!{ "user" => object!{ "roles" => array!["dev", "admin", "dev"] } }));
+    assert!(data.contains_value(&object!{ "items" => array![object!{ "qty" => 2 }, object!{ "sku" => "a" }] }));
+
+    assert!(!data.contains_value(&object!{ "missing" => Null }));
+    assert!(!data.contains_value(&object!{ "id" => "17" }));
+    assert!(!data.contains_value(&object!{ "user" => object!{ "roles" => array!["root"] } }));
+    assert!(!data.contains_value(&object!{ "items" => array![object!{ "sku" => "a", "qty" => 2 }] }));
+    assert!(!data.contains_value(&object!{ "user" => "Sam" }));
+    assert!(!data.contains_value(&array![]));
+
+    assert!(JsonValue::from("abc").contains_value(&"abc".into()));
+    assert!(!JsonValue::from("abc").contains_value(&"b".into()));
+    assert!(array![1, array![2, 3]].contains_value(&array![array![3]]));
+}
+
+#[test]
+fn object_pick_omit() {
+    let data = object!{
+        "id" => 1,
+        "name" => "Jon",
+        "internal" => object!{ "id" => 2, "debug" => true },
+        "list" => array![object!{ "id" => 3, "debug" => false }, 4]
+    };
+
+    assert_eq!(data.pick(&["list", "id"]).dump(), r#"{"id":1,"list":[{"id":3,"debug":false},4]}"#);
+    assert_eq!(data.omit(&["internal", "list", "debug"]), object!{ "id" => 1, "name" => "Jon" });
+    assert_eq!(data.pick(&[]), object!{});
+    assert_eq!(data.omit(&[]), data);
+
+    assert_eq!(data.pick_deep(&["list", "id"]).dump(), r#"{"id":1,"list":[{"id":3},4]}"#);
+    assert_eq!(data.omit_deep(&["debug"]).dump(), r#"{"id":1,"name":"Jon","internal":{"id":2},"list":[{"id":3},4]}"#);
+
+    assert_eq!(array![object!{ "a" => 1 }].pick(&["b"]), array![object!{ "a" => 1 }]);
+    assert_eq!(array![object!{ "a" => 1 }].pick_deep(&["b"]), array![object!{}]);
+    assert_eq!(JsonValue::from("text").omit_deep(&["a"]), "text");
+}
+
+#[test]
+fn const_values() {
+    use json::number::Number;
+    use json::short::Short;
+
+    static NAME: JsonValue = JsonValue::from_static("thirty bytes of static string");
+    static EMPTY: JsonValue = JsonValue::from_static("");
+    static OBJECT: JsonValue = JsonValue::new_object();
+    static NUMBER: JsonValue = JsonValue::Number(Number::from_parts(false, 1500, -3));
+    const SHORT: Short = Short::new("foo");
+
+    assert_eq!(NAME, "thirty bytes of static string");
+    assert_eq!(EMPTY, "");
+    assert_eq!(OBJECT, object!{});
+    assert_eq!(NUMBER, -1.5);
+    assert_eq!(SHORT.as_str(), "foo");
+}
+
+#[test]
+fn object_try_get() {
+    let data = object!{
+        "a" => object!{
+            "b~/c" => array![1, 2]
+        }
+    };
+
+    assert_eq!(data.try_get("a").unwrap(), &object!{ "b~/c" => array![1, 2] });
+    assert_eq!(data.try_get_path(&["a", "b~/c"]).unwrap(), &array![1, 2]);
+    assert_eq!(data.try_get_path(&[]).unwrap(), &data);
+
+    assert_eq!(data.try_get_path(&["a", "b"]), Err(JsonError::MemberNotFound {
+        key: "b".into(),
+        path: "/a".into(),
+    }));
+
+    let err = data.try_get_path(&["a", "b~/c", "d"]).unwrap_err();
+
+    assert_eq!(err, JsonError::NotAnObject { path: "/a/b~0~1c".into() });
+    assert_eq!(err.to_string(), "Not an object at /a/b~0~1c");
+}
+
+#[test]
+fn try_from_conversions() {
+    use std::convert::TryFrom;
+
+    let data = array!["foo", 300, true, -1];
+
+    assert_eq!(<&str>::try_from(&data[0]).unwrap(), "foo");
+    assert_eq!(u16::try_from(&data[1]).unwrap(), 300);
+    assert_eq!(bool::try_from(&data[2]).unwrap(), true);
+    assert_eq!(i8::try_from(&data[3]).unwrap(), -1);
+
+    assert_eq!(f64::try_from(&data[0]), Err(JsonError::WrongType {
+        expected: "number".into(),
+        found: "string".into(),
+        path: "".into(),
+    }));
+    assert_eq!(u8::try_from(&data[1]).unwrap_err().to_string(), "Wrong type, expected u8, found 300");
+    assert_eq!(u64::try_from(&data[3]).unwrap_err().to_string(), "Wrong type, expected u64, found -1");
+    assert_eq!(String::try_from(&data).unwrap_err().to_string(), "Wrong type, expected string, found array");
+}
+
+#[test]
+fn try_get_path_as_reports_path() {
+    let data = object!{
+        "item" => object!{ "price" => "12", "count" => 3 }
+    };
+
+    assert_eq!(data.try_get_path_as::<u32>(&["item", "count"]).unwrap(), 3);
+    assert_eq!(data.try_get_path_as::<f64>(&["item", "price"]), Err(JsonError::WrongType {
+        expected: "number".into(),
+        found: "string".into(),
+        path: "/item/price".into(),
+    }));
+    assert_eq!(data.try_get_path_as::<bool>(&["item", "x"]), Err(JsonError::MemberNotFound {
+        key: "x".into(),
+        path: "/item".into(),
+    }));
+
+    // Paths inside of the value converted are kept after the keys
+    use std::convert::TryFrom;
+
+    struct Point(f64, f64);
+
+    impl<'a> TryFrom<&'a JsonValue> for Point {
+        type Error = JsonError;
+
+        fn try_from(value: &'a JsonValue) -> Result<Point, JsonError> {
+            let coordinate = |index: usize| f64::try_from(&value[index]).map_err(|error| match error {
+                JsonError::WrongType { expected, found, .. } => JsonError::WrongType { expected, found, path: format!("/{}", index) },
+                error => error,
+            });
+
+            Ok(Point(coordinate(0)?, coordinate(1)?))
+        }
+    }
+
+    let shapes = object!{ "line" => object!{ "from" => array![1, "2"] } };
+    let err = shapes.try_get_path_as::<Point>(&["line", "from"]).err().unwrap();
+
+    assert_eq!(err.to_string(), "Wrong type, expected number, found string at /line/from/1");
+}
+
 #[test]
 fn object_entries() {
     let data = object!{
@@ -300,6 +815,31 @@ fn object_entries_rev() {
     assert!(entries.next().is_none());
 }
 
+#[test]
+fn object_keys_values() {
+    let mut data = object!{
+        "a" => 1,
+        "b" => "foo",
+        "c" => Null
+    };
+
+    assert_eq!(data.keys().collect::<Vec<_>>(), ["a", "b", "c"]);
+    assert_eq!(data.keys().rev().next(), Some("c"));
+    assert_eq!(data.values().len(), 3);
+    assert_eq!(data.values().collect::<Vec<_>>(), [&JsonValue::from(1), &JsonValue::from("foo"), &Null]);
+
+    for value in data.values_mut() {
+        *value = true.into();
+    }
+
+    assert_eq!(data, object!{ "a" => true, "b" => true, "c" => true });
+
+    let mut array = array![1];
+    assert!(array.keys().next().is_none());
+    assert!(array.values().next().is_none());
+    assert!(array.values_mut().next().is_none());
+}
+
 #[test]
 fn object_entries_mut() {
     let mut data = object!{
@@ -573,9 +1113,10 @@ fn error_unexpected_character() {
         ch: 'X',
         line: 3,
         column: 4,
+        expected: &["null"],
     });
 
-    assert_eq!(format!("{}", err), "Unexpected character: X at (3:4)");
+    assert_eq!(format!("{}", err), "Unexpected character: X at (3:4), expected `null`");
 }
 
 #[test]
@@ -586,9 +1127,10 @@ fn error_unexpected_unicode_character() {
         ch: '🦄',
         line: 3,
         column: 4,
+        expected: &["null"],
     });
 
-    assert_eq!(format!("{}", err), "Unexpected character: 🦄 at (3:4)");
+    assert_eq!(format!("{}", err), "Unexpected character: 🦄 at (3:4), expected `null`");
 }
 
 #[test]
@@ -599,9 +1141,46 @@ fn error_unexpected_token() {
         ch: ']',
         line: 4,
         column: 3,
+        expected: &["a value"],
     });
 
-    assert_eq!(format!("{}", err), "Unexpected character: ] at (4:3)");
+    assert_eq!(format!("{}", err), "Unexpected character: ] at (4:3), expected a value");
+}
+
+#[test]
+fn error_unexpected_invisible_character() {
+    let err = parse("[1,\t\u{1}]").unwrap_err();
+
+    assert_eq!(format!("{}", err), "Unexpected character: \\u{1} at (1:5), expected a value");
+    assert_eq!(format!("{}", parse("[1 \t2]").unwrap_err()), "Unexpected character: 2 at (1:5), expected `,` or `]`");
+    assert_eq!(format!("{}", parse("{\"a\" \u{a0}").unwrap_err()), "Unexpected character: \\u{a0} at (1:6), expected `:`");
+}
+
+#[test]
+fn error_format_with_source() {
+    let source = "[\n\t1,\t2 3\n]";
+    let err = parse(source).unwrap_err();
+
+    assert_eq!(err.format_with_source(source), "Unexpected character: 3 at (2:7), expected `,` or `]`\n  |\n2 | \t1,\t2 3\n  | \t  \t  ^");
+
+    let source = "{\"a\": [1, 2";
+    let err = parse(source).unwrap_err();
+
+    assert_eq!(err.format_with_source(source), "Unexpected end of JSON\n  |\n1 | {\"a\": [1, 2\n  |            ^");
+    assert_eq!(JsonError::wrong_type("string", &Null).format_with_source(source), "Wrong type, expected string, found null");
+}
+
+#[test]
+fn error_composes_with_question_mark() {
+    fn load(source: &str) -> Result<JsonValue, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(parse(source)?)
+    }
+
+    let err = load("[1,]").unwrap_err();
+
+    assert_eq!(err.to_string(), "Unexpected character: ] at (1:4), expected a value");
+    assert!(err.source().is_none());
+    assert_eq!(*err.downcast::<JsonError>().unwrap(), JsonError::UnexpectedCharacter { ch: ']', line: 1, column: 4, expected: &["a value"] });
 }
 
 #[test]
@@ -667,3 +1246,143 @@ fn equality() {
     assert_ne!(left, change_string);
     assert_ne!(left, change_short);
 }
+
+#[test]
+fn pointer_lookup() {
+    let mut data = object!{
+        "a/b" => object!{ "m~n" => 1 },
+        "list" => array![10, 20],
+        "" => "empty key"
+    };
+
+    assert_eq!(data.pointer(""), Some(&data.clone()));
+    assert_eq!(data.pointer("/a~1b/m~0n"), Some(&JsonValue::from(1)));
+    assert_eq!(data.pointer("/list/1"), Some(&JsonValue::from(20)));
+    assert_eq!(data.pointer("/"), Some(&JsonValue::from("empty key")));
+    assert_eq!(data.pointer("/list/01"), None);
+    assert_eq!(data.pointer("/list/2"), None);
+    assert_eq!(data.pointer("/list/-"), None);
+    assert_eq!(data.pointer("/list/1/x"), None);
+    assert_eq!(data.pointer("list"), None);
+
+    *data.pointer_mut("/list/0").unwrap() = "ten".into();
+    assert_eq!(data["list"][0], "ten");
+}
+
+#[test]
+fn set_pointer_creates_intermediates() {
+    let mut data = JsonValue::new_object();
+
+    data.set_pointer("/a/b/0/c", true).unwrap();
+    data.set_pointer("/a/b/1", 1).unwrap();
+    data.set_pointer("/a/b/0/d", 2).unwrap();
+    data.set_pointer("/a/b/-", "end").unwrap();
+    data.set_pointer("/a/k~1ey/-/x", Null).unwrap();
+    data.set_pointer("/a/07", "not an index").unwrap();
+
+    assert_eq!(data, object!{
+        "a" => object!{
+            "b" => array![object!{ "c" => true, "d" => 2 }, 1, "end"],
+            "k/ey" => array![object!{ "x" => Null }],
+            "07" => "not an index"
+        }
+    });
+
+    let mut whole = array![1];
+    whole.set_pointer("", "replaced").unwrap();
+    assert_eq!(whole, "replaced");
+
+    let mut null = Null;
+    null.set_pointer("/0/0", 1).unwrap();
+    assert_eq!(null, array![array![1]]);
+
+    let mut padded = JsonValue::new_object();
+    padded.set_pointer("/a/b/3/c", 1).unwrap();
+    padded.set_pointer("/a/b/5", 2).unwrap();
+    assert_eq!(padded, object!{ "a" => object!{ "b" => array![Null, Null, Null, object!{ "c" => 1 }, Null, 2] } });
+
+    let mut far = array![1];
+    far.set_pointer("/1025", 2).unwrap();
+    assert_eq!(far.len(), 1026);
+    assert_eq!(far[1025], 2);
+}
+
+#[test]
+fn set_pointer_errors() {
+    let mut data = object!{ "name" => "Jon", "tags" => array!["a"] };
+    let before = data.clone();
+
+    assert_eq!(data.set_pointer("name", 1), Err(JsonError::InvalidPointer { pointer: "name".into() }));
+    assert_eq!(data.set_pointer("/name/first", 1), Err(JsonError::WrongType {
+        expected: "object or array".into(),
+        found: "string".into(),
+        path: "/name".into(),
+    }));
+    assert_eq!(data.set_pointer("/tags/x", 1), Err(JsonError::WrongType {
+        expected: "object".into(),
+        found: "array".into(),
+        path: "/tags".into(),
+    }));
+    assert_eq!(data.set_pointer("/tags/0/deep", 1).unwrap_err().to_string(), "Wrong type, expected object or array, found string at /tags/0");
+
+    // Indexes needing more than 1024 `null`s of padding, such as huge ones
+    assert_eq!(data.set_pointer("/tags/1026", 1), Err(JsonError::IndexOutOfBounds {
+        index: 1026,
+        len: 1,
+        path: "/tags".into(),
+    }));
+    assert_eq!(data.set_pointer("/new/99999999999", 1), Err(JsonError::IndexOutOfBounds {
+        index: 99999999999,
+        len: 0,
+        path: "/new".into(),
+    }));
+    assert_eq!(
+        data.set_pointer("/new/-/x/1025", 1).unwrap_err().to_string(),
+        "Index 1025 is out of bounds for an array of length 0 at /new/0/x"
+    );
+
+    assert_eq!(data, before);
+}
+
+#[test]
+fn remove_pointer() {
+    let mut data = object!{
+        "a/b" => object!{ "m~n" => 1, "keep" => 2 },
+        "list" => array![10, 20, 30]
+    };
+
+    assert_eq!(data.remove_pointer("/a~1b/m~0n"), Some(1.into()));
+    assert_eq!(data.remove_pointer("/list/1"), Some(20.into()));
+    assert_eq!(data.remove_pointer("/list/2"), None);
+    assert_eq!(data.remove_pointer("/list/-"), None);
+    assert_eq!(data.remove_pointer("/list/01"), None);
+    assert_eq!(data.remove_pointer("/missing/x"), None);
+    assert_eq!(data.remove_pointer("/a~1b/keep/x"), None);
+    assert_eq!(data.remove_pointer("list"), None);
+    assert_eq!(data.remove_pointer(""), None);
+
+    assert_eq!(data, object!{
+        "a/b" => object!{ "keep" => 2 },
+        "list" => array![10, 30]
+    });
+}
+
+#[test]
+fn binary_values() {
+    let blob = JsonValue::Binary(vec![1, 2, 3]);
+
+    assert!(blob.is_binary());
+    assert!(!JsonValue::from("AQID").is_binary());
+    assert_eq!(blob.as_binary(), Some(&[1, 2, 3][..]));
+    assert_eq!(JsonValue::from("AQID").as_binary(), None);
+    assert_eq!(blob.type_name(), "binary");
+    assert!(JsonValue::Binary(vec![]).is_empty());
+    assert!(!blob.is_empty());
+
+    assert_eq!(blob, JsonValue::Binary(vec![1, 2, 3]));
+    assert_ne!(blob, JsonValue::Binary(vec![1, 2]));
+    assert_ne!(blob, JsonValue::from("AQID"));
+    assert_ne!(blob, array![1, 2, 3]);
+
+    assert_eq!(parse(&blob.dump()).unwrap(), "AQID");
+}
@@ -0,0 +1,136 @@
+#[macro_use]
+extern crate json;
+
+use std::io;
+use json::{ parse, JsonWriter };
+use json::codegen::GeneratorOptions;
+
+#[test]
+fn writer_compact() {
+    let mut writer = JsonWriter::new(Vec::new());
+
+    writer.begin_array().unwrap();
+    writer.null().unwrap();
+    writer.boolean(true).unwrap();
+    writer.string("a\"b").unwrap();
+    writer.begin_object().unwrap();
+    writer.end_object().unwrap();
+    writer.begin_array().unwrap();
+    writer.end_array().unwrap();
+    writer.value(&object!{ "x" => array![1, 2] }).unwrap();
+    writer.end_array().unwrap();
+
+    let out = writer.finish().unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), r#"[null,true,"a\"b",{},[],{"x":[1,2]}]"#);
+}
+
+#[test]
+fn writer_pretty_matches_pretty() {
+    let data = object!{
+        "name" => "Urlich",
+        "parents" => object!{
+            "mother" => "Helga"
+        },
+        "cars" => array![ "Golf", array![] ]
+    };
+
+    let mut writer = JsonWriter::pretty(Vec::new(), 2);
+
+    writer.begin_object().unwrap();
+    writer.key("name").unwrap();
+    writer.string("Urlich").unwrap();
+    writer.key("parents").unwrap();
+    writer.value(&data["parents"]).unwrap();
+    writer.key("cars").unwrap();
+    writer.begin_array().unwrap();
+    writer.string("Golf").unwrap();
+    writer.begin_array().unwrap();
+    writer.end_array().unwrap();
+    writer.end_array().unwrap();
+    writer.end_object().unwrap();
+
+    let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    assert_eq!(out, data.pretty(2));
+    assert_eq!(parse(&out).unwrap(), data);
+}
+
+#[test]
+fn writer_rejects_misplaced_tokens() {
+    let mut writer = JsonWriter::new(Vec::new());
+
+    writer.begin_object().unwrap();
+    assert_eq!(writer.number(1).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(writer.end_array().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+    writer.key("a").unwrap();
+    assert!(writer.key("b").is_err());
+    assert!(writer.end_object().is_err());
+
+    writer.number(1).unwrap();
+    writer.end_object().unwrap();
+
+    assert!(writer.null().is_err());
+    assert!(writer.finish().is_ok());
+}
+
+#[test]
+fn writer_incomplete_document() {
+    let mut writer = JsonWriter::new(Vec::new());
+
+    writer.begin_array().unwrap();
+
+    assert!(writer.finish().is_err());
+}
+
+#[test]
+fn writer_trailing_newline() {
+    let options = GeneratorOptions {
+        trailing_newline: true,
+        ..GeneratorOptions::default()
+    };
+    let mut writer = JsonWriter::with_options(Vec::new(), None, options);
+
+    writer.begin_array().unwrap();
+    writer.number(1).unwrap();
+    writer.end_array().unwrap();
+
+    assert_eq!(writer.finish().unwrap(), b"[1]\n");
+}
+
+#[test]
+fn writer_json5() {
+    let mut writer = JsonWriter::with_options(Vec::new(), Some(2), GeneratorOptions::JSON5);
+
+    writer.begin_object().unwrap();
+    writer.key("list").unwrap();
+    writer.begin_array().unwrap();
+    writer.number(1).unwrap();
+    writer.end_array().unwrap();
+    writer.end_object().unwrap();
+
+    let out = writer.finish().unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), "{\n  list: [\n    1,\n  ],\n}");
+}
+
+#[test]
+fn writer_raw() {
+    let mut writer = JsonWriter::pretty(Vec::new(), 2);
+
+    writer.begin_array().unwrap();
+    writer.raw("{\"a\":[1,2]}").unwrap();
+    writer.number(3).unwrap();
+    writer.raw("null").unwrap();
+    writer.end_array().unwrap();
+
+    let out = writer.finish().unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "[\n  {\"a\":[1,2]},\n  3,\n  null\n]");
+
+    let mut writer = JsonWriter::new(Vec::new());
+    writer.begin_object().unwrap();
+
+    // Raw fragments are values, keys are still required before them
+    assert_eq!(writer.raw("1").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+}
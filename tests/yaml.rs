@@ -0,0 +1,82 @@
+#![cfg(feature = "yaml")]
+
+#[macro_use]
+extern crate json;
+
+use json::yaml;
+use json::{ JsonValue, Null };
+
+#[test]
+fn yaml_parse_scalars() {
+    assert_eq!(yaml::parse("~").unwrap(), Null);
+    assert_eq!(yaml::parse("").unwrap(), Null);
+    assert_eq!(yaml::parse("true").unwrap(), true);
+    assert_eq!(yaml::parse("-12").unwrap(), -12);
+    assert_eq!(yaml::parse("2.50").unwrap(), 2.5);
+    assert_eq!(yaml::parse("'quoted: yes'").unwrap(), "quoted: yes");
+    assert!(yaml::parse(".inf").unwrap().as_number().unwrap().is_nan());
+}
+
+#[test]
+fn yaml_parse_nested() {
+    let source = "
+server:
+  host: localhost
+  ports: [80, 443]
+users:
+  - name: Jon
+    admin: true
+  - { name: Arya, admin: false }
+";
+
+    assert_eq!(yaml::parse(source).unwrap(), object!{
+        "server" => object!{ "host" => "localhost", "ports" => array![80, 443] },
+        "users" => array![
+            object!{ "name" => "Jon", "admin" => true },
+            object!{ "name" => "Arya", "admin" => false }
+        ]
+    });
+}
+
+#[test]
+fn yaml_parse_scalar_keys() {
+    assert_eq!(
+        yaml::parse("1: one\ntrue: yes\n~: none").unwrap(),
+        object!{ "1" => "one", "true" => "yes", "null" => "none" }
+    );
+}
+
+#[test]
+fn yaml_parse_errors() {
+    assert!(matches!(yaml::parse("a: [1, 2"), Err(yaml::Error::Syntax(_))));
+    assert_eq!(yaml::parse("? [a]\n: 1"), Err(yaml::Error::Unsupported("Mapping keys must be scalars")));
+    assert_eq!(yaml::parse("a: 1\n---\nb: 2"), Err(yaml::Error::MultipleDocuments));
+}
+
+#[test]
+fn yaml_stringify() {
+    let data = object!{
+        "name" => "needs: quotes",
+        "count" => 3,
+        "ratio" => 0.5,
+        "none" => Null,
+        "list" => array![1, "two"],
+        "empty" => JsonValue::new_object()
+    };
+
+    assert_eq!(
+        yaml::stringify(&data),
+        "name: \"needs: quotes\"\ncount: 3\nratio: 0.5\nnone: ~\nlist:\n  - 1\n  - two\nempty: {}\n"
+    );
+}
+
+#[test]
+fn yaml_round_trip() {
+    let data = object!{
+        "a" => array![object!{ "b" => "true" }, -7, 1.25, Null],
+        "c" => "multi\nline",
+        "big" => u64::MAX
+    };
+
+    assert_eq!(yaml::parse(&yaml::stringify(&data)).unwrap(), data);
+}